@@ -0,0 +1,190 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Process health, which is reported by the admin HTTP endpoints. Each thread
+//! which runs an event loop publishes a `Heartbeat` once per iteration, so a
+//! thread which has stalled can be detected by the admin thread. A thread
+//! drops its heartbeat when it exits, which lets the admin thread wait for the
+//! other threads to drain during a shutdown.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The heartbeat for a single thread.
+pub struct Heartbeat {
+    name: String,
+    start: Instant,
+    /// Nanoseconds from `start` until the most recent beat
+    last: AtomicU64,
+}
+
+impl Heartbeat {
+    fn new(name: &str, start: Instant) -> Self {
+        let heartbeat = Self {
+            name: name.to_string(),
+            start,
+            last: AtomicU64::new(0),
+        };
+        heartbeat.beat();
+        heartbeat
+    }
+
+    /// Record that the thread is making progress. This should be called once
+    /// per iteration of the event loop.
+    pub fn beat(&self) {
+        let now = self.start.elapsed().as_nanos() as u64;
+        self.last.store(now, Ordering::Relaxed);
+    }
+
+    /// The name of the thread which publishes this heartbeat.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The time since the most recent beat.
+    pub fn elapsed(&self) -> Duration {
+        let last = Duration::from_nanos(self.last.load(Ordering::Relaxed));
+        self.start.elapsed().saturating_sub(last)
+    }
+}
+
+/// The health of the process, shared between the admin thread and the threads
+/// which publish heartbeats.
+pub struct Health {
+    start: Instant,
+    heartbeats: Mutex<Vec<Arc<Heartbeat>>>,
+    listening: AtomicBool,
+    draining: AtomicBool,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Health {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            heartbeats: Mutex::new(Vec::new()),
+            listening: AtomicBool::new(false),
+            draining: AtomicBool::new(false),
+        }
+    }
+
+    /// Register a heartbeat for the thread with the provided name. The thread
+    /// is considered to have beat once at registration.
+    pub fn heartbeat(&self, name: &str) -> Arc<Heartbeat> {
+        let heartbeat = Arc::new(Heartbeat::new(name, self.start));
+        self.heartbeats.lock().unwrap().push(heartbeat.clone());
+        heartbeat
+    }
+
+    /// Returns the names of the running threads which have not beat within
+    /// the threshold, along with the time since their most recent beat.
+    pub fn stalled(&self, threshold: Duration) -> Vec<(String, Duration)> {
+        self.heartbeats
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|heartbeat| is_running(heartbeat) && heartbeat.elapsed() > threshold)
+            .map(|heartbeat| (heartbeat.name().to_string(), heartbeat.elapsed()))
+            .collect()
+    }
+
+    /// Returns the number of threads with a heartbeat which have not exited.
+    pub fn running(&self) -> usize {
+        self.heartbeats
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|heartbeat| is_running(heartbeat))
+            .count()
+    }
+
+    /// Set by the listener once it is accepting new sessions, and cleared when
+    /// it stops.
+    pub fn set_listening(&self, listening: bool) {
+        self.listening.store(listening, Ordering::Relaxed);
+    }
+
+    pub fn is_listening(&self) -> bool {
+        self.listening.load(Ordering::Relaxed)
+    }
+
+    /// Set once the process has begun to drain, eg: after a shutdown has been
+    /// requested.
+    pub fn set_draining(&self) {
+        self.draining.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Relaxed)
+    }
+}
+
+// the heartbeat is shared only with its thread, so it is the last reference
+// once the thread has exited
+fn is_running(heartbeat: &Arc<Heartbeat>) -> bool {
+    Arc::strong_count(heartbeat) > 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stalled() {
+        let health = Health::new();
+        let a = health.heartbeat("a");
+        let _b = health.heartbeat("b");
+
+        assert!(health.stalled(Duration::from_millis(50)).is_empty());
+
+        std::thread::sleep(Duration::from_millis(100));
+        a.beat();
+
+        let stalled = health.stalled(Duration::from_millis(50));
+        assert_eq!(stalled.len(), 1);
+        assert_eq!(stalled[0].0, "b");
+        assert!(stalled[0].1 >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn running() {
+        let health = Health::new();
+        let a = health.heartbeat("a");
+        let b = health.heartbeat("b");
+        assert_eq!(health.running(), 2);
+
+        // a thread which has exited is no longer running, and so is not
+        // reported as stalled
+        drop(a);
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(health.running(), 1);
+
+        let stalled = health.stalled(Duration::from_millis(50));
+        assert_eq!(stalled.len(), 1);
+        assert_eq!(stalled[0].0, "b");
+
+        drop(b);
+        assert_eq!(health.running(), 0);
+        assert!(health.stalled(Duration::from_millis(50)).is_empty());
+    }
+
+    #[test]
+    fn readiness() {
+        let health = Health::new();
+        assert!(!health.is_listening());
+        assert!(!health.is_draining());
+
+        health.set_listening(true);
+        assert!(health.is_listening());
+
+        health.set_draining();
+        assert!(health.is_draining());
+    }
+}
@@ -4,6 +4,7 @@
 
 pub mod bytes;
 pub mod expiry;
+pub mod health;
 pub mod metrics;
 pub mod signal;
 pub mod ssl;
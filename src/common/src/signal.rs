@@ -5,5 +5,33 @@
 #[derive(Clone)]
 pub enum Signal {
     FlushAll,
+    Reload,
     Shutdown,
+    /// Request a section of stats from the thread which owns the storage. The
+    /// id is returned in the `StorageStats` so the admin thread can match the
+    /// reply to the request.
+    Stats(u64, StatsSection),
+    /// Request a description of how the item with the key is stored from the
+    /// thread which owns the storage. As with `Stats`, the id is returned in
+    /// the `StorageStats`.
+    DebugKey(u64, Vec<u8>),
+}
+
+/// The sections of stats which are derived from the storage, and so can only
+/// be collected by the thread which owns it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatsSection {
+    /// Per-segment utilization and merge chains. Only one page of segments
+    /// is described per request, starting from page zero.
+    Seg(u32),
+    /// Per-TTL-bucket segment and item counts
+    Ttl,
+}
+
+/// The reply to a `Signal::Stats` or `Signal::DebugKey`, which is sent back to
+/// the admin thread. The stats are empty if the key for a `Signal::DebugKey` was
+/// not found, and are `None` if the storage does not support the request.
+pub struct StorageStats {
+    pub id: u64,
+    pub stats: Option<Vec<(String, String)>>,
 }
@@ -12,6 +12,8 @@ const ADMIN_PORT: &str = "9999";
 const ADMIN_HTTP_ENABLED: bool = false;
 const ADMIN_HTTP_HOST: &str = "127.0.0.1";
 const ADMIN_HTTP_PORT: &str = "9998";
+const ADMIN_HTTP_TOKEN_FILE: Option<String> = None;
+const ADMIN_STALL_THRESHOLD: usize = 5000;
 const ADMIN_TIMEOUT: usize = 100;
 const ADMIN_NEVENT: usize = 1024;
 const ADMIN_TW_TICK: usize = 10;
@@ -47,6 +49,14 @@ fn http_port() -> String {
     ADMIN_HTTP_PORT.to_string()
 }
 
+fn http_token_file() -> Option<String> {
+    ADMIN_HTTP_TOKEN_FILE
+}
+
+fn stall_threshold() -> usize {
+    ADMIN_STALL_THRESHOLD
+}
+
 fn timeout() -> usize {
     ADMIN_TIMEOUT
 }
@@ -84,6 +94,15 @@ pub struct Admin {
     http_host: String,
     #[serde(default = "http_port")]
     http_port: String,
+    /// Path to a file with the bearer token which is required by the HTTP
+    /// control endpoints. When not set, the control endpoints are disabled.
+    #[serde(default = "http_token_file")]
+    http_token_file: Option<String>,
+    /// The time in milliseconds after which a thread which has not completed
+    /// an iteration of its event loop is considered stalled, causing the
+    /// `/health/live` endpoint to fail.
+    #[serde(default = "stall_threshold")]
+    stall_threshold: usize,
     #[serde(default = "timeout")]
     timeout: usize,
     #[serde(default = "nevent")]
@@ -116,6 +135,14 @@ impl Admin {
         format!("{}:{}", self.http_host, self.http_port).parse()
     }
 
+    pub fn http_token_file(&self) -> Option<String> {
+        self.http_token_file.clone()
+    }
+
+    pub fn stall_threshold(&self) -> usize {
+        self.stall_threshold
+    }
+
     pub fn timeout(&self) -> usize {
         self.timeout
     }
@@ -156,6 +183,8 @@ impl Default for Admin {
             http_enabled: http_enabled(),
             http_host: http_host(),
             http_port: http_port(),
+            http_token_file: http_token_file(),
+            stall_threshold: stall_threshold(),
             timeout: timeout(),
             nevent: nevent(),
             tw_tick: tw_tick(),
@@ -166,6 +195,47 @@ impl Default for Admin {
     }
 }
 
-pub trait AdminConfig {
+pub trait AdminConfig: Serialize {
     fn admin(&self) -> &Admin;
+
+    /// The effective configuration as JSON, which is returned by the admin
+    /// HTTP endpoint.
+    fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    /// The effective configuration as a list of names and values, which is
+    /// returned by the `stats settings` admin command. Nested sections are
+    /// flattened, so the `port` of the `admin` section is named `admin.port`.
+    fn to_settings(&self) -> Vec<(String, String)> {
+        let mut settings = Vec::new();
+        if let Ok(value) = serde_json::to_value(self) {
+            flatten("", &value, &mut settings);
+        }
+        settings
+    }
+}
+
+// flattens a JSON value into a list of dotted names and values
+fn flatten(name: &str, value: &serde_json::Value, settings: &mut Vec<(String, String)>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                if name.is_empty() {
+                    flatten(key, value, settings);
+                } else {
+                    flatten(&format!("{}.{}", name, key), value, settings);
+                }
+            }
+        }
+        serde_json::Value::Null => {
+            settings.push((name.to_string(), "none".to_string()));
+        }
+        serde_json::Value::String(s) => {
+            settings.push((name.to_string(), s.to_string()));
+        }
+        value => {
+            settings.push((name.to_string(), value.to_string()));
+        }
+    }
 }
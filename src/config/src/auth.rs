@@ -0,0 +1,26 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use serde::{Deserialize, Serialize};
+
+// definitions
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Auth {
+    /// Path to a file with one `username:password` entry per line. When set,
+    /// clients must authenticate before any other commands are accepted.
+    #[serde(default)]
+    credentials: Option<String>,
+}
+
+// implementation
+impl Auth {
+    pub fn credentials(&self) -> Option<String> {
+        self.credentials.clone()
+    }
+}
+
+// trait definitions
+pub trait AuthConfig {
+    fn auth(&self) -> &Auth;
+}
@@ -0,0 +1,42 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+// by default load the file in the cwd named 'db.cdb'
+const PATH: &str = "db.cdb";
+
+// helper functions for default values
+fn path() -> String {
+    PATH.to_string()
+}
+
+// definitions
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Cdb {
+    #[serde(default = "path")]
+    path: String,
+}
+
+impl Default for Cdb {
+    fn default() -> Self {
+        Self { path: path() }
+    }
+}
+
+// implementation
+impl Cdb {
+    /// The location of the cdb file. The same path is reopened when the
+    /// storage is reloaded.
+    pub fn path(&self) -> PathBuf {
+        PathBuf::from(&self.path)
+    }
+}
+
+// trait definitions
+pub trait CdbConfig {
+    fn cdb(&self) -> &Cdb;
+}
@@ -0,0 +1,216 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use crate::*;
+
+use serde::{Deserialize, Serialize};
+
+use std::io::Read;
+
+// constants to define default values
+const DAEMONIZE: bool = false;
+const PID_FILENAME: Option<String> = None;
+const DLOG_INTERVAL: usize = 500;
+
+// helper functions
+fn daemonize() -> bool {
+    DAEMONIZE
+}
+
+fn pid_filename() -> Option<String> {
+    PID_FILENAME
+}
+
+fn dlog_interval() -> usize {
+    DLOG_INTERVAL
+}
+
+// struct definitions
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CdbserverConfig {
+    // top-level
+    #[serde(default = "daemonize")]
+    daemonize: bool,
+    #[serde(default = "pid_filename")]
+    pid_filename: Option<String>,
+    #[serde(default = "dlog_interval")]
+    dlog_interval: usize,
+
+    // application modules
+    #[serde(default)]
+    admin: Admin,
+    #[serde(default)]
+    server: Server,
+    #[serde(default)]
+    worker: Worker,
+    #[serde(default)]
+    time: Time,
+    #[serde(default)]
+    tls: Tls,
+    #[serde(default)]
+    cdb: Cdb,
+
+    // ccommon
+    #[serde(default)]
+    buf: Buf,
+    #[serde(default)]
+    debug: Debug,
+    #[serde(default)]
+    klog: Klog,
+    #[serde(default)]
+    sockio: Sockio,
+    #[serde(default)]
+    tcp: Tcp,
+}
+
+// implementation
+impl CdbserverConfig {
+    pub fn load(file: &str) -> Result<Self, std::io::Error> {
+        let mut file = std::fs::File::open(file)?;
+        let mut content = String::new();
+        file.read_to_string(&mut content)?;
+        match toml::from_str(&content) {
+            Ok(t) => Ok(t),
+            Err(e) => {
+                eprintln!("{}", e);
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    "Error parsing config",
+                ))
+            }
+        }
+    }
+
+    pub fn daemonize(&self) -> bool {
+        self.daemonize
+    }
+
+    pub fn pid_filename(&self) -> Option<String> {
+        self.pid_filename.clone()
+    }
+
+    pub fn dlog_interval(&self) -> usize {
+        self.dlog_interval
+    }
+
+    /// Prints the configuration
+    pub fn print(&self) {
+        let config_toml = self.render_config();
+        println!("Cdbserver configuration:\n\n{}", config_toml);
+    }
+
+    /// Renders the configuration as a printable string
+    fn render_config(&self) -> String {
+        toml::to_string_pretty(&self).expect("wasn't able to TOML-render config for printing")
+    }
+}
+
+impl AdminConfig for CdbserverConfig {
+    fn admin(&self) -> &Admin {
+        &self.admin
+    }
+}
+
+impl BufConfig for CdbserverConfig {
+    fn buf(&self) -> &Buf {
+        &self.buf
+    }
+}
+
+impl CdbConfig for CdbserverConfig {
+    fn cdb(&self) -> &Cdb {
+        &self.cdb
+    }
+}
+
+impl DebugConfig for CdbserverConfig {
+    fn debug(&self) -> &Debug {
+        &self.debug
+    }
+}
+
+impl KlogConfig for CdbserverConfig {
+    fn klog(&self) -> &Klog {
+        &self.klog
+    }
+}
+
+impl ServerConfig for CdbserverConfig {
+    fn server(&self) -> &Server {
+        &self.server
+    }
+}
+
+impl SockioConfig for CdbserverConfig {
+    fn sockio(&self) -> &Sockio {
+        &self.sockio
+    }
+}
+
+impl TcpConfig for CdbserverConfig {
+    fn tcp(&self) -> &Tcp {
+        &self.tcp
+    }
+}
+
+impl TimeConfig for CdbserverConfig {
+    fn time(&self) -> &Time {
+        &self.time
+    }
+}
+
+impl TlsConfig for CdbserverConfig {
+    fn tls(&self) -> &Tls {
+        &self.tls
+    }
+}
+
+impl WorkerConfig for CdbserverConfig {
+    fn worker(&self) -> &Worker {
+        &self.worker
+    }
+
+    fn worker_mut(&mut self) -> &mut Worker {
+        &mut self.worker
+    }
+}
+
+// trait implementations
+impl Default for CdbserverConfig {
+    fn default() -> Self {
+        Self {
+            daemonize: daemonize(),
+            pid_filename: pid_filename(),
+            dlog_interval: dlog_interval(),
+
+            admin: Default::default(),
+            server: Default::default(),
+            worker: Default::default(),
+            time: Default::default(),
+            cdb: Default::default(),
+
+            buf: Default::default(),
+            debug: Default::default(),
+            klog: Default::default(),
+            sockio: Default::default(),
+            tcp: Default::default(),
+            tls: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::CdbserverConfig;
+
+    #[test]
+    fn it_should_render_the_config_with_some_expected_keys() {
+        let config: CdbserverConfig = Default::default();
+        let rendered_config = config.render_config();
+        let expected_keys = vec!["cdb", "path"];
+        for key in expected_keys {
+            assert!(rendered_config.contains(key));
+        }
+    }
+}
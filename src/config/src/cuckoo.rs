@@ -0,0 +1,114 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+// defaults for item slots
+const ITEM_SIZE: usize = 64;
+const NITEM: usize = 1024;
+
+// default displacement behavior
+const MAX_DISPLACE: usize = 2;
+const POLICY: Policy = Policy::Random;
+
+// default max ttl - 30 days
+const MAX_TTL: u32 = 30 * 24 * 60 * 60;
+
+// datapool
+const DATAPOOL_PATH: Option<&str> = None;
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Policy {
+    Random,
+    Expire,
+}
+
+// helper functions for default values
+fn item_size() -> usize {
+    ITEM_SIZE
+}
+
+fn nitem() -> usize {
+    NITEM
+}
+
+fn max_displace() -> usize {
+    MAX_DISPLACE
+}
+
+fn policy() -> Policy {
+    POLICY
+}
+
+fn max_ttl() -> u32 {
+    MAX_TTL
+}
+
+fn datapool_path() -> Option<String> {
+    DATAPOOL_PATH.map(|v| v.to_string())
+}
+
+// definitions
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Cuckoo {
+    #[serde(default = "item_size")]
+    item_size: usize,
+    #[serde(default = "nitem")]
+    nitem: usize,
+    #[serde(default = "max_displace")]
+    max_displace: usize,
+    #[serde(default = "policy")]
+    policy: Policy,
+    #[serde(default = "max_ttl")]
+    max_ttl: u32,
+    #[serde(default = "datapool_path")]
+    datapool_path: Option<String>,
+}
+
+impl Default for Cuckoo {
+    fn default() -> Self {
+        Self {
+            item_size: item_size(),
+            nitem: nitem(),
+            max_displace: max_displace(),
+            policy: policy(),
+            max_ttl: max_ttl(),
+            datapool_path: datapool_path(),
+        }
+    }
+}
+
+// implementation
+impl Cuckoo {
+    pub fn item_size(&self) -> usize {
+        self.item_size
+    }
+
+    pub fn nitem(&self) -> usize {
+        self.nitem
+    }
+
+    pub fn max_displace(&self) -> usize {
+        self.max_displace
+    }
+
+    pub fn policy(&self) -> Policy {
+        self.policy
+    }
+
+    pub fn max_ttl(&self) -> u32 {
+        self.max_ttl
+    }
+
+    pub fn datapool_path(&self) -> Option<PathBuf> {
+        self.datapool_path.as_ref().map(|v| Path::new(v).to_owned())
+    }
+}
+
+// trait definitions
+pub trait CuckooConfig {
+    fn cuckoo(&self) -> &Cuckoo;
+}
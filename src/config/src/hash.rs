@@ -0,0 +1,67 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use serde::{Deserialize, Serialize};
+
+// limits for hashes stored by the rds commands
+const MAX_FIELDS: usize = 1024;
+const MAX_FIELD_SIZE: usize = 1024;
+const MAX_VALUE_SIZE: usize = 64 * 1024;
+
+// helper functions for default values
+fn max_fields() -> usize {
+    MAX_FIELDS
+}
+
+fn max_field_size() -> usize {
+    MAX_FIELD_SIZE
+}
+
+fn max_value_size() -> usize {
+    MAX_VALUE_SIZE
+}
+
+// definitions
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Hash {
+    #[serde(default = "max_fields")]
+    max_fields: usize,
+    #[serde(default = "max_field_size")]
+    max_field_size: usize,
+    #[serde(default = "max_value_size")]
+    max_value_size: usize,
+}
+
+impl Default for Hash {
+    fn default() -> Self {
+        Self {
+            max_fields: max_fields(),
+            max_field_size: max_field_size(),
+            max_value_size: max_value_size(),
+        }
+    }
+}
+
+// implementation
+impl Hash {
+    /// The maximum number of fields in a hash.
+    pub fn max_fields(&self) -> usize {
+        self.max_fields
+    }
+
+    /// The maximum size of a hash field, in bytes.
+    pub fn max_field_size(&self) -> usize {
+        self.max_field_size
+    }
+
+    /// The maximum size of a hash value, in bytes.
+    pub fn max_value_size(&self) -> usize {
+        self.max_value_size
+    }
+}
+
+// trait definitions
+pub trait HashConfig {
+    fn hash(&self) -> &Hash;
+}
@@ -7,20 +7,30 @@ extern crate log;
 
 mod admin;
 mod array;
+mod auth;
 mod buf;
+mod cdb;
+mod cdbserver;
+pub mod cuckoo;
 mod dbuf;
 mod debug;
+mod hash;
+mod httpcache;
 mod klog;
 pub mod momento_proxy;
 mod pingproxy;
 mod pingserver;
 pub mod proxy;
+mod rds;
 pub mod seg;
 mod segcache;
 mod server;
+mod slimcache;
 mod sockio;
 mod stats_log;
 mod tcp;
+mod thrift;
+mod thriftproxy;
 pub mod time;
 mod tls;
 mod units;
@@ -28,19 +38,29 @@ mod worker;
 
 pub use admin::{Admin, AdminConfig};
 pub use array::ArrayConfig;
+pub use auth::{Auth, AuthConfig};
 pub use buf::{Buf, BufConfig};
+pub use cdb::{Cdb, CdbConfig};
+pub use cdbserver::CdbserverConfig;
+pub use cuckoo::{Cuckoo, CuckooConfig};
 pub use dbuf::DbufConfig;
 pub use debug::{Debug, DebugConfig};
+pub use hash::{Hash, HashConfig};
+pub use httpcache::HttpcacheConfig;
 pub use klog::{Klog, KlogConfig};
 pub use momento_proxy::MomentoProxyConfig;
 pub use pingproxy::PingproxyConfig;
 pub use pingserver::PingserverConfig;
+pub use rds::RdsConfig;
 pub use seg::{Seg, SegConfig};
 pub use segcache::SegcacheConfig;
 pub use server::{Server, ServerConfig};
+pub use slimcache::SlimcacheConfig;
 pub use sockio::{Sockio, SockioConfig};
 pub use stats_log::StatsLogConfig;
 pub use tcp::{Tcp, TcpConfig};
+pub use thrift::{Route, Thrift, ThriftConfig};
+pub use thriftproxy::ThriftproxyConfig;
 pub use time::{Time, TimeConfig, TimeType};
 pub use tls::{Tls, TlsConfig};
 pub use worker::{Worker, WorkerConfig};
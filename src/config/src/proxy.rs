@@ -77,6 +77,16 @@ pub struct Backend {
     zk_server: Option<String>,
     zk_path: Option<String>,
     zk_endpoint: Option<String>,
+    #[serde(default)]
+    pools: Vec<Pool>,
+}
+
+/// A named set of backend endpoints, in addition to the default endpoints.
+/// Requests are only sent to a pool if the proxy routes them there.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Pool {
+    name: String,
+    endpoints: Vec<String>,
 }
 
 // implementation
@@ -142,25 +152,7 @@ impl Backend {
     // used to handle service discovery.
     pub fn socket_addrs(&self) -> Result<Vec<SocketAddr>, std::io::Error> {
         if !self.endpoints.is_empty() {
-            let mut endpoints = Vec::new();
-            for endpoint in &self.endpoints {
-                match endpoint.to_socket_addrs() {
-                    Ok(mut addrs) => {
-                        if let Some(addr) = addrs.next() {
-                            endpoints.push(addr)
-                        } else {
-                            return Err(std::io::Error::new(
-                                std::io::ErrorKind::Other,
-                                "failed to resolve endpoint address",
-                            ));
-                        }
-                    }
-                    Err(e) => {
-                        return Err(e);
-                    }
-                }
-            }
-            Ok(endpoints)
+            resolve(&self.endpoints)
         } else if let (Some(server), Some(path), endpoint) = (
             self.zk_server.as_ref(),
             self.zk_path.as_ref(),
@@ -234,6 +226,44 @@ impl Backend {
             // Vec::new()
         }
     }
+
+    /// Additional pools of endpoints, which are numbered from `1` in the order
+    /// they are configured. The default endpoints are pool `0`.
+    pub fn pools(&self) -> &[Pool] {
+        &self.pools
+    }
+}
+
+impl Pool {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn socket_addrs(&self) -> Result<Vec<SocketAddr>, std::io::Error> {
+        if self.endpoints.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "no endpoints provided",
+            ));
+        }
+        resolve(&self.endpoints)
+    }
+}
+
+fn resolve(endpoints: &[String]) -> Result<Vec<SocketAddr>, std::io::Error> {
+    let mut addrs = Vec::new();
+    for endpoint in endpoints {
+        match endpoint.to_socket_addrs()?.next() {
+            Some(addr) => addrs.push(addr),
+            None => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    "failed to resolve endpoint address",
+                ));
+            }
+        }
+    }
+    Ok(addrs)
 }
 
 struct ExitWatcher;
@@ -275,6 +305,7 @@ impl Default for Backend {
             zk_path: None,
             zk_endpoint: None,
             poolsize: backend_poolsize(),
+            pools: Vec::new(),
         }
     }
 }
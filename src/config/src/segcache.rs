@@ -49,6 +49,8 @@ pub struct SegcacheConfig {
     #[serde(default)]
     tls: Tls,
     #[serde(default)]
+    auth: Auth,
+    #[serde(default)]
     seg: Seg,
 
     // ccommon
@@ -112,6 +114,12 @@ impl AdminConfig for SegcacheConfig {
     }
 }
 
+impl AuthConfig for SegcacheConfig {
+    fn auth(&self) -> &Auth {
+        &self.auth
+    }
+}
+
 impl BufConfig for SegcacheConfig {
     fn buf(&self) -> &Buf {
         &self.buf
@@ -188,6 +196,7 @@ impl Default for SegcacheConfig {
             server: Default::default(),
             worker: Default::default(),
             time: Default::default(),
+            auth: Default::default(),
             seg: Default::default(),
 
             buf: Default::default(),
@@ -202,7 +211,7 @@ impl Default for SegcacheConfig {
 
 #[cfg(test)]
 mod test {
-    use crate::SegcacheConfig;
+    use crate::{AdminConfig, SegcacheConfig};
 
     #[test]
     fn it_should_render_the_config_with_some_expected_keys() {
@@ -222,4 +231,23 @@ mod test {
             assert!(rendered_config.contains(key));
         }
     }
+
+    #[test]
+    fn it_should_flatten_the_config_into_settings() {
+        let config: SegcacheConfig = Default::default();
+        let settings = config.to_settings();
+        let setting = |name: &str| {
+            settings
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, v)| v.as_str())
+        };
+
+        assert_eq!(setting("daemonize"), Some("false"));
+        assert_eq!(setting("pid_filename"), Some("none"));
+        assert_eq!(setting("admin.port"), Some("9999"));
+        assert_eq!(setting("seg.eviction"), Some("Merge"));
+        assert!(setting("seg.hash_power").is_some());
+        assert!(setting("admin").is_none());
+    }
 }
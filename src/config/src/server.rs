@@ -40,6 +40,8 @@ pub struct Server {
     timeout: usize,
     #[serde(default = "nevent")]
     nevent: usize,
+    #[serde(default)]
+    udp_port: Option<String>,
 }
 
 // implementation
@@ -68,6 +70,18 @@ impl Server {
     pub fn nevent(&self) -> usize {
         self.nevent
     }
+
+    /// Port to listen on for UDP, if enabled
+    pub fn udp_port(&self) -> Option<String> {
+        self.udp_port.clone()
+    }
+
+    /// Return the result of parsing the host and UDP port, if UDP is enabled
+    pub fn udp_socket_addr(&self) -> Option<Result<SocketAddr, AddrParseError>> {
+        self.udp_port
+            .as_ref()
+            .map(|port| format!("{}:{}", self.host(), port).parse())
+    }
 }
 
 // trait implementations
@@ -78,6 +92,7 @@ impl Default for Server {
             port: port(),
             timeout: timeout(),
             nevent: nevent(),
+            udp_port: None,
         }
     }
 }
@@ -0,0 +1,52 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use serde::{Deserialize, Serialize};
+
+// definitions
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Thrift {
+    #[serde(default)]
+    routes: Vec<Route>,
+    #[serde(default)]
+    methods: Vec<String>,
+}
+
+/// Sends all requests for a method to the named backend pool.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Route {
+    method: String,
+    pool: String,
+}
+
+// implementation
+impl Thrift {
+    /// Per-method routes. Methods without a route are sent to the default
+    /// backend endpoints.
+    pub fn routes(&self) -> &[Route] {
+        &self.routes
+    }
+
+    /// The methods which have their own request, exception and latency
+    /// metrics. Requests for other methods are only counted in aggregate.
+    pub fn methods(&self) -> &[String] {
+        &self.methods
+    }
+}
+
+impl Route {
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    /// The name of the backend pool for the method.
+    pub fn pool(&self) -> &str {
+        &self.pool
+    }
+}
+
+// trait definitions
+pub trait ThriftConfig {
+    fn thrift(&self) -> &Thrift;
+}
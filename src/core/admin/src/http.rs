@@ -0,0 +1,640 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! The HTTP admin endpoints. Metrics are exported in a few formats, and the
+//! control endpoints allow an operator to change the runtime behavior.
+//!
+//! The control endpoints require the bearer token from the admin config, and
+//! are disabled when no token is configured:
+//! - `POST /flush` - flush all the items from storage
+//! - `POST /shutdown` - gracefully shut down the process
+//! - `GET /log/level` and `POST /log/level` - get or set the debug log level,
+//!   eg: `warn`
+//! - `GET /klog/sample` and `POST /klog/sample` - get or set the command log
+//!   sampling rate, with `0` disabling the command log
+//! - `GET /config` - the effective configuration as JSON
+//! - `GET /debug/key?key=<key>` - describes how the item with the key is
+//!   stored, without counting as an access to the item. The key is everything
+//!   after `key=`, and is URL decoded. Storage types which cannot describe
+//!   their items reply with `501 Not Implemented`
+//!
+//! The health endpoints are intended for the probes of an orchestrator, and do
+//! not require the token:
+//! - `GET /health/live` - fails if any thread has not run its event loop
+//!   within the stall threshold
+//! - `GET /health/ready` - fails if the process is not live, the listener is
+//!   not yet accepting sessions, or the process is draining. The process
+//!   drains from when a shutdown is requested until all of the other threads
+//!   have exited. On a warm restart, the storage is restored before the
+//!   listener is started, so the process is not ready until it is restored
+
+use common::health::Health;
+use common::signal::{Signal, StorageStats};
+use logger::{info, klog_sample, max_level, set_klog_sample, set_log_level, Level};
+use protocol_admin::PERCENTILES;
+use protocol_http::{trim, Request, RequestData, Response};
+use rustcommon_metrics::*;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub(crate) struct Endpoints {
+    /// The bearer token which is required by the control endpoints
+    token: Option<Vec<u8>>,
+    /// The effective configuration as JSON
+    config: String,
+    /// The heartbeats and readiness of the process
+    health: Arc<Health>,
+    /// The time without a heartbeat after which a thread is stalled
+    stall_threshold: Duration,
+}
+
+impl Endpoints {
+    pub(crate) fn new(
+        token: Option<Vec<u8>>,
+        config: String,
+        health: Arc<Health>,
+        stall_threshold: Duration,
+    ) -> Self {
+        Self {
+            token,
+            config,
+            health,
+            stall_threshold,
+        }
+    }
+
+    /// Produce the response for a HTTP request, along with a signal which
+    /// should be sent to the other threads. The query string is ignored when
+    /// routing the request.
+    pub(crate) fn response(&self, request: &Request) -> (Response, Option<Signal>) {
+        let (method, path) = match request.data() {
+            RequestData::Get(path) => ("GET", path),
+            RequestData::Put(path, _) => ("PUT", path),
+            RequestData::Delete(path) => ("DELETE", path),
+            RequestData::Post(path, _) => ("POST", path),
+            // the body is parsed as part of the request once it has arrived
+            RequestData::Continue(_) => {
+                return (Response::builder(100).empty(), None);
+            }
+        };
+
+        let path = path.split(|b| *b == b'?').next().unwrap_or(path);
+
+        let (response, signal) = match (method, path) {
+            // Prometheus/OpenTelemetry expect the `/metrics` URI will return
+            // stats in the Prometheus format
+            ("GET", b"/metrics") => (ok("text/plain", &prometheus_stats()), None),
+            // we export Finagle/TwitterServer format stats on a few endpoints
+            // for maximum compatibility with various internal conventions
+            ("GET", b"/metrics.json" | b"/vars.json" | b"/admin/metrics.json") => {
+                (ok("application/json", &json_stats()), None)
+            }
+            // human-readable stats are exported on the `/vars` endpoint based
+            // on internal conventions
+            ("GET", b"/vars") => (ok("text/plain", &human_stats()), None),
+            ("GET", b"/health/live") => (self.live(), None),
+            ("GET", b"/health/ready") => (self.ready(), None),
+            (
+                _,
+                b"/metrics"
+                | b"/metrics.json"
+                | b"/vars.json"
+                | b"/admin/metrics.json"
+                | b"/vars"
+                | b"/health/live"
+                | b"/health/ready",
+            ) => (method_not_allowed("GET"), None),
+            (_, b"/flush" | b"/shutdown" | b"/log/level" | b"/klog/sample" | b"/config") => {
+                self.control(method, path, request)
+            }
+            _ => (Response::builder(404).body(b""), None),
+        };
+
+        (response.reply_to(request), signal)
+    }
+
+    // the process is live unless a thread has stalled, in which case the
+    // stalled threads are listed in the response
+    fn live(&self) -> Response {
+        let stalled = self.health.stalled(self.stall_threshold);
+        if stalled.is_empty() {
+            return text(200, "ok\n");
+        }
+
+        let mut body = String::new();
+        for (name, elapsed) in stalled {
+            body += &format!("stalled: {} for {}ms\n", name, elapsed.as_millis());
+        }
+        text(503, &body)
+    }
+
+    // the process is ready once it is live and accepting sessions, and until
+    // it begins to drain
+    fn ready(&self) -> Response {
+        if self.health.is_draining() {
+            return text(503, "draining\n");
+        }
+        if !self.health.is_listening() {
+            return text(503, "not listening\n");
+        }
+        self.live()
+    }
+
+    /// Returns the key for a request to `/debug/key`, or the response if the
+    /// request is not allowed. Requests for other paths return `None`, and
+    /// are handled by `response()`. The item is described by the thread which
+    /// owns the storage, so the response is produced by `debug_key_response()`
+    /// once it replies.
+    pub(crate) fn debug_key(&self, request: &Request) -> Option<Result<Vec<u8>, Response>> {
+        let (method, path) = match request.data() {
+            RequestData::Get(path) => ("GET", path),
+            RequestData::Put(path, _) => ("PUT", path),
+            RequestData::Delete(path) => ("DELETE", path),
+            RequestData::Post(path, _) => ("POST", path),
+            RequestData::Continue(_) => {
+                return None;
+            }
+        };
+
+        let (path, query) = split_once(path, b'?').unwrap_or((path, b""));
+        if path != b"/debug/key" {
+            return None;
+        }
+
+        if let Err(response) = self.authorize(request) {
+            return Some(Err(response));
+        }
+
+        if method != "GET" {
+            return Some(Err(method_not_allowed("GET")));
+        }
+
+        // the path has already been decoded, so the key may contain any of
+        // the characters which would otherwise separate query parameters
+        match query.strip_prefix(b"key=") {
+            Some(key) if !key.is_empty() => Some(Ok(key.to_vec())),
+            _ => Some(Err(text(400, "expected a key, eg: /debug/key?key=coffee"))),
+        }
+    }
+
+    // checks the bearer token for the control endpoints, returning the
+    // response for a request which is not allowed
+    fn authorize(&self, request: &Request) -> Result<(), Response> {
+        let token = match &self.token {
+            Some(token) => token,
+            None => {
+                return Err(text(
+                    403,
+                    "control endpoints are disabled, no token is configured",
+                ));
+            }
+        };
+
+        let authorized = request
+            .header("Authorization")
+            .and_then(|value| {
+                let (scheme, credentials) = split_once(value, b' ')?;
+                if scheme.eq_ignore_ascii_case(b"Bearer") {
+                    Some(constant_time_eq(token, trim(credentials)))
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(false);
+
+        if !authorized {
+            let mut response = Response::builder(401);
+            response.header("WWW-Authenticate", b"Bearer");
+            return Err(response.body(b""));
+        }
+
+        Ok(())
+    }
+
+    // handles a request to one of the control endpoints
+    fn control(&self, method: &str, path: &[u8], request: &Request) -> (Response, Option<Signal>) {
+        if let Err(response) = self.authorize(request) {
+            return (response, None);
+        }
+
+        let body = match request.data() {
+            RequestData::Post(_, body) => std::str::from_utf8(body).unwrap_or("").trim(),
+            _ => "",
+        };
+
+        match (method, path) {
+            ("POST", b"/flush") => {
+                info!("flush requested by admin endpoint");
+                (Response::builder(202).body(b""), Some(Signal::FlushAll))
+            }
+            ("POST", b"/shutdown") => {
+                info!("shutdown requested by admin endpoint");
+                (Response::builder(202).body(b""), Some(Signal::Shutdown))
+            }
+            ("GET", b"/log/level") => (
+                ok("text/plain", &format!("{}\n", max_level()).to_lowercase()),
+                None,
+            ),
+            ("POST", b"/log/level") => match body.parse::<Level>() {
+                Ok(level) => {
+                    info!("changing log level to: {}", level);
+                    set_log_level(level);
+                    (Response::builder(204).empty(), None)
+                }
+                Err(_) => (
+                    text(400, "expected one of: error, warn, info, debug, trace"),
+                    None,
+                ),
+            },
+            ("GET", b"/klog/sample") => (ok("text/plain", &format!("{}\n", klog_sample())), None),
+            ("POST", b"/klog/sample") => match body.parse::<usize>() {
+                Ok(sample) => {
+                    info!("changing klog sample rate to: {}", sample);
+                    set_klog_sample(sample);
+                    (Response::builder(204).empty(), None)
+                }
+                Err(_) => (text(400, "expected a sampling rate"), None),
+            },
+            ("GET", b"/config") => (ok("application/json", &self.config), None),
+            (_, b"/config") => (method_not_allowed("GET"), None),
+            (_, b"/log/level" | b"/klog/sample") => (method_not_allowed("GET, POST"), None),
+            _ => (method_not_allowed("POST"), None),
+        }
+    }
+}
+
+/// The response to a request to `/debug/key`, from the reply of the thread
+/// which owns the storage. Without a reply, the storage is unavailable.
+pub(crate) fn debug_key_response(reply: Option<StorageStats>) -> Response {
+    match reply.map(|reply| reply.stats) {
+        None => text(503, "storage unavailable\n"),
+        Some(None) => text(501, "not supported by this storage\n"),
+        Some(Some(stats)) if stats.is_empty() => text(404, "key not found\n"),
+        Some(Some(stats)) => {
+            let body: String = stats
+                .iter()
+                .map(|(name, value)| format!("{}: {}\n", name, value))
+                .collect();
+            ok("text/plain", &body)
+        }
+    }
+}
+
+fn ok(content_type: &str, body: &str) -> Response {
+    Response::builder(200)
+        .header("Content-Type", content_type.as_bytes())
+        .body(body.as_bytes())
+}
+
+fn text(status: u16, message: &str) -> Response {
+    Response::builder(status)
+        .header("Content-Type", b"text/plain")
+        .body(message.as_bytes())
+}
+
+fn method_not_allowed(allow: &str) -> Response {
+    Response::builder(405)
+        .header("Allow", allow.as_bytes())
+        .body(b"")
+}
+
+fn split_once(value: &[u8], delimiter: u8) -> Option<(&[u8], &[u8])> {
+    let index = value.iter().position(|b| *b == delimiter)?;
+    Some((&value[..index], &value[(index + 1)..]))
+}
+
+// compares the tokens without returning early on the first mismatch, so the
+// time taken does not reveal the length of a matching prefix
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// A "human-readable" exposition format which outputs one stat per line,
+/// with a LF used as the end of line symbol.
+///
+/// ```text
+/// get: 0
+/// get_cardinality_p25: 0
+/// get_cardinality_p50: 0
+/// get_cardinality_p75: 0
+/// get_cardinality_p90: 0
+/// get_cardinality_p9999: 0
+/// get_cardinality_p999: 0
+/// get_cardinality_p99: 0
+/// get_ex: 0
+/// get_key: 0
+/// get_key_hit: 0
+/// get_key_miss: 0
+/// ```
+fn human_stats() -> String {
+    let mut data = Vec::new();
+
+    for metric in &rustcommon_metrics::metrics() {
+        let any = match metric.as_any() {
+            Some(any) => any,
+            None => {
+                continue;
+            }
+        };
+
+        if let Some(counter) = any.downcast_ref::<Counter>() {
+            data.push(format!("{}: {}", metric.name(), counter.value()));
+        } else if let Some(gauge) = any.downcast_ref::<Gauge>() {
+            data.push(format!("{}: {}", metric.name(), gauge.value()));
+        } else if let Some(heatmap) = any.downcast_ref::<Heatmap>() {
+            for (label, value) in PERCENTILES {
+                let percentile = heatmap.percentile(*value).map(|b| b.high()).unwrap_or(0);
+                data.push(format!("{}_{}: {}", metric.name(), label, percentile));
+            }
+        }
+    }
+
+    data.sort();
+    data.join("\n") + "\n"
+}
+
+/// JSON stats output which follows the conventions found in Finagle and
+/// TwitterServer libraries. Percentiles are appended to the metric name,
+/// eg: `request_latency_p999` for the 99.9th percentile. For more details
+/// about the Finagle / TwitterServer format see:
+/// https://twitter.github.io/twitter-server/Features.html#metrics
+///
+/// ```text
+/// {"get": 0,"get_cardinality_p25": 0,"get_cardinality_p50": 0, ... }
+/// ```
+fn json_stats() -> String {
+    let head = "{".to_owned();
+
+    let mut data = Vec::new();
+
+    for metric in &rustcommon_metrics::metrics() {
+        let any = match metric.as_any() {
+            Some(any) => any,
+            None => {
+                continue;
+            }
+        };
+
+        if let Some(counter) = any.downcast_ref::<Counter>() {
+            data.push(format!("\"{}\": {}", metric.name(), counter.value()));
+        } else if let Some(gauge) = any.downcast_ref::<Gauge>() {
+            data.push(format!("\"{}\": {}", metric.name(), gauge.value()));
+        } else if let Some(heatmap) = any.downcast_ref::<Heatmap>() {
+            for (label, value) in PERCENTILES {
+                let percentile = heatmap.percentile(*value).map(|b| b.high()).unwrap_or(0);
+                data.push(format!("\"{}_{}\": {}", metric.name(), label, percentile));
+            }
+        }
+    }
+
+    data.sort();
+    let body = data.join(",");
+    let mut content = head;
+    content += &body;
+    content += "}";
+    content
+}
+
+/// Prometheus / OpenTelemetry compatible stats output. Each stat is
+/// annotated with a type. Percentiles use the label 'percentile' to
+/// indicate which percentile corresponds to the value:
+///
+/// ```text
+/// # TYPE get counter
+/// get 0
+/// # TYPE get_cardinality gauge
+/// get_cardinality{percentile="p25"} 0
+/// # TYPE get_cardinality gauge
+/// get_cardinality{percentile="p50"} 0
+/// # TYPE get_cardinality gauge
+/// get_cardinality{percentile="p75"} 0
+/// # TYPE get_cardinality gauge
+/// get_cardinality{percentile="p90"} 0
+/// # TYPE get_cardinality gauge
+/// get_cardinality{percentile="p99"} 0
+/// # TYPE get_cardinality gauge
+/// get_cardinality{percentile="p999"} 0
+/// # TYPE get_cardinality gauge
+/// get_cardinality{percentile="p9999"} 0
+/// # TYPE get_ex counter
+/// get_ex 0
+/// # TYPE get_key counter
+/// get_key 0
+/// # TYPE get_key_hit counter
+/// get_key_hit 0
+/// # TYPE get_key_miss counter
+/// get_key_miss 0
+/// ```
+fn prometheus_stats() -> String {
+    let mut data = Vec::new();
+
+    for metric in &rustcommon_metrics::metrics() {
+        let any = match metric.as_any() {
+            Some(any) => any,
+            None => {
+                continue;
+            }
+        };
+
+        if let Some(counter) = any.downcast_ref::<Counter>() {
+            data.push(format!(
+                "# TYPE {} counter\n{} {}",
+                metric.name(),
+                metric.name(),
+                counter.value()
+            ));
+        } else if let Some(gauge) = any.downcast_ref::<Gauge>() {
+            data.push(format!(
+                "# TYPE {} gauge\n{} {}",
+                metric.name(),
+                metric.name(),
+                gauge.value()
+            ));
+        } else if let Some(heatmap) = any.downcast_ref::<Heatmap>() {
+            for (label, value) in PERCENTILES {
+                let percentile = heatmap.percentile(*value).map(|b| b.high()).unwrap_or(0);
+                data.push(format!(
+                    "# TYPE {} gauge\n{}{{percentile=\"{}\"}} {}",
+                    metric.name(),
+                    metric.name(),
+                    label,
+                    percentile
+                ));
+            }
+        }
+    }
+    data.sort();
+    let mut content = data.join("\n");
+    content += "\n";
+    let parts: Vec<&str> = content.split('/').collect();
+    parts.join("_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol_common::Compose;
+    use protocol_http::RequestParser;
+
+    // the connection headers of a response which keeps the connection open
+    const KEEP_ALIVE: &str = "Connection: keep-alive\r\nKeep-Alive: timeout=60\r\n";
+
+    fn endpoints(token: Option<&[u8]>) -> Endpoints {
+        Endpoints::new(
+            token.map(|token| token.to_vec()),
+            "{}".to_string(),
+            Arc::new(Health::new()),
+            Duration::from_secs(5),
+        )
+    }
+
+    // returns the composed response to the request, along with the signal
+    fn respond(endpoints: &Endpoints, request: &str) -> (String, Option<Signal>) {
+        let mut buffer = request.as_bytes();
+        let request = RequestParser::new()
+            .do_parse(&mut buffer)
+            .expect("failed to parse request");
+        let (response, signal) = endpoints.response(&request);
+        let mut composed = Vec::new();
+        response.compose(&mut composed);
+        (String::from_utf8(composed).unwrap(), signal)
+    }
+
+    #[test]
+    fn routing() {
+        let endpoints = endpoints(None);
+
+        let (response, _) = respond(&endpoints, "GET /metrics HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\n"));
+
+        // the query string is ignored
+        let (response, _) = respond(&endpoints, "GET /vars.json?pretty=1 HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n"));
+
+        let (response, _) = respond(&endpoints, "GET /health/live HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("\r\n\r\nok\n"));
+
+        let (response, _) = respond(&endpoints, "GET /health/ready HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+        assert!(response.ends_with("\r\n\r\nnot listening\n"));
+
+        let (response, signal) = respond(&endpoints, "GET /coffee HTTP/1.1\r\n\r\n");
+        assert_eq!(
+            response,
+            format!(
+                "HTTP/1.1 404 Not Found\r\n{}Content-Length: 0\r\n\r\n",
+                KEEP_ALIVE
+            )
+        );
+        assert!(signal.is_none());
+    }
+
+    #[test]
+    fn methods() {
+        let endpoints = endpoints(Some(b"coffee"));
+
+        let (response, _) = respond(&endpoints, "DELETE /metrics HTTP/1.1\r\n\r\n");
+        assert_eq!(
+            response,
+            format!(
+                "HTTP/1.1 405 Method Not Allowed\r\nAllow: GET\r\n{}Content-Length: 0\r\n\r\n",
+                KEEP_ALIVE
+            )
+        );
+
+        let authorization = "Authorization: Bearer coffee\r\n";
+        for (request, allow) in [
+            ("GET /flush", "POST"),
+            ("GET /shutdown", "POST"),
+            ("DELETE /log/level", "GET, POST"),
+            ("DELETE /klog/sample", "GET, POST"),
+            ("DELETE /config", "GET"),
+        ] {
+            let request = format!("{} HTTP/1.1\r\n{}\r\n", request, authorization);
+            let (response, signal) = respond(&endpoints, &request);
+            assert!(response.starts_with(&format!(
+                "HTTP/1.1 405 Method Not Allowed\r\nAllow: {}\r\n",
+                allow
+            )));
+            assert!(signal.is_none());
+        }
+    }
+
+    #[test]
+    fn debug_key() {
+        let reply = |stats| Some(StorageStats { id: 0, stats });
+        let status = |reply| {
+            let mut composed = Vec::new();
+            debug_key_response(reply).compose(&mut composed);
+            String::from_utf8(composed).unwrap()
+        };
+
+        assert!(status(None).starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+        assert!(status(reply(None)).starts_with("HTTP/1.1 501 Not Implemented\r\n"));
+        assert!(status(reply(Some(Vec::new()))).starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+        let stats = vec![("segment".to_string(), "3".to_string())];
+        let response = status(reply(Some(stats)));
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("\r\n\r\nsegment: 3\n"));
+    }
+
+    #[test]
+    fn authorization() {
+        // the control endpoints are disabled without a token
+        let (response, signal) = respond(&endpoints(None), "POST /flush HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 403 Forbidden\r\n"));
+        assert!(signal.is_none());
+
+        let endpoints = endpoints(Some(b"coffee"));
+
+        let unauthorized = format!(
+            "HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: Bearer\r\n{}Content-Length: 0\r\n\r\n",
+            KEEP_ALIVE
+        );
+        for authorization in [
+            "",
+            "Authorization: Bearer tea\r\n",
+            "Authorization: Bearer coffeecoffee\r\n",
+            "Authorization: Basic coffee\r\n",
+        ] {
+            let request = format!("POST /shutdown HTTP/1.1\r\n{}\r\n", authorization);
+            let (response, signal) = respond(&endpoints, &request);
+            assert_eq!(response, unauthorized);
+            assert!(signal.is_none());
+        }
+
+        // the scheme is not case sensitive
+        let (response, signal) = respond(
+            &endpoints,
+            "POST /flush HTTP/1.1\r\nAuthorization: bearer coffee\r\n\r\n",
+        );
+        assert_eq!(
+            response,
+            format!(
+                "HTTP/1.1 202 Accepted\r\n{}Content-Length: 0\r\n\r\n",
+                KEEP_ALIVE
+            )
+        );
+        assert!(matches!(signal, Some(Signal::FlushAll)));
+
+        let (response, signal) = respond(
+            &endpoints,
+            "POST /shutdown HTTP/1.1\r\nAuthorization: Bearer coffee\r\n\r\n",
+        );
+        assert!(response.starts_with("HTTP/1.1 202 Accepted\r\n"));
+        assert!(matches!(signal, Some(Signal::Shutdown)));
+
+        let (response, _) = respond(
+            &endpoints,
+            "GET /config HTTP/1.1\r\nAuthorization: Bearer coffee\r\n\r\n",
+        );
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n"));
+        assert!(response.ends_with("\r\n\r\n{}"));
+    }
+}
@@ -4,12 +4,14 @@
 
 use ::net::event::{Event, Source};
 use ::net::*;
-use common::signal::Signal;
+use common::health::Health;
+use common::signal::{Signal, StatsSection, StorageStats};
 use common::ssl::tls_acceptor;
 use config::{AdminConfig, TlsConfig};
 use crossbeam_channel::Receiver;
 use logger::*;
 use protocol_admin::*;
+use protocol_http::{ParseData, Request, RequestParser};
 use queues::Queues;
 use rustcommon_metrics::*;
 use session::{Buf, ServerSession, Session};
@@ -17,10 +19,11 @@ use slab::Slab;
 use std::collections::VecDeque;
 use std::io::{Error, ErrorKind, Result};
 use std::sync::Arc;
-use std::time::Duration;
-use tiny_http::{Method, Request, Response};
+use std::time::{Duration, Instant};
 use waker::Waker;
 
+mod http;
+
 counter!(ADMIN_REQUEST_PARSE);
 counter!(ADMIN_RESPONSE_COMPOSE);
 counter!(ADMIN_EVENT_ERROR);
@@ -68,9 +71,19 @@ gauge!(ADMIN_SESSION_CURR, "current number of admin sessions");
 
 // consts
 
+const HTTP_LISTENER_TOKEN: Token = Token(usize::MAX - 2);
 const LISTENER_TOKEN: Token = Token(usize::MAX - 1);
 const WAKER_TOKEN: Token = Token(usize::MAX);
 
+// how long a `stats` request waits for a reply from the storage thread
+const STATS_TIMEOUT: Duration = Duration::from_secs(1);
+
+// how long a shutdown waits for the replies to admin sessions to be sent
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(1);
+
+// how long a shutdown waits for the sibling threads to exit
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
 const KB: u64 = 1024; // one kilobyte in bytes
 const S: u64 = 1_000_000_000; // one second in nanoseconds
 const US: u64 = 1_000; // one microsecond in nanoseconds
@@ -84,24 +97,146 @@ fn map_err(e: std::io::Error) -> Result<()> {
     }
 }
 
+type AsciiSession = ServerSession<AdminRequestParser, AdminResponse, AdminRequest>;
+type HttpSession = ServerSession<RequestParser, protocol_http::Response, ParseData>;
+
+/// A session on one of the admin listeners. Sessions accepted on the ASCII
+/// listener speak the admin protocol, while sessions accepted on the HTTP
+/// listener are served the HTTP endpoints.
+enum AdminSession {
+    Ascii(AsciiSession),
+    Http {
+        session: HttpSession,
+        /// Set once a response closes the connection, the session is closed
+        /// after the response has been written
+        hangup: bool,
+    },
+}
+
+impl AdminSession {
+    fn fill(&mut self) -> Result<usize> {
+        match self {
+            Self::Ascii(session) => session.fill(),
+            Self::Http { session, .. } => session.fill(),
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        match self {
+            Self::Ascii(session) => session.flush(),
+            Self::Http { session, .. } => session.flush(),
+        }
+    }
+
+    fn write_pending(&self) -> usize {
+        match self {
+            Self::Ascii(session) => session.write_pending(),
+            Self::Http { session, .. } => session.write_pending(),
+        }
+    }
+
+    fn remaining(&self) -> usize {
+        match self {
+            Self::Ascii(session) => session.remaining(),
+            Self::Http { session, .. } => session.remaining(),
+        }
+    }
+
+    fn interest(&mut self) -> Interest {
+        match self {
+            Self::Ascii(session) => session.interest(),
+            Self::Http { session, .. } => session.interest(),
+        }
+    }
+
+    fn do_handshake(&mut self) -> Result<()> {
+        match self {
+            Self::Ascii(session) => session.do_handshake(),
+            Self::Http { session, .. } => session.do_handshake(),
+        }
+    }
+
+    /// Returns true if the session should be closed now that all of the
+    /// pending responses have been written
+    fn should_hangup(&self) -> bool {
+        match self {
+            Self::Ascii(_) => false,
+            Self::Http { hangup, .. } => *hangup && self.write_pending() == 0,
+        }
+    }
+}
+
+/// A request which is waiting on a reply from the thread which owns the
+/// storage, either a `stats` request or a HTTP request to `/debug/key`. The
+/// session does not handle any more requests until it is answered.
+struct PendingStats {
+    id: u64,
+    token: Token,
+    deadline: Instant,
+    /// The HTTP request, which is needed to compose the response
+    request: Option<Request>,
+}
+
+impl Source for AdminSession {
+    fn register(&mut self, registry: &Registry, token: Token, interest: Interest) -> Result<()> {
+        match self {
+            Self::Ascii(session) => session.register(registry, token, interest),
+            Self::Http { session, .. } => session.register(registry, token, interest),
+        }
+    }
+
+    fn reregister(&mut self, registry: &Registry, token: Token, interest: Interest) -> Result<()> {
+        match self {
+            Self::Ascii(session) => session.reregister(registry, token, interest),
+            Self::Http { session, .. } => session.reregister(registry, token, interest),
+        }
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> Result<()> {
+        match self {
+            Self::Ascii(session) => session.deregister(registry),
+            Self::Http { session, .. } => session.deregister(registry),
+        }
+    }
+}
+
 pub struct Admin {
     /// A backlog of tokens that need to be handled
     backlog: VecDeque<Token>,
-    http_server: Option<tiny_http::Server>,
+    /// Set once a shutdown has begun, the time by which the sibling threads
+    /// should have exited
+    drain_deadline: Option<Instant>,
+    /// The heartbeats and readiness of the process
+    health: Arc<Health>,
+    /// The HTTP Admin Endpoints
+    http: http::Endpoints,
+    /// The network listener for the HTTP Admin Endpoint, if enabled
+    http_listener: Option<::net::Listener>,
     /// The actual network listener for the ASCII Admin Endpoint
     listener: ::net::Listener,
     /// The drain handle for the logger
     log_drain: Box<dyn Drain>,
     /// The maximum number of events to process per call to poll
     nevent: usize,
+    /// The `stats` requests which are waiting on the storage thread
+    pending_stats: Vec<PendingStats>,
     /// The actual poll instantance
     poll: Poll,
     /// The sessions which have been opened
-    sessions: Slab<ServerSession<AdminRequestParser, AdminResponse, AdminRequest>>,
+    sessions: Slab<AdminSession>,
+    /// The effective configuration, returned by `stats settings`
+    settings: Vec<(String, String)>,
+    /// The counter values as of the last `stats reset`
+    stats_baseline: Arc<StatsBaseline>,
+    /// Set once a shutdown has been requested
+    shutdown: bool,
     /// A queue for receiving signals from the parent thread
     signal_queue_rx: Receiver<Signal>,
-    /// A set of queues for sending signals to sibling threads
-    signal_queue_tx: Queues<Signal, ()>,
+    /// A set of queues for sending signals to sibling threads and receiving
+    /// their replies
+    signal_queue_tx: Queues<Signal, StorageStats>,
+    /// The id of the next `stats` request sent to the storage thread
+    stats_id: u64,
     /// The timeout for each call to poll
     timeout: Duration,
     /// The version of the service
@@ -112,11 +247,14 @@ pub struct Admin {
 
 pub struct AdminBuilder {
     backlog: VecDeque<Token>,
-    http_server: Option<tiny_http::Server>,
+    health: Arc<Health>,
+    http: http::Endpoints,
+    http_listener: Option<::net::Listener>,
     listener: ::net::Listener,
     nevent: usize,
     poll: Poll,
-    sessions: Slab<ServerSession<AdminRequestParser, AdminResponse, AdminRequest>>,
+    sessions: Slab<AdminSession>,
+    settings: Vec<(String, String)>,
     timeout: Duration,
     version: String,
     waker: Arc<Waker>,
@@ -125,6 +263,8 @@ pub struct AdminBuilder {
 impl AdminBuilder {
     pub fn new<T: AdminConfig + TlsConfig>(config: &T) -> Result<Self> {
         let tls_config = config.tls();
+        let json = config.to_json();
+        let settings = config.to_settings();
         let config = config.admin();
 
         let addr = config.socket_addr().map_err(|e| {
@@ -142,6 +282,26 @@ impl AdminBuilder {
         let poll = Poll::new()?;
         listener.register(poll.registry(), LISTENER_TOKEN, Interest::READABLE)?;
 
+        // the HTTP endpoint shares the TLS config of the ASCII endpoint
+        let http_listener = if config.http_enabled() {
+            let addr = config.http_socket_addr().map_err(|_| {
+                std::io::Error::new(std::io::ErrorKind::Other, "Bad HTTP listen address")
+            })?;
+
+            let tcp_listener = TcpListener::bind(addr)?;
+
+            let mut http_listener = match (config.use_tls(), tls_acceptor(tls_config)?) {
+                (true, Some(tls_acceptor)) => ::net::Listener::from((tcp_listener, tls_acceptor)),
+                _ => ::net::Listener::from(tcp_listener),
+            };
+
+            http_listener.register(poll.registry(), HTTP_LISTENER_TOKEN, Interest::READABLE)?;
+
+            Some(http_listener)
+        } else {
+            None
+        };
+
         let waker = Arc::new(Waker::from(
             ::net::Waker::new(poll.registry(), WAKER_TOKEN).unwrap(),
         ));
@@ -155,25 +315,42 @@ impl AdminBuilder {
 
         let backlog = VecDeque::new();
 
-        let http_server = if config.http_enabled() {
-            let addr = config.http_socket_addr().map_err(|_| {
-                std::io::Error::new(std::io::ErrorKind::Other, "Bad HTTP listen address")
-            })?;
-            let server = tiny_http::Server::http(addr).map_err(|_| {
-                std::io::Error::new(std::io::ErrorKind::Other, "Failed to create HTTP server")
-            })?;
-            Some(server)
-        } else {
-            None
+        // the control endpoints are only enabled if a token is configured
+        let token = match config.http_token_file() {
+            Some(path) => {
+                let token = std::fs::read_to_string(&path).map_err(|e| {
+                    Error::new(
+                        e.kind(),
+                        format!("failed to load http token from {}: {}", path, e),
+                    )
+                })?;
+                let token = token.trim();
+                if token.is_empty() {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!("http token file {} is empty", path),
+                    ));
+                }
+                Some(token.as_bytes().to_vec())
+            }
+            None => None,
         };
 
+        let health = Arc::new(Health::new());
+        let stall_threshold = Duration::from_millis(config.stall_threshold() as u64);
+
+        let http = http::Endpoints::new(token, json, health.clone(), stall_threshold);
+
         Ok(Self {
             backlog,
-            http_server,
+            health,
+            http,
+            http_listener,
             listener,
             nevent,
             poll,
             sessions,
+            settings,
             timeout,
             version,
             waker,
@@ -188,22 +365,36 @@ impl AdminBuilder {
         self.waker.clone()
     }
 
+    /// The health of the process, which the other threads should publish
+    /// their heartbeats to.
+    pub fn health(&self) -> Arc<Health> {
+        self.health.clone()
+    }
+
     pub fn build(
         self,
         log_drain: Box<dyn Drain>,
         signal_queue_rx: Receiver<Signal>,
-        signal_queue_tx: Queues<Signal, ()>,
+        signal_queue_tx: Queues<Signal, StorageStats>,
     ) -> Admin {
         Admin {
             backlog: self.backlog,
-            http_server: self.http_server,
+            drain_deadline: None,
+            health: self.health,
+            http: self.http,
+            http_listener: self.http_listener,
             listener: self.listener,
             log_drain,
             nevent: self.nevent,
+            pending_stats: Vec::new(),
             poll: self.poll,
             sessions: self.sessions,
+            settings: self.settings,
+            shutdown: false,
+            stats_baseline: Arc::new(StatsBaseline::default()),
             signal_queue_rx,
             signal_queue_tx,
+            stats_id: 0,
             timeout: self.timeout,
             version: self.version,
             waker: self.waker,
@@ -258,15 +449,34 @@ fn get_rusage() {
 }
 
 impl Admin {
-    /// Call accept one time
-    fn accept(&mut self) {
+    /// Call accept one time on the listener with the provided token
+    fn accept(&mut self, token: Token) {
+        let listener = if token == HTTP_LISTENER_TOKEN {
+            match self.http_listener.as_mut() {
+                Some(listener) => listener,
+                None => {
+                    return;
+                }
+            }
+        } else {
+            &mut self.listener
+        };
+
         ADMIN_SESSION_ACCEPT.increment();
 
-        match self
-            .listener
-            .accept()
-            .map(|v| ServerSession::new(Session::from(v), AdminRequestParser::default()))
-        {
+        match listener.accept().map(|v| {
+            if token == HTTP_LISTENER_TOKEN {
+                AdminSession::Http {
+                    session: ServerSession::new(Session::from(v), RequestParser::default()),
+                    hangup: false,
+                }
+            } else {
+                AdminSession::Ascii(ServerSession::new(
+                    Session::from(v),
+                    AdminRequestParser::default(),
+                ))
+            }
+        }) {
             Ok(mut session) => {
                 let s = self.sessions.vacant_entry();
                 let interest = session.interest();
@@ -283,13 +493,13 @@ impl Admin {
                     ADMIN_SESSION_ACCEPT_EX.increment();
                 }
 
-                self.backlog.push_back(LISTENER_TOKEN);
+                self.backlog.push_back(token);
                 let _ = self.waker.wake();
             }
             Err(e) => {
                 if e.kind() != ErrorKind::WouldBlock {
                     ADMIN_SESSION_ACCEPT_EX.increment();
-                    self.backlog.push_back(LISTENER_TOKEN);
+                    self.backlog.push_back(token);
                     let _ = self.waker.wake();
                 }
             }
@@ -308,50 +518,167 @@ impl Admin {
             r => r,
         }?;
 
-        match session.receive() {
-            Ok(request) => {
-                ADMIN_REQUEST_PARSE.increment();
+        self.process(token)
+    }
 
-                // do some request handling
-                match request {
-                    AdminRequest::FlushAll => {
-                        let _ = self.signal_queue_tx.try_send_all(Signal::FlushAll);
-                        session.send(AdminResponse::Ok)?;
-                    }
-                    AdminRequest::Quit => {
-                        return Err(Error::new(ErrorKind::Other, "should hangup"));
-                    }
-                    AdminRequest::Stats => {
-                        session.send(AdminResponse::Stats)?;
+    /// Handle the requests which have been read into the session buffer
+    fn process(&mut self, token: Token) -> Result<()> {
+        let session = self
+            .sessions
+            .get_mut(token.0)
+            .ok_or_else(|| Error::new(ErrorKind::Other, "non-existant session"))?;
+
+        match session {
+            // requests are held in the buffer while the session is waiting on
+            // a reply from the storage thread
+            _ if self.pending_stats.iter().any(|p| p.token == token) => {}
+            AdminSession::Ascii(session) => match session.receive() {
+                Ok(request) => {
+                    ADMIN_REQUEST_PARSE.increment();
+
+                    // do some request handling
+                    let response = match request {
+                        AdminRequest::FlushAll => {
+                            let _ = self.signal_queue_tx.try_send_all(Signal::FlushAll);
+                            Some(AdminResponse::Ok)
+                        }
+                        AdminRequest::Reload => {
+                            let _ = self.signal_queue_tx.try_send_all(Signal::Reload);
+                            Some(AdminResponse::Ok)
+                        }
+                        AdminRequest::Quit => {
+                            return Err(Error::new(ErrorKind::Other, "should hangup"));
+                        }
+                        AdminRequest::Stats => {
+                            Some(AdminResponse::stats(self.stats_baseline.clone()))
+                        }
+                        AdminRequest::StatsConns => {
+                            Some(AdminResponse::stats_conns(self.stats_baseline.clone()))
+                        }
+                        AdminRequest::StatsReset => {
+                            // the counters are shared with the HTTP endpoints,
+                            // so rather than zeroing them, we only report them
+                            // relative to their current values from now on
+                            self.stats_baseline = Arc::new(StatsBaseline::snapshot());
+                            Some(AdminResponse::reset())
+                        }
+                        AdminRequest::StatsSettings => {
+                            Some(AdminResponse::stats_info(self.settings.clone()))
+                        }
+                        AdminRequest::StatsSeg(_) | AdminRequest::StatsTtl => {
+                            let section = match request {
+                                AdminRequest::StatsSeg(page) => StatsSection::Seg(page),
+                                _ => StatsSection::Ttl,
+                            };
+
+                            // the storage derived stats are collected by the
+                            // storage thread, and the response is sent once
+                            // it replies
+                            let id = self.stats_id;
+                            self.stats_id = self.stats_id.wrapping_add(1);
+                            if self
+                                .signal_queue_tx
+                                .try_send_all(Signal::Stats(id, section))
+                                .is_ok()
+                            {
+                                self.pending_stats.push(PendingStats {
+                                    id,
+                                    token,
+                                    deadline: Instant::now() + STATS_TIMEOUT,
+                                    request: None,
+                                });
+                                None
+                            } else {
+                                Some(AdminResponse::server_error("storage stats unavailable"))
+                            }
+                        }
+                        AdminRequest::Version => Some(AdminResponse::version(self.version.clone())),
+                    };
+
+                    if let Some(response) = response {
+                        session.send(response)?;
+                        ADMIN_RESPONSE_COMPOSE.increment();
                     }
-                    AdminRequest::Version => {
-                        session.send(AdminResponse::version(self.version.clone()))?;
+                }
+                Err(e) => map_err(e)?,
+            },
+            AdminSession::Http { session, hangup } => {
+                // requests may be pipelined, so we handle every complete
+                // request in the buffer
+                while !*hangup {
+                    let ParseData(request) = match session.receive() {
+                        Ok(request) => request,
+                        Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                        Err(e) => return Err(e),
+                    };
+
+                    ADMIN_REQUEST_PARSE.increment();
+
+                    let (response, signal) = match request {
+                        Ok(request) => match self.http.debug_key(&request) {
+                            // the item is described by the storage thread, and
+                            // the response is sent once it replies
+                            Some(Ok(key)) => {
+                                let id = self.stats_id;
+                                self.stats_id = self.stats_id.wrapping_add(1);
+                                if self
+                                    .signal_queue_tx
+                                    .try_send_all(Signal::DebugKey(id, key))
+                                    .is_ok()
+                                {
+                                    self.pending_stats.push(PendingStats {
+                                        id,
+                                        token,
+                                        deadline: Instant::now() + STATS_TIMEOUT,
+                                        request: Some(request),
+                                    });
+                                    break;
+                                }
+                                (http::debug_key_response(None).reply_to(&request), None)
+                            }
+                            Some(Err(response)) => (response.reply_to(&request), None),
+                            None => self.http.response(&request),
+                        },
+                        Err(e) => (e.to_response(), None),
+                    };
+
+                    match signal {
+                        Some(Signal::Shutdown) => {
+                            self.shutdown = true;
+                        }
+                        Some(signal) => {
+                            let _ = self.signal_queue_tx.try_send_all(signal);
+                        }
+                        None => {}
                     }
+
+                    *hangup = response.should_hangup();
+                    session.send(response)?;
+
+                    ADMIN_RESPONSE_COMPOSE.increment();
                 }
+            }
+        }
 
-                ADMIN_RESPONSE_COMPOSE.increment();
+        match session.flush() {
+            Ok(_) => Ok(()),
+            Err(e) => map_err(e),
+        }?;
 
-                match session.flush() {
-                    Ok(_) => Ok(()),
-                    Err(e) => map_err(e),
-                }?;
+        if session.should_hangup() {
+            return Err(Error::new(ErrorKind::Other, "should hangup"));
+        }
 
-                if session.write_pending() > 0 || session.remaining() > 0 {
-                    let interest = session.interest();
-                    if session
-                        .reregister(self.poll.registry(), token, interest)
-                        .is_err()
-                    {
-                        return Err(Error::new(ErrorKind::Other, "failed to reregister"));
-                    }
-                }
-                Ok(())
+        if session.write_pending() > 0 || session.remaining() > 0 {
+            let interest = session.interest();
+            if session
+                .reregister(self.poll.registry(), token, interest)
+                .is_err()
+            {
+                return Err(Error::new(ErrorKind::Other, "failed to reregister"));
             }
-            Err(e) => match e.kind() {
-                ErrorKind::WouldBlock => Ok(()),
-                _ => Err(e),
-            },
         }
+        Ok(())
     }
 
     fn write(&mut self, token: Token) -> Result<()> {
@@ -366,11 +693,76 @@ impl Admin {
                 ErrorKind::WouldBlock => Ok(()),
                 _ => Err(e),
             },
+        }?;
+
+        if session.should_hangup() {
+            return Err(Error::new(ErrorKind::Other, "should hangup"));
+        }
+
+        Ok(())
+    }
+
+    /// Sends the response to a request which was waiting on the storage
+    /// thread, and then handles any requests which arrived meanwhile. Without
+    /// a reply, the storage thread did not reply in time.
+    fn respond(&mut self, pending: PendingStats, reply: Option<StorageStats>) {
+        let token = pending.token;
+        let sent = match (self.sessions.get_mut(token.0), pending.request) {
+            (Some(AdminSession::Ascii(session)), None) => {
+                let response = match reply.map(|reply| reply.stats) {
+                    Some(Some(stats)) => AdminResponse::stats_info(stats),
+                    Some(None) => AdminResponse::server_error("not supported by this storage"),
+                    None => AdminResponse::server_error("storage stats unavailable"),
+                };
+                session.send(response)
+            }
+            (Some(AdminSession::Http { session, hangup }), Some(request)) => {
+                let response = http::debug_key_response(reply).reply_to(&request);
+                *hangup = response.should_hangup();
+                session.send(response)
+            }
+            _ => {
+                return;
+            }
+        };
+
+        if sent.is_err() {
+            self.close(token);
+            return;
+        }
+
+        ADMIN_RESPONSE_COMPOSE.increment();
+
+        if self.process(token).is_err() {
+            self.close(token);
+        }
+    }
+
+    /// Handles the stats replies from the storage thread, and answers the
+    /// requests which have not received a reply in time
+    fn stats_replies(&mut self) {
+        while let Some(stats) = self.signal_queue_tx.try_recv().map(|v| v.into_inner()) {
+            // the request may have timed out or its session may have closed
+            if let Some(index) = self.pending_stats.iter().position(|p| p.id == stats.id) {
+                let pending = self.pending_stats.remove(index);
+                self.respond(pending, Some(stats));
+            }
+        }
+
+        let now = Instant::now();
+        let (expired, pending) = std::mem::take(&mut self.pending_stats)
+            .into_iter()
+            .partition(|p| p.deadline <= now);
+        self.pending_stats = pending;
+        for pending in expired {
+            self.respond(pending, None);
         }
     }
 
     /// Closes the session with the given token
     fn close(&mut self, token: Token) {
+        self.pending_stats.retain(|p| p.token != token);
+
         if self.sessions.contains(token.0) {
             ADMIN_SESSION_CLOSE.increment();
             ADMIN_SESSION_CURR.decrement();
@@ -440,206 +832,31 @@ impl Admin {
         }
     }
 
-    /// A "human-readable" exposition format which outputs one stat per line,
-    /// with a LF used as the end of line symbol.
-    ///
-    /// ```text
-    /// get: 0
-    /// get_cardinality_p25: 0
-    /// get_cardinality_p50: 0
-    /// get_cardinality_p75: 0
-    /// get_cardinality_p90: 0
-    /// get_cardinality_p9999: 0
-    /// get_cardinality_p999: 0
-    /// get_cardinality_p99: 0
-    /// get_ex: 0
-    /// get_key: 0
-    /// get_key_hit: 0
-    /// get_key_miss: 0
-    /// ```
-    fn human_stats(&self) -> String {
-        let mut data = Vec::new();
-
-        for metric in &rustcommon_metrics::metrics() {
-            let any = match metric.as_any() {
-                Some(any) => any,
-                None => {
-                    continue;
-                }
-            };
-
-            if let Some(counter) = any.downcast_ref::<Counter>() {
-                data.push(format!("{}: {}", metric.name(), counter.value()));
-            } else if let Some(gauge) = any.downcast_ref::<Gauge>() {
-                data.push(format!("{}: {}", metric.name(), gauge.value()));
-            } else if let Some(heatmap) = any.downcast_ref::<Heatmap>() {
-                for (label, value) in PERCENTILES {
-                    let percentile = heatmap.percentile(*value).map(|b| b.high()).unwrap_or(0);
-                    data.push(format!("{}_{}: {}", metric.name(), label, percentile));
-                }
-            }
-        }
-
-        data.sort();
-        data.join("\n") + "\n"
-    }
-
-    /// JSON stats output which follows the conventions found in Finagle and
-    /// TwitterServer libraries. Percentiles are appended to the metric name,
-    /// eg: `request_latency_p999` for the 99.9th percentile. For more details
-    /// about the Finagle / TwitterServer format see:
-    /// https://twitter.github.io/twitter-server/Features.html#metrics
-    ///
-    /// ```text
-    /// {"get": 0,"get_cardinality_p25": 0,"get_cardinality_p50": 0, ... }
-    /// ```
-    fn json_stats(&self) -> String {
-        let head = "{".to_owned();
-
-        let mut data = Vec::new();
-
-        for metric in &rustcommon_metrics::metrics() {
-            let any = match metric.as_any() {
-                Some(any) => any,
-                None => {
-                    continue;
-                }
-            };
+    /// Sends the replies which are still buffered in the sessions, giving up
+    /// on any sessions which have not been flushed within the timeout.
+    fn flush_sessions(&mut self) {
+        let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+        let mut events = Events::with_capacity(self.nevent);
 
-            if let Some(counter) = any.downcast_ref::<Counter>() {
-                data.push(format!("\"{}\": {}", metric.name(), counter.value()));
-            } else if let Some(gauge) = any.downcast_ref::<Gauge>() {
-                data.push(format!("\"{}\": {}", metric.name(), gauge.value()));
-            } else if let Some(heatmap) = any.downcast_ref::<Heatmap>() {
-                for (label, value) in PERCENTILES {
-                    let percentile = heatmap.percentile(*value).map(|b| b.high()).unwrap_or(0);
-                    data.push(format!("\"{}_{}\": {}", metric.name(), label, percentile));
-                }
+        loop {
+            let tokens: Vec<Token> = self
+                .sessions
+                .iter()
+                .filter(|(_, session)| session.write_pending() > 0)
+                .map(|(key, _)| Token(key))
+                .collect();
+            if tokens.is_empty() || Instant::now() >= deadline {
+                return;
             }
-        }
-
-        data.sort();
-        let body = data.join(",");
-        let mut content = head;
-        content += &body;
-        content += "}";
-        content
-    }
-
-    /// Prometheus / OpenTelemetry compatible stats output. Each stat is
-    /// annotated with a type. Percentiles use the label 'percentile' to
-    /// indicate which percentile corresponds to the value:
-    ///
-    /// ```text
-    /// # TYPE get counter
-    /// get 0
-    /// # TYPE get_cardinality gauge
-    /// get_cardinality{percentile="p25"} 0
-    /// # TYPE get_cardinality gauge
-    /// get_cardinality{percentile="p50"} 0
-    /// # TYPE get_cardinality gauge
-    /// get_cardinality{percentile="p75"} 0
-    /// # TYPE get_cardinality gauge
-    /// get_cardinality{percentile="p90"} 0
-    /// # TYPE get_cardinality gauge
-    /// get_cardinality{percentile="p99"} 0
-    /// # TYPE get_cardinality gauge
-    /// get_cardinality{percentile="p999"} 0
-    /// # TYPE get_cardinality gauge
-    /// get_cardinality{percentile="p9999"} 0
-    /// # TYPE get_ex counter
-    /// get_ex 0
-    /// # TYPE get_key counter
-    /// get_key 0
-    /// # TYPE get_key_hit counter
-    /// get_key_hit 0
-    /// # TYPE get_key_miss counter
-    /// get_key_miss 0
-    /// ```
-    fn prometheus_stats(&self) -> String {
-        let mut data = Vec::new();
-
-        for metric in &rustcommon_metrics::metrics() {
-            let any = match metric.as_any() {
-                Some(any) => any,
-                None => {
-                    continue;
-                }
-            };
 
-            if let Some(counter) = any.downcast_ref::<Counter>() {
-                data.push(format!(
-                    "# TYPE {} counter\n{} {}",
-                    metric.name(),
-                    metric.name(),
-                    counter.value()
-                ));
-            } else if let Some(gauge) = any.downcast_ref::<Gauge>() {
-                data.push(format!(
-                    "# TYPE {} gauge\n{} {}",
-                    metric.name(),
-                    metric.name(),
-                    gauge.value()
-                ));
-            } else if let Some(heatmap) = any.downcast_ref::<Heatmap>() {
-                for (label, value) in PERCENTILES {
-                    let percentile = heatmap.percentile(*value).map(|b| b.high()).unwrap_or(0);
-                    data.push(format!(
-                        "# TYPE {} gauge\n{}{{percentile=\"{}\"}} {}",
-                        metric.name(),
-                        metric.name(),
-                        label,
-                        percentile
-                    ));
+            for token in tokens {
+                if self.write(token).is_err() {
+                    self.close(token);
                 }
             }
-        }
-        data.sort();
-        let mut content = data.join("\n");
-        content += "\n";
-        let parts: Vec<&str> = content.split('/').collect();
-        parts.join("_")
-    }
 
-    /// Handle a HTTP request
-    fn handle_http_request(&self, request: Request) {
-        let url = request.url();
-        let parts: Vec<&str> = url.split('?').collect();
-        let url = parts[0];
-        match url {
-            // Prometheus/OpenTelemetry expect the `/metrics` URI will return
-            // stats in the Prometheus format
-            "/metrics" => match request.method() {
-                Method::Get => {
-                    let _ = request.respond(Response::from_string(self.prometheus_stats()));
-                }
-                _ => {
-                    let _ = request.respond(Response::empty(400));
-                }
-            },
-            // we export Finagle/TwitterServer format stats on a few endpoints
-            // for maximum compatibility with various internal conventions
-            "/metrics.json" | "/vars.json" | "/admin/metrics.json" => match request.method() {
-                Method::Get => {
-                    let _ = request.respond(Response::from_string(self.json_stats()));
-                }
-                _ => {
-                    let _ = request.respond(Response::empty(400));
-                }
-            },
-            // human-readable stats are exported on the `/vars` endpoint based
-            // on internal conventions
-            "/vars" => match request.method() {
-                Method::Get => {
-                    let _ = request.respond(Response::from_string(self.human_stats()));
-                }
-                _ => {
-                    let _ = request.respond(Response::empty(400));
-                }
-            },
-            _ => {
-                let _ = request.respond(Response::empty(404));
-            }
+            // wait for the sessions to become writable again
+            let _ = self.poll.poll(&mut events, Some(self.timeout));
         }
     }
 
@@ -652,6 +869,16 @@ impl Admin {
                 .unwrap_or_else(|_| "unknown address".to_string())
         );
 
+        if let Some(listener) = &self.http_listener {
+            info!(
+                "running admin http on: {}",
+                listener
+                    .local_addr()
+                    .map(|v| format!("{v}"))
+                    .unwrap_or_else(|_| "unknown address".to_string())
+            );
+        }
+
         let mut events = Events::with_capacity(self.nevent);
 
         loop {
@@ -668,15 +895,15 @@ impl Admin {
             // handle all events
             for event in events.iter() {
                 match event.token() {
-                    LISTENER_TOKEN => {
-                        self.accept();
+                    LISTENER_TOKEN | HTTP_LISTENER_TOKEN => {
+                        self.accept(event.token());
                     }
                     WAKER_TOKEN => {
                         self.waker.reset();
                         let tokens: Vec<Token> = self.backlog.drain(..).collect();
                         for token in tokens {
-                            if token == LISTENER_TOKEN {
-                                self.accept();
+                            if token == LISTENER_TOKEN || token == HTTP_LISTENER_TOKEN {
+                                self.accept(token);
                             }
                         }
                     }
@@ -686,29 +913,47 @@ impl Admin {
                 }
             }
 
-            // handle all http requests if the http server is enabled
-            if let Some(ref server) = self.http_server {
-                while let Ok(Some(request)) = server.try_recv() {
-                    self.handle_http_request(request);
-                }
-            }
+            self.stats_replies();
 
             // handle all signals
             while let Ok(signal) = self.signal_queue_rx.try_recv() {
                 match signal {
-                    Signal::FlushAll => {}
+                    Signal::FlushAll
+                    | Signal::Reload
+                    | Signal::Stats(..)
+                    | Signal::DebugKey(..) => {}
                     Signal::Shutdown => {
-                        // if a shutdown is received from any
-                        // thread, we will broadcast it to all
-                        // sibling threads and stop our event loop
-                        info!("shutting down");
-                        let _ = self.signal_queue_tx.try_send_all(Signal::Shutdown);
-                        if self.signal_queue_tx.wake().is_err() {
-                            fatal!("error waking threads for shutdown");
-                        }
-                        let _ = self.log_drain.flush();
-                        return;
+                        self.shutdown = true;
+                    }
+                }
+            }
+
+            // if a shutdown is received from any thread or requested by the
+            // admin endpoint, the process begins to drain and we broadcast the
+            // shutdown to all sibling threads
+            if self.shutdown && self.drain_deadline.is_none() {
+                info!("shutting down");
+                self.health.set_draining();
+                let _ = self.signal_queue_tx.try_send_all(Signal::Shutdown);
+                if self.signal_queue_tx.wake().is_err() {
+                    fatal!("error waking threads for shutdown");
+                }
+                self.drain_deadline = Some(Instant::now() + DRAIN_TIMEOUT);
+            }
+
+            // we keep serving our sessions, so that the health endpoints
+            // report the drain, until the sibling threads have exited. We then
+            // stop our event loop once the replies to our sessions, such as
+            // the reply to the shutdown request, have been sent
+            if let Some(deadline) = self.drain_deadline {
+                let running = self.health.running();
+                if running == 0 || Instant::now() >= deadline {
+                    if running > 0 {
+                        warn!("{} threads had not exited after draining", running);
                     }
+                    self.flush_sessions();
+                    let _ = self.log_drain.flush();
+                    return;
                 }
             }
 
@@ -0,0 +1,210 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! A basic integration test suite for the HTTP admin endpoints, which runs
+//! the admin event loop on its own thread.
+
+#[macro_use]
+extern crate logger;
+
+use admin::AdminBuilder;
+use common::signal::{Signal, StorageStats};
+use config::PingserverConfig;
+use logger::configure_logging;
+use net::{Poll, Token};
+use queues::Queues;
+use waker::Waker;
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::time::Duration;
+
+const CONFIG: &str = r#"
+[admin]
+port = "19999"
+http_enabled = true
+http_port = "19998"
+"#;
+
+// the connection headers of a response which keeps the connection open
+const KEEP_ALIVE: &str = "Connection: keep-alive\r\nKeep-Alive: timeout=60\r\n";
+
+fn main() {
+    debug!("launching admin");
+    let path = std::env::temp_dir().join("pelikan-admin-http.toml");
+    std::fs::write(&path, CONFIG).expect("failed to write config");
+    let config = PingserverConfig::load(path.to_str().unwrap()).expect("failed to load config");
+
+    let log_drain = configure_logging(&config);
+    let admin = AdminBuilder::new(&config).expect("failed to build admin");
+
+    // the admin thread expects a sibling thread to send signals to
+    let poll = Poll::new().expect("failed to create poll");
+    let sibling = Arc::new(Waker::from(
+        net::Waker::new(poll.registry(), Token(0)).expect("failed to create waker"),
+    ));
+    let (mut signal_queue_tx, _signal_queue_rx) =
+        Queues::<Signal, StorageStats>::new(vec![admin.waker()], vec![sibling], 64);
+    let (signal_tx, signal_rx) = crossbeam_channel::bounded(1);
+
+    let waker = admin.waker();
+    let health = admin.health();
+    let mut admin = admin.build(log_drain, signal_rx, signal_queue_tx.remove(0));
+    let thread = std::thread::spawn(move || admin.run());
+
+    std::thread::sleep(Duration::from_millis(500));
+
+    debug!("beginning tests");
+    println!();
+
+    // the stats are exported in each format, and the query string is
+    // ignored when routing the request
+    test(
+        "vars",
+        &[(
+            "GET /vars HTTP/1.1\r\n\r\n",
+            Some("HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\n"),
+        )],
+    );
+    test(
+        "metrics.json",
+        &[(
+            "GET /metrics.json?pretty=1 HTTP/1.1\r\n\r\n",
+            Some("HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n"),
+        )],
+    );
+
+    // any other path is not found
+    test(
+        "not found",
+        &[(
+            "GET /coffee HTTP/1.1\r\n\r\n",
+            Some(&format!(
+                "HTTP/1.1 404 Not Found\r\n{}Content-Length: 0\r\n\r\n",
+                KEEP_ALIVE
+            )),
+        )],
+    );
+
+    // pipelined requests are answered in order
+    test(
+        "pipelined",
+        &[(
+            "GET /coffee HTTP/1.1\r\n\r\nGET /tea HTTP/1.1\r\n\r\n",
+            Some(&format!(
+                "HTTP/1.1 404 Not Found\r\n{0}Content-Length: 0\r\n\r\n\
+                 HTTP/1.1 404 Not Found\r\n{0}Content-Length: 0\r\n\r\n",
+                KEEP_ALIVE
+            )),
+        )],
+    );
+
+    // an HTTP/1.0 client without keep-alive has its connection closed
+    test_close(
+        "http/1.0 close",
+        "GET /coffee HTTP/1.0\r\n\r\n",
+        "HTTP/1.1 404 Not Found\r\nConnection: close\r\nContent-Length: 0\r\n\r\n",
+    );
+
+    // unsupported methods are rejected and the connection is closed
+    test_close(
+        "unsupported method",
+        "PATCH /vars HTTP/1.1\r\n\r\n",
+        "HTTP/1.1 405 Method Not Allowed\r\nContent-Type: text/plain\r\nConnection: close\r\n",
+    );
+
+    // the process drains from the shutdown until the other threads have
+    // exited, and the health endpoints are served until then
+    info!("shutdown...");
+    let heartbeat = health.heartbeat("worker");
+    let _ = signal_tx.send(Signal::Shutdown);
+    let _ = waker.wake();
+    std::thread::sleep(Duration::from_millis(100));
+
+    test(
+        "draining",
+        &[(
+            "GET /health/ready HTTP/1.1\r\n\r\n",
+            Some("HTTP/1.1 503 Service Unavailable\r\n"),
+        )],
+    );
+    assert!(
+        !thread.is_finished(),
+        "admin stopped before the threads exited"
+    );
+
+    // once the other threads have exited, the admin thread stops
+    drop(heartbeat);
+    let _ = thread.join();
+    let _ = std::fs::remove_file(&path);
+
+    info!("passed!");
+}
+
+// opens a new connection, operating on request + response pairs from the
+// provided data. A response only has to match the start of what is received.
+fn test(name: &str, data: &[(&str, Option<&str>)]) {
+    info!("testing: {}", name);
+    let mut stream = connect();
+
+    for (request, response) in data {
+        stream
+            .write_all(request.as_bytes())
+            .expect("failed to send request");
+
+        std::thread::sleep(Duration::from_millis(10));
+        let mut buf = vec![0; 65536];
+
+        match (response, stream.read(&mut buf)) {
+            (Some(response), Ok(bytes)) => {
+                if !buf[0..bytes].starts_with(response.as_bytes()) {
+                    error!("expected: {:?}", response);
+                    error!("received: {:?}", String::from_utf8_lossy(&buf[0..bytes]));
+                    panic!("status: failed\n");
+                }
+            }
+            (Some(_), Err(_)) => {
+                panic!("error reading response");
+            }
+            (None, Err(e)) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+            (None, _) => {
+                panic!("expected no response");
+            }
+        }
+    }
+    info!("status: passed\n");
+}
+
+// sends a request which closes the connection once the response is sent
+fn test_close(name: &str, request: &str, response: &str) {
+    info!("testing: {}", name);
+    let mut stream = connect();
+
+    stream
+        .write_all(request.as_bytes())
+        .expect("failed to send request");
+
+    let mut buf = Vec::new();
+    if stream.read_to_end(&mut buf).is_err() {
+        panic!("connection was not closed");
+    }
+    if !buf.starts_with(response.as_bytes()) {
+        error!("expected: {:?}", response);
+        error!("received: {:?}", String::from_utf8_lossy(&buf));
+        panic!("status: failed\n");
+    }
+    info!("status: passed\n");
+}
+
+fn connect() -> TcpStream {
+    let stream = TcpStream::connect("127.0.0.1:19998").expect("failed to connect");
+    stream
+        .set_read_timeout(Some(Duration::from_millis(250)))
+        .expect("failed to set read timeout");
+    stream
+        .set_write_timeout(Some(Duration::from_millis(250)))
+        .expect("failed to set write timeout");
+    stream
+}
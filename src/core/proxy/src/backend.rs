@@ -7,6 +7,7 @@ use crate::*;
 use session::ClientSession;
 use std::collections::HashMap;
 use std::collections::VecDeque;
+use std::net::SocketAddr;
 
 heatmap!(
     BACKEND_EVENT_DEPTH,
@@ -25,9 +26,16 @@ counter!(
 counter!(BACKEND_EVENT_READ, "the number of read events received");
 counter!(BACKEND_EVENT_TOTAL, "the total number of events received");
 counter!(BACKEND_EVENT_WRITE, "the number of write events received");
+counter!(
+    BACKEND_REPLY_MISMATCH,
+    "the number of backend replies which did not match their request"
+);
+counter!(
+    BACKEND_REQUEST_FAILED,
+    "the number of requests which failed because the backend connection was closed"
+);
 
 pub struct BackendWorkerBuilder<Parser, Request, Response> {
-    free_queue: VecDeque<Token>,
     nevent: usize,
     parser: Parser,
     poll: Poll,
@@ -41,7 +49,11 @@ where
     Parser: Clone + Parse<Response>,
     Request: Compose,
 {
-    pub fn new<T: BackendConfig>(config: &T, parser: Parser) -> Result<Self> {
+    pub fn new<T: BackendConfig>(
+        config: &T,
+        parser: Parser,
+        endpoints: Vec<SocketAddr>,
+    ) -> Result<Self> {
         let config = config.backend();
 
         let poll = Poll::new()?;
@@ -54,9 +66,8 @@ where
         let timeout = Duration::from_millis(config.timeout() as u64);
 
         let mut sessions = Slab::new();
-        let mut free_queue = VecDeque::new();
 
-        for endpoint in config.socket_addrs()? {
+        for endpoint in endpoints {
             let stream = TcpStream::connect(endpoint)?;
             let mut session = ClientSession::new(Session::from(stream), parser.clone());
             let s = sessions.vacant_entry();
@@ -64,12 +75,10 @@ where
             session
                 .register(poll.registry(), Token(s.key()), interest)
                 .expect("failed to register");
-            free_queue.push_back(Token(s.key()));
             s.insert(session);
         }
 
         Ok(Self {
-            free_queue,
             nevent,
             parser,
             poll,
@@ -86,16 +95,19 @@ where
     pub fn build(
         self,
         data_queue: Queues<(Request, Response, Token), (Request, Token)>,
-        signal_queue: Queues<(), Signal>,
+        signal_queue: Queues<StorageStats, Signal>,
+        heartbeat: Arc<Heartbeat>,
     ) -> BackendWorker<Parser, Request, Response> {
         BackendWorker {
             backlog: VecDeque::new(),
             data_queue,
-            free_queue: self.free_queue,
+            heartbeat,
+            inflight: HashMap::new(),
             nevent: self.nevent,
             parser: self.parser,
             pending: HashMap::new(),
             poll: self.poll,
+            sequence: 0,
             sessions: self.sessions,
             signal_queue,
             timeout: self.timeout,
@@ -104,16 +116,29 @@ where
     }
 }
 
+/// A worker which sends requests to the backend endpoints of one pool.
+///
+/// Requests which carry a correlation id are pipelined, with many requests
+/// outstanding on each backend connection. The id of each request is replaced
+/// with one which is unique within the worker, so that requests from different
+/// clients never collide, and the original id is restored in the response.
+/// Other requests are sent one at a time on a connection with no outstanding
+/// requests.
 pub struct BackendWorker<Parser, Request, Response> {
     backlog: VecDeque<(Request, Token)>,
     data_queue: Queues<(Request, Response, Token), (Request, Token)>,
-    free_queue: VecDeque<Token>,
+    heartbeat: Arc<Heartbeat>,
+    // pipelined requests by the id they were sent with, mapped to the backend
+    // and frontend tokens and the original id
+    inflight: HashMap<i32, (Token, Token, i32)>,
     nevent: usize,
     parser: Parser,
+    // requests which are not pipelined, by backend token
     pending: HashMap<Token, Token>,
     poll: Poll,
+    sequence: i32,
     sessions: Slab<ClientSession<Parser, Request, Response>>,
-    signal_queue: Queues<(), Signal>,
+    signal_queue: Queues<StorageStats, Signal>,
     timeout: Duration,
     waker: Arc<Waker>,
 }
@@ -121,17 +146,155 @@ pub struct BackendWorker<Parser, Request, Response> {
 impl<Parser, Request, Response> BackendWorker<Parser, Request, Response>
 where
     Parser: Parse<Response> + Clone,
-    Request: Compose,
+    Request: Compose + Correlate + ErrorResponse<Response = Response>,
+    Response: Correlate,
 {
-    /// Return the `Session` to the `Listener` to handle flush/close
+    /// Return the `Session` to the `Listener` to handle flush/close. The
+    /// backend will never reply to the requests which are outstanding on the
+    /// session, so the frontends are sent an error response for each of them.
     fn close(&mut self, token: Token) {
         if self.sessions.contains(token.0) {
             let mut session = self.sessions.remove(token.0);
             let _ = session.flush();
+            let requests: Vec<Request> = session.drain_pending().collect();
+            for mut request in requests {
+                if let Some(fe_token) = self.complete(token, &mut request) {
+                    self.fail(request, fe_token);
+                }
+            }
+        }
+        self.pending.remove(&token);
+        self.inflight
+            .retain(|_, (be_token, _, _)| *be_token != token);
+
+        // with no connections left, nothing in the backlog can be sent
+        if self.sessions.is_empty() {
+            while let Some((request, fe_token)) = self.backlog.pop_front() {
+                self.fail(request, fe_token);
+            }
+        }
+    }
+
+    /// Sends an error response for the request to its frontend.
+    fn fail(&mut self, request: Request, fe_token: Token) {
+        BACKEND_REQUEST_FAILED.increment();
+        let response = request.error_response("backend connection failed");
+        let _ = self
+            .data_queue
+            .try_send_to(0, (request, response, fe_token));
+    }
+
+    /// Removes the record of a request which was sent on the backend
+    /// connection, restoring the original id of a pipelined request. Returns
+    /// the frontend token of the request.
+    fn complete(&mut self, be_token: Token, request: &mut Request) -> Option<Token> {
+        if let Some(fe_token) = self.pending.remove(&be_token) {
+            return Some(fe_token);
+        }
+        let sequence = request.correlation_id()?;
+        let (_, fe_token, id) = self.inflight.remove(&sequence)?;
+        request.set_correlation_id(id);
+        Some(fe_token)
+    }
+
+    /// Send a request to a backend connection. Pipelined requests are sent on
+    /// the connection with the fewest outstanding requests. Requests which the
+    /// backend never replies to are not tracked. Returns the request if there
+    /// is no connection which can take it.
+    fn send(
+        &mut self,
+        mut request: Request,
+        fe_token: Token,
+    ) -> std::result::Result<(), (Request, Token)> {
+        if self.sessions.is_empty() {
+            self.fail(request, fe_token);
+            return Ok(());
+        }
+
+        let expects_response = request.expects_response();
+        let pending = &self.pending;
+        let be_token = match request.correlation_id() {
+            Some(id) => {
+                let be_token = self
+                    .sessions
+                    .iter()
+                    .filter(|(key, _)| !pending.contains_key(&Token(*key)))
+                    .min_by_key(|(_, session)| session.outstanding())
+                    .map(|(key, _)| Token(key));
+                let be_token = match be_token {
+                    Some(be_token) => be_token,
+                    None => return Err((request, fe_token)),
+                };
+                if expects_response {
+                    let sequence = self.next_sequence();
+                    request.set_correlation_id(sequence);
+                    self.inflight.insert(sequence, (be_token, fe_token, id));
+                }
+                be_token
+            }
+            None => {
+                let be_token = self
+                    .sessions
+                    .iter()
+                    .find(|(key, session)| {
+                        session.outstanding() == 0 && !pending.contains_key(&Token(*key))
+                    })
+                    .map(|(key, _)| Token(key));
+                let be_token = match be_token {
+                    Some(be_token) => be_token,
+                    None => return Err((request, fe_token)),
+                };
+                if expects_response {
+                    self.pending.insert(be_token, fe_token);
+                }
+                be_token
+            }
+        };
+
+        let session = &mut self.sessions[be_token.0];
+        let result = if expects_response {
+            session.send(request)
+        } else {
+            session.send_oneway(request)
+        };
+        if result.is_err() {
+            self.close(be_token);
+            return Ok(());
+        }
+        if session.write_pending() > 0 {
+            let interest = session.interest();
+            if session
+                .reregister(self.poll.registry(), be_token, interest)
+                .is_err()
+            {
+                self.close(be_token);
+            }
+        }
+        Ok(())
+    }
+
+    /// Send requests from the backlog until no connection can take the next
+    /// request.
+    fn send_backlog(&mut self) {
+        while let Some((request, fe_token)) = self.backlog.pop_front() {
+            if let Err(entry) = self.send(request, fe_token) {
+                self.backlog.push_front(entry);
+                return;
+            }
         }
     }
 
-    /// Handle up to one response for a session
+    // returns an id which is not used by any pipelined request
+    fn next_sequence(&mut self) -> i32 {
+        loop {
+            self.sequence = self.sequence.wrapping_add(1);
+            if !self.inflight.contains_key(&self.sequence) {
+                return self.sequence;
+            }
+        }
+    }
+
+    /// Handle all buffered responses for a session
     fn read(&mut self, token: Token) -> Result<()> {
         let session = self
             .sessions
@@ -141,19 +304,35 @@ where
         // fill the session
         map_result(session.fill())?;
 
-        // process up to one request
-        match session.receive() {
-            Ok((request, response)) => {
-                if let Some(fe_token) = self.pending.remove(&token) {
-                    self.free_queue.push_back(token);
-                    self.data_queue
-                        .try_send_to(0, (request, response, fe_token))
-                        .map_err(|_| Error::new(ErrorKind::Other, "data queue is full"))
-                } else {
-                    panic!("corrupted state");
-                }
+        // pipelined responses may arrive together, so process all of them
+        loop {
+            let session = &mut self.sessions[token.0];
+            let result = if self.pending.contains_key(&token) {
+                session.receive()
+            } else {
+                session.receive_correlated()
+            };
+
+            let (mut request, mut response) = match result {
+                Ok(pair) => pair,
+                Err(e) => return map_err(e),
+            };
+
+            let fe_token = self
+                .complete(token, &mut request)
+                .ok_or_else(|| Error::new(ErrorKind::Other, "corrupted state"))?;
+            if !request.is_reply(&response) {
+                BACKEND_REPLY_MISMATCH.increment();
+                self.fail(request, fe_token);
+                return Err(Error::new(ErrorKind::Other, "mismatched reply"));
             }
-            Err(e) => map_err(e),
+            if let Some(id) = request.correlation_id() {
+                response.set_correlation_id(id);
+            }
+
+            self.data_queue
+                .try_send_to(0, (request, response, fe_token))
+                .map_err(|_| Error::new(ErrorKind::Other, "data queue is full"))?;
         }
     }
 
@@ -180,6 +359,7 @@ where
 
         loop {
             BACKEND_EVENT_LOOP.increment();
+            self.heartbeat.beat();
 
             // get events with timeout
             if self.poll.poll(&mut events, Some(self.timeout)).is_err() {
@@ -205,15 +385,12 @@ where
                         // handle all pending messages on the data queue
                         self.data_queue.try_recv_all(&mut messages);
                         for (request, fe_token) in messages.drain(..).map(|v| v.into_inner()) {
-                            if let Some(be_token) = self.free_queue.pop_front() {
-                                let session = &mut self.sessions[be_token.0];
-                                if session.send(request).is_err() {
-                                    panic!("we don't handle this right now");
-                                } else {
-                                    self.pending.insert(be_token, fe_token);
+                            if self.backlog.is_empty() {
+                                if let Err(entry) = self.send(request, fe_token) {
+                                    self.backlog.push_back(entry);
                                 }
                             } else {
-                                self.backlog.push_back((request, token));
+                                self.backlog.push_back((request, fe_token));
                             }
                         }
 
@@ -222,7 +399,10 @@ where
                             self.signal_queue.try_recv().map(|v| v.into_inner())
                         {
                             match signal {
-                                Signal::FlushAll => {}
+                                Signal::FlushAll
+                                | Signal::Reload
+                                | Signal::Stats(..)
+                                | Signal::DebugKey(..) => {}
                                 Signal::Shutdown => {
                                     // if we received a shutdown, we can return
                                     // and stop processing events
@@ -255,6 +435,9 @@ where
                                 self.close(token);
                                 continue;
                             }
+
+                            // responses free up connections for the backlog
+                            self.send_backlog();
                         }
                     }
                 }
@@ -274,16 +457,24 @@ impl<BackendParser, BackendRequest, BackendResponse>
     BackendBuilder<BackendParser, BackendRequest, BackendResponse>
 where
     BackendParser: Parse<BackendResponse> + Clone,
-    BackendRequest: Compose,
+    BackendRequest: Compose + Correlate + ErrorResponse<Response = BackendResponse>,
+    BackendResponse: Correlate,
 {
-    pub fn new<T: BackendConfig>(
-        config: &T,
-        parser: BackendParser,
-        threads: usize,
-    ) -> Result<Self> {
+    /// Creates one worker for the default endpoints, which is pool `0`, and
+    /// one worker for each additional pool of endpoints.
+    pub fn new<T: BackendConfig>(config: &T, parser: BackendParser) -> Result<Self> {
+        let mut pools = vec![config.backend().socket_addrs()?];
+        for pool in config.backend().pools() {
+            pools.push(pool.socket_addrs()?);
+        }
+
         let mut builders = Vec::new();
-        for _ in 0..threads {
-            builders.push(BackendWorkerBuilder::new(config, parser.clone())?);
+        for endpoints in pools {
+            builders.push(BackendWorkerBuilder::new(
+                config,
+                parser.clone(),
+                endpoints,
+            )?);
         }
         Ok(Self { builders })
     }
@@ -298,11 +489,123 @@ where
         mut data_queues: Vec<
             Queues<(BackendRequest, BackendResponse, Token), (BackendRequest, Token)>,
         >,
-        mut signal_queues: Vec<Queues<(), Signal>>,
+        mut signal_queues: Vec<Queues<StorageStats, Signal>>,
+        health: &Health,
     ) -> Vec<BackendWorker<BackendParser, BackendRequest, BackendResponse>> {
+        // the workers are built in order, as the frontends address each pool
+        // by the index of its data queue
         self.builders
             .drain(..)
-            .map(|b| b.build(data_queues.pop().unwrap(), signal_queues.pop().unwrap()))
+            .zip(data_queues.drain(..).zip(signal_queues.drain(..)))
+            .enumerate()
+            .map(|(id, (b, (data_queue, signal_queue)))| {
+                b.build(
+                    data_queue,
+                    signal_queue,
+                    health.heartbeat(&format!("be_{}", id)),
+                )
+            })
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol_resp::{GetRequest, MGetRequest, Request, Response, ResponseParser};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    struct Config {
+        backend: Backend,
+    }
+
+    impl BackendConfig for Config {
+        fn backend(&self) -> &Backend {
+            &self.backend
+        }
+    }
+
+    // waits for the backend worker to send a response to the frontend
+    fn recv(
+        queue: &Queues<(Request, Token), (Request, Response, Token)>,
+    ) -> (Request, Response, Token) {
+        for _ in 0..1000 {
+            if let Some(item) = queue.try_recv() {
+                return item.into_inner();
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        panic!("timed out waiting for a response");
+    }
+
+    #[test]
+    fn mismatched_reply() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind");
+        let config = Config {
+            backend: Backend::default(),
+        };
+        let builder = BackendWorkerBuilder::new(
+            &config,
+            ResponseParser::new(),
+            vec![listener.local_addr().unwrap()],
+        )
+        .expect("failed to connect");
+        let (mut backend, _) = listener.accept().expect("failed to accept");
+        backend
+            .set_read_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+
+        let poll = Poll::new().expect("failed to create event loop");
+        let waker = Arc::new(Waker::from(
+            ::net::Waker::new(poll.registry(), WAKER_TOKEN).expect("failed to create waker"),
+        ));
+        let (mut data_queue, be_data_queue) =
+            Queues::new(vec![waker.clone()], vec![builder.waker()], QUEUE_CAPACITY);
+        let (mut signal_queue, be_signal_queue) =
+            Queues::new(vec![waker], vec![builder.waker()], QUEUE_CAPACITY);
+        let mut data_queue = data_queue.remove(0);
+        let mut signal_queue = signal_queue.remove(0);
+
+        let mut worker = builder.build(
+            be_data_queue.into_iter().next().unwrap(),
+            be_signal_queue.into_iter().next().unwrap(),
+            Health::new().heartbeat("be_0"),
+        );
+        let worker = std::thread::spawn(move || worker.run());
+
+        let mut buffer = [0; 1024];
+
+        // a reply which matches its request is forwarded to the frontend
+        let get = Request::Get(GetRequest::new(b"a"));
+        data_queue.try_send_to(0, (get.clone(), Token(1))).unwrap();
+        data_queue.wake().unwrap();
+        assert!(backend.read(&mut buffer).unwrap() > 0);
+        backend.write_all(b"$1\r\nx\r\n").unwrap();
+        assert_eq!(
+            recv(&data_queue),
+            (get, Response::bulk_string(b"x"), Token(1))
+        );
+
+        // an mget reply with the wrong number of values is not forwarded,
+        // instead the request fails and the connection is closed
+        let mget = Request::MGet(MGetRequest::new(&[b"a", b"b"]));
+        data_queue.try_send_to(0, (mget.clone(), Token(2))).unwrap();
+        data_queue.wake().unwrap();
+        assert!(backend.read(&mut buffer).unwrap() > 0);
+        backend.write_all(b"*1\r\n$1\r\nx\r\n").unwrap();
+        assert_eq!(
+            recv(&data_queue),
+            (
+                mget,
+                Response::error("ERR backend connection failed"),
+                Token(2)
+            )
+        );
+        assert_eq!(backend.read(&mut buffer).unwrap(), 0);
+
+        assert!(signal_queue.try_send_to(0, Signal::Shutdown).is_ok());
+        signal_queue.wake().unwrap();
+        worker.join().unwrap();
+    }
+}
@@ -33,10 +33,10 @@ pub struct FrontendWorkerBuilder<
     nevent: usize,
     parser: FrontendParser,
     poll: Poll,
+    router: Option<Router<BackendRequest>>,
     sessions: Slab<ServerSession<FrontendParser, FrontendResponse, FrontendRequest>>,
     timeout: Duration,
     waker: Arc<Waker>,
-    _backend_request: PhantomData<BackendRequest>,
     _backend_response: PhantomData<BackendResponse>,
 }
 
@@ -65,10 +65,10 @@ impl<FrontendParser, FrontendRequest, FrontendResponse, BackendRequest, BackendR
             nevent,
             parser,
             poll,
+            router: None,
             sessions: Slab::new(),
             timeout,
             waker,
-            _backend_request: PhantomData,
             _backend_response: PhantomData,
         })
     }
@@ -77,11 +77,16 @@ impl<FrontendParser, FrontendRequest, FrontendResponse, BackendRequest, BackendR
         self.waker.clone()
     }
 
+    pub fn router(&mut self, router: Router<BackendRequest>) {
+        self.router = Some(router);
+    }
+
     pub fn build(
         self,
         data_queue: Queues<(BackendRequest, Token), (BackendRequest, BackendResponse, Token)>,
         session_queue: Queues<Session, Session>,
-        signal_queue: Queues<(), Signal>,
+        signal_queue: Queues<StorageStats, Signal>,
+        heartbeat: Arc<Heartbeat>,
     ) -> FrontendWorker<
         FrontendParser,
         FrontendRequest,
@@ -91,9 +96,11 @@ impl<FrontendParser, FrontendRequest, FrontendResponse, BackendRequest, BackendR
     > {
         FrontendWorker {
             data_queue,
+            heartbeat,
             nevent: self.nevent,
             parser: self.parser,
             poll: self.poll,
+            router: self.router,
             session_queue,
             sessions: self.sessions,
             signal_queue,
@@ -111,12 +118,15 @@ pub struct FrontendWorker<
     BackendResponse,
 > {
     data_queue: Queues<(BackendRequest, Token), (BackendRequest, BackendResponse, Token)>,
+    heartbeat: Arc<Heartbeat>,
     nevent: usize,
     parser: FrontendParser,
     poll: Poll,
+    // without a router, all requests are sent to the default pool
+    router: Option<Router<BackendRequest>>,
     session_queue: Queues<Session, Session>,
     sessions: Slab<ServerSession<FrontendParser, FrontendResponse, FrontendRequest>>,
-    signal_queue: Queues<(), Signal>,
+    signal_queue: Queues<StorageStats, Signal>,
     timeout: Duration,
     waker: Arc<Waker>,
 }
@@ -135,6 +145,7 @@ where
     FrontendResponse: From<BackendResponse>,
     BackendRequest: From<FrontendRequest>,
     BackendRequest: Compose,
+    BackendRequest: Klog<Response = BackendResponse>,
     BackendResponse: Compose,
 {
     /// Return the `Session` to the `Listener` to handle flush/close
@@ -159,10 +170,13 @@ where
 
         // process up to one request
         match session.receive() {
-            Ok(request) => self
-                .data_queue
-                .try_send_to(0, (BackendRequest::from(request), token))
-                .map_err(|_| Error::new(ErrorKind::Other, "data queue is full")),
+            Ok(request) => {
+                let request = BackendRequest::from(request);
+                let pool = self.router.as_ref().map(|r| r(&request)).unwrap_or(0);
+                self.data_queue
+                    .try_send_to(pool, (request, token))
+                    .map_err(|_| Error::new(ErrorKind::Other, "data queue is full"))
+            }
             Err(e) => map_err(e),
         }
     }
@@ -189,6 +203,7 @@ where
 
         loop {
             FRONTEND_EVENT_LOOP.increment();
+            self.heartbeat.beat();
 
             // get events with timeout
             if self.poll.poll(&mut events, Some(self.timeout)).is_err() {
@@ -232,9 +247,9 @@ where
 
                         // handle all pending messages on the data queue
                         self.data_queue.try_recv_all(&mut messages);
-                        for (_request, response, token) in
-                            messages.drain(..).map(|v| v.into_inner())
+                        for (request, response, token) in messages.drain(..).map(|v| v.into_inner())
                         {
+                            request.klog(&response);
                             if let Some(session) = self.sessions.get_mut(token.0) {
                                 if response.should_hangup() {
                                     let _ = session.send(FrontendResponse::from(response));
@@ -265,10 +280,17 @@ where
                             self.signal_queue.try_recv().map(|v| v.into_inner())
                         {
                             match signal {
-                                Signal::FlushAll => {}
+                                Signal::FlushAll
+                                | Signal::Reload
+                                | Signal::Stats(..)
+                                | Signal::DebugKey(..) => {}
                                 Signal::Shutdown => {
-                                    // if we received a shutdown, we can return
-                                    // and stop processing events
+                                    // if we received a shutdown, we send the
+                                    // responses which are still buffered, and
+                                    // then return and stop processing events
+                                    for (_, session) in self.sessions.iter_mut() {
+                                        let _ = session.flush();
+                                    }
                                     return;
                                 }
                             }
@@ -358,6 +380,12 @@ where
         self.builders.iter().map(|b| b.waker()).collect()
     }
 
+    pub fn router(&mut self, router: Router<BackendRequest>) {
+        for builder in self.builders.iter_mut() {
+            builder.router(router.clone());
+        }
+    }
+
     #[allow(clippy::type_complexity)]
     pub fn build(
         mut self,
@@ -365,7 +393,8 @@ where
             Queues<(BackendRequest, Token), (BackendRequest, BackendResponse, Token)>,
         >,
         mut session_queues: Vec<Queues<Session, Session>>,
-        mut signal_queues: Vec<Queues<(), Signal>>,
+        mut signal_queues: Vec<Queues<StorageStats, Signal>>,
+        health: &Health,
     ) -> Vec<
         FrontendWorker<
             FrontendParser,
@@ -377,11 +406,13 @@ where
     > {
         self.builders
             .drain(..)
-            .map(|b| {
+            .enumerate()
+            .map(|(id, b)| {
                 b.build(
                     data_queues.pop().unwrap(),
                     session_queues.pop().unwrap(),
                     signal_queues.pop().unwrap(),
+                    health.heartbeat(&format!("fe_{}", id)),
                 )
             })
             .collect()
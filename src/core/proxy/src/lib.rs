@@ -14,7 +14,8 @@ extern crate rustcommon_metrics;
 use ::net::event::{Event, Source};
 use ::net::*;
 use admin::AdminBuilder;
-use common::signal::Signal;
+use common::health::{Health, Heartbeat};
+use common::signal::{Signal, StorageStats};
 use common::ssl::tls_acceptor;
 use config::proxy::*;
 use config::*;
@@ -22,8 +23,8 @@ use core::marker::PhantomData;
 use core::time::Duration;
 use crossbeam_channel::{bounded, Receiver, Sender};
 use entrystore::EntryStore;
-use logger::Drain;
-use protocol_common::{Compose, Execute, Parse};
+use logger::{Drain, Klog};
+use protocol_common::{Compose, Correlate, ErrorResponse, Execute, Parse};
 use queues::Queues;
 use rustcommon_metrics::*;
 use session::{Buf, ServerSession, Session};
@@ -70,6 +71,11 @@ pub static PERCENTILES: &[(&str, f64)] = &[
     ("p9999", 99.99),
 ];
 
+/// Chooses the backend pool for a request, where pool `0` is made up of the
+/// default backend endpoints and the additional pools follow in the order they
+/// are configured.
+pub type Router<Request> = Arc<dyn Fn(&Request) -> usize + Send + Sync>;
+
 fn map_err(e: std::io::Error) -> Result<()> {
     match e.kind() {
         ErrorKind::WouldBlock => Ok(()),
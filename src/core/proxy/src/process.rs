@@ -48,7 +48,9 @@ impl<
 where
     BackendParser: 'static + Parse<BackendResponse> + Clone + Send,
     BackendRequest: 'static + Send + Compose + From<FrontendRequest> + Compose,
-    BackendResponse: 'static + Compose + Send,
+    BackendRequest: Klog<Response = BackendResponse> + Correlate,
+    BackendRequest: ErrorResponse<Response = BackendResponse>,
+    BackendResponse: 'static + Compose + Correlate + Send,
     FrontendParser: 'static + Parse<FrontendRequest> + Clone + Send,
     FrontendRequest: 'static + Send,
     FrontendResponse: 'static + Compose + Send,
@@ -61,7 +63,7 @@ where
         frontend_parser: FrontendParser,
     ) -> Result<Self> {
         let admin = AdminBuilder::new(config)?;
-        let backend = BackendBuilder::new(config, backend_parser, 1)?;
+        let backend = BackendBuilder::new(config, backend_parser)?;
         let frontend = FrontendBuilder::new(config, frontend_parser, 1)?;
         let listener = ListenerBuilder::new(config)?;
 
@@ -79,6 +81,16 @@ where
         self
     }
 
+    /// Route requests to the backend pools. By default, all requests are sent
+    /// to the default backend endpoints.
+    pub fn router<F>(mut self, router: F) -> Self
+    where
+        F: 'static + Fn(&BackendRequest) -> usize + Send + Sync,
+    {
+        self.frontend.router(Arc::new(router));
+        self
+    }
+
     pub fn spawn(self) -> Process {
         let mut thread_wakers = vec![self.listener.waker()];
         thread_wakers.extend_from_slice(&self.backend.wakers());
@@ -104,23 +116,33 @@ where
             QUEUE_CAPACITY,
         );
 
+        // every thread other than the admin publishes a heartbeat, which is
+        // used by the admin health endpoints
+        let health = self.admin.health();
+
         let mut admin = self
             .admin
             .build(self.log_drain, signal_rx, signal_queue_tx.remove(0));
 
-        let mut listener = self
-            .listener
-            .build(signal_queue_rx.remove(0), listener_session_queues.remove(0));
+        let mut listener = self.listener.build(
+            signal_queue_rx.remove(0),
+            listener_session_queues.remove(0),
+            health.clone(),
+        );
 
         let be_threads = be_data_queues.len();
 
         let mut backend_workers = self.backend.build(
             be_data_queues,
             signal_queue_rx.drain(0..be_threads).collect(),
+            &health,
+        );
+        let mut frontend_workers = self.frontend.build(
+            fe_data_queues,
+            worker_session_queues,
+            signal_queue_rx,
+            &health,
         );
-        let mut frontend_workers =
-            self.frontend
-                .build(fe_data_queues, worker_session_queues, signal_queue_rx);
 
         let admin = std::thread::Builder::new()
             .name(format!("{}_admin", THREAD_PREFIX))
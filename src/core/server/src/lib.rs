@@ -87,6 +87,13 @@
 //! execute requests. The storage thread will receive requests from a worker
 //! over a queue, execute the request, and returns the result back to the worker
 //! thread.
+//!
+//! ### UDP
+//! If a UDP port is configured and the server enables it, requests may also
+//! be received as datagrams. With a single worker thread, the worker owns the
+//! UDP socket. With multiple worker threads, a dedicated `udp` thread owns the
+//! socket and passes requests to the `storage` thread, just like the other
+//! workers.
 
 #[macro_use]
 extern crate logger;
@@ -94,7 +101,8 @@ extern crate logger;
 use ::net::event::{Event, Source};
 use ::net::*;
 use admin::AdminBuilder;
-use common::signal::Signal;
+use common::health::{Health, Heartbeat};
+use common::signal::{Signal, StorageStats};
 use common::ssl::tls_acceptor;
 use config::*;
 use core::marker::PhantomData;
@@ -116,7 +124,7 @@ mod process;
 mod workers;
 
 use listener::ListenerBuilder;
-use workers::WorkersBuilder;
+use workers::{Udp, WorkersBuilder};
 
 pub use process::{Process, ProcessBuilder};
 
@@ -132,6 +140,7 @@ const QUEUE_CAPACITY: usize = 64 * 1024;
 // determines the max number of calls to accept when the listener is ready
 const ACCEPT_BATCH: usize = 8;
 
+const UDP_TOKEN: Token = Token(usize::MAX - 2);
 const LISTENER_TOKEN: Token = Token(usize::MAX - 1);
 const WAKER_TOKEN: Token = Token(usize::MAX);
 
@@ -20,6 +20,10 @@ counter!(
 );
 
 pub struct Listener {
+    /// The health of the process, which tracks whether we are listening
+    health: Arc<Health>,
+    /// The heartbeat for this thread
+    heartbeat: Arc<Heartbeat>,
     /// The actual network listener server
     listener: ::net::Listener,
     /// The maximum number of events to process per call to poll
@@ -32,7 +36,7 @@ pub struct Listener {
     /// receive sessions which should be closed
     session_queue: Queues<Session, Session>,
     /// Queue for receieving signals from the admin thread
-    signal_queue: Queues<(), Signal>,
+    signal_queue: Queues<StorageStats, Signal>,
     /// The timeout for each call to poll
     timeout: Duration,
     /// The waker handle for this thread
@@ -94,10 +98,13 @@ impl ListenerBuilder {
 
     pub fn build(
         self,
-        signal_queue: Queues<(), Signal>,
+        signal_queue: Queues<StorageStats, Signal>,
         session_queue: Queues<Session, Session>,
+        health: Arc<Health>,
     ) -> Listener {
         Listener {
+            heartbeat: health.heartbeat("listener"),
+            health,
             listener: self.listener,
             nevent: self.nevent,
             poll: self.poll,
@@ -263,9 +270,12 @@ impl Listener {
 
         let mut events = Events::with_capacity(self.nevent);
 
+        self.health.set_listening(true);
+
         // repeatedly run accepting new connections and moving them to the worker
         loop {
             LISTENER_EVENT_LOOP.increment();
+            self.heartbeat.beat();
             if self.poll.poll(&mut events, Some(self.timeout)).is_err() {
                 error!("Error polling server");
             }
@@ -294,10 +304,14 @@ impl Listener {
                             self.signal_queue.try_recv().map(|v| v.into_inner())
                         {
                             match signal {
-                                Signal::FlushAll => {}
+                                Signal::FlushAll
+                                | Signal::Reload
+                                | Signal::Stats(..)
+                                | Signal::DebugKey(..) => {}
                                 Signal::Shutdown => {
                                     // if we received a shutdown, we can return
                                     // and stop processing events
+                                    self.health.set_listening(false);
                                     return;
                                 }
                             }
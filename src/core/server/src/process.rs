@@ -37,6 +37,27 @@ where
         })
     }
 
+    /// Serve requests over UDP if a UDP port is configured. The parser is used
+    /// for each datagram, so it should only accept requests which can be
+    /// answered without a session.
+    pub fn udp<T: ServerConfig + WorkerConfig>(
+        mut self,
+        config: &T,
+        parser: Parser,
+    ) -> Result<Self> {
+        if let Some(addr) = config.server().udp_socket_addr() {
+            let addr = addr.map_err(|e| {
+                error!("{}", e);
+                std::io::Error::new(std::io::ErrorKind::Other, "Bad UDP listen address")
+            })?;
+
+            let socket = UdpSocket::bind(addr)?;
+            self.workers.udp(config, Udp::new(socket, parser))?;
+        }
+
+        Ok(self)
+    }
+
     pub fn version(mut self, version: &str) -> Self {
         self.admin.version(version);
         self
@@ -60,15 +81,23 @@ where
             QUEUE_CAPACITY,
         );
 
+        // every thread other than the admin publishes a heartbeat, which is
+        // used by the admin health endpoints
+        let health = self.admin.health();
+
         let mut admin = self
             .admin
             .build(self.log_drain, signal_rx, signal_queue_tx.remove(0));
 
-        let mut listener = self
-            .listener
-            .build(signal_queue_rx.remove(0), listener_session_queues.remove(0));
+        let mut listener = self.listener.build(
+            signal_queue_rx.remove(0),
+            listener_session_queues.remove(0),
+            health.clone(),
+        );
 
-        let workers = self.workers.build(worker_session_queues, signal_queue_rx);
+        let workers = self
+            .workers
+            .build(worker_session_queues, signal_queue_rx, &health);
 
         let admin = std::thread::Builder::new()
             .name(format!("{}_admin", THREAD_PREFIX))
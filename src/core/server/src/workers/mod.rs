@@ -8,11 +8,15 @@ use std::thread::JoinHandle;
 mod multi;
 mod single;
 mod storage;
+mod udp;
 
 use multi::*;
 use single::*;
 use storage::*;
 
+pub use udp::Udp;
+use udp::*;
+
 heatmap!(
     WORKER_EVENT_DEPTH,
     100_000,
@@ -46,6 +50,7 @@ pub enum Workers<Parser, Request, Response, Storage> {
     Multi {
         workers: Vec<MultiWorker<Parser, Request, Response>>,
         storage: StorageWorker<Request, Response, Storage, Token>,
+        udp: Option<UdpWorker<Parser, Request, Response>>,
     },
 }
 
@@ -67,6 +72,7 @@ where
             Self::Multi {
                 mut workers,
                 mut storage,
+                udp,
             } => {
                 let mut join_handles = vec![std::thread::Builder::new()
                     .name(format!("{}_storage", THREAD_PREFIX))
@@ -82,6 +88,15 @@ where
                     )
                 }
 
+                if let Some(mut udp) = udp {
+                    join_handles.push(
+                        std::thread::Builder::new()
+                            .name(format!("{}_udp", THREAD_PREFIX))
+                            .spawn(move || udp.run())
+                            .unwrap(),
+                    )
+                }
+
                 join_handles
             }
         }
@@ -95,6 +110,7 @@ pub enum WorkersBuilder<Parser, Request, Response, Storage> {
     Multi {
         workers: Vec<MultiWorkerBuilder<Parser, Request, Response>>,
         storage: StorageWorkerBuilder<Request, Response, Storage>,
+        udp: Option<UdpWorkerBuilder<Parser, Request, Response>>,
     },
 }
 
@@ -116,6 +132,7 @@ where
             Ok(Self::Multi {
                 workers,
                 storage: StorageWorkerBuilder::new(config, storage)?,
+                udp: None,
             })
         } else {
             Ok(Self::Single {
@@ -124,15 +141,26 @@ where
         }
     }
 
+    /// Serve requests received on the UDP socket. With a single worker, the
+    /// worker handles the socket itself. Otherwise, a dedicated UDP worker
+    /// passes requests to the storage thread.
+    pub fn udp<T: WorkerConfig>(&mut self, config: &T, udp: Udp<Parser>) -> Result<()> {
+        match self {
+            Self::Single { worker } => worker.udp(udp),
+            Self::Multi { udp: builder, .. } => {
+                *builder = Some(UdpWorkerBuilder::new(config, udp)?);
+                Ok(())
+            }
+        }
+    }
+
+    /// The wakers for the workers which receive sessions from the listener.
     pub fn worker_wakers(&self) -> Vec<Arc<Waker>> {
         match self {
             Self::Single { worker } => {
                 vec![worker.waker()]
             }
-            Self::Multi {
-                workers,
-                storage: _,
-            } => workers.iter().map(|w| w.waker()).collect(),
+            Self::Multi { workers, .. } => workers.iter().map(|w| w.waker()).collect(),
         }
     }
 
@@ -141,11 +169,18 @@ where
             Self::Single { worker } => {
                 vec![worker.waker()]
             }
-            Self::Multi { workers, storage } => {
+            Self::Multi {
+                workers,
+                storage,
+                udp,
+            } => {
                 let mut wakers = vec![storage.waker()];
                 for worker in workers {
                     wakers.push(worker.waker());
                 }
+                if let Some(udp) = udp {
+                    wakers.push(udp.waker());
+                }
                 wakers
             }
         }
@@ -154,7 +189,8 @@ where
     pub fn build(
         self,
         session_queues: Vec<Queues<Session, Session>>,
-        signal_queues: Vec<Queues<(), Signal>>,
+        signal_queues: Vec<Queues<StorageStats, Signal>>,
+        health: &Health,
     ) -> Workers<Parser, Request, Response, Storage> {
         let mut signal_queues = signal_queues;
         let mut session_queues = session_queues;
@@ -162,9 +198,14 @@ where
             Self::Multi {
                 storage,
                 mut workers,
+                udp,
             } => {
                 let storage_wakers = vec![storage.waker()];
-                let worker_wakers: Vec<Arc<Waker>> = workers.iter().map(|v| v.waker()).collect();
+                let mut worker_wakers: Vec<Arc<Waker>> =
+                    workers.iter().map(|v| v.waker()).collect();
+                if let Some(udp) = &udp {
+                    worker_wakers.push(udp.waker());
+                }
                 let (mut worker_data_queues, mut storage_data_queues) =
                     Queues::new(worker_wakers, storage_wakers, QUEUE_CAPACITY);
 
@@ -174,24 +215,44 @@ where
                 // only) element of `request_queues`. We remove these and build
                 // the storage so we can loop through the remaining signal
                 // queues when launching the worker threads.
-                let s = storage.build(storage_data_queues.remove(0), signal_queues.remove(0));
+                let s = storage.build(
+                    storage_data_queues.remove(0),
+                    signal_queues.remove(0),
+                    health.heartbeat("storage"),
+                );
 
                 let mut w = Vec::new();
-                for worker_builder in workers.drain(..) {
+                for (id, worker_builder) in workers.drain(..).enumerate() {
                     w.push(worker_builder.build(
                         worker_data_queues.remove(0),
                         session_queues.remove(0),
                         signal_queues.remove(0),
+                        health.heartbeat(&format!("work_{}", id)),
                     ));
                 }
 
+                // The UDP worker follows the worker threads, so it takes the
+                // remaining data and signal queues.
+                let u = udp.map(|builder| {
+                    builder.build(
+                        worker_data_queues.remove(0),
+                        signal_queues.remove(0),
+                        health.heartbeat("udp"),
+                    )
+                });
+
                 Workers::Multi {
                     storage: s,
                     workers: w,
+                    udp: u,
                 }
             }
             Self::Single { worker } => Workers::Single {
-                worker: worker.build(session_queues.remove(0), signal_queues.remove(0)),
+                worker: worker.build(
+                    session_queues.remove(0),
+                    signal_queues.remove(0),
+                    health.heartbeat("work"),
+                ),
             },
         }
     }
@@ -44,10 +44,12 @@ impl<Parser, Request, Response> MultiWorkerBuilder<Parser, Request, Response> {
         self,
         data_queue: Queues<(Request, Token), (Request, Response, Token)>,
         session_queue: Queues<Session, Session>,
-        signal_queue: Queues<(), Signal>,
+        signal_queue: Queues<StorageStats, Signal>,
+        heartbeat: Arc<Heartbeat>,
     ) -> MultiWorker<Parser, Request, Response> {
         MultiWorker {
             data_queue,
+            heartbeat,
             nevent: self.nevent,
             parser: self.parser,
             poll: self.poll,
@@ -62,12 +64,13 @@ impl<Parser, Request, Response> MultiWorkerBuilder<Parser, Request, Response> {
 
 pub struct MultiWorker<Parser, Request, Response> {
     data_queue: Queues<(Request, Token), (Request, Response, Token)>,
+    heartbeat: Arc<Heartbeat>,
     nevent: usize,
     parser: Parser,
     poll: Poll,
     session_queue: Queues<Session, Session>,
     sessions: Slab<ServerSession<Parser, Response, Request>>,
-    signal_queue: Queues<(), Signal>,
+    signal_queue: Queues<StorageStats, Signal>,
     timeout: Duration,
     waker: Arc<Waker>,
 }
@@ -130,6 +133,7 @@ where
 
         loop {
             WORKER_EVENT_LOOP.increment();
+            self.heartbeat.beat();
 
             // get events with timeout
             if self.poll.poll(&mut events, Some(self.timeout)).is_err() {
@@ -220,10 +224,17 @@ where
                             self.signal_queue.try_recv().map(|v| v.into_inner())
                         {
                             match signal {
-                                Signal::FlushAll => {}
+                                Signal::FlushAll
+                                | Signal::Reload
+                                | Signal::Stats(..)
+                                | Signal::DebugKey(..) => {}
                                 Signal::Shutdown => {
-                                    // if we received a shutdown, we can return
-                                    // and stop processing events
+                                    // if we received a shutdown, we send the
+                                    // responses which are still buffered, and
+                                    // then return and stop processing events
+                                    for (_, session) in self.sessions.iter_mut() {
+                                        let _ = session.flush();
+                                    }
                                     return;
                                 }
                             }
@@ -13,6 +13,7 @@ pub struct SingleWorkerBuilder<Parser, Request, Response, Storage> {
     sessions: Slab<ServerSession<Parser, Response, Request>>,
     storage: Storage,
     timeout: Duration,
+    udp: Option<Udp<Parser>>,
     waker: Arc<Waker>,
 }
 
@@ -37,6 +38,7 @@ impl<Parser, Request, Response, Storage> SingleWorkerBuilder<Parser, Request, Re
             sessions: Slab::new(),
             storage,
             timeout,
+            udp: None,
             waker,
         })
     }
@@ -45,12 +47,22 @@ impl<Parser, Request, Response, Storage> SingleWorkerBuilder<Parser, Request, Re
         self.waker.clone()
     }
 
+    /// Handle requests received on the UDP socket in this worker.
+    pub fn udp(&mut self, udp: Udp<Parser>) -> Result<()> {
+        let mut udp = udp;
+        udp.register(self.poll.registry())?;
+        self.udp = Some(udp);
+        Ok(())
+    }
+
     pub fn build(
         self,
         session_queue: Queues<Session, Session>,
-        signal_queue: Queues<(), Signal>,
+        signal_queue: Queues<StorageStats, Signal>,
+        heartbeat: Arc<Heartbeat>,
     ) -> SingleWorker<Parser, Request, Response, Storage> {
         SingleWorker {
+            heartbeat,
             nevent: self.nevent,
             parser: self.parser,
             pending: self.pending,
@@ -60,21 +72,24 @@ impl<Parser, Request, Response, Storage> SingleWorkerBuilder<Parser, Request, Re
             signal_queue,
             storage: self.storage,
             timeout: self.timeout,
+            udp: self.udp,
             waker: self.waker,
         }
     }
 }
 
 pub struct SingleWorker<Parser, Request, Response, Storage> {
+    heartbeat: Arc<Heartbeat>,
     nevent: usize,
     parser: Parser,
     pending: VecDeque<Token>,
     poll: Poll,
     session_queue: Queues<Session, Session>,
     sessions: Slab<ServerSession<Parser, Response, Request>>,
-    signal_queue: Queues<(), Signal>,
+    signal_queue: Queues<StorageStats, Signal>,
     storage: Storage,
     timeout: Duration,
+    udp: Option<Udp<Parser>>,
     waker: Arc<Waker>,
 }
 
@@ -166,6 +181,27 @@ where
         }
     }
 
+    /// Handle all datagrams which are ready on the UDP socket
+    fn receive_udp(&mut self) {
+        if let Some(udp) = self.udp.as_mut() {
+            while let Ok((request, peer)) = udp.receive() {
+                let response = self.storage.execute(&request);
+                PROCESS_REQ.increment();
+                request.klog(&response);
+                udp.send(peer, &response);
+            }
+        }
+    }
+
+    /// Sends the reply to a `Signal::Stats` or `Signal::DebugKey` back to the
+    /// admin thread
+    fn send_stats(&mut self, sender: usize, stats: StorageStats) {
+        if self.signal_queue.try_send_to(sender, stats).is_err() {
+            error!("error sending stats to admin");
+        }
+        let _ = self.signal_queue.wake();
+    }
+
     fn write(&mut self, token: Token) -> Result<()> {
         let session = self
             .sessions
@@ -184,6 +220,7 @@ where
 
         loop {
             WORKER_EVENT_LOOP.increment();
+            self.heartbeat.beat();
 
             self.storage.expire();
 
@@ -244,18 +281,37 @@ where
 
                         // check if we received any signals from the admin thread
                         while let Some(signal) = self.signal_queue.try_recv() {
+                            let sender = signal.sender();
                             match signal.into_inner() {
                                 Signal::FlushAll => {
                                     self.storage.clear();
                                 }
+                                Signal::Reload => {
+                                    self.storage.reload();
+                                }
+                                Signal::Stats(id, section) => {
+                                    let stats = Some(self.storage.stats(section));
+                                    self.send_stats(sender, StorageStats { id, stats });
+                                }
+                                Signal::DebugKey(id, key) => {
+                                    let stats = self.storage.debug_key(&key);
+                                    self.send_stats(sender, StorageStats { id, stats });
+                                }
                                 Signal::Shutdown => {
-                                    // if we received a shutdown, we can return
-                                    // and stop processing events
+                                    // if we received a shutdown, we send the
+                                    // responses which are still buffered, and
+                                    // then return and stop processing events
+                                    for (_, session) in self.sessions.iter_mut() {
+                                        let _ = session.flush();
+                                    }
                                     return;
                                 }
                             }
                         }
                     }
+                    UDP_TOKEN => {
+                        self.receive_udp();
+                    }
                     _ => {
                         if event.is_error() {
                             WORKER_EVENT_ERROR.increment();
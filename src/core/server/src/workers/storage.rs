@@ -55,10 +55,12 @@ impl<Request, Response, Storage> StorageWorkerBuilder<Request, Response, Storage
     pub fn build(
         self,
         data_queue: Queues<(Request, Response, Token), (Request, Token)>,
-        signal_queue: Queues<(), Signal>,
+        signal_queue: Queues<StorageStats, Signal>,
+        heartbeat: Arc<Heartbeat>,
     ) -> StorageWorker<Request, Response, Storage, Token> {
         StorageWorker {
             data_queue,
+            heartbeat,
             nevent: self.nevent,
             poll: self.poll,
             signal_queue,
@@ -73,9 +75,10 @@ impl<Request, Response, Storage> StorageWorkerBuilder<Request, Response, Storage
 
 pub struct StorageWorker<Request, Response, Storage, Token> {
     data_queue: Queues<(Request, Response, Token), (Request, Token)>,
+    heartbeat: Arc<Heartbeat>,
     nevent: usize,
     poll: Poll,
-    signal_queue: Queues<(), Signal>,
+    signal_queue: Queues<StorageStats, Signal>,
     storage: Storage,
     timeout: Duration,
     #[allow(dead_code)]
@@ -90,6 +93,15 @@ where
     Request: Klog + Klog<Response = Response>,
     Response: Compose,
 {
+    /// Sends the reply to a `Signal::Stats` or `Signal::DebugKey` back to the
+    /// admin thread
+    fn send_stats(&mut self, sender: usize, stats: StorageStats) {
+        if self.signal_queue.try_send_to(sender, stats).is_err() {
+            error!("error sending stats to admin");
+        }
+        let _ = self.signal_queue.wake();
+    }
+
     /// Run the `StorageWorker` in a loop, handling new session events.
     pub fn run(&mut self) {
         let mut events = Events::with_capacity(self.nevent);
@@ -97,6 +109,7 @@ where
 
         loop {
             STORAGE_EVENT_LOOP.increment();
+            self.heartbeat.beat();
 
             self.storage.expire();
 
@@ -140,12 +153,25 @@ where
                 let _ = self.data_queue.wake();
 
                 // check if we received any signals from the admin thread
-                while let Some(s) = self.signal_queue.try_recv().map(|v| v.into_inner()) {
-                    match s {
+                while let Some(signal) = self.signal_queue.try_recv() {
+                    let sender = signal.sender();
+                    match signal.into_inner() {
                         Signal::FlushAll => {
                             warn!("received flush_all");
                             self.storage.clear();
                         }
+                        Signal::Reload => {
+                            warn!("received reload");
+                            self.storage.reload();
+                        }
+                        Signal::Stats(id, section) => {
+                            let stats = Some(self.storage.stats(section));
+                            self.send_stats(sender, StorageStats { id, stats });
+                        }
+                        Signal::DebugKey(id, key) => {
+                            let stats = self.storage.debug_key(&key);
+                            self.send_stats(sender, StorageStats { id, stats });
+                        }
                         Signal::Shutdown => {
                             // if we received a shutdown, we can return and stop
                             // processing events
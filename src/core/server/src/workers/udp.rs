@@ -0,0 +1,296 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Support for serving requests over UDP using the memcached framing. Each
+//! datagram starts with an 8 byte frame header which contains the request id,
+//! the sequence number of the datagram, the total number of datagrams in the
+//! message, and a reserved field. All fields are big-endian `u16`s.
+//!
+//! Requests must fit within a single datagram. Responses are split across as
+//! many datagrams as needed, each carrying the request id so the client can
+//! reassemble them.
+
+use super::*;
+use std::net::SocketAddr;
+
+const HEADER_LEN: usize = 8;
+
+// the maximum datagram size for responses, including the frame header, which
+// matches memcached and keeps responses within a typical ethernet MTU
+const MAX_DATAGRAM_LEN: usize = 1400;
+const MAX_PAYLOAD_LEN: usize = MAX_DATAGRAM_LEN - HEADER_LEN;
+
+// large enough to receive any datagram
+const RECV_BUFFER_LEN: usize = 64 * 1024;
+
+counter!(UDP_REQUEST, "the number of requests received over UDP");
+counter!(
+    UDP_REQUEST_DISCARD,
+    "the number of UDP datagrams discarded without a response"
+);
+counter!(
+    UDP_RESPONSE_DISCARD,
+    "the number of UDP responses which could not be fully sent"
+);
+
+/// The destination for the response to a UDP request.
+pub struct UdpPeer {
+    addr: SocketAddr,
+    id: u16,
+}
+
+/// A UDP socket which receives framed requests and sends framed responses.
+pub struct Udp<Parser> {
+    socket: UdpSocket,
+    parser: Parser,
+    recv_buffer: Box<[u8]>,
+    send_buffer: Vec<u8>,
+}
+
+impl<Parser> Udp<Parser> {
+    pub fn new(socket: UdpSocket, parser: Parser) -> Self {
+        Self {
+            socket,
+            parser,
+            recv_buffer: vec![0; RECV_BUFFER_LEN].into_boxed_slice(),
+            send_buffer: Vec::with_capacity(MAX_PAYLOAD_LEN),
+        }
+    }
+
+    pub fn register(&mut self, registry: &Registry) -> Result<()> {
+        self.socket
+            .register(registry, UDP_TOKEN, Interest::READABLE)
+    }
+
+    /// Receive the next request. Datagrams which do not hold exactly one
+    /// complete request in a single frame are discarded.
+    ///
+    /// An error `e` with `e.kind()` of `ErrorKind::WouldBlock` indicates that
+    /// there are no datagrams waiting to be received.
+    pub fn receive<Request>(&mut self) -> Result<(Request, UdpPeer)>
+    where
+        Parser: Parse<Request> + Clone,
+    {
+        loop {
+            let (len, addr) = self.socket.recv_from(&mut self.recv_buffer)?;
+
+            if let Some((request, id)) = self.parse(&self.recv_buffer[..len]) {
+                UDP_REQUEST.increment();
+                return Ok((request, UdpPeer { addr, id }));
+            }
+
+            UDP_REQUEST_DISCARD.increment();
+        }
+    }
+
+    fn parse<Request>(&self, datagram: &[u8]) -> Option<(Request, u16)>
+    where
+        Parser: Parse<Request> + Clone,
+    {
+        if datagram.len() < HEADER_LEN {
+            return None;
+        }
+
+        let field = |idx: usize| u16::from_be_bytes([datagram[idx], datagram[idx + 1]]);
+        let (id, sequence, total) = (field(0), field(2), field(4));
+
+        if sequence != 0 || total != 1 {
+            return None;
+        }
+
+        // there is no session, so each datagram gets a fresh copy of the parser
+        // and no parser state is carried between peers
+        let payload = &datagram[HEADER_LEN..];
+        let parsed = self.parser.clone().parse(payload).ok()?;
+
+        if parsed.consumed() != payload.len() {
+            return None;
+        }
+
+        Some((parsed.into_inner(), id))
+    }
+
+    /// Compose the response and send it to the peer, split across as many
+    /// datagrams as are needed. Sending is best-effort, if the socket would
+    /// block the remainder of the response is dropped.
+    pub fn send<Response: Compose>(&mut self, peer: UdpPeer, response: &Response) {
+        self.send_buffer.clear();
+        response.compose(&mut self.send_buffer);
+
+        let total = (self.send_buffer.len() + MAX_PAYLOAD_LEN - 1) / MAX_PAYLOAD_LEN;
+        if total > u16::MAX as usize {
+            UDP_RESPONSE_DISCARD.increment();
+            return;
+        }
+
+        let mut datagram = [0; MAX_DATAGRAM_LEN];
+        datagram[0..2].copy_from_slice(&peer.id.to_be_bytes());
+        datagram[4..6].copy_from_slice(&(total as u16).to_be_bytes());
+
+        for (sequence, chunk) in self.send_buffer.chunks(MAX_PAYLOAD_LEN).enumerate() {
+            datagram[2..4].copy_from_slice(&(sequence as u16).to_be_bytes());
+            datagram[HEADER_LEN..(HEADER_LEN + chunk.len())].copy_from_slice(chunk);
+
+            if self
+                .socket
+                .send_to(&datagram[..(HEADER_LEN + chunk.len())], peer.addr)
+                .is_err()
+            {
+                UDP_RESPONSE_DISCARD.increment();
+                return;
+            }
+        }
+    }
+}
+
+pub struct UdpWorkerBuilder<Parser, Request, Response> {
+    nevent: usize,
+    poll: Poll,
+    timeout: Duration,
+    udp: Udp<Parser>,
+    waker: Arc<Waker>,
+    _request: PhantomData<Request>,
+    _response: PhantomData<Response>,
+}
+
+impl<Parser, Request, Response> UdpWorkerBuilder<Parser, Request, Response> {
+    pub fn new<T: WorkerConfig>(config: &T, udp: Udp<Parser>) -> Result<Self> {
+        let config = config.worker();
+
+        let poll = Poll::new()?;
+
+        let mut udp = udp;
+        udp.register(poll.registry())?;
+
+        let waker = Arc::new(Waker::from(
+            ::net::Waker::new(poll.registry(), WAKER_TOKEN).unwrap(),
+        ));
+
+        let nevent = config.nevent();
+        let timeout = Duration::from_millis(config.timeout() as u64);
+
+        Ok(Self {
+            nevent,
+            poll,
+            timeout,
+            udp,
+            waker,
+            _request: PhantomData,
+            _response: PhantomData,
+        })
+    }
+
+    pub fn waker(&self) -> Arc<Waker> {
+        self.waker.clone()
+    }
+
+    pub fn build(
+        self,
+        data_queue: Queues<(Request, Token), (Request, Response, Token)>,
+        signal_queue: Queues<StorageStats, Signal>,
+        heartbeat: Arc<Heartbeat>,
+    ) -> UdpWorker<Parser, Request, Response> {
+        UdpWorker {
+            data_queue,
+            heartbeat,
+            nevent: self.nevent,
+            pending: Slab::new(),
+            poll: self.poll,
+            signal_queue,
+            timeout: self.timeout,
+            udp: self.udp,
+            waker: self.waker,
+        }
+    }
+}
+
+/// A worker which handles UDP requests when there are multiple workers. As
+/// with the other workers, requests are executed by the storage thread.
+pub struct UdpWorker<Parser, Request, Response> {
+    data_queue: Queues<(Request, Token), (Request, Response, Token)>,
+    heartbeat: Arc<Heartbeat>,
+    nevent: usize,
+    pending: Slab<UdpPeer>,
+    poll: Poll,
+    signal_queue: Queues<StorageStats, Signal>,
+    timeout: Duration,
+    udp: Udp<Parser>,
+    waker: Arc<Waker>,
+}
+
+impl<Parser, Request, Response> UdpWorker<Parser, Request, Response>
+where
+    Parser: Parse<Request> + Clone,
+    Request: Klog + Klog<Response = Response>,
+    Response: Compose,
+{
+    /// Send all datagrams which are ready to the storage thread
+    fn receive(&mut self) {
+        while let Ok((request, peer)) = self.udp.receive() {
+            let entry = self.pending.vacant_entry();
+            let token = Token(entry.key());
+            if self.data_queue.try_send_to(0, (request, token)).is_ok() {
+                entry.insert(peer);
+            } else {
+                UDP_REQUEST_DISCARD.increment();
+            }
+        }
+    }
+
+    /// Run the worker in a loop, handling new events.
+    pub fn run(&mut self) {
+        let mut events = Events::with_capacity(self.nevent);
+        let mut messages = Vec::with_capacity(QUEUE_CAPACITY);
+
+        loop {
+            self.heartbeat.beat();
+
+            // get events with timeout
+            if self.poll.poll(&mut events, Some(self.timeout)).is_err() {
+                error!("Error polling");
+            }
+
+            for event in events.iter() {
+                match event.token() {
+                    UDP_TOKEN => {
+                        self.receive();
+                    }
+                    WAKER_TOKEN => {
+                        self.waker.reset();
+
+                        // send all responses from the storage thread
+                        self.data_queue.try_recv_all(&mut messages);
+                        for (request, response, token) in messages.drain(..).map(|v| v.into_inner())
+                        {
+                            request.klog(&response);
+                            if self.pending.contains(token.0) {
+                                let peer = self.pending.remove(token.0);
+                                self.udp.send(peer, &response);
+                            }
+                        }
+
+                        // check if we received any signals from the admin thread
+                        while let Some(signal) =
+                            self.signal_queue.try_recv().map(|v| v.into_inner())
+                        {
+                            match signal {
+                                Signal::FlushAll
+                                | Signal::Reload
+                                | Signal::Stats(..)
+                                | Signal::DebugKey(..) => {}
+                                Signal::Shutdown => {
+                                    return;
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            // wakes the storage thread if necessary
+            let _ = self.data_queue.wake();
+        }
+    }
+}
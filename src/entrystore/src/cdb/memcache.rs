@@ -0,0 +1,165 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! This module defines how `Cdb` storage will be used to execute `Memcache`
+//! storage commands. Only retrievals are supported, all other commands are
+//! rejected without closing the connection.
+
+use super::*;
+use protocol_common::*;
+
+use protocol_memcache::*;
+
+const READ_ONLY: &str = "read-only";
+
+impl Execute<Request, Response> for Cdb {
+    fn execute(&mut self, request: &Request) -> Response {
+        match request {
+            Request::Get(get) => self.get(get),
+            Request::Gets(gets) => self.gets(gets),
+            Request::Set(set) => self.set(set),
+            Request::Add(add) => self.add(add),
+            Request::Replace(replace) => self.replace(replace),
+            Request::Cas(cas) => self.cas(cas),
+            Request::Incr(incr) => self.incr(incr),
+            Request::Decr(decr) => self.decr(decr),
+            Request::Append(append) => self.append(append),
+            Request::Prepend(prepend) => self.prepend(prepend),
+            Request::Delete(delete) => self.delete(delete),
+            Request::FlushAll(flush_all) => self.flush_all(flush_all),
+            Request::MetaArithmetic(meta_arithmetic) => self.meta_arithmetic(meta_arithmetic),
+            Request::MetaDebug(meta_debug) => self.meta_debug(meta_debug),
+            Request::MetaDelete(meta_delete) => self.meta_delete(meta_delete),
+            Request::MetaGet(meta_get) => self.meta_get(meta_get),
+            Request::MetaNoop(meta_noop) => self.meta_noop(meta_noop),
+            Request::MetaSet(meta_set) => self.meta_set(meta_set),
+            Request::Quit(quit) => self.quit(quit),
+            Request::Stats(stats) => self.stats(stats),
+            Request::Verbosity(verbosity) => self.verbosity(verbosity),
+            Request::Version(version) => self.version(version),
+            Request::Auth(auth) => auth.response(),
+            Request::Invalid(invalid) => invalid.response(),
+            Request::Binary(binary) => {
+                // without the meta commands, incr and decr can't create the
+                // item on a miss
+                let response = match binary.basic_request() {
+                    Some(request) => self.execute(&request),
+                    None => self.execute(binary.request()),
+                };
+                binary.response(response, None)
+            }
+        }
+    }
+}
+
+impl Storage for Cdb {
+    fn get(&mut self, get: &Get) -> Response {
+        let mut values = Vec::with_capacity(get.keys().len());
+        for key in get.keys().iter() {
+            if let Some(value) = self.data.get(key) {
+                values.push(Value::new(key, 0, None, value));
+            } else {
+                values.push(Value::none(key));
+            }
+        }
+        Values::new(values.into_boxed_slice()).into()
+    }
+
+    // items are never modified, so all items share a cas value of zero
+    fn gets(&mut self, gets: &Gets) -> Response {
+        let mut values = Vec::with_capacity(gets.keys().len());
+        for key in gets.keys().iter() {
+            if let Some(value) = self.data.get(key) {
+                values.push(Value::new(key, 0, Some(0), value));
+            } else {
+                values.push(Value::none(key));
+            }
+        }
+        Values::new(values.into_boxed_slice()).into()
+    }
+
+    fn set(&mut self, _: &Set) -> Response {
+        Response::server_error(READ_ONLY)
+    }
+
+    fn add(&mut self, _: &Add) -> Response {
+        Response::server_error(READ_ONLY)
+    }
+
+    fn replace(&mut self, _: &Replace) -> Response {
+        Response::server_error(READ_ONLY)
+    }
+
+    fn append(&mut self, _: &Append) -> Response {
+        Response::server_error(READ_ONLY)
+    }
+
+    fn prepend(&mut self, _: &Prepend) -> Response {
+        Response::server_error(READ_ONLY)
+    }
+
+    fn incr(&mut self, _: &Incr) -> Response {
+        Response::server_error(READ_ONLY)
+    }
+
+    fn decr(&mut self, _: &Decr) -> Response {
+        Response::server_error(READ_ONLY)
+    }
+
+    fn cas(&mut self, _: &Cas) -> Response {
+        Response::server_error(READ_ONLY)
+    }
+
+    fn delete(&mut self, _: &Delete) -> Response {
+        Response::server_error(READ_ONLY)
+    }
+
+    fn flush_all(&mut self, _: &FlushAll) -> Response {
+        Response::server_error(READ_ONLY)
+    }
+
+    fn meta_arithmetic(&mut self, _: &MetaArithmetic) -> Response {
+        Response::error()
+    }
+
+    fn meta_debug(&mut self, _: &MetaDebug) -> Response {
+        Response::error()
+    }
+
+    fn meta_delete(&mut self, _: &MetaDelete) -> Response {
+        Response::error()
+    }
+
+    fn meta_get(&mut self, _: &MetaGet) -> Response {
+        Response::error()
+    }
+
+    fn meta_noop(&mut self, _: &MetaNoop) -> Response {
+        Meta::new(MetaCode::Noop).into()
+    }
+
+    fn meta_set(&mut self, _: &MetaSet) -> Response {
+        Response::error()
+    }
+
+    fn quit(&mut self, _: &Quit) -> Response {
+        Response::hangup()
+    }
+
+    fn stats(&mut self, stats: &Stats) -> Response {
+        // only the general stats apply to this storage type
+        match stats.group() {
+            StatsGroup::General => StatsInfo::general(env!("CARGO_PKG_VERSION")).into(),
+            _ => StatsInfo::new().into(),
+        }
+    }
+
+    fn verbosity(&mut self, verbosity: &Verbosity) -> Response {
+        Response::ok(verbosity.noreply())
+    }
+
+    fn version(&mut self, _: &Version) -> Response {
+        Response::version(env!("CARGO_PKG_VERSION"))
+    }
+}
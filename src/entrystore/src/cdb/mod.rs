@@ -0,0 +1,64 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Read-only storage backed by a memory-mapped cdb file. The file is built
+//! offline and can be atomically replaced by renaming a new file into place and
+//! then triggering a reload from the admin port.
+//! See: [`::cdb`] crate for more details about the file format.
+
+use crate::EntryStore;
+
+use config::CdbConfig;
+
+use std::path::PathBuf;
+
+mod memcache;
+mod resp;
+
+/// A wrapper around [`cdb::Cdb`] which implements `EntryStore` and storage
+/// protocol traits.
+pub struct Cdb {
+    data: ::cdb::Cdb,
+    path: PathBuf,
+}
+
+impl Cdb {
+    /// Open `Cdb` storage based on the config.
+    pub fn new<T: CdbConfig>(config: &T) -> Result<Self, std::io::Error> {
+        let path = config.cdb().path();
+
+        let data = ::cdb::Cdb::open(&path).map_err(|e| {
+            std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("failed to open cdb file {}: {}", path.display(), e),
+            )
+        })?;
+
+        Ok(Self { data, path })
+    }
+}
+
+impl EntryStore for Cdb {
+    // the contents of the file are immutable, so there is nothing to clear
+    fn clear(&mut self) {}
+
+    // the new file is fully opened and validated before it replaces the
+    // current one, so requests are always served from a complete file. if the
+    // new file cannot be opened, we continue serving from the current one.
+    fn reload(&mut self) {
+        match ::cdb::Cdb::open(&self.path) {
+            Ok(data) => {
+                info!(
+                    "reloaded cdb file {} ({} bytes)",
+                    self.path.display(),
+                    data.len()
+                );
+                self.data = data;
+            }
+            Err(e) => {
+                error!("failed to reload cdb file {}: {}", self.path.display(), e);
+            }
+        }
+    }
+}
@@ -0,0 +1,142 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! This module defines how `Cdb` storage will be used to execute `RESP`
+//! storage commands. Only reads are supported, write commands are rejected
+//! with a `READONLY` error.
+
+use super::*;
+use protocol_common::*;
+
+use protocol_resp::*;
+
+const READ_ONLY: &str = "READONLY You can't write against a read only server.";
+const WRONGTYPE: &str = "WRONGTYPE Operation against a key holding the wrong kind of value";
+
+impl Execute<Request, Response> for Cdb {
+    fn execute(&mut self, request: &Request) -> Response {
+        match request {
+            Request::Get(get) => match self.data.get(get.key()) {
+                Some(value) => Response::bulk_string(value),
+                None => Response::null(),
+            },
+            Request::Echo(echo) => echo.response(),
+            // keys in a cdb file never change, so watched keys cannot abort
+            // a transaction
+            Request::Exec(exec) => match exec.error() {
+                Some(response) => response,
+                None => Response::array(
+                    exec.requests()
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|request| self.execute(request))
+                        .collect(),
+                ),
+            },
+            Request::Exists(exists) => {
+                let found = exists
+                    .keys()
+                    .iter()
+                    .filter(|key| self.data.get(key).is_some())
+                    .count();
+                Response::integer(found as i64)
+            }
+            Request::MGet(mget) => {
+                let values = mget
+                    .keys()
+                    .iter()
+                    .map(|key| match self.data.get(key) {
+                        Some(value) => Response::bulk_string(value),
+                        None => Response::null(),
+                    })
+                    .collect();
+                Response::array(values)
+            }
+            Request::Ping(ping) => ping.response(),
+            Request::Discard(discard) => discard.response(),
+            Request::Multi(multi) => multi.response(),
+            Request::Invalid(invalid) => invalid.response(),
+            Request::Queued(queued) => queued.response(),
+            Request::Resp3(resp3) => resp3.response(self.execute(resp3.request())),
+            Request::Unwatch(unwatch) => unwatch.response(),
+            Request::Watch(watch) => watch.watch(|_| None),
+            Request::StrLen(strlen) => match self.data.get(strlen.key()) {
+                Some(value) => Response::integer(value.len() as i64),
+                None => Response::integer(0),
+            },
+            Request::PTtl(pttl) => self.key_ttl(pttl.key()),
+            Request::Ttl(ttl) => self.key_ttl(ttl.key()),
+            Request::Type(request) => match self.data.get(request.key()) {
+                Some(_) => Response::simple_string("string"),
+                None => Response::simple_string("none"),
+            },
+            Request::BCard(bcard) => self.collection_read(bcard.outer_key(), Response::integer(0)),
+            Request::BGet(bget) => self.collection_read(bget.outer_key(), Response::null()),
+            Request::BRange(brange) => {
+                self.collection_read(brange.outer_key(), Response::array(Vec::new()))
+            }
+            Request::HExists(hexists) => self.collection_read(hexists.key(), Response::integer(0)),
+            Request::HGet(hget) => self.collection_read(hget.key(), Response::null()),
+            Request::HGetAll(hgetall) => {
+                self.collection_read(hgetall.key(), Response::array(Vec::new()))
+            }
+            Request::HLen(hlen) => self.collection_read(hlen.key(), Response::integer(0)),
+            Request::Hello(hello) => hello.response(),
+            Request::HMGet(hmget) => {
+                let nulls = hmget.fields().iter().map(|_| Response::null()).collect();
+                self.collection_read(hmget.key(), Response::array(nulls))
+            }
+            Request::LIndex(lindex) => self.collection_read(lindex.key(), Response::null()),
+            Request::LLen(llen) => self.collection_read(llen.key(), Response::integer(0)),
+            Request::LRange(lrange) => {
+                self.collection_read(lrange.key(), Response::array(Vec::new()))
+            }
+            Request::Append(_)
+            | Request::BAdd(_)
+            | Request::BDel(_)
+            | Request::Decr(_)
+            | Request::DecrBy(_)
+            | Request::Del(_)
+            | Request::Expire(_)
+            | Request::FlushDb(_)
+            | Request::GetSet(_)
+            | Request::HDel(_)
+            | Request::HIncrBy(_)
+            | Request::HSet(_)
+            | Request::Incr(_)
+            | Request::IncrBy(_)
+            | Request::LPop(_)
+            | Request::LPush(_)
+            | Request::LTrim(_)
+            | Request::MSet(_)
+            | Request::Persist(_)
+            | Request::PExpire(_)
+            | Request::RPop(_)
+            | Request::RPush(_)
+            | Request::Set(_)
+            | Request::SetNx(_) => Response::error(READ_ONLY),
+        }
+    }
+}
+
+impl Cdb {
+    /// All values in a cdb file are strings, so collection reads either find a
+    /// value of the wrong type or treat the key as an empty collection.
+    fn collection_read(&self, key: &[u8], empty: Response) -> Response {
+        if self.data.get(key).is_some() {
+            Response::error(WRONGTYPE)
+        } else {
+            empty
+        }
+    }
+
+    /// The reply to `TTL` and `PTTL`, as keys in a cdb file do not expire.
+    fn key_ttl(&self, key: &[u8]) -> Response {
+        if self.data.get(key).is_some() {
+            Response::integer(-1)
+        } else {
+            Response::integer(-2)
+        }
+    }
+}
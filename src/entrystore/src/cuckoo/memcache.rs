@@ -0,0 +1,312 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! This module defines how `Cuckoo` storage will be used to execute `Memcache`
+//! storage commands.
+
+use super::*;
+use protocol_common::*;
+
+use protocol_memcache::*;
+
+use std::time::Duration;
+
+impl Execute<Request, Response> for Cuckoo {
+    fn execute(&mut self, request: &Request) -> Response {
+        match request {
+            Request::Get(get) => self.get(get),
+            Request::Gets(gets) => self.gets(gets),
+            Request::Set(set) => self.set(set),
+            Request::Add(add) => self.add(add),
+            Request::Replace(replace) => self.replace(replace),
+            Request::Cas(cas) => self.cas(cas),
+            Request::Incr(incr) => self.incr(incr),
+            Request::Decr(decr) => self.decr(decr),
+            Request::Append(append) => self.append(append),
+            Request::Prepend(prepend) => self.prepend(prepend),
+            Request::Delete(delete) => self.delete(delete),
+            Request::FlushAll(flush_all) => self.flush_all(flush_all),
+            Request::MetaArithmetic(meta_arithmetic) => self.meta_arithmetic(meta_arithmetic),
+            Request::MetaDebug(meta_debug) => self.meta_debug(meta_debug),
+            Request::MetaDelete(meta_delete) => self.meta_delete(meta_delete),
+            Request::MetaGet(meta_get) => self.meta_get(meta_get),
+            Request::MetaNoop(meta_noop) => self.meta_noop(meta_noop),
+            Request::MetaSet(meta_set) => self.meta_set(meta_set),
+            Request::Quit(quit) => self.quit(quit),
+            Request::Stats(stats) => self.stats(stats),
+            Request::Verbosity(verbosity) => self.verbosity(verbosity),
+            Request::Version(version) => self.version(version),
+            Request::Auth(auth) => auth.response(),
+            Request::Invalid(invalid) => invalid.response(),
+            Request::Binary(binary) => {
+                // without the meta commands, incr and decr can't create the
+                // item on a miss
+                let response = match binary.basic_request() {
+                    Some(request) => self.execute(&request),
+                    None => self.execute(binary.request()),
+                };
+                // the binary protocol returns the cas value of the modified item
+                let cas = binary
+                    .modified_key()
+                    .and_then(|key| self.data.get(key))
+                    .map(|item| item.cas().into());
+                binary.response(response, cas)
+            }
+        }
+    }
+}
+
+impl Cuckoo {
+    /// Stores the value, using a numeric representation when the value can be
+    /// parsed as an unsigned integer so that it may be used with incr/decr.
+    fn store(
+        &mut self,
+        key: &[u8],
+        value: &[u8],
+        flags: u32,
+        ttl: Duration,
+    ) -> Result<(), CuckooError> {
+        let flags = flags.to_be_bytes();
+        match std::str::from_utf8(value).map(|s| s.parse::<u64>()) {
+            Ok(Ok(v)) => self.data.insert(key, v, Some(&flags), ttl),
+            _ => self.data.insert(key, value, Some(&flags), ttl),
+        }
+    }
+
+    fn values(&mut self, keys: &[Box<[u8]>], include_cas: bool) -> Response {
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys.iter() {
+            if let Some(item) = self.data.get(key) {
+                let o = item.optional().unwrap_or(&[0, 0, 0, 0]);
+                let flags = u32::from_be_bytes([o[0], o[1], o[2], o[3]]);
+                let cas = if include_cas {
+                    Some(item.cas().into())
+                } else {
+                    None
+                };
+                match item.value() {
+                    cuckoo::Value::Bytes(b) => {
+                        values.push(Value::new(item.key(), flags, cas, b));
+                    }
+                    cuckoo::Value::U64(v) => {
+                        values.push(Value::new(
+                            item.key(),
+                            flags,
+                            cas,
+                            format!("{}", v).as_bytes(),
+                        ));
+                    }
+                }
+            } else {
+                values.push(Value::none(key));
+            }
+        }
+        Values::new(values.into_boxed_slice()).into()
+    }
+}
+
+impl Storage for Cuckoo {
+    fn get(&mut self, get: &Get) -> Response {
+        self.values(get.keys(), false)
+    }
+
+    fn gets(&mut self, gets: &Gets) -> Response {
+        self.values(gets.keys(), true)
+    }
+
+    fn set(&mut self, set: &Set) -> Response {
+        let ttl = set.ttl().get().unwrap_or(0);
+
+        if ttl < 0 {
+            // immediate expire maps to a delete
+            self.data.delete(set.key());
+            Response::stored(set.noreply())
+        } else if self
+            .store(
+                set.key(),
+                set.value(),
+                set.flags(),
+                Duration::from_secs(ttl as u64),
+            )
+            .is_ok()
+        {
+            Response::stored(set.noreply())
+        } else {
+            Response::server_error("")
+        }
+    }
+
+    fn add(&mut self, add: &Add) -> Response {
+        if self.data.get(add.key()).is_some() {
+            return Response::not_stored(add.noreply());
+        }
+
+        let ttl = add.ttl().get().unwrap_or(0);
+
+        if ttl < 0 {
+            // immediate expire maps to a delete
+            self.data.delete(add.key());
+            Response::stored(add.noreply())
+        } else if self
+            .store(
+                add.key(),
+                add.value(),
+                add.flags(),
+                Duration::from_secs(ttl as u64),
+            )
+            .is_ok()
+        {
+            Response::stored(add.noreply())
+        } else {
+            Response::server_error("")
+        }
+    }
+
+    fn replace(&mut self, replace: &Replace) -> Response {
+        if self.data.get(replace.key()).is_none() {
+            return Response::not_stored(replace.noreply());
+        }
+
+        let ttl = replace.ttl().get().unwrap_or(0);
+
+        if ttl < 0 {
+            // immediate expire maps to a delete
+            self.data.delete(replace.key());
+            Response::stored(replace.noreply())
+        } else if self
+            .store(
+                replace.key(),
+                replace.value(),
+                replace.flags(),
+                Duration::from_secs(ttl as u64),
+            )
+            .is_ok()
+        {
+            Response::stored(replace.noreply())
+        } else {
+            Response::server_error("")
+        }
+    }
+
+    fn append(&mut self, _: &Append) -> Response {
+        Response::error()
+    }
+
+    fn prepend(&mut self, _: &Prepend) -> Response {
+        Response::error()
+    }
+
+    fn incr(&mut self, incr: &Incr) -> Response {
+        match self.data.wrapping_add(incr.key(), incr.value()) {
+            Ok(item) => match item.value() {
+                cuckoo::Value::U64(v) => Response::numeric(v, incr.noreply()),
+                _ => Response::server_error(""),
+            },
+            Err(CuckooError::NotFound) => Response::not_found(incr.noreply()),
+            Err(CuckooError::NotNumeric) => {
+                Response::bad_request("cannot increment or decrement non-numeric value")
+            }
+            Err(_) => Response::server_error(""),
+        }
+    }
+
+    fn decr(&mut self, decr: &Decr) -> Response {
+        match self.data.saturating_sub(decr.key(), decr.value()) {
+            Ok(item) => match item.value() {
+                cuckoo::Value::U64(v) => Response::numeric(v, decr.noreply()),
+                _ => Response::server_error(""),
+            },
+            Err(CuckooError::NotFound) => Response::not_found(decr.noreply()),
+            Err(CuckooError::NotNumeric) => {
+                Response::bad_request("cannot increment or decrement non-numeric value")
+            }
+            Err(_) => Response::server_error(""),
+        }
+    }
+
+    fn cas(&mut self, cas: &Cas) -> Response {
+        let ttl = cas.ttl().get().unwrap_or(0);
+
+        // a negative ttl results in the item being removed, but only if the cas
+        // value matches
+        let current = match self.data.get(cas.key()) {
+            Some(item) => item.cas(),
+            None => return Response::not_found(cas.noreply()),
+        };
+        if u64::from(current) != cas.cas() {
+            return Response::exists(cas.noreply());
+        }
+
+        if ttl < 0 {
+            self.data.delete(cas.key());
+            Response::stored(cas.noreply())
+        } else {
+            match self.store(
+                cas.key(),
+                cas.value(),
+                cas.flags(),
+                Duration::from_secs(ttl as u64),
+            ) {
+                Ok(_) => Response::stored(cas.noreply()),
+                Err(_) => Response::server_error(""),
+            }
+        }
+    }
+
+    fn delete(&mut self, delete: &Delete) -> Response {
+        if self.data.delete(delete.key()) {
+            Response::deleted(delete.noreply())
+        } else {
+            Response::not_found(delete.noreply())
+        }
+    }
+
+    fn flush_all(&mut self, _flush_all: &FlushAll) -> Response {
+        Response::error()
+    }
+
+    fn meta_arithmetic(&mut self, _: &MetaArithmetic) -> Response {
+        Response::error()
+    }
+
+    fn meta_debug(&mut self, _: &MetaDebug) -> Response {
+        Response::error()
+    }
+
+    fn meta_delete(&mut self, _: &MetaDelete) -> Response {
+        Response::error()
+    }
+
+    fn meta_get(&mut self, _: &MetaGet) -> Response {
+        Response::error()
+    }
+
+    fn meta_noop(&mut self, _: &MetaNoop) -> Response {
+        Meta::new(MetaCode::Noop).into()
+    }
+
+    fn meta_set(&mut self, _: &MetaSet) -> Response {
+        Response::error()
+    }
+
+    fn quit(&mut self, _quit: &Quit) -> Response {
+        Response::hangup()
+    }
+
+    fn stats(&mut self, stats: &Stats) -> Response {
+        // only the general stats apply to this storage type
+        match stats.group() {
+            StatsGroup::General => StatsInfo::general(env!("CARGO_PKG_VERSION")).into(),
+            _ => StatsInfo::new().into(),
+        }
+    }
+
+    fn verbosity(&mut self, verbosity: &Verbosity) -> Response {
+        Response::ok(verbosity.noreply())
+    }
+
+    fn version(&mut self, _version: &Version) -> Response {
+        Response::version(env!("CARGO_PKG_VERSION"))
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Cuckoo hashing based storage with fixed-size item slots and a very low
+//! per-item overhead. This storage type is suitable for use in key-value cache
+//! backends where all values are small, such as counters.
+//! See: [`::cuckoo`] crate for more details behind the underlying storage design.
+
+use crate::EntryStore;
+
+use config::cuckoo::Policy as ConfigPolicy;
+use config::CuckooConfig;
+use cuckoo::{CuckooError, Policy};
+
+mod memcache;
+
+/// A wrapper around [`cuckoo::Cuckoo`] which implements `EntryStore` and
+/// storage protocol traits.
+pub struct Cuckoo {
+    data: ::cuckoo::Cuckoo,
+}
+
+impl Cuckoo {
+    /// Create `Cuckoo` storage based on the config.
+    pub fn new<T: CuckooConfig>(config: &T) -> Result<Self, std::io::Error> {
+        let config = config.cuckoo();
+
+        // build up the displacement policy from the config
+        let policy = match config.policy() {
+            ConfigPolicy::Random => Policy::Random,
+            ConfigPolicy::Expire => Policy::Expire,
+        };
+
+        // build the datastructure from the config
+        let data = ::cuckoo::Cuckoo::builder()
+            .item_size(config.item_size())
+            .nitem(config.nitem())
+            .max_displace(config.max_displace())
+            .max_ttl(config.max_ttl())
+            .policy(policy)
+            .datapool_path(config.datapool_path())
+            .build()?;
+
+        Ok(Self { data })
+    }
+}
+
+impl EntryStore for Cuckoo {
+    fn expire(&mut self) {
+        self.data.expire();
+    }
+
+    fn clear(&mut self) {
+        self.data.clear();
+    }
+}
@@ -7,12 +7,21 @@
 //! addition to the base `EntryStore` trait. For example [`Seg`] implements both
 //! [`EntryStore`] and [`protocol::memcache::MemcacheStorage`].
 
+#[macro_use]
+extern crate logger;
+
+mod cdb;
+mod cuckoo;
 mod noop;
 mod seg;
 
+pub use self::cdb::*;
+pub use self::cuckoo::*;
 pub use self::noop::*;
 pub use self::seg::*;
 
+use common::signal::StatsSection;
+
 /// A trait defining the basic requirements of a type which may be used for
 /// storage.
 pub trait EntryStore {
@@ -24,4 +33,24 @@ pub trait EntryStore {
 
     /// Remove all existing values from the entry store.
     fn clear(&mut self);
+
+    /// Reload the contents of the entry store from its backing source. This is
+    /// only meaningful for storage types which are loaded from an external
+    /// source, the default implementation is a no-op.
+    fn reload(&mut self) {}
+
+    /// Returns the stats for a section which is derived from the contents of
+    /// the entry store, as a list of names and values. The default
+    /// implementation returns no stats.
+    fn stats(&self, _section: StatsSection) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// Describes how the item with the key is stored, as a list of names and
+    /// values. Looking up the item must not count as an access to it. A lookup
+    /// for a key which is not found returns an empty list. The default
+    /// implementation returns `None`, as the storage does not support it.
+    fn debug_key(&mut self, _key: &[u8]) -> Option<Vec<(String, String)>> {
+        None
+    }
 }
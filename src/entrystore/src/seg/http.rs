@@ -0,0 +1,146 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! This module defines how `Seg` storage will be used to execute `HTTP`
+//! storage requests.
+//!
+//! The key is the request path without the leading slash. The client flags
+//! are kept in the optional data of the item, in the same layout that is used
+//! for the flags of memcache items.
+
+use super::*;
+use protocol_common::*;
+
+use protocol_http::{Headers, ParseData, RequestData, Response, Storage, FLAGS_HEADER, TTL_HEADER};
+
+use std::time::Duration;
+
+impl Execute<ParseData, Response> for Seg {
+    fn execute(&mut self, request: &ParseData) -> Response {
+        let request = match &request.0 {
+            Ok(request) => request,
+            Err(e) => return e.to_response(),
+        };
+
+        // the body is parsed as part of the request once it has arrived
+        if let RequestData::Continue(_) = request.data() {
+            return Response::builder(100).empty();
+        }
+
+        if let RequestData::Post(_, _) = request.data() {
+            return Response::builder(405)
+                .header("Allow", b"GET, PUT, DELETE")
+                .body(b"")
+                .reply_to(request);
+        }
+
+        let key = match request_key(request.data()) {
+            Some(key) => key,
+            None => return bad_request("a key is required").reply_to(request),
+        };
+
+        let response = match request.data() {
+            RequestData::Get(_) => Storage::get(self, key, &request.headers),
+            RequestData::Put(_, value) => Storage::put(self, key, value, &request.headers),
+            RequestData::Delete(_) => Storage::delete(self, key, &request.headers),
+            RequestData::Post(_, _) | RequestData::Continue(_) => unreachable!(),
+        };
+
+        response.reply_to(request)
+    }
+}
+
+impl Storage for Seg {
+    fn get(&mut self, key: &[u8], _headers: &Headers) -> Response {
+        match self.data.get(key) {
+            Some(item) => {
+                let flags = match item.optional() {
+                    Some(o) if o.len() >= 4 => u32::from_be_bytes([o[0], o[1], o[2], o[3]]),
+                    _ => 0,
+                };
+
+                let mut response = Response::builder(200);
+                response.header(FLAGS_HEADER, flags.to_string().as_bytes());
+
+                match item.value() {
+                    seg::Value::Bytes(b) => response.body(b),
+                    seg::Value::U64(v) => response.body(format!("{}", v).as_bytes()),
+                }
+            }
+            None => Response::builder(404).body(b""),
+        }
+    }
+
+    fn put(&mut self, key: &[u8], value: &[u8], headers: &Headers) -> Response {
+        let ttl = match header_u32(headers, TTL_HEADER) {
+            Ok(ttl) => ttl.unwrap_or(0),
+            Err(response) => return response,
+        };
+        let flags = match header_u32(headers, FLAGS_HEADER) {
+            Ok(flags) => flags.unwrap_or(0),
+            Err(response) => return response,
+        };
+
+        let exists = self.data.get_no_freq_incr(key).is_some();
+
+        match self.data.insert(
+            key,
+            value,
+            Some(&flags.to_be_bytes()),
+            Duration::from_secs(ttl as u64),
+        ) {
+            Ok(()) if exists => Response::builder(204).empty(),
+            Ok(()) => Response::builder(201).body(b""),
+            Err(SegError::ItemOversized { .. }) => Response::builder(413)
+                .header("Content-Type", b"text/plain")
+                .body(b"value is too large"),
+            Err(_) => Response::builder(500)
+                .header("Content-Type", b"text/plain")
+                .body(b"failed to store the value"),
+        }
+    }
+
+    fn delete(&mut self, key: &[u8], _headers: &Headers) -> Response {
+        if self.data.delete(key) {
+            Response::builder(204).empty()
+        } else {
+            Response::builder(404).body(b"")
+        }
+    }
+}
+
+// the key is the path of the request without the leading slash
+fn request_key(data: &RequestData) -> Option<&[u8]> {
+    let path = match data {
+        RequestData::Get(path)
+        | RequestData::Put(path, _)
+        | RequestData::Delete(path)
+        | RequestData::Post(path, _)
+        | RequestData::Continue(path) => path,
+    };
+    let key = path.strip_prefix(b"/").unwrap_or(path);
+    if key.is_empty() {
+        None
+    } else {
+        Some(key)
+    }
+}
+
+// parses an optional header which holds an unsigned integer
+fn header_u32(headers: &Headers, name: &str) -> Result<Option<u32>, Response> {
+    match headers.header(name) {
+        Some(value) => std::str::from_utf8(value)
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .map(Some)
+            .ok_or_else(|| bad_request(&format!("{} header was invalid", name))),
+        None => Ok(None),
+    }
+}
+
+fn bad_request(message: &str) -> Response {
+    Response::builder(400)
+        .header("Content-Type", b"text/plain")
+        .body(message.as_bytes())
+}
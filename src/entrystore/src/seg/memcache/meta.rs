@@ -0,0 +1,447 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Meta commands. These return item details through flags, and support
+//! stale-while-revalidate: an invalidated item is kept as stale, and the first
+//! client to see it is told that it has won the right to recache the item.
+//! Other clients receive the stale value along with a flag indicating that a
+//! recache is already in progress.
+
+use super::*;
+
+impl Seg {
+    pub(super) fn mg(&mut self, request: &MetaGet) -> Response {
+        let flags = request.flags();
+        let key = request.key();
+
+        // an item has been hit if it was read since it was stored, which is
+        // tracked by its frequency. this must be checked before the read below
+        // increments it
+        let hit = if flags.return_hit() {
+            self.data.frequency(key).map(|frequency| frequency > 0)
+        } else {
+            None
+        };
+
+        let item = if flags.no_bump() {
+            self.data.get_no_freq_incr(key)
+        } else {
+            self.data.get(key)
+        };
+
+        let item = match item {
+            Some(item) => item,
+            None => return self.mg_miss(request),
+        };
+
+        let mut metadata = Metadata::from_optional(item.optional());
+        let mut cas = item.cas();
+        let value = value_bytes(&item);
+        let stale = metadata.state & STALE != 0;
+
+        // the remaining ttl is only looked up when it is needed, a ttl of zero
+        // means that the item does not expire
+        let mut ttl = if flags.return_ttl() || flags.recache().is_some() || stale {
+            self.data.ttl(key).unwrap_or_default()
+        } else {
+            Duration::ZERO
+        };
+
+        let mut rewrite = false;
+        let mut deleted = false;
+        match flags.ttl().map(ttl_from) {
+            Some(Some(new)) => {
+                ttl = new;
+                rewrite = true;
+            }
+            Some(None) => {
+                // immediate expiration removes the item once it is returned
+                self.data.delete(key);
+                deleted = true;
+            }
+            None => {}
+        }
+
+        // a stale item, or one which is close to expiring, is recached by the
+        // first client to see it
+        let recache = match flags.recache() {
+            Some(threshold) => !ttl.is_zero() && ttl.as_secs() < threshold as u64,
+            None => false,
+        };
+        let already_won = metadata.state & WIN_SENT != 0;
+        let win = !already_won && (stale || recache);
+        if win {
+            metadata.state |= WIN_SENT;
+            rewrite |= !deleted;
+        }
+
+        if rewrite {
+            // the ttl and state can only be changed by rewriting the item,
+            // which also gives it a new cas value
+            if self.store(key, &value, metadata, ttl).is_err() {
+                return Response::server_error("");
+            }
+            if let Some(item) = self.data.get_no_freq_incr(key) {
+                cas = item.cas();
+            }
+        }
+
+        let code = if flags.return_value() {
+            MetaCode::Value
+        } else {
+            MetaCode::Header
+        };
+        let mut response = Meta::new(code);
+
+        if flags.return_cas() {
+            response = response.with_flag(MetaFlag::Cas(cas.into()));
+        }
+        if flags.return_flags() {
+            response = response.with_flag(MetaFlag::Flags(metadata.flags));
+        }
+        if let Some(hit) = hit {
+            response = response.with_flag(MetaFlag::Hit(hit));
+        }
+        // the time of last access is not tracked, so the `l` flag is ignored
+        if flags.return_size() {
+            response = response.with_flag(MetaFlag::Size(value.len()));
+        }
+        if flags.return_ttl() {
+            response = response.with_flag(MetaFlag::Ttl(remaining(ttl)));
+        }
+        if stale {
+            response = response.with_flag(MetaFlag::Stale);
+        }
+        if already_won {
+            response = response.with_flag(MetaFlag::AlreadyWon);
+        } else if win {
+            response = response.with_flag(MetaFlag::Win);
+        }
+        if flags.return_value() {
+            response = response.with_data(&value);
+        }
+
+        echo(flags, key, response).into()
+    }
+
+    fn mg_miss(&mut self, request: &MetaGet) -> Response {
+        let flags = request.flags();
+
+        // with vivify-on-miss, an empty item is created and this client is
+        // told to populate it. other clients will see the empty item along
+        // with a flag indicating the recache is in progress
+        if let Some(Some(ttl)) = flags.vivify().map(ttl_from) {
+            let mut metadata = Metadata::new(0);
+            metadata.state = WIN_SENT;
+            if self.store(request.key(), b"", metadata, ttl).is_ok() {
+                let response = Meta::new(MetaCode::Miss).with_flag(MetaFlag::Win);
+                return echo(flags, request.key(), response).into();
+            }
+        }
+
+        let response = Meta::new(MetaCode::Miss).with_noreply(flags.quiet());
+        echo(flags, request.key(), response).into()
+    }
+
+    pub(super) fn ms(&mut self, request: &MetaSet) -> Response {
+        let flags = request.flags();
+        let key = request.key();
+
+        // copy out anything needed from the current item before it is replaced
+        let current = self.data.get_no_freq_incr(key).map(|item| {
+            (
+                item.cas(),
+                value_bytes(&item),
+                Metadata::from_optional(item.optional()),
+            )
+        });
+
+        // in invalidate mode, a set with an older cas value is stored but is
+        // marked as stale
+        let mut stale = false;
+        if let Some(cas) = flags.compare_cas() {
+            match &current {
+                None => return echo(flags, key, Meta::new(MetaCode::NotFound)).into(),
+                Some((current, _, _)) if cas != *current as u64 => {
+                    if flags.invalidate() && cas < *current as u64 {
+                        stale = true;
+                    } else {
+                        return echo(flags, key, Meta::new(MetaCode::Exists)).into();
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mode = request.mode();
+        let exists = current.is_some();
+        if (mode == MetaSetMode::Add && exists)
+            || (mode != MetaSetMode::Add && mode != MetaSetMode::Set && !exists)
+        {
+            return echo(flags, key, Meta::new(MetaCode::NotStored)).into();
+        }
+
+        // appends and prepends keep the flags and ttl of the current item
+        let (value, mut metadata, mut ttl) = match (mode, current) {
+            (MetaSetMode::Append, Some((_, mut value, metadata))) => {
+                value.extend_from_slice(request.value());
+                (value, metadata, self.data.ttl(key).unwrap_or_default())
+            }
+            (MetaSetMode::Prepend, Some((_, value, metadata))) => {
+                let mut v = request.value().to_vec();
+                v.extend_from_slice(&value);
+                (v, metadata, self.data.ttl(key).unwrap_or_default())
+            }
+            _ => (request.value().to_vec(), Metadata::new(0), Duration::ZERO),
+        };
+
+        if let Some(client_flags) = flags.client_flags() {
+            metadata.flags = client_flags;
+        }
+        match flags.ttl().map(ttl_from) {
+            Some(Some(new)) => ttl = new,
+            Some(None) => {
+                // immediate expire maps to a delete
+                self.data.delete(key);
+                let response = Meta::new(MetaCode::Header).with_noreply(flags.quiet());
+                return echo(flags, key, response).into();
+            }
+            None => {}
+        }
+        metadata.state = if stale { STALE } else { 0 };
+
+        if self.store(key, &value, metadata, ttl).is_err() {
+            return Response::server_error("");
+        }
+
+        let mut response = Meta::new(MetaCode::Header).with_noreply(flags.quiet());
+        if flags.return_cas() {
+            if let Some(item) = self.data.get_no_freq_incr(key) {
+                response = response.with_flag(MetaFlag::Cas(item.cas().into()));
+            }
+        }
+        echo(flags, key, response).into()
+    }
+
+    pub(super) fn md(&mut self, request: &MetaDelete) -> Response {
+        let flags = request.flags();
+        let key = request.key();
+
+        let item = match self.data.get_no_freq_incr(key) {
+            Some(item) => item,
+            None => {
+                let response = Meta::new(MetaCode::NotFound).with_noreply(flags.quiet());
+                return echo(flags, key, response).into();
+            }
+        };
+
+        if let Some(cas) = flags.compare_cas() {
+            if cas != item.cas() as u64 {
+                return echo(flags, key, Meta::new(MetaCode::Exists)).into();
+            }
+        }
+
+        if flags.invalidate() {
+            // the item is kept, but the next client to read it will be told to
+            // recache it
+            let mut metadata = Metadata::from_optional(item.optional());
+            metadata.state = (metadata.state | STALE) & !WIN_SENT;
+
+            let ttl = match flags.ttl().map(ttl_from) {
+                Some(ttl) => ttl,
+                None => Some(self.data.ttl(key).unwrap_or_default()),
+            };
+
+            match ttl {
+                Some(ttl) => {
+                    let value = value_bytes(&item);
+                    if self.store(key, &value, metadata, ttl).is_err() {
+                        return Response::server_error("");
+                    }
+                }
+                None => {
+                    self.data.delete(key);
+                }
+            }
+        } else {
+            self.data.delete(key);
+        }
+
+        let response = Meta::new(MetaCode::Header).with_noreply(flags.quiet());
+        echo(flags, key, response).into()
+    }
+
+    pub(super) fn ma(&mut self, request: &MetaArithmetic) -> Response {
+        let flags = request.flags();
+        let key = request.key();
+
+        let current = self
+            .data
+            .get_no_freq_incr(key)
+            .map(|item| (item.cas(), Metadata::from_optional(item.optional())));
+
+        // the ttl is only known here if the item is created or rewritten
+        let mut ttl = None;
+
+        let (value, mut cas, metadata) = match current {
+            None => {
+                // with autovivify, the item is created with the initial value
+                match flags.vivify().map(ttl_from) {
+                    Some(Some(vivify)) => {
+                        let value = flags.initial().unwrap_or(0);
+                        let metadata = Metadata::new(0);
+                        let mut buf = [0; Metadata::SIZE];
+                        if self
+                            .data
+                            .insert(key, value, Some(metadata.encode(&mut buf)), vivify)
+                            .is_err()
+                        {
+                            return Response::server_error("");
+                        }
+                        ttl = Some(vivify);
+                        let cas = self.data.get_no_freq_incr(key).map(|item| item.cas());
+                        (value, cas.unwrap_or(0), metadata)
+                    }
+                    _ => {
+                        let response = Meta::new(MetaCode::NotFound).with_noreply(flags.quiet());
+                        return echo(flags, key, response).into();
+                    }
+                }
+            }
+            Some((cas, metadata)) => {
+                if let Some(compare) = flags.compare_cas() {
+                    if compare != cas as u64 {
+                        return echo(flags, key, Meta::new(MetaCode::Exists)).into();
+                    }
+                }
+
+                let result = match request.mode() {
+                    MetaArithmeticMode::Increment => self.data.wrapping_add(key, request.delta()),
+                    MetaArithmeticMode::Decrement => self.data.saturating_sub(key, request.delta()),
+                };
+
+                match result {
+                    Ok(item) => match item.value() {
+                        seg::Value::U64(v) => (v, item.cas(), metadata),
+                        _ => return Response::server_error(""),
+                    },
+                    Err(SegError::NotFound) => {
+                        let response = Meta::new(MetaCode::NotFound).with_noreply(flags.quiet());
+                        return echo(flags, key, response).into();
+                    }
+                    Err(SegError::NotNumeric) => {
+                        return Response::client_error(
+                            "cannot increment or decrement non-numeric value",
+                        );
+                    }
+                    Err(_) => return Response::server_error(""),
+                }
+            }
+        };
+
+        match flags.ttl().map(ttl_from) {
+            Some(Some(new)) => {
+                // the ttl can only be changed by rewriting the item
+                let mut buf = [0; Metadata::SIZE];
+                if self
+                    .data
+                    .insert(key, value, Some(metadata.encode(&mut buf)), new)
+                    .is_err()
+                {
+                    return Response::server_error("");
+                }
+                ttl = Some(new);
+                if let Some(item) = self.data.get_no_freq_incr(key) {
+                    cas = item.cas();
+                }
+            }
+            Some(None) => {
+                self.data.delete(key);
+            }
+            None => {}
+        }
+
+        let value = format!("{}", value);
+        let mut response = if flags.return_value() {
+            Meta::new(MetaCode::Value).with_data(value.as_bytes())
+        } else {
+            Meta::new(MetaCode::Header).with_noreply(flags.quiet())
+        };
+        if flags.return_ttl() {
+            let ttl = match ttl {
+                Some(ttl) => ttl,
+                None => self.data.ttl(key).unwrap_or_default(),
+            };
+            response = response.with_flag(MetaFlag::Ttl(remaining(ttl)));
+        }
+        if flags.return_cas() {
+            response = response.with_flag(MetaFlag::Cas(cas.into()));
+        }
+        echo(flags, key, response).into()
+    }
+
+    pub(super) fn me(&mut self, request: &MetaDebug) -> Response {
+        let key = request.key();
+        match self.data.get_no_freq_incr(key) {
+            Some(item) => {
+                let ttl = self.data.ttl(key).unwrap_or_default();
+                let frequency = self.data.frequency(key).unwrap_or(0);
+                DebugInfo::new(
+                    key,
+                    request.flags().base64(),
+                    remaining(ttl),
+                    None,
+                    item.cas().into(),
+                    frequency > 0,
+                    value_bytes(&item).len(),
+                )
+                .into()
+            }
+            None => Meta::new(MetaCode::Miss).into(),
+        }
+    }
+}
+
+/// Adds the key and opaque token to the response if they were requested.
+fn echo(flags: &MetaFlags, key: &[u8], mut response: Meta) -> Meta {
+    if flags.return_key() {
+        if flags.base64() {
+            response = response.with_flag(MetaFlag::Base64);
+        }
+        response = response.with_flag(MetaFlag::Key(key.to_owned().into_boxed_slice()));
+    }
+    if let Some(opaque) = flags.opaque() {
+        response = response.with_flag(MetaFlag::Opaque(opaque.to_owned().into_boxed_slice()));
+    }
+    response
+}
+
+/// Copies the value of the item, using the text representation of numeric
+/// values.
+fn value_bytes(item: &seg::Item) -> Vec<u8> {
+    match item.value() {
+        seg::Value::Bytes(b) => b.to_vec(),
+        seg::Value::U64(v) => format!("{}", v).into_bytes(),
+    }
+}
+
+/// Converts a TTL from a meta command into the TTL for the item, where zero
+/// means that it does not expire. Returns `None` for immediate expiration.
+fn ttl_from(ttl: Ttl) -> Option<Duration> {
+    match ttl.get() {
+        None => Some(Duration::ZERO),
+        Some(seconds) if seconds < 0 => None,
+        Some(seconds) => Some(Duration::from_secs(seconds as u64)),
+    }
+}
+
+/// The remaining TTL in seconds as reported to clients, or `-1` if the item
+/// does not expire.
+fn remaining(ttl: Duration) -> i64 {
+    if ttl.is_zero() {
+        -1
+    } else {
+        ttl.as_secs() as i64
+    }
+}
@@ -0,0 +1,369 @@
+// Copyright 2021 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! This module defines how `Seg` storage will be used to execute `Memcache`
+//! storage commands.
+//!
+//! Items written through this module carry the client flags in their optional
+//! data. Items which have been invalidated by the meta commands also carry a
+//! byte of state bits for stale-while-revalidate. The TTL is kept by `Seg`
+//! itself.
+
+use super::*;
+use protocol_common::*;
+
+use protocol_memcache::*;
+
+use std::time::Duration;
+
+mod meta;
+mod stats;
+
+impl Execute<Request, Response> for Seg {
+    fn execute(&mut self, request: &Request) -> Response {
+        match request {
+            Request::Get(get) => self.get(get),
+            Request::Gets(gets) => self.gets(gets),
+            Request::Set(set) => self.set(set),
+            Request::Add(add) => self.add(add),
+            Request::Replace(replace) => self.replace(replace),
+            Request::Cas(cas) => self.cas(cas),
+            Request::Incr(incr) => self.incr(incr),
+            Request::Decr(decr) => self.decr(decr),
+            Request::Append(append) => self.append(append),
+            Request::Prepend(prepend) => self.prepend(prepend),
+            Request::Delete(delete) => self.delete(delete),
+            Request::FlushAll(flush_all) => self.flush_all(flush_all),
+            Request::MetaArithmetic(meta_arithmetic) => self.meta_arithmetic(meta_arithmetic),
+            Request::MetaDebug(meta_debug) => self.meta_debug(meta_debug),
+            Request::MetaDelete(meta_delete) => self.meta_delete(meta_delete),
+            Request::MetaGet(meta_get) => self.meta_get(meta_get),
+            Request::MetaNoop(meta_noop) => self.meta_noop(meta_noop),
+            Request::MetaSet(meta_set) => self.meta_set(meta_set),
+            Request::Quit(quit) => self.quit(quit),
+            Request::Stats(stats) => self.stats(stats),
+            Request::Verbosity(verbosity) => self.verbosity(verbosity),
+            Request::Version(version) => self.version(version),
+            Request::Auth(auth) => auth.response(),
+            Request::Invalid(invalid) => invalid.response(),
+            Request::Binary(binary) => {
+                let response = self.execute(binary.request());
+                // the binary protocol returns the cas value of the modified item
+                let cas = binary
+                    .modified_key()
+                    .and_then(|key| self.data.get_no_freq_incr(key))
+                    .map(|item| item.cas().into());
+                binary.response(response, cas)
+            }
+        }
+    }
+}
+
+impl Seg {
+    /// Stores the value, using a numeric representation when the value can be
+    /// parsed as an unsigned integer so that it may be used with incr/decr.
+    fn store(
+        &mut self,
+        key: &[u8],
+        value: &[u8],
+        metadata: Metadata,
+        ttl: Duration,
+    ) -> Result<(), SegError> {
+        let mut buf = [0; Metadata::SIZE];
+        let optional = metadata.encode(&mut buf);
+        match std::str::from_utf8(value).map(|s| s.parse::<u64>()) {
+            Ok(Ok(v)) => self.data.insert(key, v, Some(optional), ttl),
+            _ => self.data.insert(key, value, Some(optional), ttl),
+        }
+    }
+
+    fn values(&mut self, keys: &[Box<[u8]>], include_cas: bool) -> Response {
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys.iter() {
+            if let Some(item) = self.data.get(key) {
+                let flags = Metadata::from_optional(item.optional()).flags;
+                let cas = if include_cas {
+                    Some(item.cas().into())
+                } else {
+                    None
+                };
+                match item.value() {
+                    seg::Value::Bytes(b) => {
+                        values.push(Value::new(item.key(), flags, cas, b));
+                    }
+                    seg::Value::U64(v) => {
+                        values.push(Value::new(
+                            item.key(),
+                            flags,
+                            cas,
+                            format!("{}", v).as_bytes(),
+                        ));
+                    }
+                }
+            } else {
+                values.push(Value::none(key));
+            }
+        }
+        Values::new(values.into_boxed_slice()).into()
+    }
+}
+
+impl Storage for Seg {
+    fn get(&mut self, get: &Get) -> Response {
+        self.values(get.keys(), false)
+    }
+
+    fn gets(&mut self, gets: &Gets) -> Response {
+        self.values(gets.keys(), true)
+    }
+
+    fn set(&mut self, set: &Set) -> Response {
+        let ttl = set.ttl().get().unwrap_or(0);
+
+        if ttl < 0 {
+            // immediate expire maps to a delete
+            self.data.delete(set.key());
+            return Response::stored(set.noreply());
+        }
+
+        let metadata = Metadata::new(set.flags());
+        if self
+            .store(
+                set.key(),
+                set.value(),
+                metadata,
+                Duration::from_secs(ttl as u64),
+            )
+            .is_ok()
+        {
+            Response::stored(set.noreply())
+        } else {
+            Response::server_error("")
+        }
+    }
+
+    fn add(&mut self, add: &Add) -> Response {
+        if self.data.get_no_freq_incr(add.key()).is_some() {
+            return Response::not_stored(add.noreply());
+        }
+
+        let ttl = add.ttl().get().unwrap_or(0);
+
+        if ttl < 0 {
+            // immediate expire maps to a delete
+            self.data.delete(add.key());
+            return Response::stored(add.noreply());
+        }
+
+        let metadata = Metadata::new(add.flags());
+        if self
+            .store(
+                add.key(),
+                add.value(),
+                metadata,
+                Duration::from_secs(ttl as u64),
+            )
+            .is_ok()
+        {
+            Response::stored(add.noreply())
+        } else {
+            Response::server_error("")
+        }
+    }
+
+    fn replace(&mut self, replace: &Replace) -> Response {
+        if self.data.get_no_freq_incr(replace.key()).is_none() {
+            return Response::not_stored(replace.noreply());
+        }
+
+        let ttl = replace.ttl().get().unwrap_or(0);
+
+        if ttl < 0 {
+            // immediate expire maps to a delete
+            self.data.delete(replace.key());
+            return Response::stored(replace.noreply());
+        }
+
+        let metadata = Metadata::new(replace.flags());
+        if self
+            .store(
+                replace.key(),
+                replace.value(),
+                metadata,
+                Duration::from_secs(ttl as u64),
+            )
+            .is_ok()
+        {
+            Response::stored(replace.noreply())
+        } else {
+            Response::server_error("")
+        }
+    }
+
+    fn append(&mut self, _: &Append) -> Response {
+        Response::error()
+    }
+
+    fn prepend(&mut self, _: &Prepend) -> Response {
+        Response::error()
+    }
+
+    fn incr(&mut self, incr: &Incr) -> Response {
+        match self.data.wrapping_add(incr.key(), incr.value()) {
+            Ok(item) => match item.value() {
+                seg::Value::U64(v) => Response::numeric(v, incr.noreply()),
+                _ => Response::server_error(""),
+            },
+            Err(SegError::NotFound) => Response::not_found(incr.noreply()),
+            Err(SegError::NotNumeric) => Response::error(),
+            Err(_) => Response::server_error(""),
+        }
+    }
+
+    fn decr(&mut self, decr: &Decr) -> Response {
+        match self.data.saturating_sub(decr.key(), decr.value()) {
+            Ok(item) => match item.value() {
+                seg::Value::U64(v) => Response::numeric(v, decr.noreply()),
+                _ => Response::server_error(""),
+            },
+            Err(SegError::NotFound) => Response::not_found(decr.noreply()),
+            Err(SegError::NotNumeric) => Response::error(),
+            Err(_) => Response::server_error(""),
+        }
+    }
+
+    fn cas(&mut self, cas: &Cas) -> Response {
+        // duration of zero is treated as no expiry. as we have
+        // no way of checking the cas value without performing a cas
+        // and checking the result, setting the shortest possible ttl
+        // results in nearly immediate expiry
+        let ttl = cas.ttl().get().unwrap_or(1);
+
+        let ttl = if ttl < 0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_secs(ttl as u64)
+        };
+
+        let optional = cas.flags().to_be_bytes();
+
+        let result = match std::str::from_utf8(cas.value()).map(|s| s.parse::<u64>()) {
+            Ok(Ok(v)) => self
+                .data
+                .cas(cas.key(), v, Some(&optional), ttl, cas.cas() as u32),
+            _ => self.data.cas(
+                cas.key(),
+                cas.value(),
+                Some(&optional),
+                ttl,
+                cas.cas() as u32,
+            ),
+        };
+
+        match result {
+            Ok(_) => Response::stored(cas.noreply()),
+            Err(SegError::NotFound) => Response::not_found(cas.noreply()),
+            Err(SegError::Exists) => Response::exists(cas.noreply()),
+            Err(_) => Response::error(),
+        }
+    }
+
+    fn delete(&mut self, delete: &Delete) -> Response {
+        if self.data.delete(delete.key()) {
+            Response::deleted(delete.noreply())
+        } else {
+            Response::not_found(delete.noreply())
+        }
+    }
+
+    fn flush_all(&mut self, _flush_all: &FlushAll) -> Response {
+        Response::error()
+    }
+
+    fn meta_arithmetic(&mut self, meta_arithmetic: &MetaArithmetic) -> Response {
+        self.ma(meta_arithmetic)
+    }
+
+    fn meta_debug(&mut self, meta_debug: &MetaDebug) -> Response {
+        self.me(meta_debug)
+    }
+
+    fn meta_delete(&mut self, meta_delete: &MetaDelete) -> Response {
+        self.md(meta_delete)
+    }
+
+    fn meta_get(&mut self, meta_get: &MetaGet) -> Response {
+        self.mg(meta_get)
+    }
+
+    fn meta_noop(&mut self, _meta_noop: &MetaNoop) -> Response {
+        Meta::new(MetaCode::Noop).into()
+    }
+
+    fn meta_set(&mut self, meta_set: &MetaSet) -> Response {
+        self.ms(meta_set)
+    }
+
+    fn quit(&mut self, _quit: &Quit) -> Response {
+        Response::hangup()
+    }
+
+    fn stats(&mut self, stats: &Stats) -> Response {
+        self.stats_info(stats.group()).into()
+    }
+
+    fn verbosity(&mut self, verbosity: &Verbosity) -> Response {
+        Response::ok(verbosity.noreply())
+    }
+
+    fn version(&mut self, _version: &Version) -> Response {
+        Response::version(env!("CARGO_PKG_VERSION"))
+    }
+}
+
+/// The item has been invalidated and should be recached.
+const STALE: u8 = 1;
+/// A client has been told that it won the right to recache the item.
+const WIN_SENT: u8 = 2;
+
+/// Per-item metadata which is stored in the item's optional data. The client
+/// flags are always stored, while the state byte is only stored when a meta
+/// command has set one of the state bits.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+struct Metadata {
+    /// Opaque client flags.
+    flags: u32,
+    state: u8,
+}
+
+impl Metadata {
+    const SIZE: usize = 5;
+
+    fn new(flags: u32) -> Self {
+        Self { flags, state: 0 }
+    }
+
+    /// Decodes the metadata from the optional data of an item.
+    fn from_optional(optional: Option<&[u8]>) -> Self {
+        match optional {
+            Some(o) if o.len() >= 4 => Self {
+                flags: u32::from_be_bytes([o[0], o[1], o[2], o[3]]),
+                state: o.get(4).copied().unwrap_or(0),
+            },
+            _ => Self::default(),
+        }
+    }
+
+    /// Encodes the metadata into the buffer, returning the bytes which should
+    /// be stored as the optional data of the item.
+    fn encode(self, buf: &mut [u8; Self::SIZE]) -> &[u8] {
+        buf[0..4].copy_from_slice(&self.flags.to_be_bytes());
+        if self.state == 0 {
+            &buf[0..4]
+        } else {
+            buf[4] = self.state;
+            &buf[..]
+        }
+    }
+}
@@ -0,0 +1,63 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Stats commands. The general stats are shared with the other storage types,
+//! while the `items` and `slabs` groups describe the TTL buckets and segment
+//! utilization, which take the place of slab classes in segment-structured
+//! storage.
+
+use super::*;
+
+impl Seg {
+    pub(super) fn stats_info(&self, group: StatsGroup) -> StatsInfo {
+        match group {
+            StatsGroup::General => StatsInfo::general(env!("CARGO_PKG_VERSION")),
+            StatsGroup::Settings => self.stats_settings(),
+            StatsGroup::Items => self.stats_items(),
+            StatsGroup::Slabs => self.stats_slabs(),
+        }
+    }
+
+    fn stats_settings(&self) -> StatsInfo {
+        let segments = self.data.segment_stats();
+
+        let mut stats = StatsInfo::new();
+        stats.push("maxbytes", segments.total() * segments.segment_size());
+        stats.push("segment_size", segments.segment_size());
+        stats.push("hash_power", self.hash_power);
+        stats.push(
+            "eviction",
+            format!("{:?}", self.eviction).to_ascii_lowercase(),
+        );
+        stats
+    }
+
+    /// Each TTL bucket which holds segments is reported as an item class,
+    /// identified by the smallest TTL of the bucket.
+    fn stats_items(&self) -> StatsInfo {
+        let mut stats = StatsInfo::new();
+        for bucket in self.data.ttl_bucket_stats() {
+            let ttl = bucket.ttl();
+            stats.push(format!("items:{}:number", ttl), bucket.items());
+            stats.push(format!("items:{}:bytes", ttl), bucket.bytes());
+            stats.push(format!("items:{}:segments", ttl), bucket.segments());
+        }
+        stats
+    }
+
+    fn stats_slabs(&self) -> StatsInfo {
+        let segments = self.data.segment_stats();
+
+        let mut stats = StatsInfo::new();
+        stats.push("segment_size", segments.segment_size());
+        stats.push("total_segments", segments.total());
+        stats.push("free_segments", segments.free());
+        stats.push("used_segments", segments.used());
+        stats.push("live_items", segments.live_items());
+        stats.push("live_bytes", segments.live_bytes());
+        stats.push("written_bytes", segments.written_bytes());
+        stats.push("utilization", format!("{:.2}", segments.utilization()));
+        stats
+    }
+}
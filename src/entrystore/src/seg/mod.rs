@@ -8,21 +8,34 @@
 
 use crate::EntryStore;
 
+use common::signal::StatsSection;
+use common::time::{Seconds, UnixInstant};
 use config::seg::Eviction;
-use config::SegConfig;
+use config::{Hash, HashConfig, SegConfig};
 use seg::{Policy, SegError};
 
+mod http;
 mod memcache;
+mod resp;
+
+// the number of segments which are described by each page of `stats seg`
+const SEGMENTS_PER_PAGE: usize = 100;
 
 /// A wrapper around [`seg::Seg`] which implements `EntryStore` and storage
 /// protocol traits.
 pub struct Seg {
     data: ::seg::Seg,
+    hash_power: u8,
+    eviction: Eviction,
+    hash_max_fields: usize,
+    hash_max_field_size: usize,
+    hash_max_value_size: usize,
 }
 
 impl Seg {
     /// Create `Seg` storage based on the config and the `TimeType` which is
-    /// used to interpret various expiry time formats.
+    /// used to interpret various expiry time formats. Hashes are limited to
+    /// the default sizes unless other limits are set with `hash_limits()`.
     pub fn new<T: SegConfig>(config: &T) -> Result<Self, std::io::Error> {
         let config = config.seg();
 
@@ -51,7 +64,25 @@ impl Seg {
             .datapool_path(config.datapool_path())
             .build()?;
 
-        Ok(Self { data })
+        let hash = Hash::default();
+
+        Ok(Self {
+            data,
+            hash_power: config.hash_power(),
+            eviction: config.eviction(),
+            hash_max_fields: hash.max_fields(),
+            hash_max_field_size: hash.max_field_size(),
+            hash_max_value_size: hash.max_value_size(),
+        })
+    }
+
+    /// Sets the limits for hashes stored by the RESP hash commands.
+    pub fn hash_limits<T: HashConfig>(mut self, config: &T) -> Self {
+        let config = config.hash();
+        self.hash_max_fields = config.max_fields();
+        self.hash_max_field_size = config.max_field_size();
+        self.hash_max_value_size = config.max_value_size();
+        self
     }
 }
 
@@ -63,4 +94,91 @@ impl EntryStore for Seg {
     fn clear(&mut self) {
         self.data.clear();
     }
+
+    fn stats(&self, section: StatsSection) -> Vec<(String, String)> {
+        let mut stats = Vec::new();
+        match section {
+            StatsSection::Seg(page) => {
+                let summary = self.data.segment_stats();
+                stats.push(stat("segment_size", summary.segment_size()));
+                stats.push(stat("total_segments", summary.total()));
+                stats.push(stat("free_segments", summary.free()));
+                stats.push(stat("used_segments", summary.used()));
+                stats.push(stat("utilization", format!("{:.2}", summary.utilization())));
+
+                // a large heap has many segments, so they are described a
+                // page at a time to bound the work done on the storage thread
+                let segments = self.data.segment_info();
+                let pages = segments.len().div_ceil(SEGMENTS_PER_PAGE);
+                stats.push(stat("page", page));
+                stats.push(stat("pages", pages));
+
+                for segment in segments
+                    .iter()
+                    .skip(page as usize * SEGMENTS_PER_PAGE)
+                    .take(SEGMENTS_PER_PAGE)
+                {
+                    let id = segment.id();
+                    stats.push(stat(format!("seg:{}:ttl", id), segment.ttl()));
+                    stats.push(stat(format!("seg:{}:items", id), segment.live_items()));
+                    stats.push(stat(format!("seg:{}:bytes", id), segment.live_bytes()));
+                    stats.push(stat(
+                        format!("seg:{}:written_bytes", id),
+                        segment.written_bytes(),
+                    ));
+                    stats.push(stat(
+                        format!("seg:{}:utilization", id),
+                        format!("{:.2}", segment.utilization()),
+                    ));
+                    stats.push(stat(format!("seg:{}:merging", id), segment.merging() as u8));
+                }
+            }
+            StatsSection::Ttl => {
+                for bucket in self.data.ttl_bucket_stats() {
+                    let ttl = bucket.ttl();
+                    stats.push(stat(format!("ttl:{}:segments", ttl), bucket.segments()));
+                    stats.push(stat(format!("ttl:{}:items", ttl), bucket.items()));
+                    stats.push(stat(format!("ttl:{}:bytes", ttl), bucket.bytes()));
+                    stats.push(stat(
+                        format!("ttl:{}:merge_chain", ttl),
+                        bucket.merge_chain(),
+                    ));
+                }
+            }
+        }
+        stats
+    }
+
+    fn debug_key(&mut self, key: &[u8]) -> Option<Vec<(String, String)>> {
+        let info = match self.data.item_info(key) {
+            Some(info) => info,
+            None => {
+                return Some(Vec::new());
+            }
+        };
+
+        Some(vec![
+            stat("segment", info.segment()),
+            stat("offset", info.offset()),
+            stat("ttl_bucket", info.ttl()),
+            stat("remaining_ttl", info.remaining_ttl()),
+            stat("cas", info.cas()),
+            stat("frequency", info.frequency()),
+            stat("value_type", if info.typed() { "u64" } else { "raw" }),
+            stat("value_len", info.value_len()),
+            stat("merging", info.merging() as u8),
+        ])
+    }
+}
+
+fn stat(name: impl ToString, value: impl ToString) -> (String, String) {
+    (name.to_string(), value.to_string())
+}
+
+/// Returns the current unix time in seconds.
+fn now() -> u32 {
+    UnixInstant::<Seconds<u32>>::recent()
+        .checked_duration_since(UnixInstant::from_secs(0))
+        .map(|v| v.as_secs())
+        .unwrap_or(0)
 }
@@ -0,0 +1,239 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Hash commands. Each hash is stored as a [`ziplist`] in the value of a
+//! single item where every field is immediately followed by its value. Fields
+//! are found by scanning, which suits the small hashes this encoding is meant
+//! for. The TTL applies to the hash as a whole, and hashes which become empty
+//! are removed. Writes which would exceed the configured limits on the number
+//! of fields or the size of a field or value are rejected with an error.
+
+use super::*;
+
+impl Seg {
+    pub(super) fn hdel(&mut self, hdel: &HDelRequest) -> Response {
+        let (mut hash, metadata) = match self.collection_mut(hdel.key(), ValueType::Hash) {
+            Ok(Some(hash)) => hash,
+            Ok(None) => return Response::integer(0),
+            Err(response) => return response,
+        };
+
+        let mut removed = 0;
+        for field in hdel.fields().iter() {
+            if let Some(index) = find(hash.as_ziplist(), field) {
+                // remove both the field and its value
+                hash.remove(index);
+                hash.remove(index);
+                removed += 1;
+            }
+        }
+
+        if removed == 0 {
+            return Response::integer(0);
+        }
+
+        match self.store_collection(hdel.key(), &hash, metadata) {
+            Ok(()) => Response::integer(removed),
+            Err(response) => response,
+        }
+    }
+
+    pub(super) fn hexists(&mut self, hexists: &HExistsRequest) -> Response {
+        let item = match self.data.get(hexists.key()) {
+            Some(item) => item,
+            None => return Response::integer(0),
+        };
+        match as_collection(&item, ValueType::Hash) {
+            Ok(hash) => Response::integer(lookup(hash, hexists.field()).is_some() as i64),
+            Err(response) => response,
+        }
+    }
+
+    pub(super) fn hget(&mut self, hget: &HGetRequest) -> Response {
+        let item = match self.data.get(hget.key()) {
+            Some(item) => item,
+            None => return Response::null(),
+        };
+        match as_collection(&item, ValueType::Hash) {
+            Ok(hash) => match lookup(hash, hget.field()) {
+                Some(value) => Response::bulk_string(value),
+                None => Response::null(),
+            },
+            Err(response) => response,
+        }
+    }
+
+    pub(super) fn hgetall(&mut self, hgetall: &HGetAllRequest) -> Response {
+        let item = match self.data.get(hgetall.key()) {
+            Some(item) => item,
+            None => return Response::array(Vec::new()),
+        };
+        match as_collection(&item, ValueType::Hash) {
+            Ok(hash) => Response::array(hash.iter().map(Response::bulk_string).collect()),
+            Err(response) => response,
+        }
+    }
+
+    pub(super) fn hincrby(&mut self, hincrby: &HIncrByRequest) -> Response {
+        if let Err(response) = self.check_hash_entry(hincrby.field(), b"") {
+            return response;
+        }
+
+        let (mut hash, metadata) = match self.collection_mut(hincrby.key(), ValueType::Hash) {
+            Ok(Some(hash)) => hash,
+            Ok(None) => (ZipListBuf::new(), Metadata::new(ValueType::Hash)),
+            Err(response) => return response,
+        };
+
+        let index = find(hash.as_ziplist(), hincrby.field());
+        let current = match index {
+            Some(index) => {
+                let value = hash.as_ziplist().get(index + 1).unwrap_or_default();
+                match std::str::from_utf8(value)
+                    .ok()
+                    .and_then(|v| v.parse::<i64>().ok())
+                {
+                    Some(current) => current,
+                    None => return Response::error("ERR hash value is not an integer"),
+                }
+            }
+            None => 0,
+        };
+
+        let value = match current.checked_add(hincrby.increment()) {
+            Some(value) => value,
+            None => return Response::error("ERR increment or decrement would overflow"),
+        };
+
+        let encoded = format!("{}", value);
+        match index {
+            Some(index) => {
+                hash.replace(index + 1, encoded.as_bytes());
+            }
+            None => {
+                if let Err(response) = self.check_hash_len(hash.len() / 2 + 1) {
+                    return response;
+                }
+                hash.push_back(hincrby.field());
+                hash.push_back(encoded.as_bytes());
+            }
+        }
+
+        match self.store_collection(hincrby.key(), &hash, metadata) {
+            Ok(()) => Response::integer(value),
+            Err(response) => response,
+        }
+    }
+
+    pub(super) fn hlen(&mut self, hlen: &HLenRequest) -> Response {
+        let item = match self.data.get(hlen.key()) {
+            Some(item) => item,
+            None => return Response::integer(0),
+        };
+        match as_collection(&item, ValueType::Hash) {
+            Ok(hash) => Response::integer((hash.len() / 2) as i64),
+            Err(response) => response,
+        }
+    }
+
+    pub(super) fn hmget(&mut self, hmget: &HMGetRequest) -> Response {
+        let fields = hmget.fields();
+        let item = match self.data.get(hmget.key()) {
+            Some(item) => item,
+            None => return Response::array(fields.iter().map(|_| Response::null()).collect()),
+        };
+        let hash = match as_collection(&item, ValueType::Hash) {
+            Ok(hash) => hash,
+            Err(response) => return response,
+        };
+
+        let values = fields
+            .iter()
+            .map(|field| match lookup(hash, field) {
+                Some(value) => Response::bulk_string(value),
+                None => Response::null(),
+            })
+            .collect();
+        Response::array(values)
+    }
+
+    pub(super) fn hset(&mut self, hset: &HSetRequest) -> Response {
+        for (field, value) in hset.pairs().iter() {
+            if let Err(response) = self.check_hash_entry(field, value) {
+                return response;
+            }
+        }
+
+        let (mut hash, metadata) = match self.collection_mut(hset.key(), ValueType::Hash) {
+            Ok(Some(hash)) => hash,
+            Ok(None) => (ZipListBuf::new(), Metadata::new(ValueType::Hash)),
+            Err(response) => return response,
+        };
+
+        let mut added = 0;
+        for (field, value) in hset.pairs().iter() {
+            match find(hash.as_ziplist(), field) {
+                Some(index) => {
+                    hash.replace(index + 1, value);
+                }
+                None => {
+                    hash.push_back(field);
+                    hash.push_back(value);
+                    added += 1;
+                }
+            }
+        }
+
+        if added > 0 {
+            if let Err(response) = self.check_hash_len(hash.len() / 2) {
+                return response;
+            }
+        }
+
+        match self.store_collection(hset.key(), &hash, metadata) {
+            Ok(()) => Response::integer(added),
+            Err(response) => response,
+        }
+    }
+
+    /// Checks that a field and value are within the configured size limits.
+    fn check_hash_entry(&self, field: &[u8], value: &[u8]) -> Result<(), Response> {
+        if field.len() > self.hash_max_field_size {
+            return Err(Response::error("ERR hash field is too large"));
+        }
+        if value.len() > self.hash_max_value_size {
+            return Err(Response::error("ERR hash value is too large"));
+        }
+        Ok(())
+    }
+
+    /// Checks that a hash with the number of fields is within the configured
+    /// limit.
+    fn check_hash_len(&self, fields: usize) -> Result<(), Response> {
+        if fields > self.hash_max_fields {
+            return Err(Response::error("ERR hash has too many fields"));
+        }
+        Ok(())
+    }
+}
+
+/// Returns the index of the entry which holds the field, if it is present. The
+/// value is held in the following entry.
+fn find(hash: ZipList, field: &[u8]) -> Option<usize> {
+    hash.iter()
+        .step_by(2)
+        .position(|f| f == field)
+        .map(|position| position * 2)
+}
+
+/// Returns the value of the field, if it is present.
+fn lookup<'a>(hash: ZipList<'a>, field: &[u8]) -> Option<&'a [u8]> {
+    let mut entries = hash.iter();
+    while let (Some(f), Some(value)) = (entries.next(), entries.next()) {
+        if f == field {
+            return Some(value);
+        }
+    }
+    None
+}
@@ -0,0 +1,108 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Commands which operate on keys regardless of the type of their value. The
+//! remaining TTL is read from `Seg`, which rounds it to the granularity of the
+//! TTL bucket holding the item. Changing the TTL rewrites the item with the
+//! same value.
+
+use super::*;
+
+impl Seg {
+    pub(super) fn del(&mut self, del: &DelRequest) -> Response {
+        let deleted = del
+            .keys()
+            .iter()
+            .filter(|key| self.data.delete(key))
+            .count();
+        Response::integer(deleted as i64)
+    }
+
+    pub(super) fn exists(&mut self, exists: &ExistsRequest) -> Response {
+        let found = exists
+            .keys()
+            .iter()
+            .filter(|key| self.data.get_no_freq_incr(key).is_some())
+            .count();
+        Response::integer(found as i64)
+    }
+
+    pub(super) fn expire_key(&mut self, expire: &ExpireRequest) -> Response {
+        if expire.seconds() <= 0 {
+            return Response::integer(self.data.delete(expire.key()) as i64);
+        }
+        let seconds = (expire.seconds() as u64).min(u32::MAX as u64);
+        self.set_ttl(expire.key(), Duration::from_secs(seconds))
+    }
+
+    pub(super) fn flushdb(&mut self) -> Response {
+        self.data.clear();
+        Response::simple_string("OK")
+    }
+
+    pub(super) fn persist(&mut self, persist: &PersistRequest) -> Response {
+        match self.data.ttl(persist.key()) {
+            Some(ttl) if !ttl.is_zero() => self.set_ttl(persist.key(), Duration::ZERO),
+            _ => Response::integer(0),
+        }
+    }
+
+    pub(super) fn pexpire(&mut self, pexpire: &PExpireRequest) -> Response {
+        if pexpire.milliseconds() <= 0 {
+            return Response::integer(self.data.delete(pexpire.key()) as i64);
+        }
+        let seconds = (pexpire.milliseconds() as u64 / 1000).clamp(1, u32::MAX as u64);
+        self.set_ttl(pexpire.key(), Duration::from_secs(seconds))
+    }
+
+    pub(super) fn pttl(&mut self, pttl: &PTtlRequest) -> Response {
+        match self.data.ttl(pttl.key()) {
+            None => Response::integer(-2),
+            Some(ttl) if ttl.is_zero() => Response::integer(-1),
+            Some(ttl) => Response::integer(ttl.as_millis() as i64),
+        }
+    }
+
+    pub(super) fn ttl(&mut self, ttl: &TtlRequest) -> Response {
+        match self.data.ttl(ttl.key()) {
+            None => Response::integer(-2),
+            Some(ttl) if ttl.is_zero() => Response::integer(-1),
+            Some(ttl) => Response::integer(ttl.as_secs() as i64),
+        }
+    }
+
+    pub(super) fn key_type(&mut self, request: &TypeRequest) -> Response {
+        match self.data.get_no_freq_incr(request.key()) {
+            Some(item) => {
+                Response::simple_string(Metadata::from_optional(item.optional()).value_type.name())
+            }
+            None => Response::simple_string("none"),
+        }
+    }
+
+    /// Rewrites the item with a new TTL, keeping its value and type. A TTL of
+    /// zero removes the expiration. The reply is the number of keys which were
+    /// updated.
+    fn set_ttl(&mut self, key: &[u8], ttl: Duration) -> Response {
+        let item = match self.data.get_no_freq_incr(key) {
+            Some(item) => item,
+            None => return Response::integer(0),
+        };
+
+        let metadata = Metadata::from_optional(item.optional());
+
+        let result = match item.value() {
+            seg::Value::Bytes(b) => {
+                let value = b.to_vec();
+                self.insert(key, value.as_slice(), metadata, ttl)
+            }
+            seg::Value::U64(v) => self.insert(key, v, metadata, ttl),
+        };
+
+        match result {
+            Ok(_) => Response::integer(1),
+            Err(_) => Response::error("ERR failed to store value"),
+        }
+    }
+}
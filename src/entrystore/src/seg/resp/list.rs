@@ -0,0 +1,176 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! List commands. Each list is stored as a [`ziplist`] in the value of a
+//! single item. Lists which become empty are removed.
+
+use super::*;
+
+impl Seg {
+    pub(super) fn lindex(&mut self, lindex: &LIndexRequest) -> Response {
+        let item = match self.data.get(lindex.key()) {
+            Some(item) => item,
+            None => return Response::null(),
+        };
+        let list = match as_collection(&item, ValueType::List) {
+            Ok(list) => list,
+            Err(response) => return response,
+        };
+
+        let index = if lindex.index() < 0 {
+            list.len() as i64 + lindex.index()
+        } else {
+            lindex.index()
+        };
+        if index < 0 {
+            return Response::null();
+        }
+
+        match list.get(index as usize) {
+            Some(value) => Response::bulk_string(value),
+            None => Response::null(),
+        }
+    }
+
+    pub(super) fn llen(&mut self, llen: &LLenRequest) -> Response {
+        let item = match self.data.get(llen.key()) {
+            Some(item) => item,
+            None => return Response::integer(0),
+        };
+        match as_collection(&item, ValueType::List) {
+            Ok(list) => Response::integer(list.len() as i64),
+            Err(response) => response,
+        }
+    }
+
+    pub(super) fn lrange(&mut self, lrange: &LRangeRequest) -> Response {
+        let item = match self.data.get(lrange.key()) {
+            Some(item) => item,
+            None => return Response::array(Vec::new()),
+        };
+        let list = match as_collection(&item, ValueType::List) {
+            Ok(list) => list,
+            Err(response) => return response,
+        };
+
+        let (start, end) = range(list.len(), lrange.start(), lrange.stop());
+        let values = list
+            .iter()
+            .skip(start)
+            .take(end - start)
+            .map(Response::bulk_string)
+            .collect();
+        Response::array(values)
+    }
+
+    pub(super) fn lpush(&mut self, lpush: &LPushRequest) -> Response {
+        self.push(lpush.key(), &lpush.elements(), ZipListBuf::push_front)
+    }
+
+    pub(super) fn rpush(&mut self, rpush: &RPushRequest) -> Response {
+        self.push(rpush.key(), &rpush.elements(), ZipListBuf::push_back)
+    }
+
+    pub(super) fn lpop(&mut self, lpop: &LPopRequest) -> Response {
+        self.pop(lpop.key(), lpop.count(), ZipListBuf::pop_front)
+    }
+
+    pub(super) fn rpop(&mut self, rpop: &RPopRequest) -> Response {
+        self.pop(rpop.key(), rpop.count(), ZipListBuf::pop_back)
+    }
+
+    pub(super) fn ltrim(&mut self, ltrim: &LTrimRequest) -> Response {
+        let (mut list, metadata) = match self.collection_mut(ltrim.key(), ValueType::List) {
+            Ok(Some(list)) => list,
+            Ok(None) => return Response::simple_string("OK"),
+            Err(response) => return response,
+        };
+
+        let (start, end) = range(list.len(), ltrim.start(), ltrim.stop());
+        list.trim(start, end);
+
+        match self.store_collection(ltrim.key(), &list, metadata) {
+            Ok(()) => Response::simple_string("OK"),
+            Err(response) => response,
+        }
+    }
+
+    fn push(
+        &mut self,
+        key: &[u8],
+        elements: &[&[u8]],
+        push: fn(&mut ZipListBuf, &[u8]),
+    ) -> Response {
+        let (mut list, metadata) = match self.collection_mut(key, ValueType::List) {
+            Ok(Some(list)) => list,
+            Ok(None) => (ZipListBuf::new(), Metadata::new(ValueType::List)),
+            Err(response) => return response,
+        };
+
+        for element in elements {
+            push(&mut list, element);
+        }
+
+        match self.store_collection(key, &list, metadata) {
+            Ok(()) => Response::integer(list.len() as i64),
+            Err(response) => response,
+        }
+    }
+
+    fn pop(
+        &mut self,
+        key: &[u8],
+        count: Option<u64>,
+        pop: fn(&mut ZipListBuf) -> Option<Vec<u8>>,
+    ) -> Response {
+        let (mut list, metadata) = match self.collection_mut(key, ValueType::List) {
+            Ok(Some(list)) => list,
+            Ok(None) if count.is_some() => return Response::null_array(),
+            Ok(None) => return Response::null(),
+            Err(response) => return response,
+        };
+
+        let response = match count {
+            Some(count) => {
+                let mut values = Vec::new();
+                while (values.len() as u64) < count {
+                    match pop(&mut list) {
+                        Some(value) => values.push(Response::bulk_string(&value)),
+                        None => break,
+                    }
+                }
+                Response::array(values)
+            }
+            None => Response::bulk_string(&pop(&mut list).unwrap_or_default()),
+        };
+
+        match self.store_collection(key, &list, metadata) {
+            Ok(()) => response,
+            Err(response) => response,
+        }
+    }
+}
+
+/// Converts inclusive start and stop indices, which may be negative to count
+/// back from the end of the list, into a range of `start..end` entries within
+/// a list of `len` entries. Out of range indices are clamped and an empty
+/// range is returned if the indices do not overlap the list.
+fn range(len: usize, start: i64, stop: i64) -> (usize, usize) {
+    let len = len as i64;
+    let start = if start < 0 {
+        (len + start).max(0)
+    } else {
+        start
+    };
+    let stop = if stop < 0 {
+        len + stop
+    } else {
+        stop.min(len - 1)
+    };
+    if start > stop || start >= len {
+        (0, 0)
+    } else {
+        (start as usize, stop as usize + 1)
+    }
+}
@@ -0,0 +1,321 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! This module defines how `Seg` storage will be used to execute `RESP`
+//! storage commands.
+//!
+//! Items written through this module carry a one byte header in their optional
+//! data which records the type of the value. Collection types are encoded
+//! within the value of a single item, so any modification rewrites the whole
+//! item. The expiration time is kept by `Seg`, and each rewrite carries forward
+//! the remaining TTL of the key.
+
+use super::*;
+use protocol_common::*;
+
+use protocol_resp::*;
+
+use std::time::Duration;
+use ziplist::{ZipList, ZipListBuf};
+
+mod hash;
+mod keyspace;
+mod list;
+mod sorted_map;
+mod string;
+mod transaction;
+
+const WRONGTYPE: &str = "WRONGTYPE Operation against a key holding the wrong kind of value";
+
+impl Execute<Request, Response> for Seg {
+    fn execute(&mut self, request: &Request) -> Response {
+        match request {
+            Request::Append(append) => self.append(append),
+            Request::BAdd(badd) => self.badd(badd),
+            Request::BCard(bcard) => self.bcard(bcard),
+            Request::BDel(bdel) => self.bdel(bdel),
+            Request::BGet(bget) => self.bget(bget),
+            Request::BRange(brange) => self.brange(brange),
+            Request::Decr(decr) => self.decr(decr),
+            Request::DecrBy(decrby) => self.decrby(decrby),
+            Request::Del(del) => self.del(del),
+            Request::Discard(discard) => discard.response(),
+            Request::Echo(echo) => echo.response(),
+            Request::Exec(exec) => self.exec(exec),
+            Request::Exists(exists) => self.exists(exists),
+            Request::Expire(expire) => self.expire_key(expire),
+            Request::FlushDb(_) => self.flushdb(),
+            Request::Get(get) => self.get(get),
+            Request::GetSet(getset) => self.getset(getset),
+            Request::HDel(hdel) => self.hdel(hdel),
+            Request::HExists(hexists) => self.hexists(hexists),
+            Request::HGet(hget) => self.hget(hget),
+            Request::HGetAll(hgetall) => self.hgetall(hgetall),
+            Request::HIncrBy(hincrby) => self.hincrby(hincrby),
+            Request::HLen(hlen) => self.hlen(hlen),
+            Request::HMGet(hmget) => self.hmget(hmget),
+            Request::HSet(hset) => self.hset(hset),
+            Request::Hello(hello) => hello.response(),
+            Request::Incr(incr) => self.incr(incr),
+            Request::IncrBy(incrby) => self.incrby(incrby),
+            Request::Invalid(invalid) => invalid.response(),
+            Request::LIndex(lindex) => self.lindex(lindex),
+            Request::LLen(llen) => self.llen(llen),
+            Request::LPop(lpop) => self.lpop(lpop),
+            Request::LPush(lpush) => self.lpush(lpush),
+            Request::LRange(lrange) => self.lrange(lrange),
+            Request::LTrim(ltrim) => self.ltrim(ltrim),
+            Request::MGet(mget) => self.mget(mget),
+            Request::MSet(mset) => self.mset(mset),
+            Request::Multi(multi) => multi.response(),
+            Request::Persist(persist) => self.persist(persist),
+            Request::PExpire(pexpire) => self.pexpire(pexpire),
+            Request::Ping(ping) => ping.response(),
+            Request::PTtl(pttl) => self.pttl(pttl),
+            Request::Queued(queued) => queued.response(),
+            Request::Resp3(resp3) => resp3.response(self.execute(resp3.request())),
+            Request::RPop(rpop) => self.rpop(rpop),
+            Request::RPush(rpush) => self.rpush(rpush),
+            Request::Set(set) => self.set(set),
+            Request::SetNx(setnx) => self.setnx(setnx),
+            Request::StrLen(strlen) => self.strlen(strlen),
+            Request::Ttl(ttl) => self.ttl(ttl),
+            Request::Type(request) => self.key_type(request),
+            Request::Unwatch(unwatch) => unwatch.response(),
+            Request::Watch(watch) => self.watch(watch),
+        }
+    }
+}
+
+impl Seg {
+    fn get(&mut self, get: &GetRequest) -> Response {
+        match self.data.get(get.key()) {
+            Some(item) => {
+                if Metadata::from_optional(item.optional()).value_type != ValueType::String {
+                    return Response::error(WRONGTYPE);
+                }
+                match item.value() {
+                    seg::Value::Bytes(b) => Response::bulk_string(b),
+                    seg::Value::U64(v) => Response::bulk_string(format!("{}", v).as_bytes()),
+                }
+            }
+            None => Response::null(),
+        }
+    }
+
+    fn set(&mut self, set: &SetRequest) -> Response {
+        // copy out anything needed from the current item before it is replaced
+        let (exists, previous) = match self.data.get_no_freq_incr(set.key()) {
+            Some(item) => {
+                let metadata = Metadata::from_optional(item.optional());
+                if set.get_old() && metadata.value_type != ValueType::String {
+                    return Response::error(WRONGTYPE);
+                }
+                let previous = match item.value() {
+                    _ if !set.get_old() => Response::null(),
+                    seg::Value::Bytes(b) => Response::bulk_string(b),
+                    seg::Value::U64(v) => Response::bulk_string(format!("{}", v).as_bytes()),
+                };
+                (true, previous)
+            }
+            None => (false, Response::null()),
+        };
+
+        let stored = if set.get_old() {
+            previous
+        } else {
+            Response::simple_string("OK")
+        };
+
+        match set.mode() {
+            SetMode::Add if exists => return Response::null(),
+            SetMode::Replace if !exists => return Response::null(),
+            _ => {}
+        }
+
+        let ttl = match set.expire_time() {
+            None => Some(Duration::ZERO),
+            Some(ExpireTime::KeepTtl) => Some(self.data.ttl(set.key()).unwrap_or_default()),
+            Some(ExpireTime::Seconds(s)) => expire_after(s),
+            Some(ExpireTime::Milliseconds(ms)) => expire_after((ms / 1000).max(1)),
+            Some(ExpireTime::UnixSeconds(s)) => expire_at(s),
+            Some(ExpireTime::UnixMilliseconds(ms)) => expire_at(ms / 1000),
+        };
+
+        let ttl = match ttl {
+            Some(ttl) => ttl,
+            None => {
+                // an expiration in the past maps to a delete
+                self.data.delete(set.key());
+                return stored;
+            }
+        };
+
+        let metadata = Metadata::new(ValueType::String);
+        match self.insert(set.key(), set.value(), metadata, ttl) {
+            Ok(_) => stored,
+            Err(_) => Response::error("ERR failed to store value"),
+        }
+    }
+}
+
+/// The type of the value held by an item.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum ValueType {
+    String = 0,
+    List = 1,
+    Hash = 2,
+    SortedMap = 3,
+}
+
+impl ValueType {
+    /// The name of the type, as reported by `TYPE`.
+    fn name(&self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::List => "list",
+            Self::Hash => "hash",
+            Self::SortedMap => "sortedmap",
+        }
+    }
+
+    /// The name of the type, as used in error replies.
+    fn description(&self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::List => "list",
+            Self::Hash => "hash",
+            Self::SortedMap => "sorted map",
+        }
+    }
+
+    /// Whether the entries of a collection of this type are pairs of a key
+    /// and its value.
+    fn is_paired(&self) -> bool {
+        matches!(self, Self::Hash | Self::SortedMap)
+    }
+
+    fn corrupt(&self) -> Response {
+        Response::error(format!("ERR {} is corrupt", self.description()))
+    }
+}
+
+/// Per-item metadata which is stored in the item's optional data.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Metadata {
+    value_type: ValueType,
+}
+
+impl Metadata {
+    const SIZE: usize = 1;
+
+    fn new(value_type: ValueType) -> Self {
+        Self { value_type }
+    }
+
+    /// Decodes the metadata from the optional data of an item. Items which
+    /// were not written through this module are treated as strings.
+    fn from_optional(optional: Option<&[u8]>) -> Self {
+        let value_type = match optional {
+            Some([1]) => ValueType::List,
+            Some([2]) => ValueType::Hash,
+            Some([3]) => ValueType::SortedMap,
+            _ => ValueType::String,
+        };
+        Self::new(value_type)
+    }
+
+    fn to_bytes(self) -> [u8; Self::SIZE] {
+        [self.value_type as u8]
+    }
+}
+
+impl Seg {
+    /// Writes the item with the metadata. A TTL of zero means that the key
+    /// does not expire.
+    fn insert<'a>(
+        &mut self,
+        key: &'a [u8],
+        value: impl Into<seg::Value<'a>>,
+        metadata: Metadata,
+        ttl: Duration,
+    ) -> Result<(), SegError> {
+        self.data
+            .insert(key, value, Some(&metadata.to_bytes()), ttl)
+    }
+
+    /// Copies the collection stored at the key so that it can be modified.
+    /// Returns `Ok(None)` if the key does not exist, and an error response if
+    /// the key holds some other type of value.
+    #[allow(clippy::type_complexity)]
+    fn collection_mut(
+        &mut self,
+        key: &[u8],
+        value_type: ValueType,
+    ) -> Result<Option<(ZipListBuf, Metadata)>, Response> {
+        let item = match self.data.get(key) {
+            Some(item) => item,
+            None => return Ok(None),
+        };
+        let metadata = Metadata::from_optional(item.optional());
+        let collection = as_collection(&item, value_type)?;
+        let collection =
+            ZipListBuf::try_from(collection.as_bytes()).map_err(|_| value_type.corrupt())?;
+        Ok(Some((collection, metadata)))
+    }
+
+    /// Writes the collection back to storage, keeping the remaining TTL of the
+    /// key. Empty collections are removed.
+    fn store_collection(
+        &mut self,
+        key: &[u8],
+        collection: &ZipListBuf,
+        metadata: Metadata,
+    ) -> Result<(), Response> {
+        if collection.is_empty() {
+            self.data.delete(key);
+            return Ok(());
+        }
+        let ttl = self.data.ttl(key).unwrap_or_default();
+        self.insert(key, collection.as_bytes(), metadata, ttl)
+            .map_err(|_| {
+                Response::error(format!(
+                    "ERR {} is too large",
+                    metadata.value_type.description()
+                ))
+            })
+    }
+}
+
+/// Borrows the collection stored in the item, or returns an error response if
+/// the item holds some other type of value.
+fn as_collection(item: &seg::Item, value_type: ValueType) -> Result<ZipList<'_>, Response> {
+    if Metadata::from_optional(item.optional()).value_type != value_type {
+        return Err(Response::error(WRONGTYPE));
+    }
+    let collection = match item.value() {
+        seg::Value::Bytes(b) => ZipList::new(b).map_err(|_| value_type.corrupt())?,
+        seg::Value::U64(_) => return Err(value_type.corrupt()),
+    };
+    // every key must be followed by its value
+    if value_type.is_paired() && collection.len() % 2 != 0 {
+        return Err(value_type.corrupt());
+    }
+    Ok(collection)
+}
+
+/// The TTL for a key which expires after the given number of seconds, or
+/// `None` if the key has already expired.
+fn expire_after(seconds: u64) -> Option<Duration> {
+    match seconds {
+        0 => None,
+        seconds => Some(Duration::from_secs(seconds.min(u32::MAX as u64))),
+    }
+}
+
+/// The TTL for a key which expires at the given unix time in seconds, or
+/// `None` if the key has already expired.
+fn expire_at(unix_seconds: u64) -> Option<Duration> {
+    expire_after(unix_seconds.saturating_sub(now() as u64))
+}
@@ -0,0 +1,143 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Sorted map commands, which were originally added to Twitter's internal
+//! version of redis32 as btree commands. Each sorted map is stored as a
+//! [`ziplist`] in the value of a single item where every inner key is
+//! immediately followed by its value, and the pairs are kept in ascending
+//! order of their inner keys. The TTL applies to the outer key, and maps which
+//! become empty are removed.
+
+use super::*;
+use std::cmp::Ordering;
+
+impl Seg {
+    pub(super) fn badd(&mut self, badd: &BAddRequest) -> Response {
+        let map = self.collection_mut(badd.outer_key(), ValueType::SortedMap);
+        let (mut map, metadata) = match map {
+            Ok(Some(map)) => map,
+            Ok(None) => (ZipListBuf::new(), Metadata::new(ValueType::SortedMap)),
+            Err(response) => return response,
+        };
+
+        let mut added = 0;
+        for (inner_key, value) in badd.inner_key_value_pairs().iter() {
+            match search(map.as_ziplist(), inner_key) {
+                Ok(index) => {
+                    map.replace(index + 1, value);
+                }
+                Err(index) => {
+                    map.insert(index, inner_key);
+                    map.insert(index + 1, value);
+                    added += 1;
+                }
+            }
+        }
+
+        match self.store_collection(badd.outer_key(), &map, metadata) {
+            Ok(()) => Response::integer(added),
+            Err(response) => response,
+        }
+    }
+
+    pub(super) fn bcard(&mut self, bcard: &BCardRequest) -> Response {
+        let item = match self.data.get(bcard.outer_key()) {
+            Some(item) => item,
+            None => return Response::integer(0),
+        };
+        match as_collection(&item, ValueType::SortedMap) {
+            Ok(map) => Response::integer((map.len() / 2) as i64),
+            Err(response) => response,
+        }
+    }
+
+    pub(super) fn bdel(&mut self, bdel: &BDelRequest) -> Response {
+        let map = self.collection_mut(bdel.outer_key(), ValueType::SortedMap);
+        let (mut map, metadata) = match map {
+            Ok(Some(map)) => map,
+            Ok(None) => return Response::integer(0),
+            Err(response) => return response,
+        };
+
+        let mut removed = 0;
+        for inner_key in bdel.inner_keys().iter() {
+            if let Ok(index) = search(map.as_ziplist(), inner_key) {
+                // remove both the inner key and its value
+                map.remove(index);
+                map.remove(index);
+                removed += 1;
+            }
+        }
+
+        if removed == 0 {
+            return Response::integer(0);
+        }
+
+        match self.store_collection(bdel.outer_key(), &map, metadata) {
+            Ok(()) => Response::integer(removed),
+            Err(response) => response,
+        }
+    }
+
+    pub(super) fn bget(&mut self, bget: &BGetRequest) -> Response {
+        let item = match self.data.get(bget.outer_key()) {
+            Some(item) => item,
+            None => return Response::null(),
+        };
+        let map = match as_collection(&item, ValueType::SortedMap) {
+            Ok(map) => map,
+            Err(response) => return response,
+        };
+        match search(map, bget.inner_key()) {
+            Ok(index) => Response::bulk_string(map.get(index + 1).unwrap_or_default()),
+            Err(_) => Response::null(),
+        }
+    }
+
+    pub(super) fn brange(&mut self, brange: &BRangeRequest) -> Response {
+        let item = match self.data.get(brange.outer_key()) {
+            Some(item) => item,
+            None => return Response::array(Vec::new()),
+        };
+        let map = match as_collection(&item, ValueType::SortedMap) {
+            Ok(map) => map,
+            Err(response) => return response,
+        };
+
+        let start = match brange.inner_key_start() {
+            Some(inner_key) => match search(map, inner_key) {
+                Ok(index) | Err(index) => index,
+            },
+            None => 0,
+        };
+        let count = brange
+            .count()
+            .map(|count| count.saturating_mul(2).min(usize::MAX as u64) as usize)
+            .unwrap_or(usize::MAX);
+
+        let values = map
+            .iter()
+            .skip(start)
+            .take(count)
+            .map(Response::bulk_string)
+            .collect();
+        Response::array(values)
+    }
+}
+
+/// Searches the map for the inner key. Returns `Ok` with the index of the
+/// entry which holds the inner key if it is present, otherwise `Err` with the
+/// index at which it would be inserted to keep the map in order.
+fn search(map: ZipList, inner_key: &[u8]) -> Result<usize, usize> {
+    let mut entries = map.iter();
+    let mut index = 0;
+    while let (Some(key), Some(_)) = (entries.next(), entries.next()) {
+        match key.cmp(inner_key) {
+            Ordering::Less => index += 2,
+            Ordering::Equal => return Ok(index),
+            Ordering::Greater => return Err(index),
+        }
+    }
+    Err(index)
+}
@@ -0,0 +1,160 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! String commands beyond `GET` and `SET`. Integers are stored as their
+//! decimal representation, so the arithmetic commands parse and rewrite the
+//! whole value. Commands which modify an existing value keep the TTL of the
+//! key, while commands which replace the value discard it.
+
+use super::*;
+
+const NOT_AN_INTEGER: &str = "ERR value is not an integer or out of range";
+const OVERFLOW: &str = "ERR increment or decrement would overflow";
+
+impl Seg {
+    pub(super) fn append(&mut self, append: &AppendRequest) -> Response {
+        let mut value = match self.data.get(append.key()) {
+            Some(item) => match as_string(&item) {
+                Ok(value) => value,
+                Err(response) => return response,
+            },
+            None => Vec::new(),
+        };
+
+        value.extend_from_slice(append.value());
+
+        let metadata = Metadata::new(ValueType::String);
+        let ttl = self.data.ttl(append.key()).unwrap_or_default();
+        match self.insert(append.key(), value.as_slice(), metadata, ttl) {
+            Ok(_) => Response::integer(value.len() as i64),
+            Err(_) => Response::error("ERR failed to store value"),
+        }
+    }
+
+    pub(super) fn decr(&mut self, decr: &DecrRequest) -> Response {
+        self.add(decr.key(), -1)
+    }
+
+    pub(super) fn decrby(&mut self, decrby: &DecrByRequest) -> Response {
+        match decrby.decrement().checked_neg() {
+            Some(delta) => self.add(decrby.key(), delta),
+            None => Response::error(OVERFLOW),
+        }
+    }
+
+    pub(super) fn getset(&mut self, getset: &GetSetRequest) -> Response {
+        let previous = match self.data.get_no_freq_incr(getset.key()) {
+            Some(item) => match as_string(&item) {
+                Ok(value) => Response::bulk_string(&value),
+                Err(response) => return response,
+            },
+            None => Response::null(),
+        };
+
+        let metadata = Metadata::new(ValueType::String);
+        match self.insert(getset.key(), getset.value(), metadata, Duration::ZERO) {
+            Ok(_) => previous,
+            Err(_) => Response::error("ERR failed to store value"),
+        }
+    }
+
+    pub(super) fn incr(&mut self, incr: &IncrRequest) -> Response {
+        self.add(incr.key(), 1)
+    }
+
+    pub(super) fn incrby(&mut self, incrby: &IncrByRequest) -> Response {
+        self.add(incrby.key(), incrby.increment())
+    }
+
+    pub(super) fn mget(&mut self, mget: &MGetRequest) -> Response {
+        let values = mget
+            .keys()
+            .iter()
+            .map(|key| match self.data.get(key) {
+                Some(item) => match as_string(&item) {
+                    Ok(value) => Response::bulk_string(&value),
+                    Err(_) => Response::null(),
+                },
+                None => Response::null(),
+            })
+            .collect();
+        Response::array(values)
+    }
+
+    pub(super) fn mset(&mut self, mset: &MSetRequest) -> Response {
+        let metadata = Metadata::new(ValueType::String);
+        for (key, value) in mset.pairs().iter() {
+            if self.insert(key, *value, metadata, Duration::ZERO).is_err() {
+                return Response::error("ERR failed to store value");
+            }
+        }
+        Response::simple_string("OK")
+    }
+
+    pub(super) fn setnx(&mut self, setnx: &SetNxRequest) -> Response {
+        if self.data.get_no_freq_incr(setnx.key()).is_some() {
+            return Response::integer(0);
+        }
+
+        let metadata = Metadata::new(ValueType::String);
+        match self.insert(setnx.key(), setnx.value(), metadata, Duration::ZERO) {
+            Ok(_) => Response::integer(1),
+            Err(_) => Response::error("ERR failed to store value"),
+        }
+    }
+
+    pub(super) fn strlen(&mut self, strlen: &StrLenRequest) -> Response {
+        match self.data.get(strlen.key()) {
+            Some(item) => match as_string(&item) {
+                Ok(value) => Response::integer(value.len() as i64),
+                Err(response) => response,
+            },
+            None => Response::integer(0),
+        }
+    }
+
+    /// Adds the delta to the integer stored at the key, keeping the remaining
+    /// TTL of the key. Keys which do not exist are treated as zero.
+    fn add(&mut self, key: &[u8], delta: i64) -> Response {
+        let current = match self.data.get(key) {
+            Some(item) => {
+                let current = match as_string(&item) {
+                    Ok(value) => std::str::from_utf8(&value)
+                        .ok()
+                        .and_then(|v| v.parse::<i64>().ok()),
+                    Err(response) => return response,
+                };
+                match current {
+                    Some(current) => current,
+                    None => return Response::error(NOT_AN_INTEGER),
+                }
+            }
+            None => 0,
+        };
+
+        let value = match current.checked_add(delta) {
+            Some(value) => value,
+            None => return Response::error(OVERFLOW),
+        };
+
+        let metadata = Metadata::new(ValueType::String);
+        let ttl = self.data.ttl(key).unwrap_or_default();
+        match self.insert(key, format!("{}", value).as_bytes(), metadata, ttl) {
+            Ok(_) => Response::integer(value),
+            Err(_) => Response::error("ERR failed to store value"),
+        }
+    }
+}
+
+/// Copies the string stored in the item, or returns an error response if the
+/// item holds some other type of value.
+fn as_string(item: &seg::Item) -> Result<Vec<u8>, Response> {
+    if Metadata::from_optional(item.optional()).value_type != ValueType::String {
+        return Err(Response::error(WRONGTYPE));
+    }
+    match item.value() {
+        seg::Value::Bytes(b) => Ok(b.to_vec()),
+        seg::Value::U64(v) => Ok(format!("{}", v).into_bytes()),
+    }
+}
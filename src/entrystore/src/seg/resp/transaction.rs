@@ -0,0 +1,41 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Transactions. All requests are executed by a single storage thread, so the
+//! queued commands of a transaction are run back to back without any other
+//! request in between. Watched keys are compared by their CAS value, which
+//! seg changes whenever an item is inserted. The CAS value is shared by the
+//! keys in a hash bucket, so a write to another key in the same bucket may
+//! also abort the transaction, but a write to a watched key always does.
+
+use super::*;
+
+impl Seg {
+    pub(super) fn exec(&mut self, exec: &ExecRequest) -> Response {
+        if let Some(response) = exec.error() {
+            return response;
+        }
+        let requests = exec.requests().unwrap_or_default();
+
+        if !exec.unchanged(|key| self.key_cas(key)) {
+            return Response::null_array();
+        }
+
+        Response::array(
+            requests
+                .iter()
+                .map(|request| self.execute(request))
+                .collect(),
+        )
+    }
+
+    pub(super) fn watch(&mut self, watch: &WatchRequest) -> Response {
+        watch.watch(|key| self.key_cas(key))
+    }
+
+    /// The CAS value of the key, or `None` if the key does not exist.
+    fn key_cas(&mut self, key: &[u8]) -> Option<u32> {
+        self.data.get_no_freq_incr(key).map(|item| item.cas())
+    }
+}
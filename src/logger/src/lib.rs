@@ -38,6 +38,9 @@ pub use rustcommon_logger::*;
 
 use config::{DebugConfig, KlogConfig};
 
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
 ////////////////////////////////////////////////////////////////////////////////
 // TODO(bmartin): everything below is Pelikan specific, and should be factored
 // out into a helper when we move this crate into rustcommon
@@ -46,12 +49,56 @@ use config::{DebugConfig, KlogConfig};
 #[macro_export]
 macro_rules! klog {
     ($($arg:tt)*) => (
-        // we choose error level here because it is the lowest level and will
-        // not be filtered unless the level filter is set to `off`
-        error!(target: "klog", $($arg)*);
+        // commands are sampled before the message is formatted, so the
+        // sampling rate can be changed at runtime
+        if $crate::klog_sampled() {
+            // we choose error level here because it is the lowest level and
+            // will not be filtered unless the level filter is set to `off`
+            error!(target: "klog", $($arg)*);
+        }
     )
 }
 
+// log 1 in every N commands, with 0 disabling the command log
+static KLOG_SAMPLE: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    // each thread counts its own commands, so that sampling does not contend
+    // on a counter shared by all the workers
+    static KLOG_COUNT: Cell<usize> = Cell::new(0);
+}
+
+/// Returns true if the current command should be written to the command log.
+#[doc(hidden)]
+pub fn klog_sampled() -> bool {
+    match KLOG_SAMPLE.load(Ordering::Relaxed) {
+        0 => false,
+        1 => true,
+        sample => KLOG_COUNT.with(|count| {
+            let current = count.get();
+            count.set(current.wrapping_add(1));
+            current % sample == 0
+        }),
+    }
+}
+
+/// Returns the current sampling rate of the command log.
+pub fn klog_sample() -> usize {
+    KLOG_SAMPLE.load(Ordering::Relaxed)
+}
+
+/// Change the sampling rate of the command log, so that 1 in every `sample`
+/// commands is logged. A rate of `0` stops logging commands.
+pub fn set_klog_sample(sample: usize) {
+    KLOG_SAMPLE.store(sample, Ordering::Relaxed);
+}
+
+/// Change the level of the debug log. The level can be raised above the
+/// configured level as the logger itself does not filter messages.
+pub fn set_log_level(level: Level) {
+    set_max_level(level.to_level_filter());
+}
+
 pub trait Klog {
     type Response;
 
@@ -81,6 +128,7 @@ pub fn configure_logging<T: DebugConfig + KlogConfig>(config: &T) -> Box<dyn Dra
     let klog_config = config.klog();
 
     let klog = if let Some(file) = klog_config.file() {
+        set_klog_sample(klog_config.sample());
         let backup = klog_config.backup().unwrap_or(format!("{}.old", file));
         let output = Box::new(
             File::new(&file, &backup, klog_config.max_size()).expect("failed to open klog file"),
@@ -88,7 +136,8 @@ pub fn configure_logging<T: DebugConfig + KlogConfig>(config: &T) -> Box<dyn Dra
         SamplingLogBuilder::new()
             .output(output)
             .format(klog_format)
-            .sample(klog_config.sample())
+            // sampling is done by the `klog!` macro
+            .sample(1)
             .log_queue_depth(klog_config.queue_depth())
             .single_message_size(klog_config.single_message_size())
             .build()
@@ -97,10 +146,16 @@ pub fn configure_logging<T: DebugConfig + KlogConfig>(config: &T) -> Box<dyn Dra
         NopLogBuilder::new().build()
     };
 
-    MultiLogBuilder::new()
-        .level_filter(debug_config.log_level().to_level_filter())
+    // messages are only filtered by the max level, which allows the level to
+    // be changed at runtime
+    let drain = MultiLogBuilder::new()
+        .level_filter(LevelFilter::Trace)
         .default(debug_log)
         .add_target("klog", klog)
         .build()
-        .start()
+        .start();
+
+    set_log_level(debug_config.log_level());
+
+    drain
 }
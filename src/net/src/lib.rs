@@ -7,12 +7,14 @@ mod listener;
 mod stream;
 mod tcp;
 mod tls_tcp;
+mod udp;
 
 pub use connector::*;
 pub use listener::*;
 pub use stream::*;
 pub use tcp::*;
 pub use tls_tcp::*;
+pub use udp::*;
 
 pub mod event {
     pub use mio::event::*;
@@ -46,6 +48,15 @@ gauge!(TCP_CONN_CURR, "current number of open TCP streams");
 counter!(TCP_RECV_BYTE, "number of bytes received on TCP streams");
 counter!(TCP_SEND_BYTE, "number of bytes sent on TCP streams");
 
+counter!(UDP_RECV, "number of datagrams received on UDP sockets");
+counter!(UDP_RECV_BYTE, "number of bytes received on UDP sockets");
+counter!(UDP_SEND, "number of datagrams sent on UDP sockets");
+counter!(UDP_SEND_BYTE, "number of bytes sent on UDP sockets");
+counter!(
+    UDP_SEND_EX,
+    "number of times sending a datagram resulted in an exception"
+);
+
 counter!(STREAM_ACCEPT, "number of calls to accept");
 counter!(
     STREAM_ACCEPT_EX,
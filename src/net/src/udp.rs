@@ -0,0 +1,139 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use crate::*;
+
+/// A non-blocking UDP socket. Unlike a `TcpListener`, there are no sessions
+/// to accept, each datagram is received along with the address of the peer
+/// which sent it.
+pub struct UdpSocket {
+    inner: mio::net::UdpSocket,
+}
+
+impl Deref for UdpSocket {
+    type Target = mio::net::UdpSocket;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl UdpSocket {
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> Result<UdpSocket> {
+        let s = std::net::UdpSocket::bind(addr)?;
+        s.set_nonblocking(true)?;
+
+        let inner = mio::net::UdpSocket::from_std(s);
+
+        Ok(Self { inner })
+    }
+
+    /// Receives a single datagram. If the datagram is larger than the buffer,
+    /// the excess bytes are discarded.
+    ///
+    /// An error `e` with `e.kind()` of `ErrorKind::WouldBlock` indicates that
+    /// there are no datagrams waiting to be received.
+    pub fn recv_from(&self, buf: &mut [u8]) -> Result<(usize, SocketAddr)> {
+        match self.inner.recv_from(buf) {
+            Ok((amt, addr)) => {
+                UDP_RECV.increment();
+                UDP_RECV_BYTE.add(amt as _);
+                Ok((amt, addr))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Sends a single datagram to the peer.
+    pub fn send_to(&self, buf: &[u8], addr: SocketAddr) -> Result<usize> {
+        match self.inner.send_to(buf, addr) {
+            Ok(amt) => {
+                UDP_SEND.increment();
+                UDP_SEND_BYTE.add(amt as _);
+                Ok(amt)
+            }
+            Err(e) => {
+                if e.kind() != ErrorKind::WouldBlock {
+                    UDP_SEND_EX.increment();
+                }
+                Err(e)
+            }
+        }
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.inner.local_addr()
+    }
+}
+
+impl event::Source for UdpSocket {
+    fn register(
+        &mut self,
+        registry: &mio::Registry,
+        token: mio::Token,
+        interests: mio::Interest,
+    ) -> Result<()> {
+        self.inner.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &mio::Registry,
+        token: mio::Token,
+        interests: mio::Interest,
+    ) -> Result<()> {
+        self.inner.reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &mio::Registry) -> Result<()> {
+        self.inner.deregister(registry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ping_pong() {
+        let server = UdpSocket::bind("127.0.0.1:0").expect("failed to bind");
+        let client = UdpSocket::bind("127.0.0.1:0").expect("failed to bind");
+
+        let server_addr = server.local_addr().expect("socket has no local addr");
+        let client_addr = client.local_addr().expect("socket has no local addr");
+
+        let mut buf = [0; 4096];
+
+        // nothing has been sent yet
+        match server.recv_from(&mut buf) {
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        client
+            .send_to(b"PING\r\n", server_addr)
+            .expect("failed to send");
+
+        std::thread::sleep(std::time::Duration::from_millis(100));
+
+        match server.recv_from(&mut buf) {
+            Ok((6, addr)) => {
+                assert_eq!(addr, client_addr);
+                assert_eq!(&buf[0..6], b"PING\r\n");
+                server.send_to(b"PONG\r\n", addr).expect("failed to send");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        std::thread::sleep(std::time::Duration::from_millis(100));
+
+        match client.recv_from(&mut buf) {
+            Ok((6, addr)) => {
+                assert_eq!(addr, server_addr);
+                assert_eq!(&buf[0..6], b"PONG\r\n");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
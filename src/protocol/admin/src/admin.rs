@@ -4,14 +4,17 @@
 
 //! Implements the `Admin` protocol.
 
-// TODO(bmartin): we will replace the admin protocol and listener with a HTTP
-// listener in the future.
+// TODO(bmartin): the HTTP admin endpoints are served from the same event loop
+// as this protocol, and we will replace the admin protocol with them in the
+// future.
 
 use crate::*;
 use common::bytes::SliceExtension;
 use rustcommon_metrics::*;
 
+use std::collections::HashMap;
 use std::io::{Error, ErrorKind, Result};
+use std::sync::Arc;
 
 // TODO(bmartin): see TODO for protocol::data::Request, this is cleaner here
 // since the variants are simple, but better to take the same approach in both
@@ -19,7 +22,14 @@ use std::io::{Error, ErrorKind, Result};
 #[derive(PartialEq, Eq, Debug)]
 pub enum AdminRequest {
     FlushAll,
+    Reload,
     Stats,
+    StatsConns,
+    StatsReset,
+    /// A page of the per-segment stats
+    StatsSeg(u32),
+    StatsSettings,
+    StatsTtl,
     Version,
     Quit,
 }
@@ -46,18 +56,36 @@ impl Parse<AdminRequest> for AdminRequestParser {
             let mut single_byte_windows = trimmed_buffer.windows(1);
             if let Some(command_verb_end) = single_byte_windows.position(|w| w == b" ") {
                 let command_verb = &trimmed_buffer[0..command_verb_end];
-                // TODO(bmartin): 'stats slab' will go here eventually which will
-                // remove the need for ignoring this lint.
-                #[allow(clippy::match_single_binding)]
-                match command_verb {
-                    _ => Err(Error::from(ErrorKind::InvalidInput)),
-                }
+                let argument = trimmed_buffer[command_verb_end..].trim();
+                let request = match (command_verb, argument) {
+                    (b"stats", b"conns") => AdminRequest::StatsConns,
+                    (b"stats", b"reset") => AdminRequest::StatsReset,
+                    (b"stats", b"seg") => AdminRequest::StatsSeg(0),
+                    (b"stats", argument) if argument.starts_with(b"seg ") => {
+                        match std::str::from_utf8(argument[4..].trim())
+                            .ok()
+                            .and_then(|page| page.parse().ok())
+                        {
+                            Some(page) => AdminRequest::StatsSeg(page),
+                            None => {
+                                return Err(Error::from(ErrorKind::InvalidInput));
+                            }
+                        }
+                    }
+                    (b"stats", b"settings") => AdminRequest::StatsSettings,
+                    (b"stats", b"ttl") => AdminRequest::StatsTtl,
+                    _ => {
+                        return Err(Error::from(ErrorKind::InvalidInput));
+                    }
+                };
+                Ok(ParseOk::new(request, command_end + CRLF.len()))
             } else {
                 match &trimmed_buffer[0..] {
                     b"flush_all" => Ok(ParseOk::new(
                         AdminRequest::FlushAll,
                         command_end + CRLF.len(),
                     )),
+                    b"reload" => Ok(ParseOk::new(AdminRequest::Reload, command_end + CRLF.len())),
                     b"stats" => Ok(ParseOk::new(AdminRequest::Stats, command_end + CRLF.len())),
                     b"quit" => Ok(ParseOk::new(AdminRequest::Quit, command_end + CRLF.len())),
                     b"version" => Ok(ParseOk::new(
@@ -87,10 +115,44 @@ impl Compose for Version {
     }
 }
 
+/// The values of the counters when the stats were last reset. The counters
+/// are reported relative to these values by `stats` and `stats conns`, so that
+/// a `stats reset` leaves the counters which are exported elsewhere, such as
+/// by the HTTP admin endpoints, untouched.
+#[derive(Default)]
+pub struct StatsBaseline {
+    counters: HashMap<String, u64>,
+}
+
+impl StatsBaseline {
+    /// Takes a snapshot of the current value of every counter.
+    pub fn snapshot() -> Self {
+        let mut counters = HashMap::new();
+        for metric in &rustcommon_metrics::metrics() {
+            if let Some(counter) = metric
+                .as_any()
+                .and_then(|any| any.downcast_ref::<Counter>())
+            {
+                counters.insert(metric.name().to_string(), counter.value());
+            }
+        }
+        Self { counters }
+    }
+
+    // the value of the counter since the snapshot was taken
+    fn since(&self, name: &str, value: u64) -> u64 {
+        value.wrapping_sub(self.counters.get(name).copied().unwrap_or(0))
+    }
+}
+
 pub enum AdminResponse {
     Hangup,
     Ok,
-    Stats,
+    Reset,
+    ServerError(String),
+    Stats(Arc<StatsBaseline>),
+    StatsConns(Arc<StatsBaseline>),
+    StatsInfo(Vec<(String, String)>),
     Version(Version),
 }
 
@@ -103,8 +165,26 @@ impl AdminResponse {
         Self::Ok
     }
 
-    pub fn stats() -> Self {
-        Self::Stats
+    pub fn reset() -> Self {
+        Self::Reset
+    }
+
+    pub fn server_error(message: &str) -> Self {
+        Self::ServerError(message.to_string())
+    }
+
+    pub fn stats(baseline: Arc<StatsBaseline>) -> Self {
+        Self::Stats(baseline)
+    }
+
+    pub fn stats_conns(baseline: Arc<StatsBaseline>) -> Self {
+        Self::StatsConns(baseline)
+    }
+
+    /// A section of stats which is provided by the caller, such as the
+    /// settings or the stats which are collected from the storage.
+    pub fn stats_info(stats: Vec<(String, String)>) -> Self {
+        Self::StatsInfo(stats)
     }
 
     pub fn version(version: String) -> Self {
@@ -120,48 +200,88 @@ impl Compose for AdminResponse {
                 buf.put_slice(b"OK\r\n");
                 4
             }
-            Self::Stats => {
-                let mut size = 0;
-                let mut data = Vec::new();
-                for metric in &rustcommon_metrics::metrics() {
-                    let any = match metric.as_any() {
-                        Some(any) => any,
-                        None => {
-                            continue;
-                        }
-                    };
-
-                    if let Some(counter) = any.downcast_ref::<Counter>() {
-                        data.push(format!("STAT {} {}\r\n", metric.name(), counter.value()));
-                    } else if let Some(gauge) = any.downcast_ref::<Gauge>() {
-                        data.push(format!("STAT {} {}\r\n", metric.name(), gauge.value()));
-                    } else if let Some(heatmap) = any.downcast_ref::<Heatmap>() {
-                        for (label, value) in PERCENTILES {
-                            let percentile =
-                                heatmap.percentile(*value).map(|b| b.high()).unwrap_or(0);
-                            data.push(format!(
-                                "STAT {}_{} {}\r\n",
-                                metric.name(),
-                                label,
-                                percentile
-                            ));
-                        }
-                    }
-                }
-
-                data.sort();
-                for line in data {
-                    size += line.as_bytes().len();
-                    buf.put_slice(line.as_bytes());
-                }
-                buf.put_slice(b"END\r\n");
-                size + 5
+            Self::Reset => {
+                buf.put_slice(b"RESET\r\n");
+                7
+            }
+            Self::ServerError(message) => {
+                buf.put_slice(b"SERVER_ERROR ");
+                buf.put_slice(message.as_bytes());
+                buf.put_slice(b"\r\n");
+                15 + message.len()
+            }
+            Self::Stats(baseline) => compose_stats(buf, metric_stats(baseline, |_| true)),
+            Self::StatsConns(baseline) => {
+                compose_stats(buf, metric_stats(baseline, is_conn_metric))
             }
+            Self::StatsInfo(stats) => compose_stats(
+                buf,
+                stats
+                    .iter()
+                    .map(|(name, value)| format!("STAT {} {}\r\n", name, value))
+                    .collect(),
+            ),
             Self::Version(v) => v.compose(buf),
         }
     }
 }
 
+// the connection stats are those of the sockets and the sessions on them
+fn is_conn_metric(name: &str) -> bool {
+    name.starts_with("tcp_")
+        || name.starts_with("stream_")
+        || name.starts_with("session_")
+        || name.contains("_session_")
+}
+
+// renders a sorted list of stats lines for the metrics with matching names,
+// with the counters relative to the baseline
+fn metric_stats(baseline: &StatsBaseline, filter: fn(&str) -> bool) -> Vec<String> {
+    let mut data = Vec::new();
+    for metric in &rustcommon_metrics::metrics() {
+        if !filter(metric.name()) {
+            continue;
+        }
+
+        let any = match metric.as_any() {
+            Some(any) => any,
+            None => {
+                continue;
+            }
+        };
+
+        if let Some(counter) = any.downcast_ref::<Counter>() {
+            let value = baseline.since(metric.name(), counter.value());
+            data.push(format!("STAT {} {}\r\n", metric.name(), value));
+        } else if let Some(gauge) = any.downcast_ref::<Gauge>() {
+            data.push(format!("STAT {} {}\r\n", metric.name(), gauge.value()));
+        } else if let Some(heatmap) = any.downcast_ref::<Heatmap>() {
+            for (label, value) in PERCENTILES {
+                let percentile = heatmap.percentile(*value).map(|b| b.high()).unwrap_or(0);
+                data.push(format!(
+                    "STAT {}_{} {}\r\n",
+                    metric.name(),
+                    label,
+                    percentile
+                ));
+            }
+        }
+    }
+
+    data.sort();
+    data
+}
+
+fn compose_stats(buf: &mut dyn BufMut, lines: Vec<String>) -> usize {
+    let mut size = 0;
+    for line in lines {
+        size += line.len();
+        buf.put_slice(line.as_bytes());
+    }
+    buf.put_slice(b"END\r\n");
+    size + 5
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -198,6 +318,15 @@ mod tests {
         assert_eq!(parsed.unwrap().into_inner(), AdminRequest::Quit);
     }
 
+    #[test]
+    fn parse_reload() {
+        let parser = AdminRequestParser::new();
+
+        let parsed = parser.parse(b"reload\r\n");
+        assert!(parsed.is_ok());
+        assert_eq!(parsed.unwrap().into_inner(), AdminRequest::Reload);
+    }
+
     #[test]
     fn parse_stats() {
         let parser = AdminRequestParser::new();
@@ -207,6 +336,86 @@ mod tests {
         assert_eq!(parsed.unwrap().into_inner(), AdminRequest::Stats);
     }
 
+    #[test]
+    fn parse_stats_subcommands() {
+        let parser = AdminRequestParser::new();
+
+        let requests: Vec<(&[u8], AdminRequest)> = vec![
+            (b"stats conns\r\n", AdminRequest::StatsConns),
+            (b"stats reset\r\n", AdminRequest::StatsReset),
+            (b"stats seg\r\n", AdminRequest::StatsSeg(0)),
+            (b"stats seg 2\r\n", AdminRequest::StatsSeg(2)),
+            (b"stats seg   12 \r\n", AdminRequest::StatsSeg(12)),
+            (b"stats settings\r\n", AdminRequest::StatsSettings),
+            (b"stats ttl\r\n", AdminRequest::StatsTtl),
+            (b"  stats   ttl  \r\n", AdminRequest::StatsTtl),
+        ];
+        for (buffer, request) in requests {
+            let parsed = parser.parse(buffer).expect("failed to parse request");
+            assert_eq!(parsed.consumed(), buffer.len());
+            assert_eq!(parsed.into_inner(), request);
+        }
+    }
+
+    #[test]
+    fn parse_invalid_arguments() {
+        let parser = AdminRequestParser::new();
+
+        let buffers: Vec<&[u8]> = vec![
+            b"stats slab\r\n",
+            b"stats seg ttl\r\n",
+            b"stats seg -1\r\n",
+            b"stats seg 1 2\r\n",
+            b"version seg\r\n",
+            b"flush_all 0\r\n",
+        ];
+        for buffer in buffers.iter() {
+            if let Err(e) = parser.parse(buffer) {
+                assert_eq!(e.kind(), ErrorKind::InvalidInput);
+            } else {
+                panic!("parser should not have returned a request");
+            }
+        }
+    }
+
+    #[test]
+    fn compose_stats_info() {
+        let response = AdminResponse::stats_info(vec![
+            ("ttl:60:segments".to_string(), "2".to_string()),
+            ("ttl:60:items".to_string(), "10".to_string()),
+        ]);
+
+        let mut buf = Vec::new();
+        let size = response.compose(&mut buf);
+        assert_eq!(size, buf.len());
+        assert_eq!(
+            buf,
+            b"STAT ttl:60:segments 2\r\nSTAT ttl:60:items 10\r\nEND\r\n".to_vec()
+        );
+
+        let mut buf = Vec::new();
+        let size = AdminResponse::reset().compose(&mut buf);
+        assert_eq!(size, buf.len());
+        assert_eq!(buf, b"RESET\r\n".to_vec());
+
+        let mut buf = Vec::new();
+        let size = AdminResponse::server_error("unavailable").compose(&mut buf);
+        assert_eq!(size, buf.len());
+        assert_eq!(buf, b"SERVER_ERROR unavailable\r\n".to_vec());
+    }
+
+    #[test]
+    fn stats_baseline() {
+        let mut baseline = StatsBaseline::default();
+        assert_eq!(baseline.since("requests", 10), 10);
+
+        // counters are reported relative to their value at the snapshot,
+        // and counters which were not in the snapshot are reported as is
+        baseline.counters.insert("requests".to_string(), 4);
+        assert_eq!(baseline.since("requests", 10), 6);
+        assert_eq!(baseline.since("responses", 10), 10);
+    }
+
     #[test]
     fn parse_version() {
         let parser = AdminRequestParser::new();
@@ -21,6 +21,48 @@ pub trait Compose {
     }
 }
 
+/// Messages which carry an id that the server echoes back in its response.
+/// This allows a client to pipeline requests on a connection and to match the
+/// responses to their requests, even if they are returned out of order.
+///
+/// The default implementation is for protocols without such an id.
+pub trait Correlate {
+    /// The id of the message, if it has one.
+    fn correlation_id(&self) -> Option<i32> {
+        None
+    }
+
+    /// Replaces the id of the message. Returns `false` if the message has no
+    /// id which can be replaced.
+    fn set_correlation_id(&mut self, _id: i32) -> bool {
+        false
+    }
+
+    /// Returns `false` for messages which the server never replies to, such
+    /// as oneway calls, which must not be left waiting for a response.
+    fn expects_response(&self) -> bool {
+        true
+    }
+}
+
+/// Requests which a proxy can reply to itself, which it does when the backend
+/// fails before it has replied to the request.
+pub trait ErrorResponse {
+    type Response;
+
+    /// An error reply to the request carrying the message. If the protocol has
+    /// no error reply which fits the request, the reply should hang up the
+    /// connection instead.
+    fn error_response(&self, message: &str) -> Self::Response;
+
+    /// Returns `false` if the response cannot be the reply to this request,
+    /// which means that replies have been paired with the wrong requests and
+    /// the connection can no longer be trusted.
+    fn is_reply(&self, _response: &Self::Response) -> bool {
+        true
+    }
+}
+
 pub trait Execute<Request, Response: Compose> {
     fn execute(&mut self, request: &Request) -> Response;
 }
@@ -14,6 +14,14 @@ pub enum Error {
     MissingContentLength,
     #[error("method was unsupported")]
     BadRequestMethod,
+    #[error("chunked body was invalid")]
+    BadChunk,
+    #[error("Transfer-Encoding was unsupported")]
+    UnsupportedTransferEncoding,
+    #[error("Expect header was unsupported")]
+    BadExpectation,
+    #[error("request body was too large")]
+    ValueTooLarge,
 
     /// Contains the number of additional bytes needed to parse the rest of the
     /// request, if known.
@@ -35,7 +43,7 @@ impl Error {
                 .should_close(true)
                 .header("Content-Type", b"text/plain")
                 .body(
-                    format!("Unsupported method, only GET, PUT, and DELETE are supported")
+                    format!("Unsupported method, only GET, PUT, POST, and DELETE are supported")
                         .as_bytes(),
                 ),
             Self::BadContentLength => Response::builder(400)
@@ -46,8 +54,24 @@ impl Error {
                 .should_close(true)
                 .header("Content-Type", b"text/plain")
                 .body(
-                    format!("A Content-Length header is required for all PUT requests").as_bytes(),
+                    b"A Content-Length or Transfer-Encoding header is required for all PUT requests",
                 ),
+            Self::BadChunk => Response::builder(400)
+                .should_close(true)
+                .header("Content-Type", b"text/plain")
+                .body(b"Chunked body was invalid"),
+            Self::UnsupportedTransferEncoding => Response::builder(501)
+                .should_close(true)
+                .header("Content-Type", b"text/plain")
+                .body(b"Only the chunked Transfer-Encoding is supported"),
+            Self::BadExpectation => Response::builder(417)
+                .should_close(true)
+                .header("Content-Type", b"text/plain")
+                .body(b"Only the 100-continue expectation is supported"),
+            Self::ValueTooLarge => Response::builder(413)
+                .should_close(true)
+                .header("Content-Type", b"text/plain")
+                .body(b"Request body was too large"),
             Self::InternalError(message) => Response::builder(500)
                 .should_close(true)
                 .header("Content-Type", b"text/plain")
@@ -11,9 +11,16 @@
 //! - `DELETE` - remove a key from the cache
 //!
 //! In all cases the key is passed in as the request path in the request
-//! and the value is passed in as the request body. The protocol supports
-//! reusing the HTTP connection for multiple requests. The only length
-//! specification supported by pelikan is setting the Content-Length header.
+//! and the value is passed in as the request body. `POST` requests are also
+//! parsed, for endpoints which are not part of the cache such as the admin
+//! endpoints. Connections are kept open
+//! for multiple requests following the `Connection` header, and requests may
+//! be pipelined. Request bodies may be sent with a Content-Length header or
+//! with chunked encoding, and a client may wait for `100 Continue` before it
+//! sends the body. Large values are returned with chunked encoding.
+//!
+//! The TTL and the client flags of a value are passed as headers, see
+//! [`TTL_HEADER`] and [`FLAGS_HEADER`].
 
 #[macro_use]
 extern crate thiserror;
@@ -27,10 +34,19 @@ pub use crate::error::Error;
 pub use crate::request::Headers;
 pub use crate::request::{ParseData, Request, RequestData, RequestParser};
 pub use crate::response::Response;
+pub use crate::util::trim;
 
 pub type Result<T> = std::result::Result<T, Error>;
 pub type ParseResult = Result<Request>;
 
+/// The request header which sets the TTL of a value in seconds. A value which
+/// is stored without a TTL does not expire.
+pub const TTL_HEADER: &str = "X-Pelikan-TTL";
+
+/// The header which carries the opaque client flags of a value. It may be set
+/// when a value is stored, and is returned with the value.
+pub const FLAGS_HEADER: &str = "X-Pelikan-Flags";
+
 pub trait Storage {
     fn get(&mut self, key: &[u8], headers: &Headers) -> Response;
     fn put(&mut self, key: &[u8], value: &[u8], headers: &Headers) -> Response;
@@ -2,9 +2,11 @@
 // Licensed under the Apache License, Version 2.0
 // http://www.apache.org/licenses/LICENSE-2.0
 
+use std::cell::Cell;
 use std::fmt;
 use std::mem::MaybeUninit;
 
+use crate::util::trim;
 use crate::{response::status_line, Error, ParseResult};
 use httparse::{Header, ParserConfig, Status};
 use logger::{error, klog};
@@ -37,6 +39,9 @@ impl Headers {
 pub struct Request {
     pub data: RequestData,
     pub headers: Headers,
+    /// The minor version of the request, `0` for HTTP/1.0 and `1` for
+    /// HTTP/1.1.
+    pub version: u8,
 }
 
 impl Request {
@@ -47,6 +52,28 @@ impl Request {
     pub fn header(&self, hdr: &str) -> Option<&[u8]> {
         self.headers.header(hdr)
     }
+
+    /// Returns `true` if the connection should be kept open after the
+    /// response. HTTP/1.1 connections are persistent unless the client sends
+    /// `Connection: close`, while HTTP/1.0 connections are only kept open if
+    /// the client sends `Connection: keep-alive`.
+    pub fn keep_alive(&self) -> bool {
+        let has_option = |option: &str| {
+            self.header("Connection")
+                .map(|value| {
+                    value
+                        .split(|b| *b == b',')
+                        .any(|v| trim(v).eq_ignore_ascii_case(option.as_bytes()))
+                })
+                .unwrap_or(false)
+        };
+
+        if self.version == 0 {
+            has_option("keep-alive")
+        } else {
+            !has_option("close")
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -54,11 +81,33 @@ pub enum RequestData {
     Get(Vec<u8>),
     Put(Vec<u8>, Vec<u8>),
     Delete(Vec<u8>),
+    Post(Vec<u8>, Vec<u8>),
+    /// A `PUT` or `POST` which is waiting for `100 Continue` before the client sends the
+    /// body. Nothing is consumed from the buffer, and the request is parsed
+    /// again once the body has arrived.
+    Continue(Vec<u8>),
 }
 
-#[derive(Clone, Default)]
+/// The largest request body which is accepted by default.
+pub const DEFAULT_MAX_VALUE_SIZE: usize = 512 * 1024 * 1024; // 512MB max value size
+
+#[derive(Clone)]
 pub struct RequestParser {
     config: ParserConfig,
+    max_value_size: usize,
+    // set once `100 Continue` has been sent for the request at the start of
+    // the buffer, as the parser is cloned for each session
+    continued: Cell<bool>,
+}
+
+impl Default for RequestParser {
+    fn default() -> Self {
+        Self {
+            config: ParserConfig::default(),
+            max_value_size: DEFAULT_MAX_VALUE_SIZE,
+            continued: Cell::new(false),
+        }
+    }
 }
 
 impl RequestParser {
@@ -66,7 +115,15 @@ impl RequestParser {
         Self::default()
     }
 
+    /// Sets the largest request body which is accepted, whether it is sent
+    /// with a Content-Length or with chunked encoding.
+    pub fn max_value_size(mut self, bytes: usize) -> Self {
+        self.max_value_size = bytes;
+        self
+    }
+
     pub fn do_parse(&self, buf: &mut &[u8]) -> ParseResult {
+        let start = *buf;
         let mut headers = [MaybeUninit::uninit(); 32];
         let mut request = httparse::Request::new(&mut []);
         let status =
@@ -89,39 +146,157 @@ impl RequestParser {
 
         let key = urlencoding::decode_binary(&key.as_bytes()).into_owned();
         let headers = Headers::from_httparse(request.headers);
+        let version = request.version.unwrap_or(1);
 
-        match method {
-            "GET" => Ok(Request {
-                data: RequestData::Get(key),
-                headers,
-            }),
-            "DELETE" => Ok(Request {
-                data: RequestData::Delete(key),
-                headers,
-            }),
-            "PUT" => {
-                let content_length = headers
-                    .header("Content-Length")
-                    .ok_or(Error::BadContentLength)?;
-                let len: usize = std::str::from_utf8(content_length)
-                    .map_err(|_| Error::BadContentLength)?
-                    .parse()
-                    .map_err(|_| Error::BadContentLength)?;
-
-                if buf.len() < len {
-                    return Err(Error::PartialRequest(Some(len - buf.len())));
-                }
-
-                let (value, newbuf) = buf.split_at(len);
-                *buf = newbuf;
+        let expect_continue = match headers.header("Expect") {
+            Some(expect) if expect.eq_ignore_ascii_case(b"100-continue") => true,
+            Some(_) => return Err(Error::BadExpectation),
+            None => false,
+        };
 
-                Ok(Request {
-                    data: RequestData::Put(key, value.to_owned()),
-                    headers,
-                })
+        let data = match method {
+            "GET" | "DELETE" | "PUT" | "POST" => {
+                let body = match body_length(&headers)? {
+                    Some(BodyLength::Chunked) => parse_chunked(buf, self.max_value_size),
+                    Some(BodyLength::Length(len)) => {
+                        if len > self.max_value_size {
+                            return Err(Error::ValueTooLarge);
+                        }
+                        if buf.len() < len {
+                            Err(Error::PartialRequest(Some(len - buf.len())))
+                        } else {
+                            let (value, newbuf) = buf.split_at(len);
+                            *buf = newbuf;
+                            Ok(value.to_owned())
+                        }
+                    }
+                    None if method == "PUT" => return Err(Error::MissingContentLength),
+                    None => Ok(Vec::new()),
+                };
+
+                // the client may wait for `100 Continue` before sending the
+                // body, which is only sent once for each request
+                let body = match body {
+                    Err(Error::PartialRequest(needed)) if method == "PUT" || method == "POST" => {
+                        if expect_continue && !self.continued.replace(true) {
+                            *buf = start;
+                            return Ok(Request {
+                                data: RequestData::Continue(key),
+                                headers,
+                                version,
+                            });
+                        }
+                        return Err(Error::PartialRequest(needed));
+                    }
+                    body => body?,
+                };
+
+                match method {
+                    "GET" => RequestData::Get(key),
+                    "DELETE" => RequestData::Delete(key),
+                    "POST" => RequestData::Post(key, body),
+                    _ => RequestData::Put(key, body),
+                }
             }
             _ => return Err(Error::BadRequestMethod),
+        };
+
+        self.continued.set(false);
+
+        Ok(Request {
+            data,
+            headers,
+            version,
+        })
+    }
+}
+
+enum BodyLength {
+    Chunked,
+    Length(usize),
+}
+
+// determines how the length of the request body is given. A transfer-encoding
+// takes precedence over a content-length, and only chunked is supported.
+fn body_length(headers: &Headers) -> Result<Option<BodyLength>, Error> {
+    if let Some(encoding) = headers.header("Transfer-Encoding") {
+        return if trim(encoding).eq_ignore_ascii_case(b"chunked") {
+            Ok(Some(BodyLength::Chunked))
+        } else {
+            Err(Error::UnsupportedTransferEncoding)
+        };
+    }
+
+    match headers.header("Content-Length") {
+        Some(content_length) => std::str::from_utf8(content_length)
+            .map_err(|_| Error::BadContentLength)?
+            .parse()
+            .map(|len| Some(BodyLength::Length(len)))
+            .map_err(|_| Error::BadContentLength),
+        None => Ok(None),
+    }
+}
+
+// decodes a chunked body, which is a sequence of chunks each starting with the
+// length in hex, followed by a zero length chunk and optional trailers. The
+// buffer is only advanced once the whole body has been received, and the body
+// may not decode to more than the max value size.
+fn parse_chunked(buf: &mut &[u8], max_value_size: usize) -> Result<Vec<u8>, Error> {
+    let mut remaining = *buf;
+    let mut body = Vec::new();
+
+    loop {
+        let line = take_line(&mut remaining)?;
+
+        // chunk extensions are ignored
+        let size = trim(line.split(|b| *b == b';').next().unwrap_or(line));
+        let size = std::str::from_utf8(size)
+            .ok()
+            .and_then(|size| usize::from_str_radix(size, 16).ok())
+            .ok_or(Error::BadChunk)?;
+
+        if size == 0 {
+            break;
+        }
+
+        // the size is checked before it is used, so that a huge chunk size
+        // cannot overflow. The body is never larger than the max value size,
+        // so the subtraction cannot underflow
+        if size > max_value_size - body.len() {
+            return Err(Error::ValueTooLarge);
         }
+        if remaining.len() < size + CRLF.len() {
+            return Err(Error::PartialRequest(None));
+        }
+        let (chunk, rest) = remaining.split_at(size);
+        if !rest.starts_with(CRLF) {
+            return Err(Error::BadChunk);
+        }
+        body.extend_from_slice(chunk);
+        remaining = &rest[CRLF.len()..];
+    }
+
+    // trailers end with an empty line, and are ignored
+    while !take_line(&mut remaining)?.is_empty() {}
+
+    *buf = remaining;
+    Ok(body)
+}
+
+const CRLF: &[u8] = b"\r\n";
+
+// the longest line allowed for a chunk size or a trailer
+const MAX_LINE_LEN: usize = 4096;
+
+fn take_line<'a>(buf: &mut &'a [u8]) -> Result<&'a [u8], Error> {
+    match buf.windows(CRLF.len()).position(|w| w == CRLF) {
+        Some(end) => {
+            let line = &buf[..end];
+            *buf = &buf[(end + CRLF.len())..];
+            Ok(line)
+        }
+        None if buf.len() > MAX_LINE_LEN => Err(Error::BadChunk),
+        None => Err(Error::PartialRequest(None)),
     }
 }
 
@@ -164,6 +339,17 @@ impl logger::Klog for Request {
                     line
                 )
             }
+            RequestData::Post(key, val) => {
+                klog!(
+                    "POST '{}' {} => {} {}",
+                    BStr::new(key),
+                    val.len(),
+                    status,
+                    line
+                )
+            }
+            // the request is logged once the body has arrived
+            RequestData::Continue(_) => {}
         };
     }
 }
@@ -190,6 +376,12 @@ impl fmt::Debug for RequestData {
                 .field(&BStr::new(value))
                 .finish(),
             Self::Delete(key) => f.debug_tuple("Delete").field(&BStr::new(key)).finish(),
+            Self::Post(key, value) => f
+                .debug_tuple("Post")
+                .field(&BStr::new(key))
+                .field(&BStr::new(value))
+                .finish(),
+            Self::Continue(key) => f.debug_tuple("Continue").field(&BStr::new(key)).finish(),
         }
     }
 }
@@ -2,11 +2,16 @@
 // Licensed under the Apache License, Version 2.0
 // http://www.apache.org/licenses/LICENSE-2.0
 
+use crate::Request;
 use arrayvec::ArrayVec;
 use phf::{phf_map, Map};
 use protocol_common::{BufMut, Compose};
 use std::io::Write;
 
+/// The size of each chunk of a chunked response body. Bodies which are larger
+/// than one chunk are sent with chunked encoding to clients which support it.
+pub const CHUNK_SIZE: usize = 64 * 1024;
+
 pub struct Response {
     builder: ResponseBuilder,
     body: Option<Vec<u8>>,
@@ -20,12 +25,27 @@ impl Response {
     pub fn status(&self) -> u16 {
         self.builder.status
     }
+
+    /// Applies the connection semantics of the request to the response. The
+    /// connection is closed after the response unless the client asked to keep
+    /// it open, and large bodies are sent with chunked encoding to HTTP/1.1
+    /// clients.
+    pub fn reply_to(mut self, request: &Request) -> Self {
+        if !request.keep_alive() {
+            self.builder.close = true;
+        }
+        if request.version > 0 && self.body.as_ref().map(|b| b.len()).unwrap_or(0) > CHUNK_SIZE {
+            self.builder.chunked = true;
+        }
+        self
+    }
 }
 
 pub struct ResponseBuilder {
     headers: Vec<u8>,
     status: u16,
     close: bool,
+    chunked: bool,
 }
 
 impl ResponseBuilder {
@@ -42,6 +62,7 @@ impl ResponseBuilder {
         Self {
             headers: data,
             close: false,
+            chunked: false,
             status,
         }
     }
@@ -62,6 +83,12 @@ impl ResponseBuilder {
         self
     }
 
+    /// Send the body with chunked encoding instead of a Content-Length header.
+    pub fn chunked(&mut self, chunked: bool) -> &mut Self {
+        self.chunked = chunked;
+        self
+    }
+
     /// Build a response with no body
     pub fn empty(&mut self) -> Response {
         assert!(!self.headers.is_empty());
@@ -88,6 +115,7 @@ impl ResponseBuilder {
         Self {
             headers: std::mem::take(&mut self.headers),
             close: self.close,
+            chunked: self.chunked,
             status: self.status,
         }
     }
@@ -99,6 +127,13 @@ impl Compose for Response {
 
         dst.put_slice(&self.builder.headers);
 
+        // an informational response is followed by the final response, so it
+        // has no connection headers or body
+        if self.builder.status < 200 {
+            dst.put_slice(b"\r\n");
+            return dst.count();
+        }
+
         if self.builder.close {
             dst.put_slice(b"Connection: close\r\n");
         } else {
@@ -106,19 +141,33 @@ impl Compose for Response {
             dst.put_slice(b"Keep-Alive: timeout=60\r\n");
         }
 
-        if let Some(body) = &self.body {
-            let mut lenbuf = ArrayVec::<u8, 24>::new();
-            write!(&mut lenbuf, "{}", body.len()).unwrap();
+        match &self.body {
+            Some(body) if self.builder.chunked => {
+                dst.put_slice(b"Transfer-Encoding: chunked\r\n\r\n");
 
-            dst.put_slice(b"Content-Length: ");
-            dst.put_slice(&lenbuf);
-            dst.put_slice(b"\r\n");
-        }
+                for chunk in body.chunks(CHUNK_SIZE) {
+                    let mut lenbuf = ArrayVec::<u8, 24>::new();
+                    write!(&mut lenbuf, "{:x}\r\n", chunk.len()).unwrap();
+
+                    dst.put_slice(&lenbuf);
+                    dst.put_slice(chunk);
+                    dst.put_slice(b"\r\n");
+                }
 
-        dst.put_slice(b"\r\n");
+                dst.put_slice(b"0\r\n\r\n");
+            }
+            Some(body) => {
+                let mut lenbuf = ArrayVec::<u8, 24>::new();
+                write!(&mut lenbuf, "{}", body.len()).unwrap();
 
-        if let Some(body) = &self.body {
-            dst.put_slice(body);
+                dst.put_slice(b"Content-Length: ");
+                dst.put_slice(&lenbuf);
+                dst.put_slice(b"\r\n\r\n");
+                dst.put_slice(body);
+            }
+            None => {
+                dst.put_slice(b"\r\n");
+            }
         }
 
         dst.count()
@@ -180,7 +229,7 @@ const STATUSES: Map<u16, &'static str> = phf_map! {
     414u16 => "URI Too Long",
     415u16 => "Unsupported Media Type",
     416u16 => "Range Not Satisfiable",
-    417u16 => "Expectation Mailed",
+    417u16 => "Expectation Failed",
     418u16 => "I'm a Teapot",
     421u16 => "Misdirected Request",
     422u16 => "Unprocessable Entity",
@@ -37,3 +37,18 @@ where
         self.buf.chunk_mut()
     }
 }
+
+/// Removes leading and trailing ascii whitespace, as found around header
+/// values and list elements.
+pub fn trim(value: &[u8]) -> &[u8] {
+    let start = value
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(value.len());
+    let end = value
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map(|end| end + 1)
+        .unwrap_or(start);
+    &value[start..end]
+}
@@ -3,6 +3,7 @@
 // http://www.apache.org/licenses/LICENSE-2.0
 
 use assert_matches::assert_matches;
+use protocol_common::Parse;
 use protocol_http::{Error as ParseError, Request, RequestData, RequestParser};
 
 fn parse_to_end(data: &[u8]) -> protocol_http::Result<Request> {
@@ -66,6 +67,44 @@ fn parse_delete() {
     assert_eq!(key, b"/test");
 }
 
+#[test]
+fn parse_post() {
+    let data: &[u8] = b"\
+        POST /test HTTP/1.1\r\n\
+        Content-Length: 5\r\n\
+        \r\n\
+        debug\
+    ";
+
+    let request = parse_to_end(data).expect("failed to parse request");
+    let (key, value) =
+        assert_matches!(request.data(), RequestData::Post(key, value) => (key, value));
+
+    assert_eq!(key, b"/test");
+    assert_eq!(value, b"debug");
+
+    // a body is optional
+    let data: &[u8] = b"\
+        POST /test HTTP/1.1\r\n\
+        \r\n\
+    ";
+
+    let request = parse_to_end(data).expect("failed to parse request");
+    let value = assert_matches!(request.data(), RequestData::Post(_, value) => value);
+
+    assert!(value.is_empty());
+}
+
+#[test]
+fn parse_unsupported_method() {
+    let data: &[u8] = b"\
+        PATCH /test HTTP/1.1\r\n\
+        \r\n\
+    ";
+
+    assert_matches!(parse_to_end(data), Err(ParseError::BadRequestMethod));
+}
+
 #[test]
 fn parse_header_case_insensitive() {
     let data: &[u8] = b"\
@@ -106,3 +145,252 @@ fn parse_incomplete() {
 
     assert_matches!(result, Err(ParseError::PartialRequest(Some(100))));
 }
+
+#[test]
+fn parse_pipelined() {
+    let parser = RequestParser::new();
+    let mut data: &[u8] = b"\
+        PUT /a HTTP/1.1\r\n\
+        Content-Length: 1\r\n\
+        \r\n\
+        1\
+        GET /a HTTP/1.1\r\n\
+        \r\n\
+        DELETE /a HTTP/1.1\r\n\
+        \r\n\
+    ";
+
+    let request = parser.do_parse(&mut data).expect("failed to parse request");
+    assert_matches!(request.data(), RequestData::Put(key, value) if key == b"/a" && value == b"1");
+    let request = parser.do_parse(&mut data).expect("failed to parse request");
+    assert_matches!(request.data(), RequestData::Get(key) if key == b"/a");
+    let request = parser.do_parse(&mut data).expect("failed to parse request");
+    assert_matches!(request.data(), RequestData::Delete(key) if key == b"/a");
+
+    assert_eq!(data.len(), 0);
+}
+
+#[test]
+fn parse_get_with_body() {
+    // the body is consumed so that the next request can be parsed
+    let data: &[u8] = b"\
+        GET /test HTTP/1.1\r\n\
+        Content-Length: 4\r\n\
+        \r\n\
+        body\
+    ";
+
+    let request = parse_to_end(data).expect("failed to parse request");
+    assert_matches!(request.data(), RequestData::Get(key) if key == b"/test");
+}
+
+#[test]
+fn parse_chunked() {
+    let data: &[u8] = b"\
+        PUT /test HTTP/1.1\r\n\
+        Transfer-Encoding: chunked\r\n\
+        \r\n\
+        4\r\n\
+        abcd\r\n\
+        A;name=value\r\n\
+        efghijklmn\r\n\
+        0\r\n\
+        \r\n\
+    ";
+
+    let request = parse_to_end(data).expect("failed to parse request");
+    let value = assert_matches!(request.data(), RequestData::Put(_, value) => value);
+
+    assert_eq!(value, b"abcdefghijklmn");
+}
+
+#[test]
+fn parse_chunked_trailers() {
+    let data: &[u8] = b"\
+        PUT /test HTTP/1.1\r\n\
+        Transfer-Encoding: chunked\r\n\
+        \r\n\
+        3\r\n\
+        abc\r\n\
+        0\r\n\
+        Checksum: none\r\n\
+        \r\n\
+    ";
+
+    let request = parse_to_end(data).expect("failed to parse request");
+    let value = assert_matches!(request.data(), RequestData::Put(_, value) => value);
+
+    assert_eq!(value, b"abc");
+}
+
+#[test]
+fn parse_chunked_incomplete() {
+    let data: &[u8] = b"\
+        PUT /test HTTP/1.1\r\n\
+        Transfer-Encoding: chunked\r\n\
+        \r\n\
+        10\r\n\
+        abc\
+    ";
+    assert_matches!(parse_to_end(data), Err(ParseError::PartialRequest(None)));
+
+    // missing the end of the trailers
+    let data: &[u8] = b"\
+        PUT /test HTTP/1.1\r\n\
+        Transfer-Encoding: chunked\r\n\
+        \r\n\
+        0\r\n\
+    ";
+    assert_matches!(parse_to_end(data), Err(ParseError::PartialRequest(None)));
+}
+
+#[test]
+fn parse_chunked_invalid() {
+    let data: &[u8] = b"\
+        PUT /test HTTP/1.1\r\n\
+        Transfer-Encoding: chunked\r\n\
+        \r\n\
+        zz\r\n\
+        abc\r\n\
+        0\r\n\
+        \r\n\
+    ";
+    assert_matches!(parse_to_end(data), Err(ParseError::BadChunk));
+
+    // chunk longer than its size
+    let data: &[u8] = b"\
+        PUT /test HTTP/1.1\r\n\
+        Transfer-Encoding: chunked\r\n\
+        \r\n\
+        2\r\n\
+        abc\r\n\
+        0\r\n\
+        \r\n\
+    ";
+    assert_matches!(parse_to_end(data), Err(ParseError::BadChunk));
+}
+
+#[test]
+fn parse_value_too_large() {
+    // a chunk size which would overflow when its length is added
+    let data: &[u8] = b"\
+        PUT /test HTTP/1.1\r\n\
+        Transfer-Encoding: chunked\r\n\
+        \r\n\
+        ffffffffffffffff\r\n\
+        abc\r\n\
+        0\r\n\
+        \r\n\
+    ";
+    assert_matches!(parse_to_end(data), Err(ParseError::ValueTooLarge));
+
+    // chunks which are each small, but which decode to a body which is too
+    // large in total
+    let parser = RequestParser::new().max_value_size(6);
+    let mut data: &[u8] = b"\
+        PUT /test HTTP/1.1\r\n\
+        Transfer-Encoding: chunked\r\n\
+        \r\n\
+        4\r\n\
+        abcd\r\n\
+        4\r\n\
+        efgh\r\n\
+        0\r\n\
+        \r\n\
+    ";
+    assert_matches!(parser.do_parse(&mut data), Err(ParseError::ValueTooLarge));
+
+    let mut data: &[u8] = b"\
+        PUT /test HTTP/1.1\r\n\
+        Content-Length: 8\r\n\
+        \r\n\
+        abcdefgh\
+    ";
+    assert_matches!(parser.do_parse(&mut data), Err(ParseError::ValueTooLarge));
+}
+
+#[test]
+fn parse_unsupported_transfer_encoding() {
+    let data: &[u8] = b"\
+        PUT /test HTTP/1.1\r\n\
+        Transfer-Encoding: gzip, chunked\r\n\
+        \r\n\
+    ";
+
+    assert_matches!(
+        parse_to_end(data),
+        Err(ParseError::UnsupportedTransferEncoding)
+    );
+}
+
+#[test]
+fn parse_missing_content_length() {
+    let data: &[u8] = b"\
+        PUT /test HTTP/1.1\r\n\
+        \r\n\
+    ";
+
+    assert_matches!(parse_to_end(data), Err(ParseError::MissingContentLength));
+}
+
+#[test]
+fn parse_expect_continue() {
+    let parser = RequestParser::new();
+    let mut data = b"\
+        PUT /test HTTP/1.1\r\n\
+        Content-Length: 4\r\n\
+        Expect: 100-continue\r\n\
+        \r\n\
+    "
+    .to_vec();
+
+    // the headers are not consumed until the body has arrived
+    let parsed = parser.parse(&data).expect("failed to parse request");
+    assert_eq!(parsed.consumed(), 0);
+    let request = parsed.into_inner().0.expect("failed to parse request");
+    assert_matches!(request.data(), RequestData::Continue(key) if key == b"/test");
+
+    // continue is only sent once
+    let result = parser.parse(&data);
+    assert_eq!(
+        result.err().map(|e| e.kind()),
+        Some(std::io::ErrorKind::WouldBlock)
+    );
+
+    data.extend_from_slice(b"abcd");
+    let parsed = parser.parse(&data).expect("failed to parse request");
+    assert_eq!(parsed.consumed(), data.len());
+    let request = parsed.into_inner().0.expect("failed to parse request");
+    assert_matches!(request.data(), RequestData::Put(_, value) if value == b"abcd");
+}
+
+#[test]
+fn parse_expect_unsupported() {
+    let data: &[u8] = b"\
+        PUT /test HTTP/1.1\r\n\
+        Content-Length: 4\r\n\
+        Expect: something-else\r\n\
+        \r\n\
+        abcd\
+    ";
+
+    assert_matches!(parse_to_end(data), Err(ParseError::BadExpectation));
+}
+
+#[test]
+fn keep_alive() {
+    let keep_alive = |data: &[u8]| {
+        parse_to_end(data)
+            .expect("failed to parse request")
+            .keep_alive()
+    };
+
+    assert!(keep_alive(b"GET /test HTTP/1.1\r\n\r\n"));
+    assert!(!keep_alive(
+        b"GET /test HTTP/1.1\r\nConnection: close\r\n\r\n"
+    ));
+    assert!(!keep_alive(b"GET /test HTTP/1.0\r\n\r\n"));
+    assert!(keep_alive(
+        b"GET /test HTTP/1.0\r\nConnection: Upgrade, Keep-Alive\r\n\r\n"
+    ));
+}
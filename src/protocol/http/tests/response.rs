@@ -4,7 +4,8 @@
 
 use bstr::BStr;
 use protocol_common::Compose;
-use protocol_http::Response;
+use protocol_http::response::CHUNK_SIZE;
+use protocol_http::{RequestParser, Response};
 
 #[test]
 fn response_with_body() {
@@ -55,3 +56,72 @@ fn response_empty() {
         )
     );
 }
+
+#[test]
+fn response_chunked() {
+    let response = Response::builder(200).chunked(true).body(b"TEST BODY");
+
+    let mut data = Vec::new();
+    response.compose(&mut data);
+
+    assert_eq!(
+        BStr::new(&data),
+        BStr::new(
+            b"\
+                HTTP/1.1 200 OK\r\n\
+                Connection: keep-alive\r\n\
+                Keep-Alive: timeout=60\r\n\
+                Transfer-Encoding: chunked\r\n\
+                \r\n\
+                9\r\n\
+                TEST BODY\r\n\
+                0\r\n\
+                \r\n\
+            "
+        )
+    );
+}
+
+#[test]
+fn response_continue() {
+    let response = Response::builder(100).empty();
+
+    let mut data = Vec::new();
+    response.compose(&mut data);
+
+    assert_eq!(
+        BStr::new(&data),
+        BStr::new(b"HTTP/1.1 100 Continue\r\n\r\n")
+    );
+    assert!(!response.should_hangup());
+}
+
+#[test]
+fn response_reply_to() {
+    let parser = RequestParser::new();
+
+    // a large body is chunked for an HTTP/1.1 client
+    let request = parser
+        .do_parse(&mut &b"GET /test HTTP/1.1\r\n\r\n"[..])
+        .expect("failed to parse request");
+    let body = vec![b'a'; CHUNK_SIZE + 1];
+    let response = Response::builder(200).body(&body).reply_to(&request);
+
+    let mut data = Vec::new();
+    response.compose(&mut data);
+
+    assert!(!response.should_hangup());
+    assert!(BStr::new(&data).ends_with(b"\r\n1\r\na\r\n0\r\n\r\n"));
+
+    // but not for an HTTP/1.0 client, which also closes the connection
+    let request = parser
+        .do_parse(&mut &b"GET /test HTTP/1.0\r\n\r\n"[..])
+        .expect("failed to parse request");
+    let response = Response::builder(200).body(&body).reply_to(&request);
+
+    let mut data = Vec::new();
+    response.compose(&mut data);
+
+    assert!(response.should_hangup());
+    assert!(BStr::new(&data).ends_with(b"aaaa"));
+}
@@ -9,12 +9,12 @@
 #![no_main]
 use libfuzzer_sys::fuzz_target;
 
-use protocol_memcache::*;
 use protocol_common::Parse;
+use protocol_memcache::*;
 
 const MAX_KEY_LEN: usize = 128;
 const MAX_BATCH_SIZE: usize = 128;
-const MAX_VALUE_SIZE: usize = 4*4096;
+const MAX_VALUE_SIZE: usize = 4 * 4096;
 
 fuzz_target!(|data: &[u8]| {
     let parser = RequestParser::new()
@@ -80,11 +80,72 @@ fuzz_target!(|data: &[u8]| {
                 validate_key(decr.key());
             }
             Request::FlushAll(_) => {}
+            Request::MetaArithmetic(request) => {
+                validate_meta_key(request.key(), request.flags());
+            }
+            Request::MetaDebug(request) => {
+                validate_meta_key(request.key(), request.flags());
+            }
+            Request::MetaDelete(request) => {
+                validate_meta_key(request.key(), request.flags());
+            }
+            Request::MetaGet(request) => {
+                validate_meta_key(request.key(), request.flags());
+            }
+            Request::MetaNoop(_) => {}
+            Request::MetaSet(request) => {
+                validate_meta_key(request.key(), request.flags());
+                validate_value(request.value());
+            }
+            Request::Auth(_) => {}
+            Request::Invalid(_) => {}
             Request::Quit(_) => {}
+            Request::Stats(_) => {}
+            Request::Verbosity(_) => {}
+            Request::Version(_) => {}
+            Request::Binary(binary) => {
+                if let Some(key) = binary_key(binary.request()) {
+                    if key.len() > MAX_KEY_LEN {
+                        panic!("key is too long");
+                    }
+                }
+            }
         }
     }
 });
 
+// keys in binary protocol requests may contain any bytes
+fn binary_key(request: &Request) -> Option<&[u8]> {
+    match request {
+        Request::Gets(r) => r.keys().first().map(|k| k.as_ref()),
+        Request::Set(r) => Some(r.key()),
+        Request::Add(r) => Some(r.key()),
+        Request::Replace(r) => Some(r.key()),
+        Request::Append(r) => Some(r.key()),
+        Request::Prepend(r) => Some(r.key()),
+        Request::Cas(r) => Some(r.key()),
+        Request::Delete(r) => Some(r.key()),
+        Request::Incr(r) => Some(r.key()),
+        Request::Decr(r) => Some(r.key()),
+        Request::MetaArithmetic(r) => Some(r.key()),
+        _ => None,
+    }
+}
+
+// base64 encoded keys may decode to any bytes, so only the length is checked
+fn validate_meta_key(key: &[u8], flags: &MetaFlags) {
+    if flags.base64() {
+        if key.is_empty() {
+            panic!("key is zero-length");
+        }
+        if key.len() > MAX_KEY_LEN {
+            panic!("key is too long");
+        }
+    } else {
+        validate_key(key);
+    }
+}
+
 fn validate_key(key: &[u8]) {
     if key.is_empty() {
         panic!("key is zero-length");
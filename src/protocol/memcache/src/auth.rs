@@ -0,0 +1,114 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Credentials for authenticated sessions. When a `RequestParser` is given
+//! credentials, every session must log in before any other command will be
+//! accepted, either with the memcached ASCII authentication or with SASL
+//! PLAIN using the binary protocol.
+
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+/// The SASL mechanisms which are supported, as returned to the binary list
+/// mechanisms command.
+pub(crate) const SASL_MECHANISMS: &[u8] = b"PLAIN";
+
+/// A set of usernames and their passwords.
+#[derive(Default)]
+pub struct Credentials {
+    users: HashMap<Box<[u8]>, Box<[u8]>>,
+}
+
+impl Credentials {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load credentials from a file with one `username:password` entry per
+    /// line, which is the format used by memcached. Empty lines and lines
+    /// starting with `#` are ignored.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let content = std::fs::read_to_string(path)?;
+
+        let mut credentials = Self::new();
+
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.split_once(':') {
+                Some((username, password)) if !username.is_empty() => {
+                    credentials.insert(username, password);
+                }
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!("malformed credentials on line {}", number + 1),
+                    ));
+                }
+            }
+        }
+
+        if credentials.users.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "credentials file has no entries",
+            ));
+        }
+
+        Ok(credentials)
+    }
+
+    /// Add a user, replacing the password if the user already exists.
+    pub fn insert<U: AsRef<[u8]>, P: AsRef<[u8]>>(&mut self, username: U, password: P) {
+        self.users.insert(
+            username.as_ref().to_owned().into_boxed_slice(),
+            password.as_ref().to_owned().into_boxed_slice(),
+        );
+    }
+
+    /// Returns true if the username exists and the password matches.
+    pub fn verify(&self, username: &[u8], password: &[u8]) -> bool {
+        match self.users.get(username) {
+            Some(expected) => constant_time_eq(expected, password),
+            None => false,
+        }
+    }
+}
+
+// compares the passwords without returning early on the first mismatch, so
+// the time taken does not reveal the length of a matching prefix
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load() {
+        let path = std::env::temp_dir().join(format!("memcache-auth-{}", std::process::id()));
+
+        std::fs::write(&path, "# users\nalice:secret\n\nbob:hunter2:extra\n").unwrap();
+        let credentials = Credentials::load(&path).unwrap();
+        assert!(credentials.verify(b"alice", b"secret"));
+        assert!(credentials.verify(b"bob", b"hunter2:extra"));
+        assert!(!credentials.verify(b"alice", b"hunter2:extra"));
+        assert!(!credentials.verify(b"carol", b""));
+
+        std::fs::write(&path, "alice\n").unwrap();
+        assert!(Credentials::load(&path).is_err());
+
+        std::fs::write(&path, "# nobody\n").unwrap();
+        assert!(Credentials::load(&path).is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
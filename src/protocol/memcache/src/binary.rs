@@ -0,0 +1,171 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Framing for the memcache binary protocol. Requests and responses share a
+//! fixed 24 byte header which is followed by the extras, the key, and the
+//! value, in that order:
+//!
+//! ```text
+//!  Byte/     0       |       1       |       2       |       3       |
+//!     /              |               |               |               |
+//!    |0 1 2 3 4 5 6 7|0 1 2 3 4 5 6 7|0 1 2 3 4 5 6 7|0 1 2 3 4 5 6 7|
+//!    +---------------+---------------+---------------+---------------+
+//!   0| Magic         | Opcode        | Key length                    |
+//!    +---------------+---------------+---------------+---------------+
+//!   4| Extras length | Data type     | vbucket id / status           |
+//!    +---------------+---------------+---------------+---------------+
+//!   8| Total body length                                             |
+//!    +---------------+---------------+---------------+---------------+
+//!  12| Opaque                                                        |
+//!    +---------------+---------------+---------------+---------------+
+//!  16| CAS                                                           |
+//!    |                                                               |
+//!    +---------------+---------------+---------------+---------------+
+//! ```
+
+use protocol_common::BufMut;
+
+pub(crate) const REQUEST_MAGIC: u8 = 0x80;
+pub(crate) const RESPONSE_MAGIC: u8 = 0x81;
+pub(crate) const HEADER_LEN: usize = 24;
+
+/// The binary protocol commands which are supported. The quiet variants only
+/// send a response when there is something the client needs to know about:
+/// a hit for quiet gets, and an error for everything else.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Opcode {
+    Get = 0x00,
+    Set = 0x01,
+    Add = 0x02,
+    Replace = 0x03,
+    Delete = 0x04,
+    Increment = 0x05,
+    Decrement = 0x06,
+    Quit = 0x07,
+    Flush = 0x08,
+    GetQ = 0x09,
+    Noop = 0x0a,
+    Version = 0x0b,
+    GetK = 0x0c,
+    GetKQ = 0x0d,
+    Append = 0x0e,
+    Prepend = 0x0f,
+    SetQ = 0x11,
+    AddQ = 0x12,
+    ReplaceQ = 0x13,
+    DeleteQ = 0x14,
+    IncrementQ = 0x15,
+    DecrementQ = 0x16,
+    QuitQ = 0x17,
+    FlushQ = 0x18,
+    AppendQ = 0x19,
+    PrependQ = 0x1a,
+    SaslListMechs = 0x20,
+    SaslAuth = 0x21,
+    SaslStep = 0x22,
+}
+
+impl Opcode {
+    pub(crate) fn from_u8(value: u8) -> Option<Self> {
+        let opcode = match value {
+            0x00 => Self::Get,
+            0x01 => Self::Set,
+            0x02 => Self::Add,
+            0x03 => Self::Replace,
+            0x04 => Self::Delete,
+            0x05 => Self::Increment,
+            0x06 => Self::Decrement,
+            0x07 => Self::Quit,
+            0x08 => Self::Flush,
+            0x09 => Self::GetQ,
+            0x0a => Self::Noop,
+            0x0b => Self::Version,
+            0x0c => Self::GetK,
+            0x0d => Self::GetKQ,
+            0x0e => Self::Append,
+            0x0f => Self::Prepend,
+            0x11 => Self::SetQ,
+            0x12 => Self::AddQ,
+            0x13 => Self::ReplaceQ,
+            0x14 => Self::DeleteQ,
+            0x15 => Self::IncrementQ,
+            0x16 => Self::DecrementQ,
+            0x17 => Self::QuitQ,
+            0x18 => Self::FlushQ,
+            0x19 => Self::AppendQ,
+            0x1a => Self::PrependQ,
+            0x20 => Self::SaslListMechs,
+            0x21 => Self::SaslAuth,
+            0x22 => Self::SaslStep,
+            _ => {
+                return None;
+            }
+        };
+        Some(opcode)
+    }
+
+    /// Returns true for the quiet variants of commands.
+    pub fn is_quiet(&self) -> bool {
+        matches!(
+            self,
+            Self::GetQ
+                | Self::GetKQ
+                | Self::SetQ
+                | Self::AddQ
+                | Self::ReplaceQ
+                | Self::DeleteQ
+                | Self::IncrementQ
+                | Self::DecrementQ
+                | Self::QuitQ
+                | Self::FlushQ
+                | Self::AppendQ
+                | Self::PrependQ
+        )
+    }
+
+    /// Returns true for the get family of commands.
+    pub fn is_get(&self) -> bool {
+        matches!(self, Self::Get | Self::GetQ | Self::GetK | Self::GetKQ)
+    }
+
+    /// Returns true if the key is included in the response.
+    pub fn returns_key(&self) -> bool {
+        matches!(self, Self::GetK | Self::GetKQ)
+    }
+}
+
+/// The fields of a binary protocol message. The same layout is used for both
+/// requests and responses, with the `status` field holding the vbucket id for
+/// requests.
+pub(crate) struct Frame<'a> {
+    pub magic: u8,
+    pub opcode: Opcode,
+    pub status: u16,
+    pub opaque: u32,
+    pub cas: u64,
+    pub extras: &'a [u8],
+    pub key: &'a [u8],
+    pub value: &'a [u8],
+}
+
+impl<'a> Frame<'a> {
+    pub fn compose(&self, session: &mut dyn BufMut) -> usize {
+        let body = self.extras.len() + self.key.len() + self.value.len();
+
+        session.put_u8(self.magic);
+        session.put_u8(self.opcode as u8);
+        session.put_u16(self.key.len() as u16);
+        session.put_u8(self.extras.len() as u8);
+        session.put_u8(0);
+        session.put_u16(self.status);
+        session.put_u32(body as u32);
+        session.put_u32(self.opaque);
+        session.put_u64(self.cas);
+        session.put_slice(self.extras);
+        session.put_slice(self.key);
+        session.put_slice(self.value);
+
+        HEADER_LEN + body
+    }
+}
@@ -5,6 +5,8 @@
 #[macro_use]
 extern crate logger;
 
+mod auth;
+mod binary;
 mod request;
 mod response;
 mod storage;
@@ -12,6 +14,8 @@ mod util;
 
 pub(crate) use util::*;
 
+pub use auth::Credentials;
+pub use binary::Opcode;
 pub use request::*;
 pub use response::*;
 pub use storage::*;
@@ -99,6 +103,53 @@ counter!(CAS_STORED);
 counter!(FLUSH_ALL);
 counter!(FLUSH_ALL_EX);
 
+counter!(META_GET);
+counter!(META_GET_EX);
+counter!(META_GET_HIT);
+counter!(META_GET_MISS);
+
+counter!(META_SET);
+counter!(META_SET_EX);
+counter!(META_SET_STORED);
+counter!(META_SET_NOT_STORED);
+counter!(META_SET_EXISTS);
+counter!(META_SET_NOT_FOUND);
+
+counter!(META_DELETE);
+counter!(META_DELETE_EX);
+counter!(META_DELETE_DELETED);
+counter!(META_DELETE_NOT_FOUND);
+counter!(META_DELETE_EXISTS);
+
+counter!(META_ARITHMETIC);
+counter!(META_ARITHMETIC_EX);
+counter!(META_ARITHMETIC_STORED);
+counter!(META_ARITHMETIC_NOT_FOUND);
+counter!(META_ARITHMETIC_EXISTS);
+
+counter!(META_DEBUG);
+counter!(META_DEBUG_EX);
+
+counter!(META_NOOP);
+
 counter!(QUIT);
 
+counter!(STATS);
+counter!(STATS_EX);
+
+counter!(VERBOSITY);
+counter!(VERBOSITY_EX);
+
+counter!(VERSION);
+
+counter!(BINARY);
+counter!(BINARY_EX);
+
+counter!(AUTH_SUCCESS);
+counter!(AUTH_FAILURE);
+counter!(AUTH_REQUIRED);
+
+counter!(UNKNOWN_REQUEST);
+counter!(MALFORMED_REQUEST);
+
 common::metrics::test_no_duplicates!();
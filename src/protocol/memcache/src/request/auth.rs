@@ -0,0 +1,294 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+/// The result of authentication, which is decided by the parser so that the
+/// session state is updated before any pipelined requests are parsed.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum AuthOutcome {
+    /// The credentials were accepted and the session is now authenticated.
+    Authenticated,
+    /// The credentials were rejected.
+    Failed,
+    /// A command was sent before the session authenticated.
+    Required,
+    /// The SASL mechanisms were requested.
+    Mechanisms,
+    /// SASL was used but authentication is not enabled.
+    Unsupported,
+}
+
+/// A request which is handled by the authentication layer instead of the
+/// storage. The credentials are not kept, so this request is never logged or
+/// re-encoded.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Auth {
+    pub(crate) outcome: AuthOutcome,
+    pub(crate) noreply: bool,
+}
+
+impl Auth {
+    pub fn outcome(&self) -> AuthOutcome {
+        self.outcome
+    }
+
+    pub fn noreply(&self) -> bool {
+        self.noreply
+    }
+
+    /// The response for the request. A successful login is acknowledged as a
+    /// stored item, as memcached does for ASCII authentication.
+    pub fn response(&self) -> Response {
+        match self.outcome {
+            AuthOutcome::Authenticated => Response::stored(self.noreply),
+            AuthOutcome::Failed => Response::auth_error("authentication failure"),
+            AuthOutcome::Required => Response::auth_error("unauthenticated"),
+            AuthOutcome::Mechanisms => Response::ok(false),
+            AuthOutcome::Unsupported => Response::error(),
+        }
+    }
+
+    fn required() -> Self {
+        AUTH_REQUIRED.increment();
+        Self {
+            outcome: AuthOutcome::Required,
+            noreply: false,
+        }
+    }
+}
+
+impl RequestParser {
+    /// Check the credentials and mark the session as authenticated if they
+    /// are valid.
+    fn login(&self, username: &[u8], password: &[u8], noreply: bool) -> Auth {
+        let valid = self
+            .credentials
+            .as_ref()
+            .map(|credentials| credentials.verify(username, password))
+            .unwrap_or(false);
+
+        let outcome = if valid {
+            AUTH_SUCCESS.increment();
+            self.authenticated.set(true);
+            AuthOutcome::Authenticated
+        } else {
+            AUTH_FAILURE.increment();
+            AuthOutcome::Failed
+        };
+
+        Auth { outcome, noreply }
+    }
+
+    /// Handles the binary protocol SASL commands. Only the PLAIN mechanism is
+    /// supported, which completes in a single step with a message containing
+    /// the authorization identity, username, and password separated by nulls.
+    pub(crate) fn sasl(&self, opcode: Opcode, mechanism: &[u8], message: &[u8]) -> Auth {
+        let outcome = if self.credentials.is_none() {
+            AuthOutcome::Unsupported
+        } else {
+            match opcode {
+                Opcode::SaslListMechs => AuthOutcome::Mechanisms,
+                Opcode::SaslAuth if mechanism == crate::auth::SASL_MECHANISMS => {
+                    let mut fields = message.split(|b| *b == 0);
+                    match (fields.next(), fields.next(), fields.next(), fields.next()) {
+                        (Some(_authzid), Some(username), Some(password), None) => {
+                            return self.login(username, password, false);
+                        }
+                        _ => {
+                            AUTH_FAILURE.increment();
+                            AuthOutcome::Failed
+                        }
+                    }
+                }
+                _ => {
+                    AUTH_FAILURE.increment();
+                    AuthOutcome::Failed
+                }
+            }
+        };
+
+        Auth {
+            outcome,
+            noreply: false,
+        }
+    }
+
+    /// Until the session has authenticated, a text protocol `set` is treated
+    /// as a login with the value holding the username and password separated
+    /// by a space, and all other commands except `quit` are rejected.
+    pub(crate) fn authorize(&self, request: Request) -> Request {
+        if self.credentials.is_none() || self.authenticated.get() {
+            return request;
+        }
+
+        match request {
+            Request::Auth(_) | Request::Quit(_) => request,
+            Request::Set(set) => {
+                let value = set.value();
+                let (username, password) = match value.iter().position(|b| *b == b' ') {
+                    Some(idx) => (&value[..idx], &value[(idx + 1)..]),
+                    None => (value, &b""[..]),
+                };
+                Request::Auth(self.login(username, password, set.noreply()))
+            }
+            Request::Binary(mut binary) => {
+                if !matches!(binary.request(), Request::Auth(_) | Request::Quit(_)) {
+                    binary.request = Box::new(Request::Auth(Auth::required()));
+                }
+                Request::Binary(binary)
+            }
+            _ => Request::Auth(Auth::required()),
+        }
+    }
+}
+
+impl Compose for Auth {
+    fn compose(&self, _session: &mut dyn BufMut) -> usize {
+        0
+    }
+}
+
+impl Klog for Auth {
+    type Response = Response;
+
+    fn klog(&self, _response: &Self::Response) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parser() -> RequestParser {
+        let mut credentials = Credentials::new();
+        credentials.insert("alice", "secret");
+        RequestParser::new().credentials(credentials)
+    }
+
+    fn outcome(request: Request) -> AuthOutcome {
+        match request {
+            Request::Auth(auth) => auth.outcome(),
+            Request::Binary(binary) => match binary.request() {
+                Request::Auth(auth) => auth.outcome(),
+                request => panic!("unexpected request: {:?}", request),
+            },
+            request => panic!("unexpected request: {:?}", request),
+        }
+    }
+
+    fn sasl(opcode: Opcode, key: &[u8], value: &[u8]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        crate::binary::Frame {
+            magic: crate::binary::REQUEST_MAGIC,
+            opcode,
+            status: 0,
+            opaque: 0,
+            cas: 0,
+            extras: &[],
+            key,
+            value,
+        }
+        .compose(&mut buf);
+        buf
+    }
+
+    #[test]
+    fn disabled() {
+        // without credentials, requests are passed through unchanged
+        let parser = RequestParser::new();
+        assert!(matches!(
+            parser.parse_request(b"get key\r\n"),
+            Ok((_, Request::Get(_)))
+        ));
+
+        let buf = sasl(Opcode::SaslAuth, b"PLAIN", b"\0alice\0secret");
+        let (_, request) = parser.parse_request(&buf).unwrap();
+        assert_eq!(outcome(request), AuthOutcome::Unsupported);
+    }
+
+    #[test]
+    fn ascii() {
+        let parser = parser();
+
+        // commands are rejected until authenticated
+        let (_, request) = parser.parse_request(b"get key\r\n").unwrap();
+        assert_eq!(outcome(request), AuthOutcome::Required);
+
+        // bad credentials
+        let (_, request) = parser
+            .parse_request(b"set auth 0 0 11\r\nalice wrong\r\n")
+            .unwrap();
+        assert_eq!(outcome(request), AuthOutcome::Failed);
+        assert_eq!(
+            Auth {
+                outcome: AuthOutcome::Failed,
+                noreply: false
+            }
+            .response(),
+            Response::auth_error("authentication failure")
+        );
+
+        // good credentials, followed by a pipelined request
+        let (remaining, request) = parser
+            .parse_request(b"set auth 0 0 12\r\nalice secret\r\nget key\r\n")
+            .unwrap();
+        assert_eq!(outcome(request), AuthOutcome::Authenticated);
+        assert!(matches!(
+            parser.parse_request(remaining),
+            Ok((_, Request::Get(_)))
+        ));
+    }
+
+    #[test]
+    fn sessions() {
+        // each session gets its own clone of the parser, so authenticating one
+        // session does not authenticate the others
+        let parser = parser();
+        let session = parser.clone();
+        let (_, request) = session
+            .parse_request(b"set auth 0 0 12\r\nalice secret\r\n")
+            .unwrap();
+        assert_eq!(outcome(request), AuthOutcome::Authenticated);
+
+        let (_, request) = parser.clone().parse_request(b"get key\r\n").unwrap();
+        assert_eq!(outcome(request), AuthOutcome::Required);
+
+        // quit is always allowed
+        assert!(matches!(
+            parser.parse_request(b"quit\r\n"),
+            Ok((_, Request::Quit(_)))
+        ));
+    }
+
+    #[test]
+    fn binary() {
+        let parser = parser();
+
+        let buf = sasl(Opcode::Get, b"key", b"");
+        let (_, request) = parser.parse_request(&buf).unwrap();
+        assert_eq!(outcome(request), AuthOutcome::Required);
+
+        let buf = sasl(Opcode::SaslListMechs, b"", b"");
+        let (_, request) = parser.parse_request(&buf).unwrap();
+        assert_eq!(outcome(request), AuthOutcome::Mechanisms);
+
+        let buf = sasl(Opcode::SaslAuth, b"CRAM-MD5", b"alice secret");
+        let (_, request) = parser.parse_request(&buf).unwrap();
+        assert_eq!(outcome(request), AuthOutcome::Failed);
+
+        let buf = sasl(Opcode::SaslAuth, b"PLAIN", b"\0alice\0wrong");
+        let (_, request) = parser.parse_request(&buf).unwrap();
+        assert_eq!(outcome(request), AuthOutcome::Failed);
+
+        let buf = sasl(Opcode::SaslAuth, b"PLAIN", b"\0alice\0secret");
+        let (_, request) = parser.parse_request(&buf).unwrap();
+        assert_eq!(outcome(request), AuthOutcome::Authenticated);
+
+        let buf = sasl(Opcode::Get, b"key", b"");
+        assert!(matches!(
+            parser.parse_request(&buf),
+            Ok((_, Request::Binary(binary))) if matches!(binary.request(), Request::Gets(_))
+        ));
+    }
+}
@@ -0,0 +1,672 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use crate::binary::*;
+
+// the expiration value for incr and decr which indicates that the item should
+// not be created on a miss
+const NO_VIVIFY: u32 = 0xffff_ffff;
+
+// the smallest expiration time which is treated as a unix timestamp, used to
+// encode immediate expiration as a time in the past
+const UNIX_EXPIRED: u32 = 60 * 60 * 24 * 30 + 1;
+
+/// A request which was received using the binary protocol. The command is
+/// mapped onto one of the text protocol requests so that it can be executed by
+/// the same storage implementation. The opcode and opaque value are kept so
+/// the response can be returned using the binary protocol.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Binary {
+    pub(crate) opcode: Opcode,
+    pub(crate) opaque: u32,
+    pub(crate) request: Box<Request>,
+}
+
+impl Binary {
+    pub fn opcode(&self) -> Opcode {
+        self.opcode
+    }
+
+    pub fn opaque(&self) -> u32 {
+        self.opaque
+    }
+
+    /// The request that this binary protocol command maps onto.
+    pub fn request(&self) -> &Request {
+        &self.request
+    }
+
+    /// The key of the item which this command modifies, if any. The binary
+    /// protocol returns the cas value of the item after it is modified.
+    pub fn modified_key(&self) -> Option<&[u8]> {
+        match self.request.as_ref() {
+            Request::Set(r) => Some(r.key()),
+            Request::Cas(r) => Some(r.key()),
+            Request::Add(r) => Some(r.key()),
+            Request::Replace(r) => Some(r.key()),
+            Request::Append(r) => Some(r.key()),
+            Request::Prepend(r) => Some(r.key()),
+            Request::Incr(r) => Some(r.key()),
+            Request::Decr(r) => Some(r.key()),
+            Request::MetaArithmetic(r) => Some(r.key()),
+            _ => None,
+        }
+    }
+
+    /// The request to execute on storage which does not support the meta
+    /// commands, if it differs from `request()`. An incr or decr which would
+    /// create the item on a miss is mapped onto a plain incr or decr, which
+    /// leaves the item missing.
+    pub fn basic_request(&self) -> Option<Request> {
+        match self.request.as_ref() {
+            Request::MetaArithmetic(r) => {
+                let key = r.key().to_owned().into_boxed_slice();
+                let noreply = r.flags().quiet();
+                Some(match r.mode() {
+                    MetaArithmeticMode::Increment => Request::Incr(Incr {
+                        key,
+                        value: r.delta(),
+                        noreply,
+                    }),
+                    MetaArithmeticMode::Decrement => Request::Decr(Decr {
+                        key,
+                        value: r.delta(),
+                        noreply,
+                    }),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Wrap the response to the inner request so that it will be sent using
+    /// the binary protocol. The cas value is that of the item after it was
+    /// modified, and is only sent for successful responses.
+    pub fn response(&self, response: Response, cas: Option<u64>) -> Response {
+        let key = match self.request.as_ref() {
+            Request::Gets(r) if self.opcode.returns_key() => r.keys().first().cloned(),
+            _ => None,
+        };
+        let response = BinaryResponse::new(self.opcode, self.opaque, key, response);
+        Response::Binary(response.with_cas(cas.unwrap_or(0)))
+    }
+}
+
+impl RequestParser {
+    pub(crate) fn parse_binary_no_stats<'a>(&self, input: &'a [u8]) -> IResult<&'a [u8], Binary> {
+        let (input, header) = take(HEADER_LEN)(input)?;
+
+        if header[0] != REQUEST_MAGIC {
+            return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+        }
+
+        let opcode = Opcode::from_u8(header[1])
+            .ok_or(nom::Err::Failure((input, nom::error::ErrorKind::Tag)))?;
+        let key_len = u16::from_be_bytes([header[2], header[3]]) as usize;
+        let extras_len = header[4] as usize;
+        let body_len = u32::from_be_bytes([header[8], header[9], header[10], header[11]]) as usize;
+        let opaque = u32::from_be_bytes([header[12], header[13], header[14], header[15]]);
+        let cas = u64::from_be_bytes([
+            header[16], header[17], header[18], header[19], header[20], header[21], header[22],
+            header[23],
+        ]);
+
+        if key_len > self.max_key_len
+            || body_len < extras_len + key_len
+            || body_len - extras_len - key_len > self.max_value_size
+        {
+            return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+        }
+
+        let (input, body) = take(body_len)(input)?;
+        let (extras, body) = body.split_at(extras_len);
+        let (key, value) = body.split_at(key_len);
+
+        // checks that the message has the expected extras, key, and value for
+        // the opcode
+        let invalid = |extras: usize, has_key: bool, has_value: bool| {
+            extras_len != extras || (key_len != 0) != has_key || (!has_value && !value.is_empty())
+        };
+
+        let noreply = opcode.is_quiet();
+        let ttl = |exptime: &[u8]| {
+            let exptime = u32::from_be_bytes([exptime[0], exptime[1], exptime[2], exptime[3]]);
+            Ttl::new(exptime as i64, self.time_type)
+        };
+
+        let request = match opcode {
+            Opcode::Get | Opcode::GetQ | Opcode::GetK | Opcode::GetKQ => {
+                if invalid(0, true, false) {
+                    return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+                }
+                // gets is used so that the cas value is included in the response
+                Request::Gets(Gets {
+                    keys: vec![key.to_owned().into_boxed_slice()].into_boxed_slice(),
+                })
+            }
+            Opcode::Set
+            | Opcode::SetQ
+            | Opcode::Add
+            | Opcode::AddQ
+            | Opcode::Replace
+            | Opcode::ReplaceQ => {
+                if invalid(8, true, true) {
+                    return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+                }
+                let key = key.to_owned().into_boxed_slice();
+                let value = value.to_owned().into_boxed_slice();
+                let flags = u32::from_be_bytes([extras[0], extras[1], extras[2], extras[3]]);
+                let ttl = ttl(&extras[4..8]);
+                match opcode {
+                    Opcode::Set | Opcode::SetQ if cas != 0 => Request::Cas(Cas {
+                        key,
+                        value,
+                        flags,
+                        ttl,
+                        cas,
+                        noreply,
+                    }),
+                    Opcode::Set | Opcode::SetQ => Request::Set(Set {
+                        key,
+                        value,
+                        flags,
+                        ttl,
+                        noreply,
+                    }),
+                    Opcode::Add | Opcode::AddQ => Request::Add(Add {
+                        key,
+                        value,
+                        flags,
+                        ttl,
+                        noreply,
+                    }),
+                    _ => Request::Replace(Replace {
+                        key,
+                        value,
+                        flags,
+                        ttl,
+                        noreply,
+                    }),
+                }
+            }
+            Opcode::Append | Opcode::AppendQ | Opcode::Prepend | Opcode::PrependQ => {
+                if invalid(0, true, true) {
+                    return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+                }
+                let key = key.to_owned().into_boxed_slice();
+                let value = value.to_owned().into_boxed_slice();
+                if matches!(opcode, Opcode::Append | Opcode::AppendQ) {
+                    Request::Append(Append {
+                        key,
+                        value,
+                        flags: 0,
+                        ttl: Ttl::none(),
+                        noreply,
+                    })
+                } else {
+                    Request::Prepend(Prepend {
+                        key,
+                        value,
+                        flags: 0,
+                        ttl: Ttl::none(),
+                        noreply,
+                    })
+                }
+            }
+            Opcode::Delete | Opcode::DeleteQ => {
+                if invalid(0, true, false) {
+                    return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+                }
+                Request::Delete(Delete {
+                    key: key.to_owned().into_boxed_slice(),
+                    noreply,
+                })
+            }
+            Opcode::Increment | Opcode::IncrementQ | Opcode::Decrement | Opcode::DecrementQ => {
+                if invalid(20, true, false) {
+                    return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+                }
+                let key = key.to_owned().into_boxed_slice();
+                let delta = u64::from_be_bytes(extras[0..8].try_into().unwrap());
+                let initial = u64::from_be_bytes(extras[8..16].try_into().unwrap());
+                let exptime = u32::from_be_bytes(extras[16..20].try_into().unwrap());
+                let decrement = matches!(opcode, Opcode::Decrement | Opcode::DecrementQ);
+
+                if exptime == NO_VIVIFY {
+                    if decrement {
+                        Request::Decr(Decr {
+                            key,
+                            value: delta,
+                            noreply,
+                        })
+                    } else {
+                        Request::Incr(Incr {
+                            key,
+                            value: delta,
+                            noreply,
+                        })
+                    }
+                } else {
+                    // creating the item on a miss is only possible with the
+                    // meta arithmetic command
+                    Request::MetaArithmetic(MetaArithmetic {
+                        key,
+                        flags: MetaFlags {
+                            return_value: true,
+                            quiet: noreply,
+                            delta: Some(delta),
+                            initial: Some(initial),
+                            vivify: Some(ttl(&extras[16..20])),
+                            mode: if decrement { Some(b'D') } else { None },
+                            ..Default::default()
+                        },
+                    })
+                }
+            }
+            Opcode::Flush | Opcode::FlushQ => {
+                if (extras_len != 0 && extras_len != 4) || key_len != 0 || !value.is_empty() {
+                    return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+                }
+                let delay = if extras_len == 4 {
+                    u32::from_be_bytes([extras[0], extras[1], extras[2], extras[3]])
+                } else {
+                    0
+                };
+                Request::FlushAll(FlushAll { delay, noreply })
+            }
+            Opcode::Noop => {
+                if invalid(0, false, false) {
+                    return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+                }
+                Request::MetaNoop(MetaNoop {})
+            }
+            Opcode::Quit | Opcode::QuitQ => {
+                if invalid(0, false, false) {
+                    return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+                }
+                Request::Quit(Quit {})
+            }
+            Opcode::Version => {
+                if invalid(0, false, false) {
+                    return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+                }
+                Request::Version(Version {})
+            }
+            Opcode::SaslListMechs => {
+                if invalid(0, false, false) {
+                    return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+                }
+                Request::Auth(self.sasl(opcode, key, value))
+            }
+            Opcode::SaslAuth | Opcode::SaslStep => {
+                if invalid(0, true, true) {
+                    return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+                }
+                Request::Auth(self.sasl(opcode, key, value))
+            }
+        };
+
+        Ok((
+            input,
+            Binary {
+                opcode,
+                opaque,
+                request: Box::new(request),
+            },
+        ))
+    }
+
+    pub fn parse_binary<'a>(&self, input: &'a [u8]) -> IResult<&'a [u8], Binary> {
+        match self.parse_binary_no_stats(input) {
+            Ok((input, request)) => {
+                BINARY.increment();
+                match request.request() {
+                    Request::Add(_) => ADD.increment(),
+                    Request::Append(_) => APPEND.increment(),
+                    Request::Cas(_) => CAS.increment(),
+                    Request::Decr(_) => DECR.increment(),
+                    Request::Delete(_) => DELETE.increment(),
+                    Request::FlushAll(_) => FLUSH_ALL.increment(),
+                    Request::Gets(_) => {
+                        GETS.increment();
+                        GETS_KEY.increment();
+                    }
+                    Request::Incr(_) => INCR.increment(),
+                    Request::MetaArithmetic(_) => META_ARITHMETIC.increment(),
+                    Request::MetaNoop(_) => META_NOOP.increment(),
+                    Request::Prepend(_) => PREPEND.increment(),
+                    Request::Quit(_) => QUIT.increment(),
+                    Request::Replace(_) => REPLACE.increment(),
+                    Request::Set(_) => SET.increment(),
+                    Request::Version(_) => VERSION.increment(),
+                    _ => {}
+                }
+                Ok((input, request))
+            }
+            Err(e) => {
+                if !e.is_incomplete() {
+                    BINARY.increment();
+                    BINARY_EX.increment();
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Compose for Binary {
+    fn compose(&self, session: &mut dyn BufMut) -> usize {
+        let mut extras = Vec::new();
+        let mut cas = 0;
+
+        let ttl = |ttl: Ttl| match ttl.get() {
+            None => 0,
+            Some(t) if t < 0 => UNIX_EXPIRED,
+            Some(t) => t as u32,
+        };
+
+        let (key, value): (&[u8], &[u8]) = match self.request.as_ref() {
+            Request::Gets(r) => (r.keys().first().map(|k| k.as_ref()).unwrap_or(b""), b""),
+            Request::Set(r) => {
+                extras.extend_from_slice(&r.flags().to_be_bytes());
+                extras.extend_from_slice(&ttl(r.ttl()).to_be_bytes());
+                (r.key(), r.value())
+            }
+            Request::Cas(r) => {
+                extras.extend_from_slice(&r.flags().to_be_bytes());
+                extras.extend_from_slice(&ttl(r.ttl()).to_be_bytes());
+                cas = r.cas();
+                (r.key(), r.value())
+            }
+            Request::Add(r) => {
+                extras.extend_from_slice(&r.flags().to_be_bytes());
+                extras.extend_from_slice(&ttl(r.ttl()).to_be_bytes());
+                (r.key(), r.value())
+            }
+            Request::Replace(r) => {
+                extras.extend_from_slice(&r.flags().to_be_bytes());
+                extras.extend_from_slice(&ttl(r.ttl()).to_be_bytes());
+                (r.key(), r.value())
+            }
+            Request::Append(r) => (r.key(), r.value()),
+            Request::Prepend(r) => (r.key(), r.value()),
+            Request::Delete(r) => (r.key(), b""),
+            Request::Incr(r) => {
+                extras.extend_from_slice(&r.value().to_be_bytes());
+                extras.extend_from_slice(&0_u64.to_be_bytes());
+                extras.extend_from_slice(&NO_VIVIFY.to_be_bytes());
+                (r.key(), b"")
+            }
+            Request::Decr(r) => {
+                extras.extend_from_slice(&r.value().to_be_bytes());
+                extras.extend_from_slice(&0_u64.to_be_bytes());
+                extras.extend_from_slice(&NO_VIVIFY.to_be_bytes());
+                (r.key(), b"")
+            }
+            Request::MetaArithmetic(r) => {
+                extras.extend_from_slice(&r.delta().to_be_bytes());
+                extras.extend_from_slice(&r.flags().initial().unwrap_or(0).to_be_bytes());
+                let exptime = r.flags().vivify().map(ttl).unwrap_or(NO_VIVIFY);
+                extras.extend_from_slice(&exptime.to_be_bytes());
+                (r.key(), b"")
+            }
+            Request::FlushAll(r) => {
+                if r.delay() != 0 {
+                    extras.extend_from_slice(&r.delay().to_be_bytes());
+                }
+                (b"", b"")
+            }
+            _ => (b"", b""),
+        };
+
+        Frame {
+            magic: REQUEST_MAGIC,
+            opcode: self.opcode,
+            status: 0,
+            opaque: self.opaque,
+            cas,
+            extras: &extras,
+            key,
+            value,
+        }
+        .compose(session)
+    }
+}
+
+impl Klog for Binary {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        match response {
+            Response::Binary(r) => self.request.klog(r.inner()),
+            _ => self.request.klog(response),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(
+        opcode: Opcode,
+        opaque: u32,
+        cas: u64,
+        extras: &[u8],
+        key: &[u8],
+        value: &[u8],
+    ) -> Vec<u8> {
+        let mut buf = Vec::new();
+        Frame {
+            magic: REQUEST_MAGIC,
+            opcode,
+            status: 0,
+            opaque,
+            cas,
+            extras,
+            key,
+            value,
+        }
+        .compose(&mut buf);
+        buf
+    }
+
+    #[test]
+    fn parse() {
+        let parser = RequestParser::new();
+
+        // get maps onto gets so that the cas value is returned
+        let buf = frame(Opcode::GetK, 7, 0, b"", b"key", b"");
+        assert_eq!(
+            parser.parse_request(&buf),
+            Ok((
+                &b""[..],
+                Request::Binary(Binary {
+                    opcode: Opcode::GetK,
+                    opaque: 7,
+                    request: Box::new(Request::Gets(Gets {
+                        keys: vec![b"key".to_vec().into_boxed_slice()].into_boxed_slice(),
+                    })),
+                })
+            ))
+        );
+
+        // set with flags and an expiration
+        let buf = frame(
+            Opcode::SetQ,
+            0,
+            0,
+            &[0, 0, 0, 1, 0, 0, 0, 60],
+            b"key",
+            b"value",
+        );
+        if let Ok((_, Request::Binary(request))) = parser.parse_request(&buf) {
+            assert_eq!(request.opcode(), Opcode::SetQ);
+            assert_eq!(
+                request.request(),
+                &Request::Set(Set {
+                    key: b"key".to_vec().into_boxed_slice(),
+                    value: b"value".to_vec().into_boxed_slice(),
+                    flags: 1,
+                    ttl: Ttl::new(60, TimeType::Memcache),
+                    noreply: true,
+                })
+            );
+        } else {
+            panic!("invalid parse result");
+        }
+
+        // set with a cas value is a cas request
+        let buf = frame(Opcode::Set, 0, 42, &[0; 8], b"key", b"value");
+        if let Ok((_, Request::Binary(request))) = parser.parse_request(&buf) {
+            assert!(matches!(request.request(), Request::Cas(r) if r.cas() == 42));
+        } else {
+            panic!("invalid parse result");
+        }
+
+        // incr without creating the item on a miss
+        let mut extras = Vec::new();
+        extras.extend_from_slice(&5_u64.to_be_bytes());
+        extras.extend_from_slice(&0_u64.to_be_bytes());
+        extras.extend_from_slice(&NO_VIVIFY.to_be_bytes());
+        let buf = frame(Opcode::Increment, 0, 0, &extras, b"key", b"");
+        if let Ok((_, Request::Binary(request))) = parser.parse_request(&buf) {
+            assert!(matches!(request.request(), Request::Incr(r) if r.value() == 5));
+        } else {
+            panic!("invalid parse result");
+        }
+
+        // decr with an initial value
+        let mut extras = Vec::new();
+        extras.extend_from_slice(&5_u64.to_be_bytes());
+        extras.extend_from_slice(&10_u64.to_be_bytes());
+        extras.extend_from_slice(&0_u32.to_be_bytes());
+        let buf = frame(Opcode::Decrement, 0, 0, &extras, b"key", b"");
+        if let Ok((_, Request::Binary(request))) = parser.parse_request(&buf) {
+            if let Request::MetaArithmetic(r) = request.request() {
+                assert_eq!(r.mode(), MetaArithmeticMode::Decrement);
+                assert_eq!(r.delta(), 5);
+                assert_eq!(r.flags().initial(), Some(10));
+                assert_eq!(r.flags().vivify(), Some(Ttl::none()));
+            } else {
+                panic!("invalid parse result");
+            }
+        } else {
+            panic!("invalid parse result");
+        }
+
+        // commands without arguments
+        let buf = frame(Opcode::Noop, 0, 0, b"", b"", b"");
+        if let Ok((_, Request::Binary(request))) = parser.parse_request(&buf) {
+            assert_eq!(request.request(), &Request::MetaNoop(MetaNoop {}));
+        } else {
+            panic!("invalid parse result");
+        }
+        let buf = frame(Opcode::Version, 0, 0, b"", b"", b"");
+        if let Ok((_, Request::Binary(request))) = parser.parse_request(&buf) {
+            assert_eq!(request.request(), &Request::Version(Version {}));
+        } else {
+            panic!("invalid parse result");
+        }
+
+        // incomplete requests
+        let buf = frame(Opcode::Set, 0, 0, &[0; 8], b"key", b"value");
+        for i in 1..buf.len() {
+            assert!(parser
+                .parse_request(&buf[0..i])
+                .unwrap_err()
+                .is_incomplete());
+        }
+
+        // missing key
+        let buf = frame(Opcode::Get, 0, 0, b"", b"", b"");
+        assert!(parser.parse_request(&buf).is_err());
+
+        // wrong size extras
+        let buf = frame(Opcode::Set, 0, 0, &[0; 4], b"key", b"value");
+        assert!(parser.parse_request(&buf).is_err());
+
+        // unknown opcode
+        let mut buf = frame(Opcode::Noop, 0, 0, b"", b"", b"");
+        buf[1] = 0x10;
+        assert!(parser.parse_request(&buf).is_err());
+
+        // pipelined with text protocol requests
+        let mut buf = frame(Opcode::GetQ, 0, 0, b"", b"key", b"");
+        buf.extend_from_slice(b"get key\r\n");
+        let (input, _) = parser.parse_request(&buf).unwrap();
+        assert_eq!(input, b"get key\r\n");
+    }
+
+    #[test]
+    fn basic_request() {
+        let parser = RequestParser::new();
+
+        // incr and decr which create the item on a miss map onto plain incr
+        // and decr for storage without the meta commands
+        let mut extras = Vec::new();
+        extras.extend_from_slice(&5_u64.to_be_bytes());
+        extras.extend_from_slice(&10_u64.to_be_bytes());
+        extras.extend_from_slice(&60_u32.to_be_bytes());
+        let buf = frame(Opcode::DecrementQ, 0, 0, &extras, b"key", b"");
+        if let Ok((_, Request::Binary(request))) = parser.parse_request(&buf) {
+            assert_eq!(request.modified_key(), Some(&b"key"[..]));
+            assert_eq!(
+                request.basic_request(),
+                Some(Request::Decr(Decr {
+                    key: b"key".to_vec().into_boxed_slice(),
+                    value: 5,
+                    noreply: true,
+                }))
+            );
+        } else {
+            panic!("invalid parse result");
+        }
+
+        let buf = frame(Opcode::GetK, 0, 0, b"", b"key", b"");
+        if let Ok((_, Request::Binary(request))) = parser.parse_request(&buf) {
+            assert_eq!(request.modified_key(), None);
+            assert_eq!(request.basic_request(), None);
+        } else {
+            panic!("invalid parse result");
+        }
+    }
+
+    #[test]
+    fn compose() {
+        let parser = RequestParser::new();
+
+        let mut extras = Vec::new();
+        extras.extend_from_slice(&5_u64.to_be_bytes());
+        extras.extend_from_slice(&10_u64.to_be_bytes());
+        extras.extend_from_slice(&60_u32.to_be_bytes());
+
+        for buf in [
+            frame(Opcode::GetKQ, 1, 0, b"", b"key", b""),
+            frame(
+                Opcode::Set,
+                2,
+                0,
+                &[0, 0, 0, 1, 0, 0, 0, 60],
+                b"key",
+                b"value",
+            ),
+            frame(Opcode::Set, 3, 42, &[0; 8], b"key", b"value"),
+            frame(Opcode::AddQ, 4, 0, &[0; 8], b"key", b"value"),
+            frame(Opcode::Append, 5, 0, b"", b"key", b"value"),
+            frame(Opcode::DeleteQ, 6, 0, b"", b"key", b""),
+            frame(Opcode::Decrement, 7, 0, &extras, b"key", b""),
+            frame(Opcode::Flush, 8, 0, &[0, 0, 0, 1], b"", b""),
+            frame(Opcode::Noop, 9, 0, b"", b"", b""),
+            frame(Opcode::Version, 10, 0, b"", b"", b""),
+        ] {
+            let (_, request) = parser.parse_request(&buf).unwrap();
+            let mut composed = Vec::new();
+            let len = request.compose(&mut composed);
+            assert_eq!(len, buf.len());
+            assert_eq!(composed, buf);
+        }
+    }
+}
@@ -0,0 +1,236 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+// the longest command line which is buffered while waiting for the end of an
+// invalid request, matching the limit used by memcached
+const MAX_LINE_LEN: usize = 2048;
+
+/// Why a text protocol request could not be parsed.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum InvalidReason {
+    /// The command is not one which is supported.
+    UnknownCommand,
+    /// The command line has the wrong arguments.
+    BadCommandLine,
+    /// The data block of a storage command is not terminated by a CRLF.
+    BadDataChunk,
+}
+
+/// A request which could not be parsed. The parser skips to the end of the
+/// request, including the data block of storage commands, so that the error
+/// can be reported without closing the session.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Invalid {
+    pub(crate) reason: InvalidReason,
+}
+
+impl Invalid {
+    fn new(reason: InvalidReason) -> Self {
+        match reason {
+            InvalidReason::UnknownCommand => UNKNOWN_REQUEST.increment(),
+            InvalidReason::BadCommandLine | InvalidReason::BadDataChunk => {
+                MALFORMED_REQUEST.increment()
+            }
+        }
+        Self { reason }
+    }
+
+    pub fn reason(&self) -> InvalidReason {
+        self.reason
+    }
+
+    /// The response for the request, which does not depend on the storage.
+    pub fn response(&self) -> Response {
+        match self.reason {
+            InvalidReason::UnknownCommand => Response::unknown_command(),
+            InvalidReason::BadCommandLine => Response::bad_request("bad command line format"),
+            InvalidReason::BadDataChunk => Response::bad_request("bad data chunk"),
+        }
+    }
+}
+
+impl RequestParser {
+    /// Skips over a text protocol request which failed to parse. Returns the
+    /// number of bytes to consume along with the invalid request, or an error
+    /// if more data is needed or if the session cannot be recovered.
+    pub(crate) fn recover(&self, buffer: &[u8]) -> Result<(usize, Invalid), std::io::Error> {
+        // binary requests are framed by their length, but a request which
+        // fails to parse may also have a corrupt length
+        if self.udp || buffer.first() == Some(&crate::binary::REQUEST_MAGIC) {
+            return Err(std::io::Error::from(std::io::ErrorKind::InvalidInput));
+        }
+
+        let line_len = match buffer.windows(2).position(|w| w == CRLF) {
+            Some(len) => len,
+            None if buffer.len() > MAX_LINE_LEN => {
+                return Err(std::io::Error::from(std::io::ErrorKind::InvalidInput));
+            }
+            None => return Err(std::io::Error::from(std::io::ErrorKind::WouldBlock)),
+        };
+        let line = &buffer[..line_len];
+        let consumed = line_len + CRLF.len();
+
+        let command = match self.parse_command(buffer) {
+            Ok((_, command)) => command,
+            Err(_) => return Ok((consumed, Invalid::new(InvalidReason::UnknownCommand))),
+        };
+
+        // storage commands are followed by a data block, which has to be
+        // skipped as well if its length can be found
+        let bytes = match command {
+            Command::Add
+            | Command::Append
+            | Command::Cas
+            | Command::Prepend
+            | Command::Replace
+            | Command::Set => data_len(line, 4),
+            Command::MetaSet => data_len(line, 2),
+            _ => None,
+        };
+
+        match bytes {
+            Some(bytes) if bytes > self.max_value_size => {
+                Err(std::io::Error::from(std::io::ErrorKind::InvalidInput))
+            }
+            Some(bytes) => {
+                let end = consumed + bytes + CRLF.len();
+                if buffer.len() < end {
+                    Err(std::io::Error::from(std::io::ErrorKind::WouldBlock))
+                } else if &buffer[(end - CRLF.len())..end] != CRLF {
+                    Ok((end, Invalid::new(InvalidReason::BadDataChunk)))
+                } else {
+                    Ok((end, Invalid::new(InvalidReason::BadCommandLine)))
+                }
+            }
+            None => Ok((consumed, Invalid::new(InvalidReason::BadCommandLine))),
+        }
+    }
+}
+
+// finds the length of the data block from the argument at the given position
+// of the command line
+fn data_len(line: &[u8], position: usize) -> Option<usize> {
+    line.split(|b| *b == b' ')
+        .filter(|token| !token.is_empty())
+        .nth(position)
+        .and_then(|token| std::str::from_utf8(token).ok())
+        .and_then(|token| token.parse::<usize>().ok())
+}
+
+impl Compose for Invalid {
+    fn compose(&self, _session: &mut dyn BufMut) -> usize {
+        0
+    }
+}
+
+impl Klog for Invalid {
+    type Response = Response;
+
+    fn klog(&self, _response: &Self::Response) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invalid(parser: &RequestParser, buffer: &[u8]) -> (usize, InvalidReason) {
+        let parsed = parser.parse(buffer).expect("failed to recover");
+        let consumed = parsed.consumed();
+        match parsed.into_inner() {
+            Request::Invalid(invalid) => (consumed, invalid.reason()),
+            request => panic!("unexpected request: {:?}", request),
+        }
+    }
+
+    #[test]
+    fn unknown_command() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            invalid(&parser, b"foo bar\r\nget 0\r\n"),
+            (9, InvalidReason::UnknownCommand)
+        );
+
+        // the error is only reported once the whole line is received
+        assert!(parser.parse(b"foo bar").is_err());
+    }
+
+    #[test]
+    fn bad_command_line() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            invalid(&parser, b"get\r\n"),
+            (5, InvalidReason::BadCommandLine)
+        );
+        assert_eq!(
+            invalid(&parser, b"verbosity high\r\nget 0\r\n"),
+            (16, InvalidReason::BadCommandLine)
+        );
+    }
+
+    #[test]
+    fn storage() {
+        let parser = RequestParser::new();
+
+        // the data block of a bad storage command is skipped
+        assert_eq!(
+            invalid(&parser, b"set 0 abc 0 1\r\n0\r\nget 0\r\n"),
+            (18, InvalidReason::BadCommandLine)
+        );
+        assert_eq!(
+            invalid(&parser, b"ms 0 1 MX\r\n0\r\n"),
+            (14, InvalidReason::BadCommandLine)
+        );
+        assert_eq!(
+            invalid(&parser, b"set 0 0 0 1\r\n00\r\n"),
+            (16, InvalidReason::BadDataChunk)
+        );
+
+        // wait for the data block before skipping it
+        assert!(parser.parse(b"set 0 abc 0 10\r\n0").is_err());
+
+        // without a length, only the command line is skipped
+        assert_eq!(
+            invalid(&parser, b"set 0 0 0\r\n"),
+            (11, InvalidReason::BadCommandLine)
+        );
+    }
+
+    #[test]
+    fn unrecoverable() {
+        let parser = RequestParser::new();
+
+        // a line which is too long to be buffered
+        let mut buffer = b"foo ".to_vec();
+        buffer.extend_from_slice(&[b'a'; MAX_LINE_LEN]);
+        assert_eq!(
+            parser.parse(&buffer).err().map(|e| e.kind()),
+            Some(std::io::ErrorKind::InvalidInput)
+        );
+
+        // a data block which is too large to be skipped
+        let parser = RequestParser::new().max_value_size(1);
+        assert_eq!(
+            parser
+                .parse(b"set 0 0 0 2\r\n00\r\n")
+                .err()
+                .map(|e| e.kind()),
+            Some(std::io::ErrorKind::InvalidInput)
+        );
+    }
+
+    #[test]
+    fn response() {
+        let invalid = Invalid::new(InvalidReason::UnknownCommand);
+        assert_eq!(invalid.response(), Response::unknown_command());
+        assert!(!invalid.response().should_hangup());
+
+        let invalid = Invalid::new(InvalidReason::BadDataChunk);
+        assert_eq!(invalid.response(), Response::bad_request("bad data chunk"));
+        assert!(!invalid.response().should_hangup());
+
+        assert!(Response::error().should_hangup());
+    }
+}
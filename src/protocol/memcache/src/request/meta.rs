@@ -0,0 +1,454 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Flags which are shared by the meta commands. Each meta command is a key
+//! followed by a space separated list of flags. A flag is a single character
+//! which may be followed immediately by a token, eg: `mg key v t T30`. Not all
+//! flags are valid for every command, so each command provides the set of
+//! flags that it accepts.
+
+use super::*;
+
+// the longest opaque token which will be accepted
+const MAX_OPAQUE_LEN: usize = 32;
+
+// the most flags which will be accepted for a single request
+const MAX_FLAGS: usize = 32;
+
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct MetaFlags {
+    pub(crate) base64: bool,
+    pub(crate) return_cas: bool,
+    pub(crate) return_flags: bool,
+    pub(crate) return_hit: bool,
+    pub(crate) return_key: bool,
+    pub(crate) return_last_access: bool,
+    pub(crate) quiet: bool,
+    pub(crate) return_size: bool,
+    pub(crate) return_ttl: bool,
+    pub(crate) no_bump: bool,
+    pub(crate) return_value: bool,
+    pub(crate) invalidate: bool,
+    pub(crate) opaque: Option<Box<[u8]>>,
+    pub(crate) compare_cas: Option<u64>,
+    pub(crate) client_flags: Option<u32>,
+    pub(crate) vivify: Option<Ttl>,
+    pub(crate) recache: Option<u32>,
+    pub(crate) ttl: Option<Ttl>,
+    pub(crate) initial: Option<u64>,
+    pub(crate) delta: Option<u64>,
+    pub(crate) mode: Option<u8>,
+}
+
+impl MetaFlags {
+    /// The key was provided as base64 (`b`), and should be returned that way.
+    pub fn base64(&self) -> bool {
+        self.base64
+    }
+
+    /// Return the CAS value of the item (`c`).
+    pub fn return_cas(&self) -> bool {
+        self.return_cas
+    }
+
+    /// Return the client flags of the item (`f`).
+    pub fn return_flags(&self) -> bool {
+        self.return_flags
+    }
+
+    /// Return whether the item has been fetched before (`h`).
+    pub fn return_hit(&self) -> bool {
+        self.return_hit
+    }
+
+    /// Return the key of the item (`k`).
+    pub fn return_key(&self) -> bool {
+        self.return_key
+    }
+
+    /// Return the number of seconds since the item was last accessed (`l`).
+    pub fn return_last_access(&self) -> bool {
+        self.return_last_access
+    }
+
+    /// Suppress the uninteresting responses for the command (`q`).
+    pub fn quiet(&self) -> bool {
+        self.quiet
+    }
+
+    /// Return the size of the item value (`s`).
+    pub fn return_size(&self) -> bool {
+        self.return_size
+    }
+
+    /// Return the remaining TTL of the item, or `-1` if it does not expire
+    /// (`t`).
+    pub fn return_ttl(&self) -> bool {
+        self.return_ttl
+    }
+
+    /// Do not bump the item in the LRU or update its access time (`u`).
+    pub fn no_bump(&self) -> bool {
+        self.no_bump
+    }
+
+    /// Return the item value (`v`).
+    pub fn return_value(&self) -> bool {
+        self.return_value
+    }
+
+    /// Invalidate mode (`I`). Items are marked as stale instead of being
+    /// removed or rejected.
+    pub fn invalidate(&self) -> bool {
+        self.invalidate
+    }
+
+    /// An opaque token which is returned as-is in the response (`O`).
+    pub fn opaque(&self) -> Option<&[u8]> {
+        self.opaque.as_deref()
+    }
+
+    /// Only perform the operation if the item CAS matches this value (`C`).
+    pub fn compare_cas(&self) -> Option<u64> {
+        self.compare_cas
+    }
+
+    /// The client flags to store with the item (`F`).
+    pub fn client_flags(&self) -> Option<u32> {
+        self.client_flags
+    }
+
+    /// Create the item with this TTL if it does not exist (`N`).
+    pub fn vivify(&self) -> Option<Ttl> {
+        self.vivify
+    }
+
+    /// Win the recache if the remaining TTL is less than this many seconds
+    /// (`R`).
+    pub fn recache(&self) -> Option<u32> {
+        self.recache
+    }
+
+    /// Update the TTL of the item (`T`).
+    pub fn ttl(&self) -> Option<Ttl> {
+        self.ttl
+    }
+
+    /// The initial value for an arithmetic command when the item is created
+    /// (`J`).
+    pub fn initial(&self) -> Option<u64> {
+        self.initial
+    }
+
+    /// The amount to add or subtract for an arithmetic command (`D`).
+    pub fn delta(&self) -> Option<u64> {
+        self.delta
+    }
+
+    /// Encodes the key, using base64 if the key was provided that way.
+    pub(crate) fn encode_key(&self, key: &[u8]) -> Vec<u8> {
+        if self.base64 {
+            base64::encode(key).into_bytes()
+        } else {
+            key.to_vec()
+        }
+    }
+
+    /// Writes out the flags, each with a leading space.
+    pub(crate) fn compose(&self, session: &mut dyn BufMut) -> usize {
+        let mut flags = Vec::new();
+        for (set, flag) in [
+            (self.base64, b'b'),
+            (self.return_cas, b'c'),
+            (self.return_flags, b'f'),
+            (self.return_hit, b'h'),
+            (self.return_key, b'k'),
+            (self.return_last_access, b'l'),
+            (self.quiet, b'q'),
+            (self.return_size, b's'),
+            (self.return_ttl, b't'),
+            (self.no_bump, b'u'),
+            (self.return_value, b'v'),
+            (self.invalidate, b'I'),
+        ] {
+            if set {
+                flags.push(b' ');
+                flags.push(flag);
+            }
+        }
+        if let Some(opaque) = &self.opaque {
+            flags.extend_from_slice(b" O");
+            flags.extend_from_slice(opaque);
+        }
+        if let Some(cas) = self.compare_cas {
+            let _ = write!(flags, " C{}", cas);
+        }
+        if let Some(client_flags) = self.client_flags {
+            let _ = write!(flags, " F{}", client_flags);
+        }
+        if let Some(vivify) = self.vivify {
+            let _ = write!(flags, " N{}", vivify.get().unwrap_or(0));
+        }
+        if let Some(recache) = self.recache {
+            let _ = write!(flags, " R{}", recache);
+        }
+        if let Some(ttl) = self.ttl {
+            let _ = write!(flags, " T{}", ttl.get().unwrap_or(0));
+        }
+        if let Some(initial) = self.initial {
+            let _ = write!(flags, " J{}", initial);
+        }
+        if let Some(delta) = self.delta {
+            let _ = write!(flags, " D{}", delta);
+        }
+        if let Some(mode) = self.mode {
+            flags.extend_from_slice(&[b' ', b'M', mode]);
+        }
+
+        session.put_slice(&flags);
+        flags.len()
+    }
+}
+
+/// Parses the token which follows a flag as a number.
+fn token<T: std::str::FromStr>(token: &[u8]) -> Option<T> {
+    std::str::from_utf8(token).ok()?.parse::<T>().ok()
+}
+
+impl RequestParser {
+    /// The longest key token which may be provided. Base64 encoding expands
+    /// the key, so this is the encoded length of the longest key.
+    fn max_meta_key_len(&self) -> usize {
+        4 * ((self.max_key_len + 2) / 3)
+    }
+
+    /// Parses the key token of a meta command. The key may still be base64
+    /// encoded, see `decode_meta_key`.
+    pub(crate) fn parse_meta_key<'a>(&self, input: &'a [u8]) -> IResult<&'a [u8], &'a [u8]> {
+        let (input, _) = space1(input)?;
+        let (input, key) = key(input, self.max_meta_key_len())?;
+
+        match key {
+            Some(k) => Ok((input, k)),
+            None => Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag))),
+        }
+    }
+
+    /// Decodes the key token once the flags are known, returning `None` if the
+    /// key is invalid.
+    pub(crate) fn decode_meta_key(&self, key: &[u8], flags: &MetaFlags) -> Option<Box<[u8]>> {
+        let key = if flags.base64 {
+            base64::decode(key).ok()?
+        } else {
+            key.to_vec()
+        };
+
+        if key.is_empty() || key.len() > self.max_key_len {
+            None
+        } else {
+            Some(key.into_boxed_slice())
+        }
+    }
+
+    /// Parses the flags which follow the key, and any other positional
+    /// arguments, of a meta command through to the end of the line. Flags
+    /// which are not in the allowed set are rejected.
+    pub(crate) fn parse_meta_flags<'a>(
+        &self,
+        input: &'a [u8],
+        allowed: &[u8],
+    ) -> IResult<&'a [u8], MetaFlags> {
+        let mut flags = MetaFlags::default();
+        let mut count = 0;
+        let mut input = input;
+
+        loop {
+            let (i, _) = space0(input)?;
+            let (i, t) = take_till(|b| (b == b' ' || b == b'\r'))(i)?;
+
+            if t.is_empty() {
+                input = i;
+                break;
+            }
+
+            count += 1;
+            if count > MAX_FLAGS || !allowed.contains(&t[0]) {
+                return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+            }
+
+            let (flag, t) = (t[0], &t[1..]);
+
+            // boolean flags must not have a token
+            let valid = match flag {
+                b'b' => {
+                    flags.base64 = true;
+                    t.is_empty()
+                }
+                b'c' => {
+                    flags.return_cas = true;
+                    t.is_empty()
+                }
+                b'f' => {
+                    flags.return_flags = true;
+                    t.is_empty()
+                }
+                b'h' => {
+                    flags.return_hit = true;
+                    t.is_empty()
+                }
+                b'k' => {
+                    flags.return_key = true;
+                    t.is_empty()
+                }
+                b'l' => {
+                    flags.return_last_access = true;
+                    t.is_empty()
+                }
+                b'q' => {
+                    flags.quiet = true;
+                    t.is_empty()
+                }
+                b's' => {
+                    flags.return_size = true;
+                    t.is_empty()
+                }
+                b't' => {
+                    flags.return_ttl = true;
+                    t.is_empty()
+                }
+                b'u' => {
+                    flags.no_bump = true;
+                    t.is_empty()
+                }
+                b'v' => {
+                    flags.return_value = true;
+                    t.is_empty()
+                }
+                b'I' => {
+                    flags.invalidate = true;
+                    t.is_empty()
+                }
+                b'O' => {
+                    flags.opaque = Some(t.to_owned().into_boxed_slice());
+                    t.len() <= MAX_OPAQUE_LEN
+                }
+                b'C' => {
+                    flags.compare_cas = token(t);
+                    flags.compare_cas.is_some()
+                }
+                b'F' => {
+                    flags.client_flags = token(t);
+                    flags.client_flags.is_some()
+                }
+                b'N' => {
+                    flags.vivify = token(t).map(|v| Ttl::new(v, self.time_type));
+                    flags.vivify.is_some()
+                }
+                b'R' => {
+                    flags.recache = token(t);
+                    flags.recache.is_some()
+                }
+                b'T' => {
+                    flags.ttl = token(t).map(|v| Ttl::new(v, self.time_type));
+                    flags.ttl.is_some()
+                }
+                b'J' => {
+                    flags.initial = token(t);
+                    flags.initial.is_some()
+                }
+                b'D' => {
+                    flags.delta = token(t);
+                    flags.delta.is_some()
+                }
+                b'M' => {
+                    flags.mode = t.first().copied();
+                    t.len() == 1
+                }
+                _ => false,
+            };
+
+            if !valid {
+                return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+            }
+
+            input = i;
+        }
+
+        let (input, _) = crlf(input)?;
+
+        Ok((input, flags))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_flags() {
+        let parser = RequestParser::new();
+
+        assert_eq!(
+            parser.parse_meta_flags(b"\r\n", b""),
+            Ok((&b""[..], MetaFlags::default()))
+        );
+
+        assert_eq!(
+            parser.parse_meta_flags(b" v  t Oabc T30 \r\n", b"vtOT"),
+            Ok((
+                &b""[..],
+                MetaFlags {
+                    return_value: true,
+                    return_ttl: true,
+                    opaque: Some(b"abc".to_vec().into_boxed_slice()),
+                    ttl: Some(Ttl::new(30, TimeType::Memcache)),
+                    ..Default::default()
+                }
+            ))
+        );
+
+        // flags which are not allowed are rejected
+        assert!(parser.parse_meta_flags(b" v\r\n", b"t").is_err());
+
+        // boolean flags do not take a token
+        assert!(parser.parse_meta_flags(b" v1\r\n", b"v").is_err());
+
+        // numeric flags require a valid number
+        assert!(parser.parse_meta_flags(b" T\r\n", b"T").is_err());
+        assert!(parser.parse_meta_flags(b" Tabc\r\n", b"T").is_err());
+        assert!(parser.parse_meta_flags(b" C-1\r\n", b"C").is_err());
+
+        // opaque tokens are limited in length
+        let mut request = b" O".to_vec();
+        request.extend_from_slice(&[b'a'; MAX_OPAQUE_LEN + 1]);
+        request.extend_from_slice(b"\r\n");
+        assert!(parser.parse_meta_flags(&request, b"O").is_err());
+
+        // incomplete
+        assert!(parser
+            .parse_meta_flags(b" v t", b"vt")
+            .unwrap_err()
+            .is_incomplete());
+    }
+
+    #[test]
+    fn base64_key() {
+        let parser = RequestParser::new();
+        let flags = MetaFlags {
+            base64: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            parser.decode_meta_key(b"Zm9v", &flags),
+            Some(b"foo".to_vec().into_boxed_slice())
+        );
+        assert_eq!(parser.decode_meta_key(b"!!!", &flags), None);
+        assert_eq!(
+            parser.decode_meta_key(b"Zm9v", &MetaFlags::default()),
+            Some(b"Zm9v".to_vec().into_boxed_slice())
+        );
+        assert_eq!(flags.encode_key(b"foo"), b"Zm9v".to_vec());
+    }
+}
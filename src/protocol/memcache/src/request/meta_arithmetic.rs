@@ -0,0 +1,183 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+// flags which are valid for the meta arithmetic command
+const ALLOWED: &[u8] = b"bcCDJkMNOqTtv";
+
+/// The mode switch for the meta arithmetic command.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum MetaArithmeticMode {
+    Increment,
+    Decrement,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct MetaArithmetic {
+    pub(crate) key: Box<[u8]>,
+    pub(crate) flags: MetaFlags,
+}
+
+impl MetaArithmetic {
+    pub fn key(&self) -> &[u8] {
+        self.key.as_ref()
+    }
+
+    pub fn flags(&self) -> &MetaFlags {
+        &self.flags
+    }
+
+    pub fn mode(&self) -> MetaArithmeticMode {
+        match self.flags.mode {
+            Some(b'D') | Some(b'd') | Some(b'-') => MetaArithmeticMode::Decrement,
+            _ => MetaArithmeticMode::Increment,
+        }
+    }
+
+    /// The amount to add or subtract, which defaults to one.
+    pub fn delta(&self) -> u64 {
+        self.flags.delta.unwrap_or(1)
+    }
+}
+
+impl RequestParser {
+    // this is to be called after parsing the command, so we do not match the verb
+    pub(crate) fn parse_meta_arithmetic_no_stats<'a>(
+        &self,
+        input: &'a [u8],
+    ) -> IResult<&'a [u8], MetaArithmetic> {
+        let (input, key) = self.parse_meta_key(input)?;
+        let (input, flags) = self.parse_meta_flags(input, ALLOWED)?;
+
+        if !matches!(
+            flags.mode,
+            None | Some(b'I' | b'i' | b'+' | b'D' | b'd' | b'-')
+        ) {
+            return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+        }
+
+        let key = self
+            .decode_meta_key(key, &flags)
+            .ok_or(nom::Err::Failure((input, nom::error::ErrorKind::Tag)))?;
+
+        Ok((input, MetaArithmetic { key, flags }))
+    }
+
+    pub fn parse_meta_arithmetic<'a>(&self, input: &'a [u8]) -> IResult<&'a [u8], MetaArithmetic> {
+        match self.parse_meta_arithmetic_no_stats(input) {
+            Ok((input, request)) => {
+                META_ARITHMETIC.increment();
+                Ok((input, request))
+            }
+            Err(e) => {
+                if !e.is_incomplete() {
+                    META_ARITHMETIC.increment();
+                    META_ARITHMETIC_EX.increment();
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Compose for MetaArithmetic {
+    fn compose(&self, session: &mut dyn BufMut) -> usize {
+        let verb = b"ma ";
+        let key = self.flags.encode_key(&self.key);
+
+        session.put_slice(verb);
+        session.put_slice(&key);
+        let flags = self.flags.compose(session);
+        session.put_slice(CRLF);
+
+        verb.len() + key.len() + flags + CRLF.len()
+    }
+}
+
+impl Klog for MetaArithmetic {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        let (code, len) = match response {
+            Response::Meta(ref res) => match res.code() {
+                MetaCode::Value | MetaCode::Header => {
+                    META_ARITHMETIC_STORED.increment();
+                    (STORED, res.data().map(|d| d.len()).unwrap_or(0))
+                }
+                MetaCode::Exists => {
+                    META_ARITHMETIC_EXISTS.increment();
+                    (EXISTS, 0)
+                }
+                _ => {
+                    META_ARITHMETIC_NOT_FOUND.increment();
+                    (NOT_FOUND, 0)
+                }
+            },
+            _ => {
+                return;
+            }
+        };
+        klog!(
+            "\"ma {} {}\" {} {}",
+            string_key(self.key()),
+            self.delta(),
+            code,
+            len
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let parser = RequestParser::new();
+
+        // basic meta arithmetic command
+        assert_eq!(
+            parser.parse_request(b"ma 0\r\n"),
+            Ok((
+                &b""[..],
+                Request::MetaArithmetic(MetaArithmetic {
+                    key: b"0".to_vec().into_boxed_slice(),
+                    flags: MetaFlags::default(),
+                })
+            ))
+        );
+
+        // decrement with autovivify
+        let request = parser
+            .parse_request(b"ma 0 MD D5 N60 J10 v\r\n")
+            .map(|(_, request)| request);
+        if let Ok(Request::MetaArithmetic(request)) = request {
+            assert_eq!(request.mode(), MetaArithmeticMode::Decrement);
+            assert_eq!(request.delta(), 5);
+            assert_eq!(request.flags().initial(), Some(10));
+            assert_eq!(
+                request.flags().vivify(),
+                Some(Ttl::new(60, TimeType::Memcache))
+            );
+            assert!(request.flags().return_value());
+        } else {
+            panic!("invalid parse result");
+        }
+
+        // mode defaults to increment
+        if let Ok((_, Request::MetaArithmetic(request))) = parser.parse_request(b"ma 0\r\n") {
+            assert_eq!(request.mode(), MetaArithmeticMode::Increment);
+            assert_eq!(request.delta(), 1);
+        } else {
+            panic!("invalid parse result");
+        }
+
+        // unknown mode
+        assert!(parser.parse_request(b"ma 0 MS\r\n").is_err());
+
+        // delta must be unsigned
+        assert!(parser.parse_request(b"ma 0 D-1\r\n").is_err());
+    }
+}
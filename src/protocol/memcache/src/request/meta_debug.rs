@@ -0,0 +1,119 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+// flags which are valid for the meta debug command
+const ALLOWED: &[u8] = b"b";
+
+/// Returns human readable information about an item without changing its
+/// access time or its position in the eviction order.
+#[derive(Debug, PartialEq, Eq)]
+pub struct MetaDebug {
+    pub(crate) key: Box<[u8]>,
+    pub(crate) flags: MetaFlags,
+}
+
+impl MetaDebug {
+    pub fn key(&self) -> &[u8] {
+        self.key.as_ref()
+    }
+
+    pub fn flags(&self) -> &MetaFlags {
+        &self.flags
+    }
+}
+
+impl RequestParser {
+    // this is to be called after parsing the command, so we do not match the verb
+    pub(crate) fn parse_meta_debug_no_stats<'a>(
+        &self,
+        input: &'a [u8],
+    ) -> IResult<&'a [u8], MetaDebug> {
+        let (input, key) = self.parse_meta_key(input)?;
+        let (input, flags) = self.parse_meta_flags(input, ALLOWED)?;
+
+        let key = self
+            .decode_meta_key(key, &flags)
+            .ok_or(nom::Err::Failure((input, nom::error::ErrorKind::Tag)))?;
+
+        Ok((input, MetaDebug { key, flags }))
+    }
+
+    pub fn parse_meta_debug<'a>(&self, input: &'a [u8]) -> IResult<&'a [u8], MetaDebug> {
+        match self.parse_meta_debug_no_stats(input) {
+            Ok((input, request)) => {
+                META_DEBUG.increment();
+                Ok((input, request))
+            }
+            Err(e) => {
+                if !e.is_incomplete() {
+                    META_DEBUG.increment();
+                    META_DEBUG_EX.increment();
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Compose for MetaDebug {
+    fn compose(&self, session: &mut dyn BufMut) -> usize {
+        let verb = b"me ";
+        let key = self.flags.encode_key(&self.key);
+
+        session.put_slice(verb);
+        session.put_slice(&key);
+        let flags = self.flags.compose(session);
+        session.put_slice(CRLF);
+
+        verb.len() + key.len() + flags + CRLF.len()
+    }
+}
+
+impl Klog for MetaDebug {
+    type Response = Response;
+
+    fn klog(&self, _response: &Self::Response) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let parser = RequestParser::new();
+
+        // basic meta debug command
+        assert_eq!(
+            parser.parse_request(b"me 0\r\n"),
+            Ok((
+                &b""[..],
+                Request::MetaDebug(MetaDebug {
+                    key: b"0".to_vec().into_boxed_slice(),
+                    flags: MetaFlags::default(),
+                })
+            ))
+        );
+
+        // base64 encoded key
+        assert_eq!(
+            parser.parse_request(b"me Zm9v b\r\n"),
+            Ok((
+                &b""[..],
+                Request::MetaDebug(MetaDebug {
+                    key: b"foo".to_vec().into_boxed_slice(),
+                    flags: MetaFlags {
+                        base64: true,
+                        ..Default::default()
+                    },
+                })
+            ))
+        );
+
+        // no other flags are accepted
+        assert!(parser.parse_request(b"me 0 v\r\n").is_err());
+    }
+}
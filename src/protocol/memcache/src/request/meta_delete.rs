@@ -0,0 +1,140 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+// flags which are valid for the meta delete command
+const ALLOWED: &[u8] = b"bCIkOqT";
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct MetaDelete {
+    pub(crate) key: Box<[u8]>,
+    pub(crate) flags: MetaFlags,
+}
+
+impl MetaDelete {
+    pub fn key(&self) -> &[u8] {
+        self.key.as_ref()
+    }
+
+    pub fn flags(&self) -> &MetaFlags {
+        &self.flags
+    }
+}
+
+impl RequestParser {
+    // this is to be called after parsing the command, so we do not match the verb
+    pub(crate) fn parse_meta_delete_no_stats<'a>(
+        &self,
+        input: &'a [u8],
+    ) -> IResult<&'a [u8], MetaDelete> {
+        let (input, key) = self.parse_meta_key(input)?;
+        let (input, flags) = self.parse_meta_flags(input, ALLOWED)?;
+
+        let key = self
+            .decode_meta_key(key, &flags)
+            .ok_or(nom::Err::Failure((input, nom::error::ErrorKind::Tag)))?;
+
+        Ok((input, MetaDelete { key, flags }))
+    }
+
+    pub fn parse_meta_delete<'a>(&self, input: &'a [u8]) -> IResult<&'a [u8], MetaDelete> {
+        match self.parse_meta_delete_no_stats(input) {
+            Ok((input, request)) => {
+                META_DELETE.increment();
+                Ok((input, request))
+            }
+            Err(e) => {
+                if !e.is_incomplete() {
+                    META_DELETE.increment();
+                    META_DELETE_EX.increment();
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Compose for MetaDelete {
+    fn compose(&self, session: &mut dyn BufMut) -> usize {
+        let verb = b"md ";
+        let key = self.flags.encode_key(&self.key);
+
+        session.put_slice(verb);
+        session.put_slice(&key);
+        let flags = self.flags.compose(session);
+        session.put_slice(CRLF);
+
+        verb.len() + key.len() + flags + CRLF.len()
+    }
+}
+
+impl Klog for MetaDelete {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        let code = match response {
+            Response::Meta(ref res) => match res.code() {
+                MetaCode::Header => {
+                    META_DELETE_DELETED.increment();
+                    DELETED
+                }
+                MetaCode::Exists => {
+                    META_DELETE_EXISTS.increment();
+                    EXISTS
+                }
+                _ => {
+                    META_DELETE_NOT_FOUND.increment();
+                    NOT_FOUND
+                }
+            },
+            _ => {
+                return;
+            }
+        };
+        klog!("\"md {}\" {} 0", string_key(self.key()), code);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let parser = RequestParser::new();
+
+        // basic meta delete command
+        assert_eq!(
+            parser.parse_request(b"md 0\r\n"),
+            Ok((
+                &b""[..],
+                Request::MetaDelete(MetaDelete {
+                    key: b"0".to_vec().into_boxed_slice(),
+                    flags: MetaFlags::default(),
+                })
+            ))
+        );
+
+        // invalidate instead of removing the item
+        assert_eq!(
+            parser.parse_request(b"md 0 I T30 q\r\n"),
+            Ok((
+                &b""[..],
+                Request::MetaDelete(MetaDelete {
+                    key: b"0".to_vec().into_boxed_slice(),
+                    flags: MetaFlags {
+                        invalidate: true,
+                        ttl: Some(Ttl::new(30, TimeType::Memcache)),
+                        quiet: true,
+                        ..Default::default()
+                    },
+                })
+            ))
+        );
+
+        // values are not returned by delete
+        assert!(parser.parse_request(b"md 0 v\r\n").is_err());
+    }
+}
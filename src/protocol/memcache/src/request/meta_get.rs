@@ -0,0 +1,172 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+// flags which are valid for the meta get command
+const ALLOWED: &[u8] = b"bcfhklOqstuvNRT";
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct MetaGet {
+    pub(crate) key: Box<[u8]>,
+    pub(crate) flags: MetaFlags,
+}
+
+impl MetaGet {
+    pub fn key(&self) -> &[u8] {
+        self.key.as_ref()
+    }
+
+    pub fn flags(&self) -> &MetaFlags {
+        &self.flags
+    }
+}
+
+impl RequestParser {
+    // this is to be called after parsing the command, so we do not match the verb
+    pub(crate) fn parse_meta_get_no_stats<'a>(
+        &self,
+        input: &'a [u8],
+    ) -> IResult<&'a [u8], MetaGet> {
+        let (input, key) = self.parse_meta_key(input)?;
+        let (input, flags) = self.parse_meta_flags(input, ALLOWED)?;
+
+        let key = self
+            .decode_meta_key(key, &flags)
+            .ok_or(nom::Err::Failure((input, nom::error::ErrorKind::Tag)))?;
+
+        Ok((input, MetaGet { key, flags }))
+    }
+
+    pub fn parse_meta_get<'a>(&self, input: &'a [u8]) -> IResult<&'a [u8], MetaGet> {
+        match self.parse_meta_get_no_stats(input) {
+            Ok((input, request)) => {
+                META_GET.increment();
+                Ok((input, request))
+            }
+            Err(e) => {
+                if !e.is_incomplete() {
+                    META_GET.increment();
+                    META_GET_EX.increment();
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Compose for MetaGet {
+    fn compose(&self, session: &mut dyn BufMut) -> usize {
+        let verb = b"mg ";
+        let key = self.flags.encode_key(&self.key);
+
+        session.put_slice(verb);
+        session.put_slice(&key);
+        let flags = self.flags.compose(session);
+        session.put_slice(CRLF);
+
+        verb.len() + key.len() + flags + CRLF.len()
+    }
+}
+
+impl Klog for MetaGet {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        let (code, len) = match response {
+            Response::Meta(ref res) if res.code() == MetaCode::Miss => {
+                META_GET_MISS.increment();
+                (MISS, 0)
+            }
+            Response::Meta(ref res) => {
+                META_GET_HIT.increment();
+                (HIT, res.data().map(|d| d.len()).unwrap_or(0))
+            }
+            _ => {
+                return;
+            }
+        };
+        klog!("\"mg {}\" {} {}", string_key(self.key()), code, len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let parser = RequestParser::new();
+
+        // basic meta get command
+        assert_eq!(
+            parser.parse_request(b"mg 0\r\n"),
+            Ok((
+                &b""[..],
+                Request::MetaGet(MetaGet {
+                    key: b"0".to_vec().into_boxed_slice(),
+                    flags: MetaFlags::default(),
+                })
+            ))
+        );
+
+        // with flags
+        assert_eq!(
+            parser.parse_request(b"mg 0 v c f t Oq1 N30\r\n"),
+            Ok((
+                &b""[..],
+                Request::MetaGet(MetaGet {
+                    key: b"0".to_vec().into_boxed_slice(),
+                    flags: MetaFlags {
+                        return_value: true,
+                        return_cas: true,
+                        return_flags: true,
+                        return_ttl: true,
+                        opaque: Some(b"q1".to_vec().into_boxed_slice()),
+                        vivify: Some(Ttl::new(30, TimeType::Memcache)),
+                        ..Default::default()
+                    },
+                })
+            ))
+        );
+
+        // base64 encoded key
+        assert_eq!(
+            parser.parse_request(b"mg Zm9v b v\r\n"),
+            Ok((
+                &b""[..],
+                Request::MetaGet(MetaGet {
+                    key: b"foo".to_vec().into_boxed_slice(),
+                    flags: MetaFlags {
+                        base64: true,
+                        return_value: true,
+                        ..Default::default()
+                    },
+                })
+            ))
+        );
+
+        // command name is not case sensitive
+        assert_eq!(
+            parser.parse_request(b"mg 0 v\r\n"),
+            parser.parse_request(b"MG 0 v\r\n"),
+        );
+
+        // flags from other commands are rejected
+        assert!(parser.parse_request(b"mg 0 F1\r\n").is_err());
+
+        // a key is required
+        assert!(parser.parse_request(b"mg \r\n").is_err());
+    }
+
+    #[test]
+    fn compose() {
+        let parser = RequestParser::new();
+        let request = b"mg Zm9v b k v Oabc T30\r\n";
+        let (_, parsed) = parser.parse_request(request).unwrap();
+        let mut buf = Vec::new();
+        assert_eq!(parsed.compose(&mut buf), request.len());
+        assert_eq!(&buf, request);
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+/// A meta no-op, which is typically sent after a pipeline of quiet mode
+/// requests so the client knows that all prior responses have been received.
+#[derive(Debug, PartialEq, Eq)]
+pub struct MetaNoop {}
+
+impl RequestParser {
+    // this is to be called after parsing the command, so we do not match the verb
+    pub fn parse_meta_noop<'a>(&self, input: &'a [u8]) -> IResult<&'a [u8], MetaNoop> {
+        let (input, _) = space0(input)?;
+        let (input, _) = crlf(input)?;
+
+        META_NOOP.increment();
+
+        Ok((input, MetaNoop {}))
+    }
+}
+
+impl Compose for MetaNoop {
+    fn compose(&self, session: &mut dyn BufMut) -> usize {
+        session.put_slice(b"mn\r\n");
+        4
+    }
+}
+
+impl Klog for MetaNoop {
+    type Response = Response;
+
+    fn klog(&self, _response: &Self::Response) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let parser = RequestParser::new();
+
+        // meta no-op command
+        assert_eq!(
+            parser.parse_request(b"mn\r\n"),
+            Ok((&b""[..], Request::MetaNoop(MetaNoop {})))
+        );
+    }
+}
@@ -0,0 +1,224 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+// flags which are valid for the meta set command
+const ALLOWED: &[u8] = b"bcCFIkOqTM";
+
+/// The mode switch for the meta set command, which selects between the
+/// behaviors of the classic storage commands.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum MetaSetMode {
+    Add,
+    Append,
+    Prepend,
+    Replace,
+    Set,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct MetaSet {
+    pub(crate) key: Box<[u8]>,
+    pub(crate) value: Box<[u8]>,
+    pub(crate) flags: MetaFlags,
+}
+
+impl MetaSet {
+    pub fn key(&self) -> &[u8] {
+        self.key.as_ref()
+    }
+
+    pub fn value(&self) -> &[u8] {
+        self.value.as_ref()
+    }
+
+    pub fn flags(&self) -> &MetaFlags {
+        &self.flags
+    }
+
+    pub fn mode(&self) -> MetaSetMode {
+        match self.flags.mode {
+            Some(b'E') | Some(b'e') => MetaSetMode::Add,
+            Some(b'A') | Some(b'a') => MetaSetMode::Append,
+            Some(b'P') | Some(b'p') => MetaSetMode::Prepend,
+            Some(b'R') | Some(b'r') => MetaSetMode::Replace,
+            _ => MetaSetMode::Set,
+        }
+    }
+}
+
+impl RequestParser {
+    // this is to be called after parsing the command, so we do not match the verb
+    pub(crate) fn parse_meta_set_no_stats<'a>(
+        &self,
+        input: &'a [u8],
+    ) -> IResult<&'a [u8], MetaSet> {
+        let (input, key) = self.parse_meta_key(input)?;
+        let (input, _) = space1(input)?;
+        let (input, bytes) = parse_usize(input)?;
+
+        if bytes > self.max_value_size {
+            return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+        }
+
+        let (input, flags) = self.parse_meta_flags(input, ALLOWED)?;
+
+        if !matches!(
+            flags.mode,
+            None | Some(b'E' | b'e' | b'A' | b'a' | b'P' | b'p' | b'R' | b'r' | b'S' | b's')
+        ) {
+            return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+        }
+
+        let key = self
+            .decode_meta_key(key, &flags)
+            .ok_or(nom::Err::Failure((input, nom::error::ErrorKind::Tag)))?;
+
+        let (input, value) = take(bytes)(input)?;
+        let (input, _) = crlf(input)?;
+
+        Ok((
+            input,
+            MetaSet {
+                key,
+                value: value.to_owned().into_boxed_slice(),
+                flags,
+            },
+        ))
+    }
+
+    pub fn parse_meta_set<'a>(&self, input: &'a [u8]) -> IResult<&'a [u8], MetaSet> {
+        match self.parse_meta_set_no_stats(input) {
+            Ok((input, request)) => {
+                META_SET.increment();
+                Ok((input, request))
+            }
+            Err(e) => {
+                if !e.is_incomplete() {
+                    META_SET.increment();
+                    META_SET_EX.increment();
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Compose for MetaSet {
+    fn compose(&self, session: &mut dyn BufMut) -> usize {
+        let verb = b"ms ";
+        let key = self.flags.encode_key(&self.key);
+        let vlen = format!(" {}", self.value.len()).into_bytes();
+
+        session.put_slice(verb);
+        session.put_slice(&key);
+        session.put_slice(&vlen);
+        let flags = self.flags.compose(session);
+        session.put_slice(CRLF);
+        session.put_slice(&self.value);
+        session.put_slice(CRLF);
+
+        verb.len() + key.len() + vlen.len() + flags + CRLF.len() + self.value.len() + CRLF.len()
+    }
+}
+
+impl Klog for MetaSet {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        let code = match response {
+            Response::Meta(ref res) => match res.code() {
+                MetaCode::Header => {
+                    META_SET_STORED.increment();
+                    STORED
+                }
+                MetaCode::Exists => {
+                    META_SET_EXISTS.increment();
+                    EXISTS
+                }
+                MetaCode::NotFound => {
+                    META_SET_NOT_FOUND.increment();
+                    NOT_FOUND
+                }
+                _ => {
+                    META_SET_NOT_STORED.increment();
+                    NOT_STORED
+                }
+            },
+            _ => {
+                return;
+            }
+        };
+        klog!(
+            "\"ms {} {}\" {}",
+            string_key(self.key()),
+            self.value().len(),
+            code
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let parser = RequestParser::new();
+
+        // basic meta set command
+        assert_eq!(
+            parser.parse_request(b"ms 0 1\r\n0\r\n"),
+            Ok((
+                &b""[..],
+                Request::MetaSet(MetaSet {
+                    key: b"0".to_vec().into_boxed_slice(),
+                    value: b"0".to_vec().into_boxed_slice(),
+                    flags: MetaFlags::default(),
+                })
+            ))
+        );
+
+        // with flags and a mode switch
+        let request = parser
+            .parse_request(b"ms 0 2 T60 F7 C42 ME q\r\nhi\r\n")
+            .map(|(_, request)| request);
+        if let Ok(Request::MetaSet(request)) = request {
+            assert_eq!(request.value(), b"hi");
+            assert_eq!(request.mode(), MetaSetMode::Add);
+            assert_eq!(
+                request.flags().ttl(),
+                Some(Ttl::new(60, TimeType::Memcache))
+            );
+            assert_eq!(request.flags().client_flags(), Some(7));
+            assert_eq!(request.flags().compare_cas(), Some(42));
+            assert!(request.flags().quiet());
+        } else {
+            panic!("invalid parse result");
+        }
+
+        // value is binary safe
+        assert_eq!(
+            parser.parse_request(b"ms 0 3\r\n\r\n\0\r\n"),
+            Ok((
+                &b""[..],
+                Request::MetaSet(MetaSet {
+                    key: b"0".to_vec().into_boxed_slice(),
+                    value: b"\r\n\0".to_vec().into_boxed_slice(),
+                    flags: MetaFlags::default(),
+                })
+            ))
+        );
+
+        // unknown mode
+        assert!(parser.parse_request(b"ms 0 1 MX\r\n0\r\n").is_err());
+
+        // datalen is required
+        assert!(parser.parse_request(b"ms 0\r\n0\r\n").is_err());
+
+        // value must be followed by CRLF
+        assert!(parser.parse_request(b"ms 0 1\r\n00\r\n").is_err());
+    }
+}
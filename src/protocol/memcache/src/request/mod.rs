@@ -4,13 +4,17 @@
 
 use crate::*;
 use common::expiry::TimeType;
+use core::cell::Cell;
 use core::fmt::{Display, Formatter};
 use core::num::NonZeroI32;
 use protocol_common::{BufMut, Parse, ParseOk};
 use std::borrow::Cow;
+use std::sync::Arc;
 
 mod add;
 mod append;
+mod auth;
+mod binary;
 mod cas;
 mod decr;
 mod delete;
@@ -18,13 +22,26 @@ mod flush_all;
 mod get;
 mod gets;
 mod incr;
+mod invalid;
+mod meta;
+mod meta_arithmetic;
+mod meta_debug;
+mod meta_delete;
+mod meta_get;
+mod meta_noop;
+mod meta_set;
 mod prepend;
 mod quit;
 mod replace;
 mod set;
+mod stats;
+mod verbosity;
+mod version;
 
 pub use add::Add;
 pub use append::Append;
+pub use auth::{Auth, AuthOutcome};
+pub use binary::Binary;
 pub use cas::Cas;
 pub use decr::Decr;
 pub use delete::Delete;
@@ -32,10 +49,21 @@ pub use flush_all::FlushAll;
 pub use get::Get;
 pub use gets::Gets;
 pub use incr::Incr;
+pub use invalid::{Invalid, InvalidReason};
+pub use meta::MetaFlags;
+pub use meta_arithmetic::{MetaArithmetic, MetaArithmeticMode};
+pub use meta_debug::MetaDebug;
+pub use meta_delete::MetaDelete;
+pub use meta_get::MetaGet;
+pub use meta_noop::MetaNoop;
+pub use meta_set::{MetaSet, MetaSetMode};
 pub use prepend::Prepend;
 pub use quit::Quit;
 pub use replace::Replace;
 pub use set::Set;
+pub use stats::{Stats, StatsGroup};
+pub use verbosity::Verbosity;
+pub use version::Version;
 
 pub const DEFAULT_MAX_BATCH_SIZE: usize = 1024;
 pub const DEFAULT_MAX_KEY_LEN: usize = 250;
@@ -54,12 +82,17 @@ fn string_key(key: &[u8]) -> Cow<'_, str> {
     String::from_utf8_lossy(key)
 }
 
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 pub struct RequestParser {
     max_value_size: usize,
     max_batch_size: usize,
     max_key_len: usize,
     time_type: TimeType,
+    credentials: Option<Arc<Credentials>>,
+    // each session has its own clone of the parser, so this tracks whether
+    // the session has authenticated
+    authenticated: Cell<bool>,
+    udp: bool,
 }
 
 impl RequestParser {
@@ -87,6 +120,20 @@ impl RequestParser {
         self
     }
 
+    /// Require sessions to authenticate with one of the provided credentials
+    /// before any other commands are accepted.
+    pub fn credentials(mut self, credentials: Credentials) -> Self {
+        self.credentials = Some(Arc::new(credentials));
+        self
+    }
+
+    /// Restrict the parser to the commands which are served over UDP. Only
+    /// `get` and `gets` are accepted, everything else fails to parse.
+    pub fn udp(mut self) -> Self {
+        self.udp = true;
+        self
+    }
+
     fn parse_command<'a>(&self, input: &'a [u8]) -> IResult<&'a [u8], Command> {
         let (remaining, command_bytes) = take_till(|b| (b == b' ' || b == b'\r'))(input)?;
         let command = match command_bytes {
@@ -99,12 +146,21 @@ impl RequestParser {
             b"incr" | b"INCR" => Command::Incr,
             b"get" | b"GET" => Command::Get,
             b"gets" | b"GETS" => Command::Gets,
+            b"ma" | b"MA" => Command::MetaArithmetic,
+            b"md" | b"MD" => Command::MetaDelete,
+            b"me" | b"ME" => Command::MetaDebug,
+            b"mg" | b"MG" => Command::MetaGet,
+            b"mn" | b"MN" => Command::MetaNoop,
+            b"ms" | b"MS" => Command::MetaSet,
             b"prepend" | b"PREPEND" => Command::Prepend,
             b"quit" | b"QUIT" => Command::Quit,
             b"replace" | b"REPLACE" => Command::Replace,
             b"set" | b"SET" => Command::Set,
+            b"stats" | b"STATS" => Command::Stats,
+            b"verbosity" | b"VERBOSITY" => Command::Verbosity,
+            b"version" | b"VERSION" => Command::Version,
             _ => {
-                // TODO(bmartin): we can return an unknown command error here
+                // unknown commands are reported to the client by `recover()`
                 return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
             }
         };
@@ -112,7 +168,28 @@ impl RequestParser {
     }
 
     pub fn parse_request<'a>(&self, input: &'a [u8]) -> IResult<&'a [u8], Request> {
-        match self.parse_command(input)? {
+        let (input, request) = self.parse_request_no_auth(input)?;
+        Ok((input, self.authorize(request)))
+    }
+
+    fn parse_request_no_auth<'a>(&self, input: &'a [u8]) -> IResult<&'a [u8], Request> {
+        // binary protocol requests always start with the magic byte, which is
+        // never the start of a text protocol request
+        if input.first() == Some(&crate::binary::REQUEST_MAGIC) {
+            if self.udp {
+                return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+            }
+            let (input, request) = self.parse_binary(input)?;
+            return Ok((input, Request::Binary(request)));
+        }
+
+        let (remaining, command) = self.parse_command(input)?;
+
+        if self.udp && !matches!(command, Command::Get | Command::Gets) {
+            return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+        }
+
+        match (remaining, command) {
             (input, Command::Add) => {
                 let (input, request) = self.parse_add(input)?;
                 Ok((input, Request::Add(request)))
@@ -149,6 +226,30 @@ impl RequestParser {
                 let (input, request) = self.parse_gets(input)?;
                 Ok((input, Request::Gets(request)))
             }
+            (input, Command::MetaArithmetic) => {
+                let (input, request) = self.parse_meta_arithmetic(input)?;
+                Ok((input, Request::MetaArithmetic(request)))
+            }
+            (input, Command::MetaDebug) => {
+                let (input, request) = self.parse_meta_debug(input)?;
+                Ok((input, Request::MetaDebug(request)))
+            }
+            (input, Command::MetaDelete) => {
+                let (input, request) = self.parse_meta_delete(input)?;
+                Ok((input, Request::MetaDelete(request)))
+            }
+            (input, Command::MetaGet) => {
+                let (input, request) = self.parse_meta_get(input)?;
+                Ok((input, Request::MetaGet(request)))
+            }
+            (input, Command::MetaNoop) => {
+                let (input, request) = self.parse_meta_noop(input)?;
+                Ok((input, Request::MetaNoop(request)))
+            }
+            (input, Command::MetaSet) => {
+                let (input, request) = self.parse_meta_set(input)?;
+                Ok((input, Request::MetaSet(request)))
+            }
             (input, Command::Prepend) => {
                 let (input, request) = self.parse_prepend(input)?;
                 Ok((input, Request::Prepend(request)))
@@ -165,6 +266,18 @@ impl RequestParser {
                 let (input, request) = self.parse_set(input)?;
                 Ok((input, Request::Set(request)))
             }
+            (input, Command::Stats) => {
+                let (input, request) = self.parse_stats(input)?;
+                Ok((input, Request::Stats(request)))
+            }
+            (input, Command::Verbosity) => {
+                let (input, request) = self.parse_verbosity(input)?;
+                Ok((input, Request::Verbosity(request)))
+            }
+            (input, Command::Version) => {
+                let (input, request) = self.parse_version(input)?;
+                Ok((input, Request::Version(request)))
+            }
         }
     }
 }
@@ -176,6 +289,9 @@ impl Default for RequestParser {
             max_batch_size: DEFAULT_MAX_BATCH_SIZE,
             max_key_len: DEFAULT_MAX_KEY_LEN,
             time_type: TimeType::Memcache,
+            credentials: None,
+            authenticated: Cell::new(false),
+            udp: false,
         }
     }
 }
@@ -185,7 +301,10 @@ impl Parse<Request> for RequestParser {
         match self.parse_request(buffer) {
             Ok((input, request)) => Ok(ParseOk::new(request, buffer.len() - input.len())),
             Err(Err::Incomplete(_)) => Err(std::io::Error::from(std::io::ErrorKind::WouldBlock)),
-            Err(_) => Err(std::io::Error::from(std::io::ErrorKind::InvalidInput)),
+            Err(_) => {
+                let (consumed, invalid) = self.recover(buffer)?;
+                Ok(ParseOk::new(Request::Invalid(invalid), consumed))
+            }
         }
     }
 }
@@ -195,17 +314,29 @@ impl Compose for Request {
         match self {
             Self::Add(r) => r.compose(session),
             Self::Append(r) => r.compose(session),
+            Self::Auth(r) => r.compose(session),
+            Self::Binary(r) => r.compose(session),
             Self::Cas(r) => r.compose(session),
             Self::Decr(r) => r.compose(session),
             Self::Delete(r) => r.compose(session),
             Self::FlushAll(r) => r.compose(session),
             Self::Incr(r) => r.compose(session),
+            Self::Invalid(r) => r.compose(session),
             Self::Get(r) => r.compose(session),
             Self::Gets(r) => r.compose(session),
+            Self::MetaArithmetic(r) => r.compose(session),
+            Self::MetaDebug(r) => r.compose(session),
+            Self::MetaDelete(r) => r.compose(session),
+            Self::MetaGet(r) => r.compose(session),
+            Self::MetaNoop(r) => r.compose(session),
+            Self::MetaSet(r) => r.compose(session),
             Self::Prepend(r) => r.compose(session),
             Self::Quit(r) => r.compose(session),
             Self::Replace(r) => r.compose(session),
             Self::Set(r) => r.compose(session),
+            Self::Stats(r) => r.compose(session),
+            Self::Verbosity(r) => r.compose(session),
+            Self::Version(r) => r.compose(session),
         }
     }
 }
@@ -217,17 +348,29 @@ impl Klog for Request {
         match self {
             Self::Add(r) => r.klog(response),
             Self::Append(r) => r.klog(response),
+            Self::Auth(r) => r.klog(response),
+            Self::Binary(r) => r.klog(response),
             Self::Cas(r) => r.klog(response),
             Self::Decr(r) => r.klog(response),
             Self::Delete(r) => r.klog(response),
             Self::FlushAll(r) => r.klog(response),
             Self::Incr(r) => r.klog(response),
+            Self::Invalid(r) => r.klog(response),
             Self::Get(r) => r.klog(response),
             Self::Gets(r) => r.klog(response),
+            Self::MetaArithmetic(r) => r.klog(response),
+            Self::MetaDebug(r) => r.klog(response),
+            Self::MetaDelete(r) => r.klog(response),
+            Self::MetaGet(r) => r.klog(response),
+            Self::MetaNoop(r) => r.klog(response),
+            Self::MetaSet(r) => r.klog(response),
             Self::Prepend(r) => r.klog(response),
             Self::Quit(r) => r.klog(response),
             Self::Replace(r) => r.klog(response),
             Self::Set(r) => r.klog(response),
+            Self::Stats(r) => r.klog(response),
+            Self::Verbosity(r) => r.klog(response),
+            Self::Version(r) => r.klog(response),
         }
     }
 }
@@ -236,17 +379,29 @@ impl Klog for Request {
 pub enum Request {
     Add(Add),
     Append(Append),
+    Auth(Auth),
+    Binary(Binary),
     Cas(Cas),
     Decr(Decr),
     Delete(Delete),
     FlushAll(FlushAll),
     Incr(Incr),
+    Invalid(Invalid),
     Get(Get),
     Gets(Gets),
+    MetaArithmetic(MetaArithmetic),
+    MetaDebug(MetaDebug),
+    MetaDelete(MetaDelete),
+    MetaGet(MetaGet),
+    MetaNoop(MetaNoop),
+    MetaSet(MetaSet),
     Prepend(Prepend),
     Quit(Quit),
     Replace(Replace),
     Set(Set),
+    Stats(Stats),
+    Verbosity(Verbosity),
+    Version(Version),
 }
 
 impl Display for Request {
@@ -254,17 +409,29 @@ impl Display for Request {
         match self {
             Request::Add(_) => write!(f, "add"),
             Request::Append(_) => write!(f, "append"),
+            Request::Auth(_) => write!(f, "auth"),
+            Request::Binary(r) => r.request().fmt(f),
             Request::Cas(_) => write!(f, "cas"),
             Request::Decr(_) => write!(f, "decr"),
             Request::Delete(_) => write!(f, "delete"),
             Request::FlushAll(_) => write!(f, "flush_all"),
             Request::Incr(_) => write!(f, "incr"),
+            Request::Invalid(_) => write!(f, "invalid"),
             Request::Get(_) => write!(f, "get"),
             Request::Gets(_) => write!(f, "gets"),
+            Request::MetaArithmetic(_) => write!(f, "ma"),
+            Request::MetaDebug(_) => write!(f, "me"),
+            Request::MetaDelete(_) => write!(f, "md"),
+            Request::MetaGet(_) => write!(f, "mg"),
+            Request::MetaNoop(_) => write!(f, "mn"),
+            Request::MetaSet(_) => write!(f, "ms"),
             Request::Prepend(_) => write!(f, "prepend"),
             Request::Quit(_) => write!(f, "quit"),
             Request::Replace(_) => write!(f, "replace"),
             Request::Set(_) => write!(f, "set"),
+            Request::Stats(_) => write!(f, "stats"),
+            Request::Verbosity(_) => write!(f, "verbosity"),
+            Request::Version(_) => write!(f, "version"),
         }
     }
 }
@@ -280,10 +447,19 @@ pub enum Command {
     Incr,
     Get,
     Gets,
+    MetaArithmetic,
+    MetaDebug,
+    MetaDelete,
+    MetaGet,
+    MetaNoop,
+    MetaSet,
     Prepend,
     Quit,
     Replace,
     Set,
+    Stats,
+    Verbosity,
+    Version,
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -412,5 +588,59 @@ mod tests {
             parser.parse_command(b"set key \"value\"\r\n"),
             Ok((&b" key \"value\"\r\n"[..], Command::Set))
         );
+
+        assert_eq!(
+            parser.parse_command(b"mg key v\r\n"),
+            Ok((&b" key v\r\n"[..], Command::MetaGet))
+        );
+        assert_eq!(
+            parser.parse_command(b"mn\r\n"),
+            Ok((&b"\r\n"[..], Command::MetaNoop))
+        );
+    }
+
+    #[test]
+    fn udp() {
+        let parser = RequestParser::new().udp();
+        assert!(matches!(
+            parser.parse_request(b"get key\r\n"),
+            Ok((_, Request::Get(_)))
+        ));
+        assert!(matches!(
+            parser.parse_request(b"gets a b\r\n"),
+            Ok((_, Request::Gets(_)))
+        ));
+
+        // incomplete requests are still reported as incomplete
+        assert!(matches!(
+            parser.parse_request(b"get key"),
+            Err(nom::Err::Incomplete(_))
+        ));
+
+        // all other commands are rejected
+        assert!(matches!(
+            parser.parse_request(b"set key 0 0 1\r\n1\r\n"),
+            Err(nom::Err::Failure(_))
+        ));
+        assert!(matches!(
+            parser.parse_request(b"mg key v\r\n"),
+            Err(nom::Err::Failure(_))
+        ));
+        let mut buf = Vec::new();
+        crate::binary::Frame {
+            magic: crate::binary::REQUEST_MAGIC,
+            opcode: crate::binary::Opcode::Get,
+            status: 0,
+            opaque: 0,
+            cas: 0,
+            extras: &[],
+            key: b"key",
+            value: &[],
+        }
+        .compose(&mut buf);
+        assert!(matches!(
+            parser.parse_request(&buf),
+            Err(nom::Err::Failure(_))
+        ));
     }
 }
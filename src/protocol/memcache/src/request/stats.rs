@@ -0,0 +1,178 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+/// The group of statistics which is being requested. This is the optional
+/// argument to the `stats` command.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum StatsGroup {
+    /// `stats` - the general server statistics
+    General,
+    /// `stats settings` - the server configuration
+    Settings,
+    /// `stats items` - per item class statistics, which are TTL buckets for
+    /// segment-structured storage
+    Items,
+    /// `stats slabs` - storage utilization, which is segment utilization for
+    /// segment-structured storage
+    Slabs,
+}
+
+impl StatsGroup {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::General => "",
+            Self::Settings => "settings",
+            Self::Items => "items",
+            Self::Slabs => "slabs",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub(crate) group: StatsGroup,
+}
+
+impl Stats {
+    pub fn group(&self) -> StatsGroup {
+        self.group
+    }
+}
+
+impl RequestParser {
+    // this is to be called after parsing the command, so we do not match the verb
+    pub fn parse_stats_no_stats<'a>(&self, input: &'a [u8]) -> IResult<&'a [u8], Stats> {
+        let mut input = input;
+        let mut group = StatsGroup::General;
+
+        // if we have a space, we might have a group name
+        if let Ok((i, _)) = space1(input) {
+            let (i, name) = take_till(|b| (b == b' ' || b == b'\r'))(i)?;
+            group = match name {
+                b"" => StatsGroup::General,
+                b"settings" | b"SETTINGS" => StatsGroup::Settings,
+                b"items" | b"ITEMS" => StatsGroup::Items,
+                b"slabs" | b"SLABS" => StatsGroup::Slabs,
+                _ => {
+                    return Err(nom::Err::Failure((i, nom::error::ErrorKind::Tag)));
+                }
+            };
+            input = i;
+        }
+
+        let (input, _) = space0(input)?;
+        let (input, _) = crlf(input)?;
+
+        Ok((input, Stats { group }))
+    }
+
+    // this is to be called after parsing the command, so we do not match the verb
+    pub fn parse_stats<'a>(&self, input: &'a [u8]) -> IResult<&'a [u8], Stats> {
+        match self.parse_stats_no_stats(input) {
+            Ok((input, request)) => {
+                STATS.increment();
+                Ok((input, request))
+            }
+            Err(e) => {
+                if !e.is_incomplete() {
+                    STATS.increment();
+                    STATS_EX.increment();
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Compose for Stats {
+    fn compose(&self, session: &mut dyn BufMut) -> usize {
+        let verb = b"stats";
+        let group = self.group.as_str().as_bytes();
+
+        let mut size = verb.len() + CRLF.len();
+
+        session.put_slice(verb);
+        if !group.is_empty() {
+            session.put_slice(b" ");
+            session.put_slice(group);
+            size += 1 + group.len();
+        }
+        session.put_slice(CRLF);
+
+        size
+    }
+}
+
+impl Klog for Stats {
+    type Response = Response;
+
+    fn klog(&self, _response: &Self::Response) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let parser = RequestParser::new();
+
+        // general stats
+        assert_eq!(
+            parser.parse_request(b"stats\r\n"),
+            Ok((
+                &b""[..],
+                Request::Stats(Stats {
+                    group: StatsGroup::General
+                })
+            ))
+        );
+
+        // stats groups
+        assert_eq!(
+            parser.parse_request(b"stats settings\r\n"),
+            Ok((
+                &b""[..],
+                Request::Stats(Stats {
+                    group: StatsGroup::Settings
+                })
+            ))
+        );
+        assert_eq!(
+            parser.parse_request(b"stats items \r\n"),
+            Ok((
+                &b""[..],
+                Request::Stats(Stats {
+                    group: StatsGroup::Items
+                })
+            ))
+        );
+        assert_eq!(
+            parser.parse_request(b"stats slabs\r\n"),
+            Ok((
+                &b""[..],
+                Request::Stats(Stats {
+                    group: StatsGroup::Slabs
+                })
+            ))
+        );
+
+        // unknown groups are rejected
+        assert!(parser.parse_request(b"stats sizes\r\n").is_err());
+    }
+
+    #[test]
+    fn compose() {
+        let parser = RequestParser::new();
+
+        for request in [&b"stats\r\n"[..], b"stats settings\r\n", b"stats items\r\n"] {
+            let (_, parsed) = parser.parse_request(request).unwrap();
+            let mut buf = Vec::new();
+            assert_eq!(parsed.compose(&mut buf), request.len());
+            assert_eq!(buf, request);
+        }
+    }
+}
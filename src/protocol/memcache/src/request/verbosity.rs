@@ -0,0 +1,126 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+/// A request to change the logging verbosity. Clients commonly send this when
+/// connecting, so it is accepted even when the storage does not act on it.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Verbosity {
+    pub(crate) level: u32,
+    pub(crate) noreply: bool,
+}
+
+impl Verbosity {
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+
+    pub fn noreply(&self) -> bool {
+        self.noreply
+    }
+}
+
+impl RequestParser {
+    // this is to be called after parsing the command, so we do not match the verb
+    pub fn parse_verbosity_no_stats<'a>(&self, input: &'a [u8]) -> IResult<&'a [u8], Verbosity> {
+        let (input, _) = space1(input)?;
+        let (mut input, level) = parse_u32(input)?;
+
+        let mut noreply = false;
+
+        // if we have a space, we might have a noreply
+        if let Ok((i, _)) = space1(input) {
+            if i.len() > 7 && &i[0..7] == b"noreply" {
+                noreply = true;
+                input = &i[7..];
+            }
+        }
+
+        let (input, _) = space0(input)?;
+        let (input, _) = crlf(input)?;
+
+        Ok((input, Verbosity { level, noreply }))
+    }
+
+    // this is to be called after parsing the command, so we do not match the verb
+    pub fn parse_verbosity<'a>(&self, input: &'a [u8]) -> IResult<&'a [u8], Verbosity> {
+        match self.parse_verbosity_no_stats(input) {
+            Ok((input, request)) => {
+                VERBOSITY.increment();
+                Ok((input, request))
+            }
+            Err(e) => {
+                if !e.is_incomplete() {
+                    VERBOSITY.increment();
+                    VERBOSITY_EX.increment();
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Compose for Verbosity {
+    fn compose(&self, session: &mut dyn BufMut) -> usize {
+        let verb = b"verbosity ";
+        let level = format!("{}", self.level).into_bytes();
+        let header_end = if self.noreply {
+            " noreply\r\n".as_bytes()
+        } else {
+            "\r\n".as_bytes()
+        };
+
+        let size = verb.len() + level.len() + header_end.len();
+
+        session.put_slice(verb);
+        session.put_slice(&level);
+        session.put_slice(header_end);
+
+        size
+    }
+}
+
+impl Klog for Verbosity {
+    type Response = Response;
+
+    fn klog(&self, _response: &Self::Response) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let parser = RequestParser::new();
+
+        assert_eq!(
+            parser.parse_request(b"verbosity 1\r\n"),
+            Ok((
+                &b""[..],
+                Request::Verbosity(Verbosity {
+                    level: 1,
+                    noreply: false,
+                })
+            ))
+        );
+
+        // noreply
+        assert_eq!(
+            parser.parse_request(b"verbosity 0 noreply\r\n"),
+            Ok((
+                &b""[..],
+                Request::Verbosity(Verbosity {
+                    level: 0,
+                    noreply: true,
+                })
+            ))
+        );
+
+        // the level is required
+        assert!(parser.parse_request(b"verbosity\r\n").is_err());
+        assert!(parser.parse_request(b"verbosity high\r\n").is_err());
+    }
+}
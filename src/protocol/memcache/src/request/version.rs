@@ -0,0 +1,53 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+/// A request for the server version.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Version {}
+
+impl RequestParser {
+    // this is to be called after parsing the command, so we do not match the verb
+    pub fn parse_version<'a>(&self, input: &'a [u8]) -> IResult<&'a [u8], Version> {
+        let (input, _) = space0(input)?;
+        let (input, _) = crlf(input)?;
+
+        VERSION.increment();
+
+        Ok((input, Version {}))
+    }
+}
+
+impl Compose for Version {
+    fn compose(&self, session: &mut dyn BufMut) -> usize {
+        session.put_slice(b"version\r\n");
+        9
+    }
+}
+
+impl Klog for Version {
+    type Response = Response;
+
+    fn klog(&self, _response: &Self::Response) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let parser = RequestParser::new();
+
+        assert_eq!(
+            parser.parse_request(b"version\r\n"),
+            Ok((&b""[..], Request::Version(Version {})))
+        );
+        assert_eq!(
+            parser.parse_request(b"VERSION \r\n"),
+            Ok((&b""[..], Request::Version(Version {})))
+        );
+    }
+}
@@ -0,0 +1,29 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+const MSG_PREFIX: &[u8] = b"CLIENT_ERROR ";
+
+/// The response when authentication fails or is required. This is sent as a
+/// client error using the text protocol, and as an authentication error
+/// status using the binary protocol.
+#[derive(Debug, PartialEq, Eq)]
+pub struct AuthError {
+    pub(crate) inner: String,
+}
+
+impl Compose for AuthError {
+    fn compose(&self, session: &mut dyn BufMut) -> usize {
+        let msg = self.inner.as_bytes();
+
+        let size = MSG_PREFIX.len() + msg.len() + CRLF.len();
+
+        session.put_slice(MSG_PREFIX);
+        session.put_slice(msg);
+        session.put_slice(CRLF);
+
+        size
+    }
+}
@@ -0,0 +1,303 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use crate::auth::SASL_MECHANISMS;
+use crate::binary::*;
+
+// response status codes
+pub(crate) const NO_ERROR: u16 = 0x0000;
+pub(crate) const KEY_NOT_FOUND: u16 = 0x0001;
+pub(crate) const KEY_EXISTS: u16 = 0x0002;
+pub(crate) const INVALID_ARGUMENTS: u16 = 0x0004;
+pub(crate) const ITEM_NOT_STORED: u16 = 0x0005;
+pub(crate) const NON_NUMERIC: u16 = 0x0006;
+pub(crate) const AUTH_ERROR: u16 = 0x0020;
+pub(crate) const UNKNOWN_COMMAND: u16 = 0x0081;
+pub(crate) const INTERNAL_ERROR: u16 = 0x0084;
+
+/// A response which is sent using the binary protocol. This wraps the response
+/// to the text protocol request that the binary request was mapped onto, and
+/// converts it to the matching status code and body when composed.
+#[derive(Debug, PartialEq, Eq)]
+pub struct BinaryResponse {
+    opcode: Opcode,
+    opaque: u32,
+    key: Option<Box<[u8]>>,
+    cas: u64,
+    inner: Box<Response>,
+}
+
+impl BinaryResponse {
+    /// Create a new binary response. The key is only included in the response
+    /// for the opcodes which return it.
+    pub fn new(opcode: Opcode, opaque: u32, key: Option<Box<[u8]>>, inner: Response) -> Self {
+        Self {
+            opcode,
+            opaque,
+            key,
+            cas: 0,
+            inner: Box::new(inner),
+        }
+    }
+
+    /// Sets the cas value which is returned for a successful response to a
+    /// command which modifies an item.
+    pub fn with_cas(mut self, cas: u64) -> Self {
+        self.cas = cas;
+        self
+    }
+
+    pub fn opcode(&self) -> Opcode {
+        self.opcode
+    }
+
+    pub fn opaque(&self) -> u32 {
+        self.opaque
+    }
+
+    /// The text protocol response which this response is composed from.
+    pub fn inner(&self) -> &Response {
+        &self.inner
+    }
+
+    /// The status code for the response.
+    pub fn status(&self) -> u16 {
+        let arithmetic = matches!(
+            self.opcode,
+            Opcode::Increment | Opcode::IncrementQ | Opcode::Decrement | Opcode::DecrementQ
+        );
+
+        match self.inner.as_ref() {
+            Response::Values(values) => match values.values().first() {
+                Some(value) if value.len().is_some() => NO_ERROR,
+                _ => KEY_NOT_FOUND,
+            },
+            Response::NotStored(_) => match self.opcode {
+                Opcode::Add | Opcode::AddQ => KEY_EXISTS,
+                Opcode::Replace | Opcode::ReplaceQ => KEY_NOT_FOUND,
+                _ => ITEM_NOT_STORED,
+            },
+            Response::Exists(_) => KEY_EXISTS,
+            Response::NotFound(_) => KEY_NOT_FOUND,
+            Response::Meta(meta) => match meta.code() {
+                MetaCode::Miss | MetaCode::NotFound => KEY_NOT_FOUND,
+                MetaCode::Exists => KEY_EXISTS,
+                MetaCode::NotStored => ITEM_NOT_STORED,
+                _ => NO_ERROR,
+            },
+            Response::Error(_) | Response::ClientError(_) if arithmetic => NON_NUMERIC,
+            Response::Error(_) => UNKNOWN_COMMAND,
+            Response::ClientError(_) => INVALID_ARGUMENTS,
+            Response::AuthError(_) => AUTH_ERROR,
+            Response::ServerError(_) | Response::DebugInfo(_) | Response::Binary(_) => {
+                INTERNAL_ERROR
+            }
+            _ => NO_ERROR,
+        }
+    }
+}
+
+impl Compose for BinaryResponse {
+    fn compose(&self, session: &mut dyn BufMut) -> usize {
+        let status = self.status();
+
+        // quiet gets only respond on a hit, and all other quiet commands only
+        // respond on an error
+        if self.opcode.is_quiet() {
+            if self.opcode.is_get() {
+                if status == KEY_NOT_FOUND {
+                    return 0;
+                }
+            } else if status == NO_ERROR {
+                return 0;
+            }
+        }
+
+        let mut extras: &[u8] = &[];
+        let mut cas = if status == NO_ERROR { self.cas } else { 0 };
+        let flags;
+        let numeric;
+
+        let value: &[u8] = match self.inner.as_ref() {
+            Response::Values(values) => match values.values().first() {
+                Some(value) if value.len().is_some() => {
+                    flags = value.flags().to_be_bytes();
+                    extras = &flags;
+                    cas = value.cas().unwrap_or(0);
+                    value.data().unwrap_or(b"")
+                }
+                _ => b"Not found",
+            },
+            Response::Numeric(n) => {
+                numeric = n.value().to_be_bytes();
+                &numeric
+            }
+            Response::Meta(meta) if status == NO_ERROR => {
+                // the value for arithmetic commands is sent as a 64bit integer
+                match meta
+                    .data()
+                    .and_then(|d| std::str::from_utf8(d).ok())
+                    .and_then(|d| d.parse::<u64>().ok())
+                {
+                    Some(v) => {
+                        numeric = v.to_be_bytes();
+                        &numeric
+                    }
+                    None => b"",
+                }
+            }
+            Response::VersionInfo(v) => v.version().as_bytes(),
+            Response::Okay(_) if self.opcode == Opcode::SaslListMechs => SASL_MECHANISMS,
+            Response::Stored(_) if self.opcode == Opcode::SaslAuth => b"Authenticated",
+            Response::AuthError(e) => e.inner.as_bytes(),
+            Response::ClientError(e) => e.inner.as_bytes(),
+            Response::ServerError(e) => e.inner.as_bytes(),
+            _ => match status {
+                KEY_NOT_FOUND => b"Not found",
+                KEY_EXISTS => b"Data exists for key.",
+                ITEM_NOT_STORED => b"Not stored.",
+                NON_NUMERIC => b"Non-numeric server-side value for incr or decr",
+                UNKNOWN_COMMAND => b"Unknown command",
+                _ => b"",
+            },
+        };
+
+        Frame {
+            magic: RESPONSE_MAGIC,
+            opcode: self.opcode,
+            status,
+            opaque: self.opaque,
+            cas,
+            extras,
+            key: self.key.as_deref().unwrap_or(b""),
+            value,
+        }
+        .compose(session)
+    }
+
+    fn should_hangup(&self) -> bool {
+        matches!(self.inner.as_ref(), Response::Hangup)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compose(response: BinaryResponse) -> Vec<u8> {
+        let mut buf = Vec::new();
+        let len = response.compose(&mut buf);
+        assert_eq!(len, buf.len());
+        buf
+    }
+
+    #[test]
+    fn compose_hit() {
+        let value = Value::new(b"key", 1, Some(42), b"value");
+        let response = BinaryResponse::new(
+            Opcode::GetK,
+            7,
+            Some(b"key".to_vec().into_boxed_slice()),
+            Response::values(vec![value].into_boxed_slice()),
+        );
+        assert_eq!(response.status(), NO_ERROR);
+
+        let buf = compose(response);
+        assert_eq!(buf.len(), 24 + 4 + 3 + 5);
+        assert_eq!(buf[0], RESPONSE_MAGIC);
+        assert_eq!(buf[1], Opcode::GetK as u8);
+        assert_eq!(&buf[2..4], &[0, 3]);
+        assert_eq!(buf[4], 4);
+        assert_eq!(&buf[6..8], &[0, 0]);
+        assert_eq!(&buf[8..12], &[0, 0, 0, 12]);
+        assert_eq!(&buf[12..16], &[0, 0, 0, 7]);
+        assert_eq!(&buf[16..24], &42_u64.to_be_bytes());
+        assert_eq!(&buf[24..28], &[0, 0, 0, 1]);
+        assert_eq!(&buf[28..31], b"key");
+        assert_eq!(&buf[31..], b"value");
+    }
+
+    #[test]
+    fn compose_quiet() {
+        // quiet get misses are not sent
+        let miss = || Response::values(vec![Value::none(b"key")].into_boxed_slice());
+        assert!(compose(BinaryResponse::new(Opcode::GetQ, 0, None, miss())).is_empty());
+        let buf = compose(BinaryResponse::new(Opcode::Get, 0, None, miss()));
+        assert_eq!(&buf[6..8], &KEY_NOT_FOUND.to_be_bytes());
+        assert_eq!(&buf[24..], b"Not found");
+
+        // quiet stores only send errors
+        let response = BinaryResponse::new(Opcode::SetQ, 0, None, Response::stored(true));
+        assert!(compose(response).is_empty());
+        let response = BinaryResponse::new(Opcode::AddQ, 0, None, Response::not_stored(true));
+        let buf = compose(response);
+        assert_eq!(&buf[6..8], &KEY_EXISTS.to_be_bytes());
+    }
+
+    #[test]
+    fn compose_numeric() {
+        let response =
+            BinaryResponse::new(Opcode::Increment, 0, None, Response::numeric(10, false));
+        let buf = compose(response);
+        assert_eq!(&buf[6..8], &NO_ERROR.to_be_bytes());
+        assert_eq!(&buf[24..], &10_u64.to_be_bytes());
+
+        // meta arithmetic is used to create items on a miss
+        let response = BinaryResponse::new(
+            Opcode::Decrement,
+            0,
+            None,
+            Meta::new(MetaCode::Value).with_data(b"5").into(),
+        );
+        let buf = compose(response);
+        assert_eq!(&buf[24..], &5_u64.to_be_bytes());
+
+        let response = BinaryResponse::new(Opcode::Increment, 0, None, Response::error());
+        assert_eq!(response.status(), NON_NUMERIC);
+    }
+
+    #[test]
+    fn compose_cas() {
+        // the cas value of the modified item is returned on success
+        let response =
+            BinaryResponse::new(Opcode::Set, 0, None, Response::stored(false)).with_cas(42);
+        let buf = compose(response);
+        assert_eq!(&buf[6..8], &NO_ERROR.to_be_bytes());
+        assert_eq!(&buf[16..24], &42_u64.to_be_bytes());
+
+        let response =
+            BinaryResponse::new(Opcode::Increment, 0, None, Response::numeric(10, false))
+                .with_cas(7);
+        let buf = compose(response);
+        assert_eq!(&buf[16..24], &7_u64.to_be_bytes());
+
+        // and is not returned for errors
+        let response =
+            BinaryResponse::new(Opcode::Add, 0, None, Response::not_stored(false)).with_cas(42);
+        let buf = compose(response);
+        assert_eq!(&buf[6..8], &KEY_EXISTS.to_be_bytes());
+        assert_eq!(&buf[16..24], &0_u64.to_be_bytes());
+    }
+
+    #[test]
+    fn compose_auth() {
+        let response = BinaryResponse::new(Opcode::SaslListMechs, 0, None, Response::ok(false));
+        let buf = compose(response);
+        assert_eq!(&buf[6..8], &NO_ERROR.to_be_bytes());
+        assert_eq!(&buf[24..], b"PLAIN");
+
+        // authentication errors are sent even for quiet commands
+        let response = BinaryResponse::new(
+            Opcode::SetQ,
+            0,
+            None,
+            Response::auth_error("unauthenticated"),
+        );
+        assert!(!response.should_hangup());
+        let buf = compose(response);
+        assert_eq!(&buf[6..8], &AUTH_ERROR.to_be_bytes());
+        assert_eq!(&buf[24..], b"unauthenticated");
+    }
+}
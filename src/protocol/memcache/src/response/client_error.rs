@@ -9,6 +9,8 @@ const MSG_PREFIX: &[u8] = b"CLIENT_ERROR ";
 #[derive(Debug, PartialEq, Eq)]
 pub struct ClientError {
     pub(crate) inner: String,
+    // whether the session is closed once the error is sent
+    pub(crate) hangup: bool,
 }
 
 impl ClientError {
@@ -43,6 +45,7 @@ pub fn parse(input: &[u8]) -> IResult<&[u8], ClientError> {
         input,
         ClientError {
             inner: unsafe { std::str::from_utf8_unchecked(string).to_owned() },
+            hangup: true,
         },
     ))
 }
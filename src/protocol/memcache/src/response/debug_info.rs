@@ -0,0 +1,158 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+/// The response to a meta debug request, which has the form:
+/// `ME <key> exp=<ttl> la=<seconds> cas=<cas> fetch=<yes|no> size=<bytes>`
+/// where `la` is omitted if the storage does not track the last access.
+#[derive(Debug, PartialEq, Eq)]
+pub struct DebugInfo {
+    key: Box<[u8]>,
+    base64: bool,
+    exp: i64,
+    la: Option<u32>,
+    cas: u64,
+    fetch: bool,
+    size: usize,
+}
+
+impl DebugInfo {
+    /// Create the response. `exp` is the remaining TTL, or `-1` if the item
+    /// does not expire. `la` is the number of seconds since the item was last
+    /// accessed, if known, and `fetch` indicates if the item has been read
+    /// since it was stored.
+    pub fn new(
+        key: &[u8],
+        base64: bool,
+        exp: i64,
+        la: Option<u32>,
+        cas: u64,
+        fetch: bool,
+        size: usize,
+    ) -> Self {
+        Self {
+            key: key.to_owned().into_boxed_slice(),
+            base64,
+            exp,
+            la,
+            cas,
+            fetch,
+            size,
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+}
+
+impl Compose for DebugInfo {
+    fn compose(&self, session: &mut dyn BufMut) -> usize {
+        let prefix = b"ME ";
+        let key = if self.base64 {
+            base64::encode(&self.key).into_bytes()
+        } else {
+            self.key.to_vec()
+        };
+        let la = match self.la {
+            Some(la) => format!(" la={}", la),
+            None => String::new(),
+        };
+        let fields = format!(
+            " exp={}{} cas={} fetch={} size={}\r\n",
+            self.exp,
+            la,
+            self.cas,
+            if self.fetch { "yes" } else { "no" },
+            self.size
+        )
+        .into_bytes();
+
+        session.put_slice(prefix);
+        session.put_slice(&key);
+        session.put_slice(&fields);
+
+        prefix.len() + key.len() + fields.len()
+    }
+}
+
+pub fn parse(input: &[u8]) -> IResult<&[u8], DebugInfo> {
+    let (input, _) = space1(input)?;
+    let (mut input, key) = take_till(|b| (b == b' ' || b == b'\r'))(input)?;
+
+    let mut response = DebugInfo::new(key, false, -1, None, 0, false, 0);
+
+    loop {
+        let (i, _) = space0(input)?;
+        let (i, field) = take_till(|b| (b == b' ' || b == b'\r'))(i)?;
+
+        if field.is_empty() {
+            input = i;
+            break;
+        }
+
+        let field = std::str::from_utf8(field)
+            .map_err(|_| nom::Err::Failure((input, nom::error::ErrorKind::Tag)))?;
+        let (name, value) = field
+            .split_once('=')
+            .ok_or(nom::Err::Failure((input, nom::error::ErrorKind::Tag)))?;
+
+        let valid = match name {
+            "exp" => value.parse().map(|v| response.exp = v).is_ok(),
+            "la" => value.parse().map(|v| response.la = Some(v)).is_ok(),
+            "cas" => value.parse().map(|v| response.cas = v).is_ok(),
+            "fetch" => {
+                response.fetch = value == "yes";
+                value == "yes" || value == "no"
+            }
+            "size" => value.parse().map(|v| response.size = v).is_ok(),
+            // other fields may be reported by other implementations
+            _ => true,
+        };
+
+        if !valid {
+            return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+        }
+
+        input = i;
+    }
+
+    let (input, _) = crlf(input)?;
+
+    Ok((input, response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            response(b"ME foo exp=-1 la=3 cas=7 fetch=yes cls=1 size=3\r\n"),
+            Ok((
+                &b""[..],
+                Response::DebugInfo(DebugInfo::new(b"foo", false, -1, Some(3), 7, true, 3)),
+            ))
+        );
+
+        assert!(response(b"ME foo fetch=maybe\r\n").is_err());
+    }
+
+    #[test]
+    fn compose() {
+        let info = DebugInfo::new(b"foo", true, 30, Some(0), 1, false, 3);
+        let mut buf = Vec::new();
+        let expected = b"ME Zm9v exp=30 la=0 cas=1 fetch=no size=3\r\n";
+        assert_eq!(info.compose(&mut buf), expected.len());
+        assert_eq!(&buf, expected);
+
+        let info = DebugInfo::new(b"foo", false, -1, None, 1, true, 3);
+        let mut buf = Vec::new();
+        let expected = b"ME foo exp=-1 cas=1 fetch=yes size=3\r\n";
+        assert_eq!(info.compose(&mut buf), expected.len());
+        assert_eq!(&buf, expected);
+    }
+}
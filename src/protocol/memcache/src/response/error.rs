@@ -7,7 +7,10 @@ use super::*;
 const MSG: &[u8] = b"ERROR\r\n";
 
 #[derive(Debug, PartialEq, Eq)]
-pub struct Error {}
+pub struct Error {
+    // whether the session is closed once the error is sent
+    pub(crate) hangup: bool,
+}
 
 impl Default for Error {
     fn default() -> Self {
@@ -17,7 +20,7 @@ impl Default for Error {
 
 impl Error {
     pub fn new() -> Self {
-        Self {}
+        Self { hangup: true }
     }
 
     pub fn is_empty(&self) -> bool {
@@ -39,7 +42,7 @@ impl Compose for Error {
 pub fn parse(input: &[u8]) -> IResult<&[u8], Error> {
     let (input, _) = space0(input)?;
     let (input, _) = crlf(input)?;
-    Ok((input, Error {}))
+    Ok((input, Error::new()))
 }
 
 #[cfg(test)]
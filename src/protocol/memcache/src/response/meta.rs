@@ -0,0 +1,345 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+/// The two character code which begins each meta response.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum MetaCode {
+    /// `VA` - the response includes a value
+    Value,
+    /// `HD` - success, without a value
+    Header,
+    /// `EN` - the item was not found by a meta get
+    Miss,
+    /// `NS` - the item was not stored
+    NotStored,
+    /// `EX` - the CAS value did not match
+    Exists,
+    /// `NF` - the item was not found
+    NotFound,
+    /// `MN` - the response to a meta no-op
+    Noop,
+}
+
+impl MetaCode {
+    fn as_bytes(&self) -> &'static [u8] {
+        match self {
+            Self::Value => b"VA",
+            Self::Header => b"HD",
+            Self::Miss => b"EN",
+            Self::NotStored => b"NS",
+            Self::Exists => b"EX",
+            Self::NotFound => b"NF",
+            Self::Noop => b"MN",
+        }
+    }
+}
+
+/// Flags which may be returned in a meta response.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum MetaFlag {
+    /// `b` - the returned key is base64 encoded
+    Base64,
+    /// `c` - the CAS value of the item
+    Cas(u64),
+    /// `f` - the client flags of the item
+    Flags(u32),
+    /// `h` - whether the item had been fetched before
+    Hit(bool),
+    /// `k` - the key of the item
+    Key(Box<[u8]>),
+    /// `l` - seconds since the item was last accessed
+    LastAccess(u32),
+    /// `O` - the opaque token from the request
+    Opaque(Box<[u8]>),
+    /// `s` - the size of the item value
+    Size(usize),
+    /// `t` - the remaining TTL of the item, `-1` for no expiration
+    Ttl(i64),
+    /// `W` - the client has won the right to recache the item
+    Win,
+    /// `X` - the item is stale
+    Stale,
+    /// `Z` - a win has already been sent for the item
+    AlreadyWon,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Meta {
+    code: MetaCode,
+    data: Option<Box<[u8]>>,
+    flags: Vec<MetaFlag>,
+    noreply: bool,
+}
+
+impl Meta {
+    pub fn new(code: MetaCode) -> Self {
+        Self {
+            code,
+            data: None,
+            flags: Vec::new(),
+            noreply: false,
+        }
+    }
+
+    /// Attach the item value to the response. Only used with `MetaCode::Value`.
+    pub fn with_data(mut self, data: &[u8]) -> Self {
+        self.data = Some(data.to_owned().into_boxed_slice());
+        self
+    }
+
+    /// Add a flag to the response. Flags are returned in the order in which
+    /// they are added.
+    pub fn with_flag(mut self, flag: MetaFlag) -> Self {
+        self.flags.push(flag);
+        self
+    }
+
+    /// Suppress the response, which is used to implement quiet mode.
+    pub fn with_noreply(mut self, noreply: bool) -> Self {
+        self.noreply = noreply;
+        self
+    }
+
+    pub fn code(&self) -> MetaCode {
+        self.code
+    }
+
+    pub fn data(&self) -> Option<&[u8]> {
+        self.data.as_deref()
+    }
+
+    pub fn flags(&self) -> &[MetaFlag] {
+        &self.flags
+    }
+}
+
+impl Compose for Meta {
+    fn compose(&self, session: &mut dyn BufMut) -> usize {
+        if self.noreply {
+            return 0;
+        }
+
+        let mut header = self.code.as_bytes().to_vec();
+
+        if self.code == MetaCode::Value {
+            let _ = write!(header, " {}", self.data().unwrap_or_default().len());
+        }
+
+        let base64 = self.flags.contains(&MetaFlag::Base64);
+
+        for flag in self.flags.iter() {
+            let _ = match flag {
+                MetaFlag::Base64 => write!(header, " b"),
+                MetaFlag::Cas(cas) => write!(header, " c{}", cas),
+                MetaFlag::Flags(flags) => write!(header, " f{}", flags),
+                MetaFlag::Hit(hit) => write!(header, " h{}", *hit as u8),
+                MetaFlag::Key(key) => {
+                    header.extend_from_slice(b" k");
+                    if base64 {
+                        header.extend_from_slice(base64::encode(key).as_bytes());
+                    } else {
+                        header.extend_from_slice(key);
+                    }
+                    Ok(())
+                }
+                MetaFlag::LastAccess(seconds) => write!(header, " l{}", seconds),
+                MetaFlag::Opaque(opaque) => {
+                    header.extend_from_slice(b" O");
+                    header.extend_from_slice(opaque);
+                    Ok(())
+                }
+                MetaFlag::Size(size) => write!(header, " s{}", size),
+                MetaFlag::Ttl(ttl) => write!(header, " t{}", ttl),
+                MetaFlag::Win => write!(header, " W"),
+                MetaFlag::Stale => write!(header, " X"),
+                MetaFlag::AlreadyWon => write!(header, " Z"),
+            };
+        }
+
+        header.extend_from_slice(CRLF);
+        session.put_slice(&header);
+
+        let mut size = header.len();
+
+        if self.code == MetaCode::Value {
+            let data = self.data().unwrap_or_default();
+            session.put_slice(data);
+            session.put_slice(CRLF);
+            size += data.len() + CRLF.len();
+        }
+
+        size
+    }
+}
+
+/// Parses the token which follows a flag as a number.
+fn token<'a, T: std::str::FromStr>(
+    input: &'a [u8],
+    token: &[u8],
+) -> Result<T, nom::Err<(&'a [u8], ErrorKind)>> {
+    std::str::from_utf8(token)
+        .ok()
+        .and_then(|t| t.parse::<T>().ok())
+        .ok_or(nom::Err::Failure((input, nom::error::ErrorKind::Tag)))
+}
+
+pub fn parse(input: &[u8], code: MetaCode) -> IResult<&[u8], Meta> {
+    let mut response = Meta::new(code);
+    let mut input = input;
+
+    let mut bytes = 0;
+    if code == MetaCode::Value {
+        let (i, _) = space1(input)?;
+        let (i, b) = parse_usize(i)?;
+        input = i;
+        bytes = b;
+    }
+
+    loop {
+        let (i, _) = space0(input)?;
+        let (i, t) = take_till(|b| (b == b' ' || b == b'\r'))(i)?;
+
+        if t.is_empty() {
+            input = i;
+            break;
+        }
+
+        let flag = match (t[0], &t[1..]) {
+            (b'b', b"") => MetaFlag::Base64,
+            (b'c', t) => MetaFlag::Cas(token(input, t)?),
+            (b'f', t) => MetaFlag::Flags(token(input, t)?),
+            (b'h', b"0") => MetaFlag::Hit(false),
+            (b'h', b"1") => MetaFlag::Hit(true),
+            (b'k', t) => MetaFlag::Key(t.to_owned().into_boxed_slice()),
+            (b'l', t) => MetaFlag::LastAccess(token(input, t)?),
+            (b'O', t) => MetaFlag::Opaque(t.to_owned().into_boxed_slice()),
+            (b's', t) => MetaFlag::Size(token(input, t)?),
+            (b't', t) => MetaFlag::Ttl(token(input, t)?),
+            (b'W', b"") => MetaFlag::Win,
+            (b'X', b"") => MetaFlag::Stale,
+            (b'Z', b"") => MetaFlag::AlreadyWon,
+            _ => {
+                return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+            }
+        };
+        response = response.with_flag(flag);
+
+        input = i;
+    }
+
+    let (mut input, _) = crlf(input)?;
+
+    // keys are returned encoded when the base64 flag is present
+    if response.flags.contains(&MetaFlag::Base64) {
+        for flag in response.flags.iter_mut() {
+            if let MetaFlag::Key(key) = flag {
+                *key = base64::decode(&key)
+                    .map_err(|_| nom::Err::Failure((input, nom::error::ErrorKind::Tag)))?
+                    .into_boxed_slice();
+            }
+        }
+    }
+
+    if code == MetaCode::Value {
+        let (i, data) = take(bytes)(input)?;
+        let (i, _) = crlf(i)?;
+        response = response.with_data(data);
+        input = i;
+    }
+
+    Ok((input, response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        // value with flags
+        assert_eq!(
+            response(b"VA 1 c42 t-1 f7\r\n0\r\n"),
+            Ok((
+                &b""[..],
+                Response::Meta(
+                    Meta::new(MetaCode::Value)
+                        .with_flag(MetaFlag::Cas(42))
+                        .with_flag(MetaFlag::Ttl(-1))
+                        .with_flag(MetaFlag::Flags(7))
+                        .with_data(b"0")
+                ),
+            ))
+        );
+
+        // header only
+        assert_eq!(
+            response(b"HD\r\n"),
+            Ok((&b""[..], Response::Meta(Meta::new(MetaCode::Header))))
+        );
+
+        // miss with a win token
+        assert_eq!(
+            response(b"EN W\r\n"),
+            Ok((
+                &b""[..],
+                Response::Meta(Meta::new(MetaCode::Miss).with_flag(MetaFlag::Win))
+            ))
+        );
+
+        // base64 encoded key
+        assert_eq!(
+            response(b"HD b kZm9v Oabc\r\n"),
+            Ok((
+                &b""[..],
+                Response::Meta(
+                    Meta::new(MetaCode::Header)
+                        .with_flag(MetaFlag::Base64)
+                        .with_flag(MetaFlag::Key(b"foo".to_vec().into_boxed_slice()))
+                        .with_flag(MetaFlag::Opaque(b"abc".to_vec().into_boxed_slice()))
+                ),
+            ))
+        );
+
+        // other codes
+        for (input, code) in [
+            (&b"NS\r\n"[..], MetaCode::NotStored),
+            (&b"EX\r\n"[..], MetaCode::Exists),
+            (&b"NF\r\n"[..], MetaCode::NotFound),
+            (&b"MN\r\n"[..], MetaCode::Noop),
+        ] {
+            assert_eq!(
+                response(input),
+                Ok((&b""[..], Response::Meta(Meta::new(code))))
+            );
+        }
+
+        // unknown flag
+        assert!(response(b"HD Q\r\n").is_err());
+
+        // value is incomplete
+        assert!(response(b"VA 2\r\n0").unwrap_err().is_incomplete());
+    }
+
+    #[test]
+    fn compose() {
+        let meta = Meta::new(MetaCode::Value)
+            .with_flag(MetaFlag::Hit(true))
+            .with_flag(MetaFlag::Base64)
+            .with_flag(MetaFlag::Key(b"foo".to_vec().into_boxed_slice()))
+            .with_flag(MetaFlag::Stale)
+            .with_data(b"bar");
+        let mut buf = Vec::new();
+        let expected = b"VA 3 h1 b kZm9v X\r\nbar\r\n";
+        assert_eq!(meta.compose(&mut buf), expected.len());
+        assert_eq!(&buf, expected);
+
+        // quiet mode suppresses the response
+        let meta = Meta::new(MetaCode::Header).with_noreply(true);
+        let mut buf = Vec::new();
+        assert_eq!(meta.compose(&mut buf), 0);
+        assert!(buf.is_empty());
+    }
+}
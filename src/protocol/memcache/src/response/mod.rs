@@ -5,27 +5,41 @@
 use crate::*;
 use protocol_common::{BufMut, Parse, ParseOk};
 
+mod auth_error;
+mod binary;
 mod client_error;
+mod debug_info;
 mod deleted;
 mod error;
 mod exists;
+mod meta;
 mod not_found;
 mod not_stored;
 mod numeric;
+mod okay;
 mod server_error;
+mod stats_info;
 mod stored;
 mod values;
+mod version_info;
 
+pub use auth_error::AuthError;
+pub use binary::BinaryResponse;
 pub use client_error::ClientError;
+pub use debug_info::DebugInfo;
 pub use deleted::Deleted;
 pub use error::Error;
 pub use exists::Exists;
+pub use meta::{Meta, MetaCode, MetaFlag};
 pub use not_found::NotFound;
 pub use not_stored::NotStored;
 pub use numeric::Numeric;
+pub use okay::Okay;
 pub use server_error::ServerError;
+pub use stats_info::StatsInfo;
 pub use stored::Stored;
 pub use values::{Value, Values};
+pub use version_info::VersionInfo;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum Response {
@@ -39,17 +53,41 @@ pub enum Response {
     Values(Values),
     Numeric(Numeric),
     Deleted(Deleted),
+    Meta(Meta),
+    DebugInfo(DebugInfo),
+    VersionInfo(VersionInfo),
+    StatsInfo(StatsInfo),
+    Okay(Okay),
+    AuthError(AuthError),
+    Binary(BinaryResponse),
     Hangup,
 }
 
 impl Response {
     pub fn error() -> Self {
-        Self::Error(Error {})
+        Self::Error(Error::new())
     }
 
     pub fn client_error<T: ToString>(string: T) -> Self {
         Self::ClientError(ClientError {
             inner: string.to_string(),
+            hangup: true,
+        })
+    }
+
+    /// An `ERROR` for a request which the parser skipped over. Unlike
+    /// `error()`, the session is kept open.
+    pub fn unknown_command() -> Self {
+        Self::Error(Error { hangup: false })
+    }
+
+    /// A `CLIENT_ERROR` for a request which the parser skipped over, or which
+    /// could not be applied to the stored item. Unlike `client_error()`, the
+    /// session is kept open.
+    pub fn bad_request<T: ToString>(reason: T) -> Self {
+        Self::ClientError(ClientError {
+            inner: reason.to_string(),
+            hangup: false,
         })
     }
 
@@ -90,6 +128,20 @@ impl Response {
     pub fn deleted(noreply: bool) -> Self {
         Self::Deleted(Deleted::new(noreply))
     }
+
+    pub fn version<T: ToString>(version: T) -> Self {
+        Self::VersionInfo(VersionInfo::new(version))
+    }
+
+    pub fn ok(noreply: bool) -> Self {
+        Self::Okay(Okay::new(noreply))
+    }
+
+    pub fn auth_error<T: ToString>(string: T) -> Self {
+        Self::AuthError(AuthError {
+            inner: string.to_string(),
+        })
+    }
 }
 
 impl From<Values> for Response {
@@ -98,6 +150,24 @@ impl From<Values> for Response {
     }
 }
 
+impl From<Meta> for Response {
+    fn from(other: Meta) -> Self {
+        Self::Meta(other)
+    }
+}
+
+impl From<DebugInfo> for Response {
+    fn from(other: DebugInfo) -> Self {
+        Self::DebugInfo(other)
+    }
+}
+
+impl From<StatsInfo> for Response {
+    fn from(other: StatsInfo) -> Self {
+        Self::StatsInfo(other)
+    }
+}
+
 impl Compose for Response {
     fn compose(&self, session: &mut dyn BufMut) -> usize {
         match self {
@@ -111,12 +181,25 @@ impl Compose for Response {
             Self::Values(e) => e.compose(session),
             Self::Numeric(e) => e.compose(session),
             Self::Deleted(e) => e.compose(session),
+            Self::Meta(e) => e.compose(session),
+            Self::DebugInfo(e) => e.compose(session),
+            Self::VersionInfo(e) => e.compose(session),
+            Self::StatsInfo(e) => e.compose(session),
+            Self::Okay(e) => e.compose(session),
+            Self::AuthError(e) => e.compose(session),
+            Self::Binary(e) => e.compose(session),
             Self::Hangup => 0,
         }
     }
 
     fn should_hangup(&self) -> bool {
-        matches!(self, Self::Error(_) | Self::ClientError(_) | Self::Hangup)
+        match self {
+            // errors are reported with a status code in the binary protocol
+            Self::Binary(e) => e.should_hangup(),
+            Self::Error(e) => e.hangup,
+            Self::ClientError(e) => e.hangup,
+            _ => matches!(self, Self::AuthError(_) | Self::Hangup),
+        }
     }
 }
 
@@ -133,6 +216,10 @@ pub enum ResponseType {
     Empty,
     Numeric(u64),
     Deleted,
+    Meta(MetaCode),
+    DebugInfo,
+    StatsInfo,
+    Okay,
 }
 
 pub struct ResponseParser {}
@@ -150,6 +237,16 @@ pub(crate) fn response_type(input: &[u8]) -> IResult<&[u8], ResponseType> {
         b"VALUE" => ResponseType::Values,
         b"END" => ResponseType::Empty,
         b"DELETED" => ResponseType::Deleted,
+        b"VA" => ResponseType::Meta(MetaCode::Value),
+        b"HD" => ResponseType::Meta(MetaCode::Header),
+        b"EN" => ResponseType::Meta(MetaCode::Miss),
+        b"NS" => ResponseType::Meta(MetaCode::NotStored),
+        b"EX" => ResponseType::Meta(MetaCode::Exists),
+        b"NF" => ResponseType::Meta(MetaCode::NotFound),
+        b"MN" => ResponseType::Meta(MetaCode::Noop),
+        b"ME" => ResponseType::DebugInfo,
+        b"STAT" => ResponseType::StatsInfo,
+        b"OK" => ResponseType::Okay,
         _ => {
             if let Ok(s) = std::str::from_utf8(response_type_token) {
                 if let Ok(value) = s.parse::<u64>() {
@@ -219,6 +316,22 @@ pub(crate) fn response(input: &[u8]) -> IResult<&[u8], Response> {
             let (input, response) = deleted::parse(input)?;
             Ok((input, Response::Deleted(response)))
         }
+        (input, ResponseType::Meta(code)) => {
+            let (input, response) = meta::parse(input, code)?;
+            Ok((input, Response::Meta(response)))
+        }
+        (input, ResponseType::DebugInfo) => {
+            let (input, response) = debug_info::parse(input)?;
+            Ok((input, Response::DebugInfo(response)))
+        }
+        (input, ResponseType::StatsInfo) => {
+            let (input, response) = stats_info::parse(input)?;
+            Ok((input, Response::StatsInfo(response)))
+        }
+        (input, ResponseType::Okay) => {
+            let (input, response) = okay::parse(input)?;
+            Ok((input, Response::Okay(response)))
+        }
     }
 }
 
@@ -15,6 +15,10 @@ impl Numeric {
         Self { value, noreply }
     }
 
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
@@ -0,0 +1,59 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+const MSG: &[u8] = b"OK\r\n";
+
+/// The generic success response for commands which have no specific result,
+/// such as `verbosity`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Okay {
+    noreply: bool,
+}
+
+impl Okay {
+    pub fn new(noreply: bool) -> Self {
+        Self { noreply }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn len(&self) -> usize {
+        if self.noreply {
+            0
+        } else {
+            MSG.len()
+        }
+    }
+}
+
+impl Compose for Okay {
+    fn compose(&self, session: &mut dyn BufMut) -> usize {
+        if !self.noreply {
+            session.put_slice(MSG);
+            MSG.len()
+        } else {
+            0
+        }
+    }
+}
+
+pub fn parse(input: &[u8]) -> IResult<&[u8], Okay> {
+    let (input, _) = space0(input)?;
+    let (input, _) = crlf(input)?;
+    Ok((input, Okay { noreply: false }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(response(b"OK\r\n"), Ok((&b""[..], Response::ok(false))));
+    }
+}
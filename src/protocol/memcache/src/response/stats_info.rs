@@ -0,0 +1,192 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const STAT: &[u8] = b"STAT ";
+const END: &[u8] = b"END\r\n";
+
+static PERCENTILES: &[(&str, f64)] = &[
+    ("p25", 25.0),
+    ("p50", 50.0),
+    ("p75", 75.0),
+    ("p90", 90.0),
+    ("p99", 99.0),
+    ("p999", 99.9),
+    ("p9999", 99.99),
+];
+
+/// The response to a stats request, which is a list of named values that are
+/// sent as `STAT <name> <value>` lines and terminated by `END`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct StatsInfo {
+    pub(crate) stats: Vec<(String, String)>,
+}
+
+impl StatsInfo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The general server statistics. These begin with the server time and
+    /// version, which are followed by all registered metrics in name order.
+    /// Heatmaps are reported as a set of percentiles.
+    pub fn general<T: ToString>(version: T) -> Self {
+        let mut stats = Self::new();
+
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        stats.push("time", time);
+        stats.push("version", version);
+
+        let mut metrics = Vec::new();
+        for metric in &rustcommon_metrics::metrics() {
+            let any = match metric.as_any() {
+                Some(any) => any,
+                None => {
+                    continue;
+                }
+            };
+
+            if let Some(counter) = any.downcast_ref::<Counter>() {
+                metrics.push((metric.name().to_string(), counter.value().to_string()));
+            } else if let Some(gauge) = any.downcast_ref::<Gauge>() {
+                metrics.push((metric.name().to_string(), gauge.value().to_string()));
+            } else if let Some(heatmap) = any.downcast_ref::<Heatmap>() {
+                for (label, value) in PERCENTILES {
+                    let percentile = heatmap.percentile(*value).map(|b| b.high()).unwrap_or(0);
+                    metrics.push((
+                        format!("{}_{}", metric.name(), label),
+                        percentile.to_string(),
+                    ));
+                }
+            }
+        }
+        metrics.sort();
+        stats.stats.extend(metrics);
+
+        stats
+    }
+
+    /// Append a stat to the response.
+    pub fn push<N: ToString, V: ToString>(&mut self, name: N, value: V) {
+        self.stats.push((name.to_string(), value.to_string()));
+    }
+
+    /// Returns the value for the stat with the provided name.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.stats
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn stats(&self) -> &[(String, String)] {
+        &self.stats
+    }
+}
+
+impl Compose for StatsInfo {
+    fn compose(&self, session: &mut dyn BufMut) -> usize {
+        let mut size = 0;
+
+        for (name, value) in &self.stats {
+            session.put_slice(STAT);
+            session.put_slice(name.as_bytes());
+            session.put_slice(b" ");
+            session.put_slice(value.as_bytes());
+            session.put_slice(CRLF);
+            size += STAT.len() + name.len() + 1 + value.len() + CRLF.len();
+        }
+
+        session.put_slice(END);
+        size + END.len()
+    }
+}
+
+// this is to be called after parsing the first STAT token, so we do not match
+// the verb for the first line
+pub fn parse(input: &[u8]) -> IResult<&[u8], StatsInfo> {
+    let mut stats = StatsInfo::new();
+    let mut input = input;
+
+    loop {
+        let (i, _) = space1(input)?;
+        let (i, name) = take_till(|b| (b == b' ' || b == b'\r'))(i)?;
+        let (i, _) = space1(i)?;
+        let (i, value) = take_till(|b| b == b'\r')(i)?;
+        let (i, _) = crlf(i)?;
+
+        stats.push(
+            String::from_utf8_lossy(name),
+            String::from_utf8_lossy(value).trim_end(),
+        );
+
+        // each line is either another stat or the end of the response
+        let (i, token) = take_till(|b| (b == b' ' || b == b'\r'))(i)?;
+        match token {
+            b"STAT" => {
+                input = i;
+            }
+            b"END" => {
+                let (i, _) = crlf(i)?;
+                return Ok((i, stats));
+            }
+            _ => {
+                return Err(nom::Err::Failure((i, nom::error::ErrorKind::Tag)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compose() {
+        let mut stats = StatsInfo::new();
+        stats.push("curr_items", 42);
+        stats.push("version", "0.1.0");
+
+        let mut buf = Vec::new();
+        let len = Response::from(stats).compose(&mut buf);
+        assert_eq!(len, buf.len());
+        assert_eq!(buf, b"STAT curr_items 42\r\nSTAT version 0.1.0\r\nEND\r\n");
+
+        // an empty response is just the terminator
+        let mut buf = Vec::new();
+        Response::from(StatsInfo::new()).compose(&mut buf);
+        assert_eq!(buf, b"END\r\n");
+    }
+
+    #[test]
+    fn general() {
+        GET.increment();
+
+        let stats = StatsInfo::general("1.2.3");
+        assert_eq!(stats.stats()[0].0, "time");
+        assert_eq!(stats.get("version"), Some("1.2.3"));
+        assert!(stats.get("get").is_some());
+        assert!(stats.get("get_cardinality_p99").is_some());
+    }
+
+    #[test]
+    fn parse() {
+        let mut stats = StatsInfo::new();
+        stats.push("pid", 1);
+        stats.push("uptime", 10);
+
+        assert_eq!(
+            response(b"STAT pid 1\r\nSTAT uptime 10\r\nEND\r\n"),
+            Ok((&b""[..], Response::from(stats)))
+        );
+
+        // incomplete
+        assert!(response(b"STAT pid 1\r\nSTAT").is_err());
+    }
+}
@@ -50,6 +50,18 @@ impl Value {
         &self.key
     }
 
+    pub fn flags(&self) -> u32 {
+        self.flags
+    }
+
+    pub fn cas(&self) -> Option<u64> {
+        self.cas
+    }
+
+    pub fn data(&self) -> Option<&[u8]> {
+        self.data.as_deref()
+    }
+
     #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> Option<usize> {
         self.data.as_ref().map(|v| v.len())
@@ -0,0 +1,37 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+const MSG_PREFIX: &[u8] = b"VERSION ";
+
+/// The response to a version request.
+#[derive(Debug, PartialEq, Eq)]
+pub struct VersionInfo {
+    pub(crate) inner: String,
+}
+
+impl VersionInfo {
+    pub fn new<T: ToString>(version: T) -> Self {
+        Self {
+            inner: version.to_string(),
+        }
+    }
+
+    pub fn version(&self) -> &str {
+        &self.inner
+    }
+}
+
+impl Compose for VersionInfo {
+    fn compose(&self, session: &mut dyn BufMut) -> usize {
+        let msg = self.inner.as_bytes();
+
+        session.put_slice(MSG_PREFIX);
+        session.put_slice(msg);
+        session.put_slice(CRLF);
+
+        MSG_PREFIX.len() + msg.len() + CRLF.len()
+    }
+}
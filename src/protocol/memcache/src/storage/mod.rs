@@ -14,8 +14,17 @@ pub trait Storage {
     fn get(&mut self, request: &Get) -> Response;
     fn gets(&mut self, request: &Gets) -> Response;
     fn incr(&mut self, request: &Incr) -> Response;
+    fn meta_arithmetic(&mut self, request: &MetaArithmetic) -> Response;
+    fn meta_debug(&mut self, request: &MetaDebug) -> Response;
+    fn meta_delete(&mut self, request: &MetaDelete) -> Response;
+    fn meta_get(&mut self, request: &MetaGet) -> Response;
+    fn meta_noop(&mut self, request: &MetaNoop) -> Response;
+    fn meta_set(&mut self, request: &MetaSet) -> Response;
     fn prepend(&mut self, request: &Prepend) -> Response;
     fn quit(&mut self, request: &Quit) -> Response;
     fn replace(&mut self, request: &Replace) -> Response;
     fn set(&mut self, request: &Set) -> Response;
+    fn stats(&mut self, request: &Stats) -> Response;
+    fn verbosity(&mut self, request: &Verbosity) -> Response;
+    fn version(&mut self, request: &Version) -> Response;
 }
@@ -14,6 +14,7 @@ mod test;
 use crate::Response;
 pub use keyword::Keyword;
 use logger::Klog;
+use protocol_common::{Correlate, ErrorResponse};
 
 pub use parse::Parser as RequestParser;
 
@@ -23,6 +24,18 @@ pub enum Request {
     Ping,
 }
 
+// ping has no ids, so requests are never pipelined
+impl Correlate for Request {}
+
+// ping has no error reply, so the connection is closed instead
+impl ErrorResponse for Request {
+    type Response = Response;
+
+    fn error_response(&self, _message: &str) -> Response {
+        Response::Hangup
+    }
+}
+
 impl Klog for Request {
     type Response = Response;
 
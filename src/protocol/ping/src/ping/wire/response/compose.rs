@@ -13,6 +13,11 @@ impl Compose for Response {
                 dst.put_slice(b"PONG\r\n");
                 6
             }
+            Self::Hangup => 0,
         }
     }
+
+    fn should_hangup(&self) -> bool {
+        matches!(self, Self::Hangup)
+    }
 }
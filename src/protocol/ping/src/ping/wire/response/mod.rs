@@ -14,7 +14,14 @@ mod test;
 
 pub use parse::Parser as ResponseParser;
 
+use protocol_common::Correlate;
+
 /// A collection of all possible `Ping` responses
 pub enum Response {
     Pong,
+    /// Closes the connection without replying. The protocol has no error
+    /// reply, so this is returned for a request which could not be served.
+    Hangup,
 }
+
+impl Correlate for Response {}
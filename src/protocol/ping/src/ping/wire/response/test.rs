@@ -15,6 +15,19 @@ fn ping() {
     assert!(parser.parse(b"PONG\r\n").is_ok());
 }
 
+#[test]
+fn hangup() {
+    let mut buffer = Vec::new();
+    assert_eq!(Response::Pong.compose(&mut buffer), 6);
+    assert!(!Response::Pong.should_hangup());
+
+    // a request which can not be served closes the connection without a reply
+    let response = Request::Ping.error_response("failed");
+    assert!(response.should_hangup());
+    assert_eq!(response.compose(&mut buffer), 0);
+    assert_eq!(buffer, b"PONG\r\n");
+}
+
 #[test]
 fn incomplete() {
     let parser = ResponseParser::new();
@@ -2,6 +2,9 @@
 // Licensed under the Apache License, Version 2.0
 // http://www.apache.org/licenses/LICENSE-2.0
 
+#[macro_use]
+extern crate logger;
+
 mod message;
 mod request;
 mod response;
@@ -12,4 +15,53 @@ pub(crate) use util::*;
 pub use request::*;
 pub use response::*;
 
+use rustcommon_metrics::*;
+
+// get, set, append, incr, decr, and ping have no counters here, as those metric
+// names are already used by the memcache and ping protocols
+counter!(BADD, "number of badd requests");
+counter!(BCARD, "number of bcard requests");
+counter!(BDEL, "number of bdel requests");
+counter!(BGET, "number of bget requests");
+counter!(BRANGE, "number of brange requests");
+counter!(DECRBY, "number of decrby requests");
+counter!(DEL, "number of del requests");
+counter!(DISCARD, "number of discard requests");
+counter!(ECHO, "number of echo requests");
+counter!(EXEC, "number of exec requests");
+counter!(EXISTS, "number of exists requests");
+counter!(EXPIRE, "number of expire requests");
+counter!(FLUSHDB, "number of flushdb requests");
+counter!(GETSET, "number of getset requests");
+counter!(HDEL, "number of hdel requests");
+counter!(HELLO, "number of hello requests");
+counter!(HEXISTS, "number of hexists requests");
+counter!(HGET, "number of hget requests");
+counter!(HGETALL, "number of hgetall requests");
+counter!(HINCRBY, "number of hincrby requests");
+counter!(HLEN, "number of hlen requests");
+counter!(HMGET, "number of hmget requests");
+counter!(HSET, "number of hset requests");
+counter!(INCRBY, "number of incrby requests");
+counter!(LINDEX, "number of lindex requests");
+counter!(LLEN, "number of llen requests");
+counter!(LPOP, "number of lpop requests");
+counter!(LPUSH, "number of lpush requests");
+counter!(LRANGE, "number of lrange requests");
+counter!(LTRIM, "number of ltrim requests");
+counter!(MGET, "number of mget requests");
+counter!(MSET, "number of mset requests");
+counter!(MULTI, "number of multi requests");
+counter!(PERSIST, "number of persist requests");
+counter!(PEXPIRE, "number of pexpire requests");
+counter!(PTTL, "number of pttl requests");
+counter!(RPOP, "number of rpop requests");
+counter!(RPUSH, "number of rpush requests");
+counter!(SETNX, "number of setnx requests");
+counter!(STRLEN, "number of strlen requests");
+counter!(TTL, "number of ttl requests");
+counter!(TYPE, "number of type requests");
+counter!(UNWATCH, "number of unwatch requests");
+counter!(WATCH, "number of watch requests");
+
 common::metrics::test_no_duplicates!();
@@ -14,14 +14,12 @@ impl Compose for Array {
     fn compose(&self, session: &mut dyn BufMut) -> usize {
         let mut len = 0;
         if let Some(values) = &self.inner {
-            let header = format!("${}\r\n", values.len());
+            let header = format!("*{}\r\n", values.len());
             session.put_slice(header.as_bytes());
             len += header.as_bytes().len();
             for value in values {
                 len += value.compose(session);
             }
-            session.put_slice(b"\r\n");
-            len += 2;
         } else {
             session.put_slice(b"*-1\r\n");
             len += 5;
@@ -88,4 +86,16 @@ mod tests {
             Ok((&b""[..], Message::bulk_string("HELLO WORLD".as_bytes())))
         );
     }
+
+    #[test]
+    fn compose() {
+        let mut buf = Vec::new();
+        let message = Message::array(vec![Message::bulk_string(b"a"), Message::integer(1)]);
+        assert_eq!(message.compose(&mut buf), 15);
+        assert_eq!(buf, b"*2\r\n$1\r\na\r\n:1\r\n");
+
+        let mut buf = Vec::new();
+        assert_eq!(Message::null_array().compose(&mut buf), 5);
+        assert_eq!(buf, b"*-1\r\n");
+    }
 }
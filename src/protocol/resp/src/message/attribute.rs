@@ -0,0 +1,64 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+/// RESP3 auxiliary key-value pairs which describe the reply that follows them.
+/// The attributes and the reply are kept together as a single message.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Attribute {
+    pub(crate) attributes: Vec<(Message, Message)>,
+    pub(crate) inner: Box<Message>,
+}
+
+impl Compose for Attribute {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        compose_pairs(buf, b'|', &self.attributes) + self.inner.compose(buf)
+    }
+}
+
+pub fn parse(input: &[u8]) -> IResult<&[u8], Attribute> {
+    let (input, attributes) = pairs(input)?;
+    let (input, inner) = message(input)?;
+    Ok((
+        input,
+        Attribute {
+            attributes,
+            inner: Box::new(inner),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            message(b"|1\r\n+ttl\r\n:3600\r\n$5\r\nvalue\r\n"),
+            Ok((
+                &b""[..],
+                Message::attribute(
+                    vec![(Message::simple_string("ttl"), Message::integer(3600))],
+                    Message::bulk_string(b"value")
+                )
+            ))
+        );
+
+        // the reply must follow the attributes
+        assert!(message(b"|1\r\n+ttl\r\n:3600\r\n").is_err());
+    }
+
+    #[test]
+    fn compose() {
+        let mut buf = Vec::new();
+        let message = Message::attribute(
+            vec![(Message::simple_string("a"), Message::integer(1))],
+            Message::resp3_null(),
+        );
+        assert_eq!(message.compose(&mut buf), 15);
+        assert_eq!(buf, b"|1\r\n+a\r\n:1\r\n_\r\n");
+    }
+}
@@ -0,0 +1,70 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+/// A RESP3 integer which is outside the range of a 64bit integer. The value
+/// is kept as its decimal representation.
+#[derive(Debug, PartialEq, Eq)]
+pub struct BigNumber {
+    pub(crate) inner: String,
+}
+
+impl Compose for BigNumber {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        buf.put_slice(b"(");
+        buf.put_slice(self.inner.as_bytes());
+        buf.put_slice(b"\r\n");
+        self.inner.as_bytes().len() + 3
+    }
+}
+
+pub fn parse(input: &[u8]) -> IResult<&[u8], BigNumber> {
+    let (input, string) = not_line_ending(input)?;
+    let (input, _) = crlf(input)?;
+
+    let digits = match string.first() {
+        Some(b'-') | Some(b'+') => &string[1..],
+        _ => string,
+    };
+    if digits.is_empty() || !digits.iter().all(|b| b.is_ascii_digit()) {
+        return Err(nom::Err::Failure((input, nom::error::ErrorKind::Digit)));
+    }
+
+    Ok((
+        input,
+        BigNumber {
+            inner: unsafe { std::str::from_utf8_unchecked(string).to_owned() },
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            message(b"(3492890328409238509324850943850943825024385\r\n"),
+            Ok((
+                &b""[..],
+                Message::big_number("3492890328409238509324850943850943825024385")
+            ))
+        );
+        assert_eq!(
+            message(b"(-1\r\n"),
+            Ok((&b""[..], Message::big_number("-1")))
+        );
+        assert!(message(b"(12a\r\n").is_err());
+        assert!(message(b"(-\r\n").is_err());
+    }
+
+    #[test]
+    fn compose() {
+        let mut buf = Vec::new();
+        assert_eq!(Message::big_number("-123").compose(&mut buf), 7);
+        assert_eq!(buf, b"(-123\r\n");
+    }
+}
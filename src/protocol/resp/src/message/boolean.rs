@@ -0,0 +1,54 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Boolean {
+    pub(crate) inner: bool,
+}
+
+impl Compose for Boolean {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        if self.inner {
+            buf.put_slice(b"#t\r\n");
+        } else {
+            buf.put_slice(b"#f\r\n");
+        }
+        4
+    }
+}
+
+pub fn parse(input: &[u8]) -> IResult<&[u8], Boolean> {
+    let (input, value) = take(1usize)(input)?;
+    let value = match value {
+        b"t" => true,
+        b"f" => false,
+        _ => {
+            return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+        }
+    };
+    let (input, _) = crlf(input)?;
+    Ok((input, Boolean { inner: value }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(message(b"#t\r\n"), Ok((&b""[..], Message::boolean(true))));
+        assert_eq!(message(b"#f\r\n"), Ok((&b""[..], Message::boolean(false))));
+        assert!(message(b"#x\r\n").is_err());
+    }
+
+    #[test]
+    fn compose() {
+        let mut buf = Vec::new();
+        assert_eq!(Message::boolean(true).compose(&mut buf), 4);
+        assert_eq!(Message::boolean(false).compose(&mut buf), 4);
+        assert_eq!(buf, b"#t\r\n#f\r\n");
+    }
+}
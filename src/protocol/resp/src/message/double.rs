@@ -0,0 +1,95 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+/// A RESP3 floating point number. Values are compared by their bit pattern so
+/// that messages can still be `Eq`.
+#[derive(Debug)]
+pub struct Double {
+    pub(crate) inner: f64,
+}
+
+impl PartialEq for Double {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner.to_bits() == other.inner.to_bits()
+    }
+}
+
+impl Eq for Double {}
+
+impl Compose for Double {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let data = if self.inner.is_nan() {
+            ",nan\r\n".to_string()
+        } else if self.inner.is_infinite() {
+            if self.inner.is_sign_positive() {
+                ",inf\r\n".to_string()
+            } else {
+                ",-inf\r\n".to_string()
+            }
+        } else {
+            // the debug format is the shortest representation which round
+            // trips, and switches to exponents for very large or small values
+            format!(",{:?}\r\n", self.inner)
+        };
+        buf.put_slice(data.as_bytes());
+        data.as_bytes().len()
+    }
+}
+
+pub fn parse(input: &[u8]) -> IResult<&[u8], Double> {
+    let (input, string) = not_line_ending(input)?;
+    let (input, _) = crlf(input)?;
+
+    let value = std::str::from_utf8(string)
+        .ok()
+        .and_then(|s| s.parse::<f64>().ok())
+        .ok_or(nom::Err::Failure((input, nom::error::ErrorKind::Tag)))?;
+    Ok((input, Double { inner: value }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(message(b",1.5\r\n"), Ok((&b""[..], Message::double(1.5))));
+        assert_eq!(message(b",-2\r\n"), Ok((&b""[..], Message::double(-2.0))));
+        assert_eq!(
+            message(b",1.23e-42\r\n"),
+            Ok((&b""[..], Message::double(1.23e-42)))
+        );
+        assert_eq!(
+            message(b",inf\r\n"),
+            Ok((&b""[..], Message::double(f64::INFINITY)))
+        );
+        assert_eq!(
+            message(b",-inf\r\n"),
+            Ok((&b""[..], Message::double(f64::NEG_INFINITY)))
+        );
+        assert_eq!(
+            message(b",nan\r\n"),
+            Ok((&b""[..], Message::double(f64::NAN)))
+        );
+        assert!(message(b",abc\r\n").is_err());
+    }
+
+    #[test]
+    fn compose() {
+        for (value, expected) in [
+            (1.5, &b",1.5\r\n"[..]),
+            (10.0, b",10.0\r\n"),
+            (1e300, b",1e300\r\n"),
+            (f64::INFINITY, b",inf\r\n"),
+            (f64::NEG_INFINITY, b",-inf\r\n"),
+            (f64::NAN, b",nan\r\n"),
+        ] {
+            let mut buf = Vec::new();
+            assert_eq!(Message::double(value).compose(&mut buf), expected.len());
+            assert_eq!(buf, expected);
+        }
+    }
+}
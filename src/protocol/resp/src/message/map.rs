@@ -0,0 +1,51 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+/// A RESP3 sequence of key-value pairs. The order of the pairs is preserved.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Map {
+    pub(crate) inner: Vec<(Message, Message)>,
+}
+
+impl Compose for Map {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        compose_pairs(buf, b'%', &self.inner)
+    }
+}
+
+pub fn parse(input: &[u8]) -> IResult<&[u8], Map> {
+    let (input, inner) = pairs(input)?;
+    Ok((input, Map { inner }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            message(b"%2\r\n+first\r\n:1\r\n+second\r\n:2\r\n"),
+            Ok((
+                &b""[..],
+                Message::map(vec![
+                    (Message::simple_string("first"), Message::integer(1)),
+                    (Message::simple_string("second"), Message::integer(2)),
+                ])
+            ))
+        );
+        assert_eq!(message(b"%0\r\n"), Ok((&b""[..], Message::map(vec![]))));
+        assert!(message(b"%1\r\n+first\r\n").is_err());
+    }
+
+    #[test]
+    fn compose() {
+        let mut buf = Vec::new();
+        let message = Message::map(vec![(Message::bulk_string(b"a"), Message::boolean(true))]);
+        assert_eq!(message.compose(&mut buf), 15);
+        assert_eq!(buf, b"%1\r\n$1\r\na\r\n#t\r\n");
+    }
+}
@@ -6,17 +6,38 @@ use crate::*;
 use protocol_common::*;
 
 mod array;
+mod attribute;
+mod big_number;
+mod boolean;
 mod bulk_string;
+mod double;
 mod error;
 mod integer;
+mod map;
+mod null;
+mod push;
+mod set;
 mod simple_string;
+mod verbatim_string;
 
 pub use array::Array;
+pub use attribute::Attribute;
+pub use big_number::BigNumber;
+pub use boolean::Boolean;
 pub use bulk_string::BulkString;
+pub use double::Double;
 pub use error::Error;
 pub use integer::Integer;
+pub use map::Map;
+pub use null::Null;
+pub use push::Push;
+pub use set::Set;
 pub use simple_string::SimpleString;
+pub use verbatim_string::VerbatimString;
 
+/// A RESP message. The first five variants are the RESP2 types, the remainder
+/// were added in RESP3 and must only be sent to sessions which negotiated
+/// RESP3 with `HELLO`.
 #[derive(Debug, PartialEq, Eq)]
 pub enum Message {
     BulkString(BulkString),
@@ -24,6 +45,15 @@ pub enum Message {
     Error(Error),
     Integer(Integer),
     Array(Array),
+    Null(Null),
+    Double(Double),
+    Boolean(Boolean),
+    BigNumber(BigNumber),
+    VerbatimString(VerbatimString),
+    Map(Map),
+    Set(Set),
+    Push(Push),
+    Attribute(Attribute),
 }
 
 impl Message {
@@ -50,6 +80,61 @@ impl Message {
     pub fn bulk_string(value: &[u8]) -> Self {
         Self::BulkString(BulkString::new(value))
     }
+
+    pub fn array(values: Vec<Message>) -> Self {
+        Self::Array(Array {
+            inner: Some(values),
+        })
+    }
+
+    pub fn null_array() -> Self {
+        Self::Array(Array { inner: None })
+    }
+
+    /// The RESP3 null. RESP2 sessions must use `null()` or `null_array()`.
+    pub fn resp3_null() -> Self {
+        Self::Null(Null {})
+    }
+
+    pub fn double(value: f64) -> Self {
+        Self::Double(Double { inner: value })
+    }
+
+    pub fn boolean(value: bool) -> Self {
+        Self::Boolean(Boolean { inner: value })
+    }
+
+    pub fn big_number<T: ToString>(value: T) -> Self {
+        Self::BigNumber(BigNumber {
+            inner: value.to_string(),
+        })
+    }
+
+    pub fn verbatim_string(format: [u8; 3], value: &[u8]) -> Self {
+        Self::VerbatimString(VerbatimString {
+            format,
+            inner: value.to_owned().into_boxed_slice(),
+        })
+    }
+
+    pub fn map(pairs: Vec<(Message, Message)>) -> Self {
+        Self::Map(Map { inner: pairs })
+    }
+
+    pub fn set(values: Vec<Message>) -> Self {
+        Self::Set(Set { inner: values })
+    }
+
+    pub fn push(values: Vec<Message>) -> Self {
+        Self::Push(Push { inner: values })
+    }
+
+    pub fn attribute(attributes: Vec<(Message, Message)>, message: Message) -> Self {
+        Self::Attribute(Attribute {
+            attributes,
+            inner: Box::new(message),
+        })
+    }
 }
 
 impl Compose for Message {
@@ -60,6 +145,15 @@ impl Compose for Message {
             Self::Error(e) => e.compose(buf),
             Self::Integer(i) => i.compose(buf),
             Self::Array(a) => a.compose(buf),
+            Self::Null(n) => n.compose(buf),
+            Self::Double(d) => d.compose(buf),
+            Self::Boolean(b) => b.compose(buf),
+            Self::BigNumber(n) => n.compose(buf),
+            Self::VerbatimString(s) => s.compose(buf),
+            Self::Map(m) => m.compose(buf),
+            Self::Set(s) => s.compose(buf),
+            Self::Push(p) => p.compose(buf),
+            Self::Attribute(a) => a.compose(buf),
         }
     }
 }
@@ -71,9 +165,18 @@ pub enum MessageType {
     Integer,
     BulkString,
     Array,
+    Null,
+    Double,
+    Boolean,
+    BigNumber,
+    VerbatimString,
+    Map,
+    Set,
+    Push,
+    Attribute,
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct MessageParser {}
 
 pub(crate) fn message_type(input: &[u8]) -> IResult<&[u8], MessageType> {
@@ -84,6 +187,15 @@ pub(crate) fn message_type(input: &[u8]) -> IResult<&[u8], MessageType> {
         b":" => MessageType::Integer,
         b"$" => MessageType::BulkString,
         b"*" => MessageType::Array,
+        b"_" => MessageType::Null,
+        b"," => MessageType::Double,
+        b"#" => MessageType::Boolean,
+        b"(" => MessageType::BigNumber,
+        b"=" => MessageType::VerbatimString,
+        b"%" => MessageType::Map,
+        b"~" => MessageType::Set,
+        b">" => MessageType::Push,
+        b"|" => MessageType::Attribute,
         _ => {
             return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
         }
@@ -113,7 +225,104 @@ pub(crate) fn message(input: &[u8]) -> IResult<&[u8], Message> {
             let (input, message) = array::parse(input)?;
             Ok((input, Message::Array(message)))
         }
+        (input, MessageType::Null) => {
+            let (input, message) = null::parse(input)?;
+            Ok((input, Message::Null(message)))
+        }
+        (input, MessageType::Double) => {
+            let (input, message) = double::parse(input)?;
+            Ok((input, Message::Double(message)))
+        }
+        (input, MessageType::Boolean) => {
+            let (input, message) = boolean::parse(input)?;
+            Ok((input, Message::Boolean(message)))
+        }
+        (input, MessageType::BigNumber) => {
+            let (input, message) = big_number::parse(input)?;
+            Ok((input, Message::BigNumber(message)))
+        }
+        (input, MessageType::VerbatimString) => {
+            let (input, message) = verbatim_string::parse(input)?;
+            Ok((input, Message::VerbatimString(message)))
+        }
+        (input, MessageType::Map) => {
+            let (input, message) = map::parse(input)?;
+            Ok((input, Message::Map(message)))
+        }
+        (input, MessageType::Set) => {
+            let (input, message) = set::parse(input)?;
+            Ok((input, Message::Set(message)))
+        }
+        (input, MessageType::Push) => {
+            let (input, message) = push::parse(input)?;
+            Ok((input, Message::Push(message)))
+        }
+        (input, MessageType::Attribute) => {
+            let (input, message) = attribute::parse(input)?;
+            Ok((input, Message::Attribute(message)))
+        }
+    }
+}
+
+// parses the element count or byte length which follows the type of an
+// aggregate or blob message
+pub(crate) fn aggregate_len(input: &[u8]) -> IResult<&[u8], usize> {
+    let (input, len) = digit1(input)?;
+    let len = unsafe { std::str::from_utf8_unchecked(len) }
+        .parse::<usize>()
+        .map_err(|_| nom::Err::Failure((input, nom::error::ErrorKind::Tag)))?;
+    let (input, _) = crlf(input)?;
+    Ok((input, len))
+}
+
+// parses the length and elements of an aggregate message
+pub(crate) fn messages(input: &[u8]) -> IResult<&[u8], Vec<Message>> {
+    let (mut input, len) = aggregate_len(input)?;
+    let mut values = Vec::new();
+    for _ in 0..len {
+        let (i, value) = message(input)?;
+        values.push(value);
+        input = i;
+    }
+    Ok((input, values))
+}
+
+// parses the length and key-value pairs of a map or attribute message
+pub(crate) fn pairs(input: &[u8]) -> IResult<&[u8], Vec<(Message, Message)>> {
+    let (mut input, len) = aggregate_len(input)?;
+    let mut pairs = Vec::new();
+    for _ in 0..len {
+        let (i, key) = message(input)?;
+        let (i, value) = message(i)?;
+        pairs.push((key, value));
+        input = i;
+    }
+    Ok((input, pairs))
+}
+
+pub(crate) fn compose_aggregate(buf: &mut dyn BufMut, prefix: u8, values: &[Message]) -> usize {
+    let header = format!("{}{}\r\n", prefix as char, values.len());
+    buf.put_slice(header.as_bytes());
+    let mut len = header.as_bytes().len();
+    for value in values {
+        len += value.compose(buf);
+    }
+    len
+}
+
+pub(crate) fn compose_pairs(
+    buf: &mut dyn BufMut,
+    prefix: u8,
+    pairs: &[(Message, Message)],
+) -> usize {
+    let header = format!("{}{}\r\n", prefix as char, pairs.len());
+    buf.put_slice(header.as_bytes());
+    let mut len = header.as_bytes().len();
+    for (key, value) in pairs {
+        len += key.compose(buf);
+        len += value.compose(buf);
     }
+    len
 }
 
 impl Parse<Message> for MessageParser {
@@ -0,0 +1,39 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+/// The RESP3 null, which replaces the null bulk string and null array.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Null {}
+
+impl Compose for Null {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        buf.put_slice(b"_\r\n");
+        3
+    }
+}
+
+pub fn parse(input: &[u8]) -> IResult<&[u8], Null> {
+    let (input, _) = crlf(input)?;
+    Ok((input, Null {}))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(message(b"_\r\n"), Ok((&b""[..], Message::resp3_null())));
+        assert!(message(b"_x\r\n").is_err());
+    }
+
+    #[test]
+    fn compose() {
+        let mut buf = Vec::new();
+        assert_eq!(Message::resp3_null().compose(&mut buf), 3);
+        assert_eq!(buf, b"_\r\n");
+    }
+}
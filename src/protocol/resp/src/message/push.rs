@@ -0,0 +1,50 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+/// A RESP3 out-of-band message, such as a pubsub message or an invalidation,
+/// which is sent to the client without a matching request.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Push {
+    pub(crate) inner: Vec<Message>,
+}
+
+impl Compose for Push {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        compose_aggregate(buf, b'>', &self.inner)
+    }
+}
+
+pub fn parse(input: &[u8]) -> IResult<&[u8], Push> {
+    let (input, inner) = messages(input)?;
+    Ok((input, Push { inner }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            message(b">2\r\n$10\r\ninvalidate\r\n*1\r\n$3\r\nkey\r\n"),
+            Ok((
+                &b""[..],
+                Message::push(vec![
+                    Message::bulk_string(b"invalidate"),
+                    Message::array(vec![Message::bulk_string(b"key")])
+                ])
+            ))
+        );
+    }
+
+    #[test]
+    fn compose() {
+        let mut buf = Vec::new();
+        let message = Message::push(vec![Message::simple_string("pong")]);
+        assert_eq!(message.compose(&mut buf), 11);
+        assert_eq!(buf, b">1\r\n+pong\r\n");
+    }
+}
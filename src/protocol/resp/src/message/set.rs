@@ -0,0 +1,50 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+/// A RESP3 unordered collection of unique elements.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Set {
+    pub(crate) inner: Vec<Message>,
+}
+
+impl Compose for Set {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        compose_aggregate(buf, b'~', &self.inner)
+    }
+}
+
+pub fn parse(input: &[u8]) -> IResult<&[u8], Set> {
+    let (input, inner) = messages(input)?;
+    Ok((input, Set { inner }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            message(b"~2\r\n+a\r\n+b\r\n"),
+            Ok((
+                &b""[..],
+                Message::set(vec![
+                    Message::simple_string("a"),
+                    Message::simple_string("b")
+                ])
+            ))
+        );
+        assert!(message(b"~2\r\n+a\r\n").is_err());
+    }
+
+    #[test]
+    fn compose() {
+        let mut buf = Vec::new();
+        let message = Message::set(vec![Message::integer(1), Message::integer(2)]);
+        assert_eq!(message.compose(&mut buf), 12);
+        assert_eq!(buf, b"~2\r\n:1\r\n:2\r\n");
+    }
+}
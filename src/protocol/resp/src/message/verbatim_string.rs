@@ -0,0 +1,77 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+/// A RESP3 binary safe string which carries a three character format, such as
+/// `txt` or `mkd`, which indicates how the client should display it.
+#[derive(Debug, PartialEq, Eq)]
+pub struct VerbatimString {
+    pub(crate) format: [u8; 3],
+    pub(crate) inner: Box<[u8]>,
+}
+
+impl Compose for VerbatimString {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let header = format!("={}\r\n", self.inner.len() + 4);
+        buf.put_slice(header.as_bytes());
+        buf.put_slice(&self.format);
+        buf.put_slice(b":");
+        buf.put_slice(&self.inner);
+        buf.put_slice(b"\r\n");
+        header.as_bytes().len() + self.inner.len() + 6
+    }
+}
+
+pub fn parse(input: &[u8]) -> IResult<&[u8], VerbatimString> {
+    let (input, len) = aggregate_len(input)?;
+
+    // the length includes the format and the separator
+    if len < 4 {
+        return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+    }
+
+    let (input, value) = take(len)(input)?;
+    let (input, _) = crlf(input)?;
+
+    if value[3] != b':' {
+        return Err(nom::Err::Failure((input, nom::error::ErrorKind::Tag)));
+    }
+
+    Ok((
+        input,
+        VerbatimString {
+            format: [value[0], value[1], value[2]],
+            inner: value[4..].to_vec().into_boxed_slice(),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            message(b"=15\r\ntxt:Some string\r\n"),
+            Ok((&b""[..], Message::verbatim_string(*b"txt", b"Some string")))
+        );
+        assert_eq!(
+            message(b"=4\r\nmkd:\r\n"),
+            Ok((&b""[..], Message::verbatim_string(*b"mkd", b"")))
+        );
+        assert!(message(b"=15\r\ntxt:Some").is_err());
+        assert!(message(b"=3\r\ntxt\r\n").is_err());
+        assert!(message(b"=5\r\ntxt_x\r\n").is_err());
+    }
+
+    #[test]
+    fn compose() {
+        let mut buf = Vec::new();
+        let message = Message::verbatim_string(*b"txt", b"Some string");
+        assert_eq!(message.compose(&mut buf), 22);
+        assert_eq!(buf, b"=15\r\ntxt:Some string\r\n");
+    }
+}
@@ -0,0 +1,111 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::Error;
+use std::sync::Arc;
+
+/// Appends the value to the string stored at the key. Keys which do not exist
+/// are created as if they held an empty string.
+/// format is: append key value
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct AppendRequest {
+    key: Arc<Box<[u8]>>,
+    value: Arc<Box<[u8]>>,
+}
+
+impl TryFrom<Message> for AppendRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        let mut array = command_args(other, Arity::Exactly(2))?;
+        let key = take_key(&mut array)?;
+        let value = take_bulk_string(&mut array)?;
+        Ok(Self { key, value })
+    }
+}
+
+impl AppendRequest {
+    pub fn new(key: &[u8], value: &[u8]) -> Self {
+        Self {
+            key: Arc::new(key.to_owned().into_boxed_slice()),
+            value: Arc::new(value.to_owned().into_boxed_slice()),
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn value(&self) -> &[u8] {
+        &self.value
+    }
+}
+
+impl From<&AppendRequest> for Message {
+    fn from(other: &AppendRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![
+                Message::bulk_string(b"APPEND"),
+                Message::BulkString(BulkString::from(other.key.clone())),
+                Message::BulkString(BulkString::from(other.value.clone())),
+            ]),
+        })
+    }
+}
+
+impl Compose for AppendRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for AppendRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        if let Response::Integer(ref res) = response {
+            klog!(
+                "\"append {} {}\" {} {}",
+                string_key(self.key()),
+                self.value().len(),
+                STORED,
+                res.inner
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"append 0 1\r\n").unwrap().into_inner(),
+            Request::Append(AppendRequest::new(b"0", b"1"))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*3\r\n$6\r\nAPPEND\r\n$1\r\n0\r\n$1\r\n1\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::Append(AppendRequest::new(b"0", b"1"))
+        );
+
+        assert!(matches!(
+            parser.parse(b"append 0\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+        assert!(matches!(
+            parser.parse(b"append 0 1 2\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+    }
+}
@@ -12,7 +12,7 @@ type ArcKeyValuePair = (ArcByteSlice, ArcByteSlice);
 /// Represents the btree add command which was added to Twitter's internal
 /// version of redis32.
 /// format is: badd outer_key (inner_key value)+
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BAddRequest {
     outer_key: Arc<Box<[u8]>>,
     inner_key_value_pairs: Arc<Box<[ArcKeyValuePair]>>,
@@ -104,6 +104,24 @@ impl Compose for BAddRequest {
     }
 }
 
+impl Klog for BAddRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        BADD.increment();
+        let code = match response {
+            Response::Integer(_) => STORED,
+            _ => NOT_STORED,
+        };
+        klog!(
+            "\"badd {} {}\" {}",
+            string_key(self.outer_key()),
+            self.inner_key_value_pairs.len(),
+            code
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,113 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+
+/// Returns the number of inner keys in the sorted map stored at the outer key.
+/// format is: bcard outer_key
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct BCardRequest {
+    outer_key: Arc<Box<[u8]>>,
+}
+
+impl TryFrom<Message> for BCardRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        if let Message::Array(array) = other {
+            if array.inner.is_none() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let mut array = array.inner.unwrap();
+
+            if array.len() != 2 {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let outer_key = take_bulk_string(&mut array)?;
+            if outer_key.is_empty() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            Ok(Self { outer_key })
+        } else {
+            Err(Error::new(ErrorKind::Other, "malformed command"))
+        }
+    }
+}
+
+impl BCardRequest {
+    pub fn new(outer_key: &[u8]) -> Self {
+        Self {
+            outer_key: Arc::new(outer_key.to_owned().into_boxed_slice()),
+        }
+    }
+
+    pub fn outer_key(&self) -> &[u8] {
+        &self.outer_key
+    }
+}
+
+impl From<&BCardRequest> for Message {
+    fn from(other: &BCardRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![
+                Message::bulk_string(b"BCARD"),
+                Message::BulkString(BulkString::from(other.outer_key.clone())),
+            ]),
+        })
+    }
+}
+
+impl Compose for BCardRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for BCardRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        BCARD.increment();
+        if let Response::Integer(ref res) = response {
+            let code = if res.inner > 0 { HIT } else { MISS };
+            klog!(
+                "\"bcard {}\" {} {}",
+                string_key(self.outer_key()),
+                code,
+                res.inner
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"bcard 0\r\n").unwrap().into_inner(),
+            Request::BCard(BCardRequest::new(b"0"))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*2\r\n$5\r\nBCARD\r\n$1\r\n0\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::BCard(BCardRequest::new(b"0"))
+        );
+
+        assert!(parser.parse(b"bcard 0 1\r\n").is_err());
+    }
+}
@@ -0,0 +1,134 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+
+/// Removes one or more inner keys from the sorted map stored at the outer
+/// key.
+/// format is: bdel outer_key inner_key [inner_key ...]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct BDelRequest {
+    outer_key: Arc<Box<[u8]>>,
+    inner_keys: Box<[Arc<Box<[u8]>>]>,
+}
+
+impl TryFrom<Message> for BDelRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        if let Message::Array(array) = other {
+            if array.inner.is_none() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let mut array = array.inner.unwrap();
+
+            if array.len() < 3 {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let outer_key = take_bulk_string(&mut array)?;
+            if outer_key.is_empty() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let mut inner_keys = Vec::with_capacity(array.len() - 1);
+            while array.len() >= 2 {
+                inner_keys.push(take_bulk_string(&mut array)?);
+            }
+
+            Ok(Self {
+                outer_key,
+                inner_keys: inner_keys.into_boxed_slice(),
+            })
+        } else {
+            Err(Error::new(ErrorKind::Other, "malformed command"))
+        }
+    }
+}
+
+impl BDelRequest {
+    pub fn new(outer_key: &[u8], inner_keys: &[&[u8]]) -> Self {
+        Self {
+            outer_key: Arc::new(outer_key.to_owned().into_boxed_slice()),
+            inner_keys: inner_keys
+                .iter()
+                .map(|e| Arc::new(e.to_vec().into_boxed_slice()))
+                .collect(),
+        }
+    }
+
+    pub fn outer_key(&self) -> &[u8] {
+        &self.outer_key
+    }
+
+    pub fn inner_keys(&self) -> Box<[&[u8]]> {
+        self.inner_keys.iter().map(|e| &***e).collect()
+    }
+}
+
+impl From<&BDelRequest> for Message {
+    fn from(other: &BDelRequest) -> Message {
+        let mut v = vec![
+            Message::bulk_string(b"BDEL"),
+            Message::BulkString(BulkString::from(other.outer_key.clone())),
+        ];
+        for inner_key in other.inner_keys.iter() {
+            v.push(Message::BulkString(BulkString::from(inner_key.clone())));
+        }
+        Message::Array(Array { inner: Some(v) })
+    }
+}
+
+impl Compose for BDelRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for BDelRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        BDEL.increment();
+        if let Response::Integer(ref res) = response {
+            let code = if res.inner > 0 { DELETED } else { NOT_FOUND };
+            klog!(
+                "\"bdel {} {}\" {} {}",
+                string_key(self.outer_key()),
+                self.inner_keys.len(),
+                code,
+                res.inner
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"bdel 0 a\r\n").unwrap().into_inner(),
+            Request::BDel(BDelRequest::new(b"0", &[b"a"]))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*4\r\n$4\r\nBDEL\r\n$1\r\n0\r\n$1\r\na\r\n$1\r\nb\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::BDel(BDelRequest::new(b"0", &[b"a", b"b"]))
+        );
+
+        assert!(parser.parse(b"bdel 0\r\n").is_err());
+    }
+}
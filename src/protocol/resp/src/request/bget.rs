@@ -0,0 +1,132 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+
+/// Returns the value of an inner key within the sorted map stored at the
+/// outer key.
+/// format is: bget outer_key inner_key
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct BGetRequest {
+    outer_key: Arc<Box<[u8]>>,
+    inner_key: Arc<Box<[u8]>>,
+}
+
+impl TryFrom<Message> for BGetRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        if let Message::Array(array) = other {
+            if array.inner.is_none() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let mut array = array.inner.unwrap();
+
+            if array.len() != 3 {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let outer_key = take_bulk_string(&mut array)?;
+            if outer_key.is_empty() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let inner_key = take_bulk_string(&mut array)?;
+
+            Ok(Self {
+                outer_key,
+                inner_key,
+            })
+        } else {
+            Err(Error::new(ErrorKind::Other, "malformed command"))
+        }
+    }
+}
+
+impl BGetRequest {
+    pub fn new(outer_key: &[u8], inner_key: &[u8]) -> Self {
+        Self {
+            outer_key: Arc::new(outer_key.to_owned().into_boxed_slice()),
+            inner_key: Arc::new(inner_key.to_owned().into_boxed_slice()),
+        }
+    }
+
+    pub fn outer_key(&self) -> &[u8] {
+        &self.outer_key
+    }
+
+    pub fn inner_key(&self) -> &[u8] {
+        &self.inner_key
+    }
+}
+
+impl From<&BGetRequest> for Message {
+    fn from(other: &BGetRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![
+                Message::bulk_string(b"BGET"),
+                Message::BulkString(BulkString::from(other.outer_key.clone())),
+                Message::BulkString(BulkString::from(other.inner_key.clone())),
+            ]),
+        })
+    }
+}
+
+impl Compose for BGetRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for BGetRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        BGET.increment();
+        let (code, len) = match response {
+            Response::BulkString(BulkString { inner: Some(v) }) => (HIT, v.len()),
+            Response::BulkString(_) => (MISS, 0),
+            _ => {
+                return;
+            }
+        };
+        klog!(
+            "\"bget {} {}\" {} {}",
+            string_key(self.outer_key()),
+            string_key(self.inner_key()),
+            code,
+            len
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"bget 0 a\r\n").unwrap().into_inner(),
+            Request::BGet(BGetRequest::new(b"0", b"a"))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*3\r\n$4\r\nBGET\r\n$1\r\n0\r\n$1\r\na\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::BGet(BGetRequest::new(b"0", b"a"))
+        );
+
+        assert!(parser.parse(b"bget 0\r\n").is_err());
+        assert!(parser.parse(b"bget 0 a b\r\n").is_err());
+    }
+}
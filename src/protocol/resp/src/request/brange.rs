@@ -0,0 +1,157 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+
+/// Returns inner keys and their values, in order, from the sorted map stored
+/// at the outer key. The range begins at the first inner key which is not
+/// less than the start key, or at the beginning of the map if no start key is
+/// given. At most `count` pairs are returned if a count is provided.
+/// format is: brange outer_key [inner_key_start [count]]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct BRangeRequest {
+    outer_key: Arc<Box<[u8]>>,
+    inner_key_start: Option<Arc<Box<[u8]>>>,
+    count: Option<u64>,
+}
+
+impl TryFrom<Message> for BRangeRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        if let Message::Array(array) = other {
+            if array.inner.is_none() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let mut array = array.inner.unwrap();
+
+            if array.len() < 2 || array.len() > 4 {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let outer_key = take_bulk_string(&mut array)?;
+            if outer_key.is_empty() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let inner_key_start = if array.len() >= 2 {
+                Some(take_bulk_string(&mut array)?)
+            } else {
+                None
+            };
+
+            let count = if array.len() >= 2 {
+                Some(take_bulk_string_as_u64(&mut array)?)
+            } else {
+                None
+            };
+
+            Ok(Self {
+                outer_key,
+                inner_key_start,
+                count,
+            })
+        } else {
+            Err(Error::new(ErrorKind::Other, "malformed command"))
+        }
+    }
+}
+
+impl BRangeRequest {
+    pub fn new(outer_key: &[u8], inner_key_start: Option<&[u8]>, count: Option<u64>) -> Self {
+        Self {
+            outer_key: Arc::new(outer_key.to_owned().into_boxed_slice()),
+            inner_key_start: inner_key_start.map(|k| Arc::new(k.to_owned().into_boxed_slice())),
+            count,
+        }
+    }
+
+    pub fn outer_key(&self) -> &[u8] {
+        &self.outer_key
+    }
+
+    pub fn inner_key_start(&self) -> Option<&[u8]> {
+        self.inner_key_start.as_ref().map(|k| &***k)
+    }
+
+    pub fn count(&self) -> Option<u64> {
+        self.count
+    }
+}
+
+impl From<&BRangeRequest> for Message {
+    fn from(other: &BRangeRequest) -> Message {
+        let mut v = vec![
+            Message::bulk_string(b"BRANGE"),
+            Message::BulkString(BulkString::from(other.outer_key.clone())),
+        ];
+        if let Some(inner_key_start) = &other.inner_key_start {
+            v.push(Message::BulkString(BulkString::from(
+                inner_key_start.clone(),
+            )));
+            if let Some(count) = other.count {
+                v.push(Message::bulk_string(format!("{}", count).as_bytes()));
+            }
+        }
+        Message::Array(Array { inner: Some(v) })
+    }
+}
+
+impl Compose for BRangeRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for BRangeRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        BRANGE.increment();
+        if let Response::Array(Array { inner: Some(v) }) = response {
+            let code = if v.is_empty() { MISS } else { HIT };
+            klog!(
+                "\"brange {}\" {} {}",
+                string_key(self.outer_key()),
+                code,
+                v.len() / 2
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"brange 0\r\n").unwrap().into_inner(),
+            Request::BRange(BRangeRequest::new(b"0", None, None))
+        );
+
+        assert_eq!(
+            parser.parse(b"brange 0 a\r\n").unwrap().into_inner(),
+            Request::BRange(BRangeRequest::new(b"0", Some(b"a"), None))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*4\r\n$6\r\nBRANGE\r\n$1\r\n0\r\n$0\r\n\r\n$2\r\n10\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::BRange(BRangeRequest::new(b"0", Some(b""), Some(10)))
+        );
+
+        assert!(parser.parse(b"brange 0 a b\r\n").is_err());
+        assert!(parser.parse(b"brange 0 a 1 2\r\n").is_err());
+    }
+}
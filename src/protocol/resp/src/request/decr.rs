@@ -0,0 +1,102 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::Error;
+use std::sync::Arc;
+
+/// Decrements the integer value stored at the key by one. Keys which do not
+/// exist are treated as zero before the decrement.
+/// format is: decr key
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct DecrRequest {
+    key: Arc<Box<[u8]>>,
+}
+
+impl TryFrom<Message> for DecrRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        let mut array = command_args(other, Arity::Exactly(1))?;
+        let key = take_key(&mut array)?;
+        Ok(Self { key })
+    }
+}
+
+impl DecrRequest {
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            key: Arc::new(key.to_owned().into_boxed_slice()),
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+}
+
+impl From<&DecrRequest> for Message {
+    fn from(other: &DecrRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![
+                Message::bulk_string(b"DECR"),
+                Message::BulkString(BulkString::from(other.key.clone())),
+            ]),
+        })
+    }
+}
+
+impl Compose for DecrRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for DecrRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        if let Response::Integer(ref res) = response {
+            klog!(
+                "\"decr {}\" {} {}",
+                string_key(self.key()),
+                STORED,
+                res.inner
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"decr 0\r\n").unwrap().into_inner(),
+            Request::Decr(DecrRequest::new(b"0"))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*2\r\n$4\r\nDECR\r\n$1\r\n0\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::Decr(DecrRequest::new(b"0"))
+        );
+
+        assert!(matches!(
+            parser.parse(b"decr\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+        assert!(matches!(
+            parser.parse(b"decr 0 1\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+    }
+}
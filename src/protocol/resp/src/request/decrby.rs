@@ -0,0 +1,109 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::Error;
+use std::sync::Arc;
+
+/// Decrements the integer value stored at the key by the given amount. Keys
+/// which do not exist are treated as zero before the decrement.
+/// format is: decrby key decrement
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct DecrByRequest {
+    key: Arc<Box<[u8]>>,
+    decrement: i64,
+}
+
+impl TryFrom<Message> for DecrByRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        let mut array = command_args(other, Arity::Exactly(2))?;
+        let key = take_key(&mut array)?;
+        let decrement = take_bulk_string_as_i64(&mut array)?;
+        Ok(Self { key, decrement })
+    }
+}
+
+impl DecrByRequest {
+    pub fn new(key: &[u8], decrement: i64) -> Self {
+        Self {
+            key: Arc::new(key.to_owned().into_boxed_slice()),
+            decrement,
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn decrement(&self) -> i64 {
+        self.decrement
+    }
+}
+
+impl From<&DecrByRequest> for Message {
+    fn from(other: &DecrByRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![
+                Message::bulk_string(b"DECRBY"),
+                Message::BulkString(BulkString::from(other.key.clone())),
+                Message::bulk_string(format!("{}", other.decrement).as_bytes()),
+            ]),
+        })
+    }
+}
+
+impl Compose for DecrByRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for DecrByRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        DECRBY.increment();
+        if let Response::Integer(ref res) = response {
+            klog!(
+                "\"decrby {} {}\" {} {}",
+                string_key(self.key()),
+                self.decrement,
+                STORED,
+                res.inner
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"decrby 0 5\r\n").unwrap().into_inner(),
+            Request::DecrBy(DecrByRequest::new(b"0", 5))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*3\r\n$6\r\nDECRBY\r\n$1\r\n0\r\n$1\r\n5\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::DecrBy(DecrByRequest::new(b"0", 5))
+        );
+
+        assert!(matches!(
+            parser.parse(b"decrby 0\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+        assert!(parser.parse(b"decrby 0 a\r\n").is_err());
+    }
+}
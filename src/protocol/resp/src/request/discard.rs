@@ -0,0 +1,121 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::{Error, ErrorKind};
+
+/// Drops the commands queued since `MULTI` and forgets the watched keys.
+/// format is: discard
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DiscardRequest {
+    without_multi: bool,
+}
+
+impl TryFrom<Message> for DiscardRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        if let Message::Array(array) = other {
+            if array.inner.is_none() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let array = array.inner.unwrap();
+
+            if array.len() != 1 {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            Ok(Self {
+                without_multi: false,
+            })
+        } else {
+            Err(Error::new(ErrorKind::Other, "malformed command"))
+        }
+    }
+}
+
+impl DiscardRequest {
+    pub fn new() -> Self {
+        Self {
+            without_multi: false,
+        }
+    }
+
+    /// The reply to the request, which does not depend on the storage.
+    pub fn response(&self) -> Response {
+        if self.without_multi {
+            Response::error("ERR DISCARD without MULTI")
+        } else {
+            Response::simple_string("OK")
+        }
+    }
+}
+
+impl From<&DiscardRequest> for Message {
+    fn from(_other: &DiscardRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![Message::bulk_string(b"DISCARD")]),
+        })
+    }
+}
+
+impl Compose for DiscardRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for DiscardRequest {
+    type Response = Response;
+
+    fn klog(&self, _response: &Self::Response) {
+        DISCARD.increment();
+    }
+}
+
+impl RequestParser {
+    /// Ends the transaction without running the queued commands.
+    pub(crate) fn discard(&self, mut request: DiscardRequest) -> DiscardRequest {
+        request.without_multi = self.transaction.borrow_mut().take().is_none();
+        self.watched.take();
+        request
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+
+        parser.parse(b"multi\r\n").unwrap();
+        parser.parse(b"get 0\r\n").unwrap();
+        assert_eq!(
+            parser.parse(b"discard\r\n").unwrap().into_inner(),
+            Request::Discard(DiscardRequest::new())
+        );
+
+        // the transaction has ended
+        assert_eq!(
+            parser.parse(b"get 0\r\n").unwrap().into_inner(),
+            Request::Get(GetRequest::new(b"0"))
+        );
+
+        let discard = parser
+            .parse(b"*1\r\n$7\r\nDISCARD\r\n")
+            .unwrap()
+            .into_inner();
+        if let Request::Discard(discard) = discard {
+            assert!(matches!(discard.response(), Response::Error(_)));
+        } else {
+            panic!("expected discard request");
+        }
+
+        assert!(parser.parse(b"discard 0\r\n").is_err());
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::Error;
+use std::sync::Arc;
+
+/// Replies with a copy of the message.
+/// format is: echo message
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct EchoRequest {
+    message: Arc<Box<[u8]>>,
+}
+
+impl TryFrom<Message> for EchoRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        let mut array = command_args(other, Arity::Exactly(1))?;
+        let message = take_bulk_string(&mut array)?;
+        Ok(Self { message })
+    }
+}
+
+impl EchoRequest {
+    pub fn new(message: &[u8]) -> Self {
+        Self {
+            message: Arc::new(message.to_owned().into_boxed_slice()),
+        }
+    }
+
+    pub fn message(&self) -> &[u8] {
+        &self.message
+    }
+
+    /// The reply to the request, which does not depend on the storage.
+    pub fn response(&self) -> Response {
+        Response::bulk_string(self.message())
+    }
+}
+
+impl From<&EchoRequest> for Message {
+    fn from(other: &EchoRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![
+                Message::bulk_string(b"ECHO"),
+                Message::BulkString(BulkString::from(other.message.clone())),
+            ]),
+        })
+    }
+}
+
+impl Compose for EchoRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for EchoRequest {
+    type Response = Response;
+
+    fn klog(&self, _response: &Self::Response) {
+        ECHO.increment();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"echo hello\r\n").unwrap().into_inner(),
+            Request::Echo(EchoRequest::new(b"hello"))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*2\r\n$4\r\nECHO\r\n$0\r\n\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::Echo(EchoRequest::new(b""))
+        );
+
+        assert!(matches!(
+            parser.parse(b"echo\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+        assert!(matches!(
+            parser.parse(b"echo a b\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+    }
+}
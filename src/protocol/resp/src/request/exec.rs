@@ -0,0 +1,203 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::{Error, ErrorKind};
+
+/// Runs the commands queued since `MULTI` as one unit. The reply is an array
+/// with the reply to each command, or a nil array if one of the watched keys
+/// was changed and the transaction was aborted. If a command could not be
+/// queued, none of the commands are run and the reply is an error.
+/// format is: exec
+///
+/// The queued commands and the watched keys are moved onto the request by the
+/// `RequestParser`, which leaves the session without a transaction.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ExecRequest {
+    transaction: Option<Box<[Request]>>,
+    aborted: bool,
+    watched: WatchedKeys,
+}
+
+impl TryFrom<Message> for ExecRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        if let Message::Array(array) = other {
+            if array.inner.is_none() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let array = array.inner.unwrap();
+
+            if array.len() != 1 {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            Ok(Self::default())
+        } else {
+            Err(Error::new(ErrorKind::Other, "malformed command"))
+        }
+    }
+}
+
+impl ExecRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The queued commands, or `None` if the session was not in a transaction.
+    pub fn requests(&self) -> Option<&[Request]> {
+        self.transaction.as_deref()
+    }
+
+    /// The reply to the request if the queued commands are not run, either
+    /// because the session was not in a transaction or because one of the
+    /// commands could not be queued.
+    pub fn error(&self) -> Option<Response> {
+        match self.transaction {
+            None => Some(Response::error("ERR EXEC without MULTI")),
+            Some(_) if self.aborted => Some(Response::error(
+                "EXECABORT Transaction discarded because of previous errors.",
+            )),
+            Some(_) => None,
+        }
+    }
+
+    /// Checks each watched key against the version it had when it was
+    /// watched, returning `false` if any of them has changed.
+    pub fn unchanged(&self, version: impl FnMut(&[u8]) -> Option<u32>) -> bool {
+        self.watched.unchanged(version)
+    }
+}
+
+impl From<&ExecRequest> for Message {
+    fn from(_other: &ExecRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![Message::bulk_string(b"EXEC")]),
+        })
+    }
+}
+
+impl Compose for ExecRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for ExecRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        EXEC.increment();
+        if let (Some(requests), Response::Array(array)) = (self.requests(), response) {
+            if let Some(responses) = &array.inner {
+                for (request, response) in requests.iter().zip(responses.iter()) {
+                    request.klog(response);
+                }
+            }
+        }
+    }
+}
+
+impl RequestParser {
+    /// Ends the transaction, moving the queued commands and the watched keys
+    /// of the session onto the request.
+    pub(crate) fn exec(&self, mut request: ExecRequest) -> ExecRequest {
+        if let Some(transaction) = self.transaction.borrow_mut().take() {
+            request.transaction = Some(transaction.requests.into_boxed_slice());
+            request.aborted = transaction.aborted;
+        }
+        request.watched = self.watched.take();
+        request
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"exec\r\n").unwrap().into_inner(),
+            Request::Exec(ExecRequest::new())
+        );
+
+        parser.parse(b"multi\r\n").unwrap();
+        parser.parse(b"*2\r\n$4\r\nINCR\r\n$1\r\n0\r\n").unwrap();
+        parser.parse(b"get 0\r\n").unwrap();
+
+        let exec = parser.parse(b"*1\r\n$4\r\nEXEC\r\n").unwrap().into_inner();
+        if let Request::Exec(exec) = exec {
+            assert_eq!(
+                exec.requests(),
+                Some(
+                    &[
+                        Request::Incr(IncrRequest::new(b"0")),
+                        Request::Get(GetRequest::new(b"0"))
+                    ][..]
+                )
+            );
+        } else {
+            panic!("expected exec request");
+        }
+
+        // the transaction has ended
+        assert_eq!(
+            parser.parse(b"get 0\r\n").unwrap().into_inner(),
+            Request::Get(GetRequest::new(b"0"))
+        );
+
+        assert!(parser.parse(b"exec 0\r\n").is_err());
+    }
+
+    #[test]
+    fn error() {
+        let parser = RequestParser::new();
+        let exec = |parser: &RequestParser| match parser.parse(b"exec\r\n").unwrap().into_inner() {
+            Request::Exec(exec) => exec,
+            request => panic!("unexpected request: {:?}", request),
+        };
+
+        assert_eq!(
+            exec(&parser).error(),
+            Some(Response::error("ERR EXEC without MULTI"))
+        );
+
+        parser.parse(b"multi\r\n").unwrap();
+        parser.parse(b"get 0\r\n").unwrap();
+        assert_eq!(exec(&parser).error(), None);
+
+        // a command which could not be queued aborts the transaction
+        let aborted = Some(Response::error(
+            "EXECABORT Transaction discarded because of previous errors.",
+        ));
+        parser.parse(b"multi\r\n").unwrap();
+        parser.parse(b"get 0\r\n").unwrap();
+        parser.parse(b"foo\r\n").unwrap();
+        parser.parse(b"get 0\r\n").unwrap();
+        assert_eq!(exec(&parser).error(), aborted);
+
+        // as does queueing too many commands
+        let parser = RequestParser::new().max_queued(1);
+        parser.parse(b"multi\r\n").unwrap();
+        assert!(matches!(
+            parser.parse(b"get 0\r\n").unwrap().into_inner(),
+            Request::Queued(_)
+        ));
+        assert!(matches!(
+            parser.parse(b"get 1\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+        assert_eq!(exec(&parser).error(), aborted);
+
+        // the next transaction starts over
+        parser.parse(b"multi\r\n").unwrap();
+        parser.parse(b"get 0\r\n").unwrap();
+        assert_eq!(exec(&parser).error(), None);
+    }
+}
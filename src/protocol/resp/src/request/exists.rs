@@ -0,0 +1,110 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::Error;
+use std::sync::Arc;
+
+/// Returns the number of the keys which exist. Keys which are repeated are
+/// counted each time they appear.
+/// format is: exists key [key ...]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct ExistsRequest {
+    keys: Box<[Arc<Box<[u8]>>]>,
+}
+
+impl TryFrom<Message> for ExistsRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        let mut array = command_args(other, Arity::AtLeast(1))?;
+        let keys = take_keys(&mut array)?;
+        Ok(Self { keys })
+    }
+}
+
+impl ExistsRequest {
+    pub fn new(keys: &[&[u8]]) -> Self {
+        Self {
+            keys: keys
+                .iter()
+                .map(|k| Arc::new(k.to_vec().into_boxed_slice()))
+                .collect(),
+        }
+    }
+
+    pub fn keys(&self) -> Box<[&[u8]]> {
+        self.keys.iter().map(|k| &***k).collect()
+    }
+}
+
+impl From<&ExistsRequest> for Message {
+    fn from(other: &ExistsRequest) -> Message {
+        let mut v = vec![Message::bulk_string(b"EXISTS")];
+        for key in other.keys.iter() {
+            v.push(Message::BulkString(BulkString::from(key.clone())));
+        }
+        Message::Array(Array { inner: Some(v) })
+    }
+}
+
+impl Compose for ExistsRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for ExistsRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        EXISTS.increment();
+        if let Response::Integer(ref res) = response {
+            let code = if res.inner > 0 { HIT } else { MISS };
+            klog!(
+                "\"exists {}\" {} {}",
+                string_keys(self.keys.iter().map(|k| &***k)),
+                code,
+                res.inner
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"exists 0\r\n").unwrap().into_inner(),
+            Request::Exists(ExistsRequest::new(&[b"0"]))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*2\r\n$6\r\nEXISTS\r\n$1\r\n0\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::Exists(ExistsRequest::new(&[b"0"]))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*3\r\n$6\r\nEXISTS\r\n$1\r\n0\r\n$1\r\n1\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::Exists(ExistsRequest::new(&[b"0", b"1"]))
+        );
+
+        assert!(matches!(
+            parser.parse(b"exists\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::Error;
+use std::sync::Arc;
+
+/// Sets the time to live of the key in seconds. A time to live which is not
+/// positive deletes the key.
+/// format is: expire key seconds
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct ExpireRequest {
+    key: Arc<Box<[u8]>>,
+    seconds: i64,
+}
+
+impl TryFrom<Message> for ExpireRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        let mut array = command_args(other, Arity::Exactly(2))?;
+        let key = take_key(&mut array)?;
+        let seconds = take_bulk_string_as_i64(&mut array)?;
+        Ok(Self { key, seconds })
+    }
+}
+
+impl ExpireRequest {
+    pub fn new(key: &[u8], seconds: i64) -> Self {
+        Self {
+            key: Arc::new(key.to_owned().into_boxed_slice()),
+            seconds,
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn seconds(&self) -> i64 {
+        self.seconds
+    }
+}
+
+impl From<&ExpireRequest> for Message {
+    fn from(other: &ExpireRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![
+                Message::bulk_string(b"EXPIRE"),
+                Message::BulkString(BulkString::from(other.key.clone())),
+                Message::bulk_string(format!("{}", other.seconds).as_bytes()),
+            ]),
+        })
+    }
+}
+
+impl Compose for ExpireRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for ExpireRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        EXPIRE.increment();
+        if let Response::Integer(ref res) = response {
+            let code = if res.inner > 0 { STORED } else { NOT_FOUND };
+            klog!(
+                "\"expire {} {}\" {}",
+                string_key(self.key()),
+                self.seconds,
+                code
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"expire 0 10\r\n").unwrap().into_inner(),
+            Request::Expire(ExpireRequest::new(b"0", 10))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*3\r\n$6\r\nEXPIRE\r\n$1\r\n0\r\n$2\r\n10\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::Expire(ExpireRequest::new(b"0", 10))
+        );
+
+        assert!(matches!(
+            parser.parse(b"expire 0\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+        assert!(parser.parse(b"expire 0 a\r\n").is_err());
+    }
+}
@@ -0,0 +1,87 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::{Error, ErrorKind};
+
+/// Removes all keys. The flush is always synchronous, so the optional `ASYNC`
+/// and `SYNC` modifiers are accepted but have no effect.
+/// format is: flushdb [ASYNC|SYNC]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FlushDbRequest {}
+
+impl TryFrom<Message> for FlushDbRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        let mut array = command_args(other, Arity::Between(0, 1))?;
+        if array.len() > 1 {
+            let mode = take_bulk_string(&mut array)?;
+            if !matches!(
+                mode.as_ref().as_ref(),
+                b"ASYNC" | b"async" | b"SYNC" | b"sync"
+            ) {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+        }
+        Ok(Self {})
+    }
+}
+
+impl FlushDbRequest {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl From<&FlushDbRequest> for Message {
+    fn from(_other: &FlushDbRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![Message::bulk_string(b"FLUSHDB")]),
+        })
+    }
+}
+
+impl Compose for FlushDbRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for FlushDbRequest {
+    type Response = Response;
+
+    fn klog(&self, _response: &Self::Response) {
+        FLUSHDB.increment();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"flushdb\r\n").unwrap().into_inner(),
+            Request::FlushDb(FlushDbRequest::new())
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*2\r\n$7\r\nFLUSHDB\r\n$5\r\nASYNC\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::FlushDb(FlushDbRequest::new())
+        );
+
+        assert!(parser.parse(b"flushdb later\r\n").is_err());
+        assert!(matches!(
+            parser.parse(b"flushdb sync sync\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+    }
+}
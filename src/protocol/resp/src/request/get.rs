@@ -6,7 +6,7 @@ use super::*;
 use std::io::{Error, ErrorKind};
 use std::sync::Arc;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[allow(clippy::redundant_allocation)]
 pub struct GetRequest {
     key: Arc<Box<[u8]>>,
@@ -80,6 +80,21 @@ impl Compose for GetRequest {
     }
 }
 
+impl Klog for GetRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        let (code, len) = match response {
+            Response::BulkString(BulkString { inner: Some(v) }) => (HIT, v.len()),
+            Response::BulkString(_) => (MISS, 0),
+            _ => {
+                return;
+            }
+        };
+        klog!("\"get {}\" {} {}", string_key(self.key()), code, len);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,116 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::Error;
+use std::sync::Arc;
+
+/// Sets the key to hold the value and returns the value it held before, if
+/// any. Any existing expiration is discarded.
+/// format is: getset key value
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct GetSetRequest {
+    key: Arc<Box<[u8]>>,
+    value: Arc<Box<[u8]>>,
+}
+
+impl TryFrom<Message> for GetSetRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        let mut array = command_args(other, Arity::Exactly(2))?;
+        let key = take_key(&mut array)?;
+        let value = take_bulk_string(&mut array)?;
+        Ok(Self { key, value })
+    }
+}
+
+impl GetSetRequest {
+    pub fn new(key: &[u8], value: &[u8]) -> Self {
+        Self {
+            key: Arc::new(key.to_owned().into_boxed_slice()),
+            value: Arc::new(value.to_owned().into_boxed_slice()),
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn value(&self) -> &[u8] {
+        &self.value
+    }
+}
+
+impl From<&GetSetRequest> for Message {
+    fn from(other: &GetSetRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![
+                Message::bulk_string(b"GETSET"),
+                Message::BulkString(BulkString::from(other.key.clone())),
+                Message::BulkString(BulkString::from(other.value.clone())),
+            ]),
+        })
+    }
+}
+
+impl Compose for GetSetRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for GetSetRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        GETSET.increment();
+        let code = match response {
+            Response::BulkString(BulkString { inner: Some(_) }) => HIT,
+            Response::BulkString(_) => MISS,
+            _ => {
+                return;
+            }
+        };
+        klog!(
+            "\"getset {} {}\" {}",
+            string_key(self.key()),
+            self.value().len(),
+            code
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"getset 0 1\r\n").unwrap().into_inner(),
+            Request::GetSet(GetSetRequest::new(b"0", b"1"))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*3\r\n$6\r\nGETSET\r\n$1\r\n0\r\n$1\r\n1\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::GetSet(GetSetRequest::new(b"0", b"1"))
+        );
+
+        assert!(matches!(
+            parser.parse(b"getset 0\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+        assert!(matches!(
+            parser.parse(b"getset 0 1 2\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+    }
+}
@@ -0,0 +1,336 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+
+/// The protocol version which is used for replies to a session.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub enum ProtocolVersion {
+    #[default]
+    Resp2,
+    Resp3,
+}
+
+impl ProtocolVersion {
+    fn from_u64(version: u64) -> Option<Self> {
+        match version {
+            2 => Some(Self::Resp2),
+            3 => Some(Self::Resp3),
+            _ => None,
+        }
+    }
+
+    fn as_i64(&self) -> i64 {
+        match self {
+            Self::Resp2 => 2,
+            Self::Resp3 => 3,
+        }
+    }
+}
+
+/// Negotiates the protocol version for the session and returns information
+/// about the server.
+/// format is: hello [protover [AUTH username password] [SETNAME clientname]]
+///
+/// The version is switched by the `RequestParser` as the request is parsed,
+/// so that it applies to the reply for this request and every request after
+/// it on the same session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation, clippy::type_complexity)]
+pub struct HelloRequest {
+    version: Option<u64>,
+    auth: Option<(Arc<Box<[u8]>>, Arc<Box<[u8]>>)>,
+    name: Option<Arc<Box<[u8]>>>,
+    protocol: ProtocolVersion,
+}
+
+impl TryFrom<Message> for HelloRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        if let Message::Array(array) = other {
+            if array.inner.is_none() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let mut array = array.inner.unwrap();
+
+            let version = if array.len() > 1 {
+                Some(take_bulk_string_as_u64(&mut array)?)
+            } else {
+                None
+            };
+
+            let mut auth = None;
+            let mut name = None;
+
+            while array.len() > 1 {
+                let field = take_bulk_string(&mut array)?;
+
+                match field.as_ref().as_ref() {
+                    b"AUTH" | b"auth" => {
+                        if auth.is_some() || array.len() < 3 {
+                            return Err(Error::new(ErrorKind::Other, "malformed command"));
+                        }
+                        let username = take_bulk_string(&mut array)?;
+                        let password = take_bulk_string(&mut array)?;
+                        auth = Some((username, password));
+                    }
+                    b"SETNAME" | b"setname" => {
+                        if name.is_some() || array.len() < 2 {
+                            return Err(Error::new(ErrorKind::Other, "malformed command"));
+                        }
+                        name = Some(take_bulk_string(&mut array)?);
+                    }
+                    _ => {
+                        return Err(Error::new(ErrorKind::Other, "malformed command"));
+                    }
+                }
+            }
+
+            Ok(Self {
+                version,
+                auth,
+                name,
+                protocol: ProtocolVersion::default(),
+            })
+        } else {
+            Err(Error::new(ErrorKind::Other, "malformed command"))
+        }
+    }
+}
+
+impl HelloRequest {
+    pub fn new(version: Option<u64>) -> Self {
+        Self {
+            version,
+            auth: None,
+            name: None,
+            protocol: ProtocolVersion::default(),
+        }
+    }
+
+    /// The protocol version requested by the client, if any.
+    pub fn version(&self) -> Option<u64> {
+        self.version
+    }
+
+    pub fn username(&self) -> Option<&[u8]> {
+        self.auth
+            .as_ref()
+            .map(|(username, _)| username.as_ref().as_ref())
+    }
+
+    pub fn password(&self) -> Option<&[u8]> {
+        self.auth
+            .as_ref()
+            .map(|(_, password)| password.as_ref().as_ref())
+    }
+
+    pub fn name(&self) -> Option<&[u8]> {
+        self.name.as_ref().map(|name| name.as_ref().as_ref())
+    }
+
+    /// The protocol version of the session once this request is handled.
+    pub fn protocol(&self) -> ProtocolVersion {
+        self.protocol
+    }
+
+    /// Returns an error reply if the request cannot be accepted, in which case
+    /// the protocol version of the session is left unchanged.
+    fn error(&self) -> Option<Response> {
+        if let Some(version) = self.version {
+            if ProtocolVersion::from_u64(version).is_none() {
+                return Some(Response::error(
+                    "NOPROTO sorry, this protocol version is not supported",
+                ));
+            }
+        }
+
+        if self.auth.is_some() {
+            return Some(Response::error("ERR AUTH is not supported"));
+        }
+
+        None
+    }
+
+    /// The reply to the request, which describes the server. It is a map for
+    /// RESP3 sessions and an array of alternating keys and values for RESP2.
+    pub fn response(&self) -> Response {
+        if let Some(error) = self.error() {
+            return error;
+        }
+
+        let fields = vec![
+            ("server", Response::bulk_string(b"pelikan")),
+            (
+                "version",
+                Response::bulk_string(env!("CARGO_PKG_VERSION").as_bytes()),
+            ),
+            ("proto", Response::integer(self.protocol.as_i64())),
+            ("mode", Response::bulk_string(b"standalone")),
+            ("role", Response::bulk_string(b"master")),
+            ("modules", Response::array(Vec::new())),
+        ];
+
+        match self.protocol {
+            ProtocolVersion::Resp2 => Response::array(
+                fields
+                    .into_iter()
+                    .flat_map(|(key, value)| [Response::bulk_string(key.as_bytes()), value])
+                    .collect(),
+            ),
+            ProtocolVersion::Resp3 => Response::map(
+                fields
+                    .into_iter()
+                    .map(|(key, value)| (Response::bulk_string(key.as_bytes()), value))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl RequestParser {
+    /// Switch the session to the requested protocol version if the request is
+    /// accepted, and record the resulting version on the request.
+    pub(crate) fn hello(&self, mut request: HelloRequest) -> HelloRequest {
+        if request.error().is_none() {
+            if let Some(protocol) = request.version.and_then(ProtocolVersion::from_u64) {
+                self.protocol.set(protocol);
+            }
+        }
+        request.protocol = self.protocol.get();
+        request
+    }
+}
+
+impl From<&HelloRequest> for Message {
+    fn from(other: &HelloRequest) -> Message {
+        let mut values = vec![Message::bulk_string(b"HELLO")];
+
+        if let Some(version) = other.version {
+            values.push(Message::bulk_string(format!("{}", version).as_bytes()));
+        }
+
+        if let Some((username, password)) = &other.auth {
+            values.push(Message::bulk_string(b"AUTH"));
+            values.push(Message::BulkString(BulkString::from(username.clone())));
+            values.push(Message::BulkString(BulkString::from(password.clone())));
+        }
+
+        if let Some(name) = &other.name {
+            values.push(Message::bulk_string(b"SETNAME"));
+            values.push(Message::BulkString(BulkString::from(name.clone())));
+        }
+
+        Message::Array(Array {
+            inner: Some(values),
+        })
+    }
+}
+
+impl Compose for HelloRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for HelloRequest {
+    type Response = Response;
+
+    fn klog(&self, _response: &Self::Response) {
+        HELLO.increment();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"hello\r\n").unwrap().into_inner(),
+            Request::Hello(HelloRequest::new(None))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*2\r\n$5\r\nHELLO\r\n$1\r\n3\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::Hello(HelloRequest {
+                protocol: ProtocolVersion::Resp3,
+                ..HelloRequest::new(Some(3))
+            })
+        );
+
+        let request = parser
+            .parse(b"hello 2 AUTH user pass SETNAME client\r\n")
+            .unwrap()
+            .into_inner();
+        if let Request::Hello(hello) = request {
+            assert_eq!(hello.version(), Some(2));
+            assert_eq!(hello.username(), Some(&b"user"[..]));
+            assert_eq!(hello.password(), Some(&b"pass"[..]));
+            assert_eq!(hello.name(), Some(&b"client"[..]));
+        } else {
+            panic!("invalid parse result");
+        }
+
+        assert!(parser.parse(b"hello three\r\n").is_err());
+        assert!(parser.parse(b"hello 3 AUTH user\r\n").is_err());
+        assert!(parser.parse(b"hello 3 FOO\r\n").is_err());
+    }
+
+    #[test]
+    fn negotiate() {
+        let parser = RequestParser::new();
+        assert_eq!(parser.protocol(), ProtocolVersion::Resp2);
+
+        let hello = |parser: &RequestParser, buffer: &[u8]| match parser
+            .parse(buffer)
+            .unwrap()
+            .into_inner()
+        {
+            Request::Hello(hello) => hello,
+            request => panic!("unexpected request: {:?}", request),
+        };
+
+        // an unsupported version is rejected and the session is unchanged
+        let request = hello(&parser, b"hello 4\r\n");
+        assert!(matches!(request.response(), Response::Error(_)));
+        assert_eq!(parser.protocol(), ProtocolVersion::Resp2);
+
+        let request = hello(&parser, b"hello 3\r\n");
+        assert_eq!(request.protocol(), ProtocolVersion::Resp3);
+        assert!(matches!(request.response(), Response::Map(_)));
+        assert_eq!(parser.protocol(), ProtocolVersion::Resp3);
+
+        // without a version, the current version is reported
+        let request = hello(&parser, b"hello\r\n");
+        assert_eq!(request.protocol(), ProtocolVersion::Resp3);
+
+        // each session gets its own clone of the parser
+        assert_eq!(parser.clone().protocol(), ProtocolVersion::Resp3);
+        assert_eq!(RequestParser::new().protocol(), ProtocolVersion::Resp2);
+
+        let request = hello(&parser, b"hello 2\r\n");
+        assert_eq!(parser.protocol(), ProtocolVersion::Resp2);
+        match request.response() {
+            Response::Array(Array {
+                inner: Some(values),
+            }) => {
+                assert_eq!(values.len(), 12);
+                assert_eq!(values[4], Response::bulk_string(b"proto"));
+                assert_eq!(values[5], Response::integer(2));
+            }
+            response => panic!("unexpected response: {:?}", response),
+        }
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+
+/// Increments the integer value of a field within the hash stored at the key.
+/// Fields which do not exist are treated as zero before the increment.
+/// format is: hincrby key field increment
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct HIncrByRequest {
+    key: Arc<Box<[u8]>>,
+    field: Arc<Box<[u8]>>,
+    increment: i64,
+}
+
+impl TryFrom<Message> for HIncrByRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        if let Message::Array(array) = other {
+            if array.inner.is_none() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let mut array = array.inner.unwrap();
+
+            if array.len() != 4 {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let key = take_bulk_string(&mut array)?;
+            if key.is_empty() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let field = take_bulk_string(&mut array)?;
+            let increment = take_bulk_string_as_i64(&mut array)?;
+
+            Ok(Self {
+                key,
+                field,
+                increment,
+            })
+        } else {
+            Err(Error::new(ErrorKind::Other, "malformed command"))
+        }
+    }
+}
+
+impl HIncrByRequest {
+    pub fn new(key: &[u8], field: &[u8], increment: i64) -> Self {
+        Self {
+            key: Arc::new(key.to_owned().into_boxed_slice()),
+            field: Arc::new(field.to_owned().into_boxed_slice()),
+            increment,
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn field(&self) -> &[u8] {
+        &self.field
+    }
+
+    pub fn increment(&self) -> i64 {
+        self.increment
+    }
+}
+
+impl From<&HIncrByRequest> for Message {
+    fn from(other: &HIncrByRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![
+                Message::bulk_string(b"HINCRBY"),
+                Message::BulkString(BulkString::from(other.key.clone())),
+                Message::BulkString(BulkString::from(other.field.clone())),
+                Message::bulk_string(format!("{}", other.increment).as_bytes()),
+            ]),
+        })
+    }
+}
+
+impl Compose for HIncrByRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for HIncrByRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        HINCRBY.increment();
+        if let Response::Integer(ref res) = response {
+            klog!(
+                "\"hincrby {} {} {}\" {} {}",
+                string_key(self.key()),
+                string_key(self.field()),
+                self.increment,
+                STORED,
+                res.inner
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"hincrby 0 a 1\r\n").unwrap().into_inner(),
+            Request::HIncrBy(HIncrByRequest::new(b"0", b"a", 1))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*4\r\n$7\r\nHINCRBY\r\n$1\r\n0\r\n$1\r\na\r\n$2\r\n-5\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::HIncrBy(HIncrByRequest::new(b"0", b"a", -5))
+        );
+
+        assert!(parser.parse(b"hincrby 0 a\r\n").is_err());
+        assert!(parser.parse(b"hincrby 0 a b\r\n").is_err());
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+
+/// Returns the number of fields in the hash stored at the key.
+/// format is: hlen key
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct HLenRequest {
+    key: Arc<Box<[u8]>>,
+}
+
+impl TryFrom<Message> for HLenRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        if let Message::Array(array) = other {
+            if array.inner.is_none() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let mut array = array.inner.unwrap();
+
+            if array.len() != 2 {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let key = take_bulk_string(&mut array)?;
+            if key.is_empty() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            Ok(Self { key })
+        } else {
+            Err(Error::new(ErrorKind::Other, "malformed command"))
+        }
+    }
+}
+
+impl HLenRequest {
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            key: Arc::new(key.to_owned().into_boxed_slice()),
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+}
+
+impl From<&HLenRequest> for Message {
+    fn from(other: &HLenRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![
+                Message::bulk_string(b"HLEN"),
+                Message::BulkString(BulkString::from(other.key.clone())),
+            ]),
+        })
+    }
+}
+
+impl Compose for HLenRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for HLenRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        HLEN.increment();
+        if let Response::Integer(ref res) = response {
+            let code = if res.inner > 0 { HIT } else { MISS };
+            klog!("\"hlen {}\" {} {}", string_key(self.key()), code, res.inner);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"hlen 0\r\n").unwrap().into_inner(),
+            Request::HLen(HLenRequest::new(b"0"))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*2\r\n$4\r\nHLEN\r\n$1\r\n0\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::HLen(HLenRequest::new(b"0"))
+        );
+
+        assert!(parser.parse(b"hlen 0 1\r\n").is_err());
+    }
+}
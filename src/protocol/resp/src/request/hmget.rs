@@ -0,0 +1,138 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+
+/// Returns the values of one or more fields within the hash stored at the key.
+/// format is: hmget key field [field ...]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct HMGetRequest {
+    key: Arc<Box<[u8]>>,
+    fields: Box<[Arc<Box<[u8]>>]>,
+}
+
+impl TryFrom<Message> for HMGetRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        if let Message::Array(array) = other {
+            if array.inner.is_none() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let mut array = array.inner.unwrap();
+
+            if array.len() < 3 {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let key = take_bulk_string(&mut array)?;
+            if key.is_empty() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let mut fields = Vec::with_capacity(array.len() - 1);
+            while array.len() >= 2 {
+                fields.push(take_bulk_string(&mut array)?);
+            }
+
+            Ok(Self {
+                key,
+                fields: fields.into_boxed_slice(),
+            })
+        } else {
+            Err(Error::new(ErrorKind::Other, "malformed command"))
+        }
+    }
+}
+
+impl HMGetRequest {
+    pub fn new(key: &[u8], fields: &[&[u8]]) -> Self {
+        Self {
+            key: Arc::new(key.to_owned().into_boxed_slice()),
+            fields: fields
+                .iter()
+                .map(|e| Arc::new(e.to_vec().into_boxed_slice()))
+                .collect(),
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn fields(&self) -> Box<[&[u8]]> {
+        self.fields.iter().map(|e| &***e).collect()
+    }
+}
+
+impl From<&HMGetRequest> for Message {
+    fn from(other: &HMGetRequest) -> Message {
+        let mut v = vec![
+            Message::bulk_string(b"HMGET"),
+            Message::BulkString(BulkString::from(other.key.clone())),
+        ];
+        for field in other.fields.iter() {
+            v.push(Message::BulkString(BulkString::from(field.clone())));
+        }
+        Message::Array(Array { inner: Some(v) })
+    }
+}
+
+impl Compose for HMGetRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for HMGetRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        HMGET.increment();
+        if let Response::Array(Array { inner: Some(v) }) = response {
+            for (field, value) in self.fields.iter().zip(v.iter()) {
+                let (code, len) = match value {
+                    Response::BulkString(BulkString { inner: Some(v) }) => (HIT, v.len()),
+                    _ => (MISS, 0),
+                };
+                klog!(
+                    "\"hmget {} {}\" {} {}",
+                    string_key(self.key()),
+                    string_key(field),
+                    code,
+                    len
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"hmget 0 a\r\n").unwrap().into_inner(),
+            Request::HMGet(HMGetRequest::new(b"0", &[b"a"]))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*4\r\n$5\r\nHMGET\r\n$1\r\n0\r\n$1\r\na\r\n$1\r\nb\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::HMGet(HMGetRequest::new(b"0", &[b"a", b"b"]))
+        );
+
+        assert!(parser.parse(b"hmget 0\r\n").is_err());
+    }
+}
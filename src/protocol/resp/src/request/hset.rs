@@ -0,0 +1,149 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+
+/// Sets one or more fields to their values within the hash stored at the key.
+/// format is: hset key field value [field value ...]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+#[allow(clippy::type_complexity)]
+pub struct HSetRequest {
+    key: Arc<Box<[u8]>>,
+    pairs: Box<[(Arc<Box<[u8]>>, Arc<Box<[u8]>>)]>,
+}
+
+impl TryFrom<Message> for HSetRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        if let Message::Array(array) = other {
+            if array.inner.is_none() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let mut array = array.inner.unwrap();
+
+            // the command and key must be followed by complete pairs
+            if array.len() < 4 || array.len() % 2 != 0 {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let key = take_bulk_string(&mut array)?;
+            if key.is_empty() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let mut pairs = Vec::with_capacity((array.len() - 1) / 2);
+            while array.len() >= 3 {
+                let field = take_bulk_string(&mut array)?;
+                let value = take_bulk_string(&mut array)?;
+                pairs.push((field, value));
+            }
+
+            Ok(Self {
+                key,
+                pairs: pairs.into_boxed_slice(),
+            })
+        } else {
+            Err(Error::new(ErrorKind::Other, "malformed command"))
+        }
+    }
+}
+
+impl HSetRequest {
+    pub fn new(key: &[u8], pairs: &[(&[u8], &[u8])]) -> Self {
+        Self {
+            key: Arc::new(key.to_owned().into_boxed_slice()),
+            pairs: pairs
+                .iter()
+                .map(|(f, v)| {
+                    (
+                        Arc::new(f.to_vec().into_boxed_slice()),
+                        Arc::new(v.to_vec().into_boxed_slice()),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn pairs(&self) -> Box<[(&[u8], &[u8])]> {
+        self.pairs.iter().map(|(f, v)| (&***f, &***v)).collect()
+    }
+}
+
+impl From<&HSetRequest> for Message {
+    fn from(other: &HSetRequest) -> Message {
+        let mut v = vec![
+            Message::bulk_string(b"HSET"),
+            Message::BulkString(BulkString::from(other.key.clone())),
+        ];
+        for (field, value) in other.pairs.iter() {
+            v.push(Message::BulkString(BulkString::from(field.clone())));
+            v.push(Message::BulkString(BulkString::from(value.clone())));
+        }
+        Message::Array(Array { inner: Some(v) })
+    }
+}
+
+impl Compose for HSetRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for HSetRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        HSET.increment();
+        let code = match response {
+            Response::Integer(_) => STORED,
+            _ => NOT_STORED,
+        };
+        klog!(
+            "\"hset {} {}\" {}",
+            string_key(self.key()),
+            self.pairs.len(),
+            code
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"hset 0 a 1\r\n").unwrap().into_inner(),
+            Request::HSet(HSetRequest::new(b"0", &[(b"a", b"1")]))
+        );
+
+        assert_eq!(
+            parser.parse(b"HSET 0 a 1 b 2\r\n").unwrap().into_inner(),
+            Request::HSet(HSetRequest::new(b"0", &[(b"a", b"1"), (b"b", b"2")]))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*4\r\n$4\r\nhset\r\n$1\r\n0\r\n$1\r\na\r\n$0\r\n\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::HSet(HSetRequest::new(b"0", &[(b"a", b"")]))
+        );
+
+        assert!(parser.parse(b"hset 0 a\r\n").is_err());
+        assert!(parser.parse(b"hset 0 a 1 b\r\n").is_err());
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::Error;
+use std::sync::Arc;
+
+/// Increments the integer value stored at the key by one. Keys which do not
+/// exist are treated as zero before the increment.
+/// format is: incr key
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct IncrRequest {
+    key: Arc<Box<[u8]>>,
+}
+
+impl TryFrom<Message> for IncrRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        let mut array = command_args(other, Arity::Exactly(1))?;
+        let key = take_key(&mut array)?;
+        Ok(Self { key })
+    }
+}
+
+impl IncrRequest {
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            key: Arc::new(key.to_owned().into_boxed_slice()),
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+}
+
+impl From<&IncrRequest> for Message {
+    fn from(other: &IncrRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![
+                Message::bulk_string(b"INCR"),
+                Message::BulkString(BulkString::from(other.key.clone())),
+            ]),
+        })
+    }
+}
+
+impl Compose for IncrRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for IncrRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        if let Response::Integer(ref res) = response {
+            klog!(
+                "\"incr {}\" {} {}",
+                string_key(self.key()),
+                STORED,
+                res.inner
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"incr 0\r\n").unwrap().into_inner(),
+            Request::Incr(IncrRequest::new(b"0"))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*2\r\n$4\r\nINCR\r\n$1\r\n0\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::Incr(IncrRequest::new(b"0"))
+        );
+
+        assert!(matches!(
+            parser.parse(b"incr\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+        assert!(matches!(
+            parser.parse(b"incr 0 1\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+    }
+}
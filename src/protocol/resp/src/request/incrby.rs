@@ -0,0 +1,122 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::Error;
+use std::sync::Arc;
+
+/// Increments the integer value stored at the key by the given amount. Keys
+/// which do not exist are treated as zero before the increment.
+/// format is: incrby key increment
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct IncrByRequest {
+    key: Arc<Box<[u8]>>,
+    increment: i64,
+}
+
+impl TryFrom<Message> for IncrByRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        let mut array = command_args(other, Arity::Exactly(2))?;
+        let key = take_key(&mut array)?;
+        let increment = take_bulk_string_as_i64(&mut array)?;
+        Ok(Self { key, increment })
+    }
+}
+
+impl IncrByRequest {
+    pub fn new(key: &[u8], increment: i64) -> Self {
+        Self {
+            key: Arc::new(key.to_owned().into_boxed_slice()),
+            increment,
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn increment(&self) -> i64 {
+        self.increment
+    }
+}
+
+impl From<&IncrByRequest> for Message {
+    fn from(other: &IncrByRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![
+                Message::bulk_string(b"INCRBY"),
+                Message::BulkString(BulkString::from(other.key.clone())),
+                Message::bulk_string(format!("{}", other.increment).as_bytes()),
+            ]),
+        })
+    }
+}
+
+impl Compose for IncrByRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for IncrByRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        INCRBY.increment();
+        if let Response::Integer(ref res) = response {
+            klog!(
+                "\"incrby {} {}\" {} {}",
+                string_key(self.key()),
+                self.increment,
+                STORED,
+                res.inner
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"incrby 0 5\r\n").unwrap().into_inner(),
+            Request::IncrBy(IncrByRequest::new(b"0", 5))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*3\r\n$6\r\nINCRBY\r\n$1\r\n0\r\n$1\r\n5\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::IncrBy(IncrByRequest::new(b"0", 5))
+        );
+
+        assert_eq!(
+            parser.parse(b"incrby 0 -5\r\n").unwrap().into_inner(),
+            Request::IncrBy(IncrByRequest::new(b"0", -5))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*3\r\n$6\r\nINCRBY\r\n$1\r\n0\r\n$2\r\n-5\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::IncrBy(IncrByRequest::new(b"0", -5))
+        );
+
+        assert!(matches!(
+            parser.parse(b"incrby 0\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+        assert!(parser.parse(b"incrby 0 a\r\n").is_err());
+    }
+}
@@ -0,0 +1,159 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::sync::Arc;
+
+/// A command which is answered with an error reply, while the session is kept
+/// open. This is a command which was sent with the wrong number of arguments,
+/// or, inside a transaction, any command which could not be queued.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct InvalidRequest {
+    command: Arc<Box<[u8]>>,
+    reason: Reason,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Reason {
+    WrongArity,
+    // unknown commands and invalid arguments are only answered with an error
+    // reply inside a transaction, outside of one the session is closed
+    Malformed,
+    TooManyQueued,
+}
+
+impl InvalidRequest {
+    pub fn new(command: &[u8]) -> Self {
+        Self {
+            command: Arc::new(command.to_owned().into_boxed_slice()),
+            reason: Reason::WrongArity,
+        }
+    }
+
+    #[allow(clippy::redundant_allocation)]
+    pub(crate) fn too_many_queued(command: Arc<Box<[u8]>>) -> Self {
+        Self {
+            command,
+            reason: Reason::TooManyQueued,
+        }
+    }
+
+    /// The name of the command, as it was sent by the client.
+    pub fn command(&self) -> &[u8] {
+        &self.command
+    }
+
+    pub fn response(&self) -> Response {
+        let command = string_key(self.command()).to_lowercase();
+        Response::error(match self.reason {
+            Reason::WrongArity => {
+                format!("ERR wrong number of arguments for '{}' command", command)
+            }
+            Reason::Malformed => format!("ERR invalid '{}' command", command),
+            Reason::TooManyQueued => {
+                format!("ERR too many commands queued, '{}' was not queued", command)
+            }
+        })
+    }
+}
+
+impl RequestParser {
+    /// Turns an error for a command with the wrong number of arguments into a
+    /// request which is answered with an error reply. Inside a transaction,
+    /// all errors for a command are turned into an error reply so that the
+    /// transaction can be aborted. All other errors are returned unchanged.
+    #[allow(clippy::redundant_allocation)]
+    pub(crate) fn invalid(&self, command: Arc<Box<[u8]>>, error: Error) -> Result<Request, Error> {
+        let reason = if is_wrong_arity(&error) {
+            Reason::WrongArity
+        } else if self.transaction.borrow().is_some() {
+            Reason::Malformed
+        } else {
+            return Err(error);
+        };
+        Ok(Request::from(InvalidRequest { command, reason }))
+    }
+}
+
+impl From<&InvalidRequest> for Message {
+    fn from(other: &InvalidRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![Message::BulkString(BulkString::from(
+                other.command.clone(),
+            ))]),
+        })
+    }
+}
+
+impl Compose for InvalidRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for InvalidRequest {
+    type Response = Response;
+
+    fn klog(&self, _response: &Self::Response) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        for (buffer, command) in [
+            (&b"expire 0\r\n"[..], "expire"),
+            (b"*2\r\n$6\r\nEXPIRE\r\n$1\r\n0\r\n", "expire"),
+            (b"ping a b\r\n", "ping"),
+            (b"mset a\r\n", "mset"),
+            (b"mset a 1 b\r\n", "mset"),
+            (b"del\r\n", "del"),
+        ] {
+            let request = parser.parse(buffer).unwrap().into_inner();
+            if let Request::Invalid(invalid) = request {
+                assert_eq!(
+                    invalid.response(),
+                    Response::error(format!(
+                        "ERR wrong number of arguments for '{}' command",
+                        command
+                    ))
+                );
+            } else {
+                panic!("unexpected request: {:?}", request);
+            }
+        }
+
+        // other errors still fail the parse
+        assert!(parser.parse(b"expire 0 a\r\n").is_err());
+        assert!(parser.parse(b"foo\r\n").is_err());
+    }
+
+    #[test]
+    fn transaction() {
+        let parser = RequestParser::new();
+        parser.parse(b"multi\r\n").unwrap();
+
+        // inside a transaction any command error is an error reply
+        for (buffer, response) in [
+            (&b"expire 0 a\r\n"[..], "ERR invalid 'expire' command"),
+            (b"FOO\r\n", "ERR invalid 'foo' command"),
+            (
+                b"ttl\r\n",
+                "ERR wrong number of arguments for 'ttl' command",
+            ),
+        ] {
+            let request = parser.parse(buffer).unwrap().into_inner();
+            if let Request::Invalid(invalid) = request {
+                assert_eq!(invalid.response(), Response::error(response));
+            } else {
+                panic!("unexpected request: {:?}", request);
+            }
+        }
+    }
+}
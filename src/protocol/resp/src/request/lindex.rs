@@ -0,0 +1,129 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+
+/// Returns the element at the index within the list stored at the key.
+/// Negative indices count backwards from the tail of the list.
+/// format is: lindex key index
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct LIndexRequest {
+    key: Arc<Box<[u8]>>,
+    index: i64,
+}
+
+impl TryFrom<Message> for LIndexRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        if let Message::Array(array) = other {
+            if array.inner.is_none() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let mut array = array.inner.unwrap();
+
+            if array.len() != 3 {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let key = take_bulk_string(&mut array)?;
+            if key.is_empty() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let index = take_bulk_string_as_i64(&mut array)?;
+
+            Ok(Self { key, index })
+        } else {
+            Err(Error::new(ErrorKind::Other, "malformed command"))
+        }
+    }
+}
+
+impl LIndexRequest {
+    pub fn new(key: &[u8], index: i64) -> Self {
+        Self {
+            key: Arc::new(key.to_owned().into_boxed_slice()),
+            index,
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn index(&self) -> i64 {
+        self.index
+    }
+}
+
+impl From<&LIndexRequest> for Message {
+    fn from(other: &LIndexRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![
+                Message::bulk_string(b"LINDEX"),
+                Message::BulkString(BulkString::from(other.key.clone())),
+                Message::bulk_string(format!("{}", other.index).as_bytes()),
+            ]),
+        })
+    }
+}
+
+impl Compose for LIndexRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for LIndexRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        LINDEX.increment();
+        let (code, len) = match response {
+            Response::BulkString(BulkString { inner: Some(v) }) => (HIT, v.len()),
+            Response::BulkString(_) => (MISS, 0),
+            _ => {
+                return;
+            }
+        };
+        klog!(
+            "\"lindex {} {}\" {} {}",
+            string_key(self.key()),
+            self.index,
+            code,
+            len
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"lindex 0 1\r\n").unwrap().into_inner(),
+            Request::LIndex(LIndexRequest::new(b"0", 1))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*3\r\n$6\r\nLINDEX\r\n$1\r\n0\r\n$2\r\n-1\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::LIndex(LIndexRequest::new(b"0", -1))
+        );
+
+        assert!(parser.parse(b"lindex 0\r\n").is_err());
+        assert!(parser.parse(b"lindex 0 a\r\n").is_err());
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+
+/// Removes and returns elements from the head of the list stored at the key.
+/// format is: lpop key [count]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct LPopRequest {
+    key: Arc<Box<[u8]>>,
+    count: Option<u64>,
+}
+
+impl TryFrom<Message> for LPopRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        if let Message::Array(array) = other {
+            if array.inner.is_none() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let mut array = array.inner.unwrap();
+
+            if array.len() != 2 && array.len() != 3 {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let key = take_bulk_string(&mut array)?;
+            if key.is_empty() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let count = if array.len() == 2 {
+                Some(take_bulk_string_as_u64(&mut array)?)
+            } else {
+                None
+            };
+
+            Ok(Self { key, count })
+        } else {
+            Err(Error::new(ErrorKind::Other, "malformed command"))
+        }
+    }
+}
+
+impl LPopRequest {
+    pub fn new(key: &[u8], count: Option<u64>) -> Self {
+        Self {
+            key: Arc::new(key.to_owned().into_boxed_slice()),
+            count,
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    /// The number of elements to remove. If this is provided, the response is
+    /// an array even when only one element is removed.
+    pub fn count(&self) -> Option<u64> {
+        self.count
+    }
+}
+
+impl From<&LPopRequest> for Message {
+    fn from(other: &LPopRequest) -> Message {
+        let mut v = vec![
+            Message::bulk_string(b"LPOP"),
+            Message::BulkString(BulkString::from(other.key.clone())),
+        ];
+        if let Some(count) = other.count {
+            v.push(Message::bulk_string(format!("{}", count).as_bytes()));
+        }
+        Message::Array(Array { inner: Some(v) })
+    }
+}
+
+impl Compose for LPopRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for LPopRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        LPOP.increment();
+        let (code, len) = match response {
+            Response::BulkString(BulkString { inner: Some(v) }) => (HIT, v.len()),
+            Response::Array(Array { inner: Some(v) }) => (HIT, v.len()),
+            Response::BulkString(_) | Response::Array(_) => (MISS, 0),
+            _ => {
+                return;
+            }
+        };
+        klog!("\"lpop {}\" {} {}", string_key(self.key()), code, len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"lpop 0\r\n").unwrap().into_inner(),
+            Request::LPop(LPopRequest::new(b"0", None))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*3\r\n$4\r\nLPOP\r\n$1\r\n0\r\n$1\r\n2\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::LPop(LPopRequest::new(b"0", Some(2)))
+        );
+
+        assert!(parser.parse(b"lpop 0 -1\r\n").is_err());
+        assert!(parser.parse(b"lpop 0 1 2\r\n").is_err());
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+
+/// Inserts one or more elements at the head of the list stored at the key.
+/// format is: lpush key element [element ...]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct LPushRequest {
+    key: Arc<Box<[u8]>>,
+    elements: Box<[Arc<Box<[u8]>>]>,
+}
+
+impl TryFrom<Message> for LPushRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        if let Message::Array(array) = other {
+            if array.inner.is_none() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let mut array = array.inner.unwrap();
+
+            if array.len() < 3 {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let key = take_bulk_string(&mut array)?;
+            if key.is_empty() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let mut elements = Vec::with_capacity(array.len() - 1);
+            while array.len() >= 2 {
+                elements.push(take_bulk_string(&mut array)?);
+            }
+
+            Ok(Self {
+                key,
+                elements: elements.into_boxed_slice(),
+            })
+        } else {
+            Err(Error::new(ErrorKind::Other, "malformed command"))
+        }
+    }
+}
+
+impl LPushRequest {
+    pub fn new(key: &[u8], elements: &[&[u8]]) -> Self {
+        Self {
+            key: Arc::new(key.to_owned().into_boxed_slice()),
+            elements: elements
+                .iter()
+                .map(|e| Arc::new(e.to_vec().into_boxed_slice()))
+                .collect(),
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn elements(&self) -> Box<[&[u8]]> {
+        self.elements.iter().map(|e| &***e).collect()
+    }
+}
+
+impl From<&LPushRequest> for Message {
+    fn from(other: &LPushRequest) -> Message {
+        let mut v = vec![
+            Message::bulk_string(b"LPUSH"),
+            Message::BulkString(BulkString::from(other.key.clone())),
+        ];
+        for element in other.elements.iter() {
+            v.push(Message::BulkString(BulkString::from(element.clone())));
+        }
+        Message::Array(Array { inner: Some(v) })
+    }
+}
+
+impl Compose for LPushRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for LPushRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        LPUSH.increment();
+        if let Response::Integer(ref res) = response {
+            klog!(
+                "\"lpush {} {}\" {} {}",
+                string_key(self.key()),
+                self.elements.len(),
+                STORED,
+                res.inner
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"lpush 0 1\r\n").unwrap().into_inner(),
+            Request::LPush(LPushRequest::new(b"0", &[b"1"]))
+        );
+
+        assert_eq!(
+            parser.parse(b"LPUSH 0 1 2 3\r\n").unwrap().into_inner(),
+            Request::LPush(LPushRequest::new(b"0", &[b"1", b"2", b"3"]))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*4\r\n$5\r\nlpush\r\n$1\r\n0\r\n$1\r\n1\r\n$0\r\n\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::LPush(LPushRequest::new(b"0", &[b"1", b""]))
+        );
+
+        assert!(parser.parse(b"lpush 0\r\n").is_err());
+    }
+}
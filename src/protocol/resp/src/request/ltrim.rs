@@ -0,0 +1,132 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+
+/// Trims the list stored at the key so that it only contains the elements
+/// between the start and stop indices, inclusive. Negative indices count
+/// backwards from the tail of the list.
+/// format is: ltrim key start stop
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct LTrimRequest {
+    key: Arc<Box<[u8]>>,
+    start: i64,
+    stop: i64,
+}
+
+impl TryFrom<Message> for LTrimRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        if let Message::Array(array) = other {
+            if array.inner.is_none() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let mut array = array.inner.unwrap();
+
+            if array.len() != 4 {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let key = take_bulk_string(&mut array)?;
+            if key.is_empty() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let start = take_bulk_string_as_i64(&mut array)?;
+            let stop = take_bulk_string_as_i64(&mut array)?;
+
+            Ok(Self { key, start, stop })
+        } else {
+            Err(Error::new(ErrorKind::Other, "malformed command"))
+        }
+    }
+}
+
+impl LTrimRequest {
+    pub fn new(key: &[u8], start: i64, stop: i64) -> Self {
+        Self {
+            key: Arc::new(key.to_owned().into_boxed_slice()),
+            start,
+            stop,
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn start(&self) -> i64 {
+        self.start
+    }
+
+    pub fn stop(&self) -> i64 {
+        self.stop
+    }
+}
+
+impl From<&LTrimRequest> for Message {
+    fn from(other: &LTrimRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![
+                Message::bulk_string(b"LTRIM"),
+                Message::BulkString(BulkString::from(other.key.clone())),
+                Message::bulk_string(format!("{}", other.start).as_bytes()),
+                Message::bulk_string(format!("{}", other.stop).as_bytes()),
+            ]),
+        })
+    }
+}
+
+impl Compose for LTrimRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for LTrimRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        LTRIM.increment();
+        if let Response::SimpleString(_) = response {
+            klog!(
+                "\"ltrim {} {} {}\" {}",
+                string_key(self.key()),
+                self.start,
+                self.stop,
+                STORED
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"ltrim 0 0 -1\r\n").unwrap().into_inner(),
+            Request::LTrim(LTrimRequest::new(b"0", 0, -1))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*4\r\n$5\r\nLTRIM\r\n$1\r\n0\r\n$1\r\n1\r\n$1\r\n2\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::LTrim(LTrimRequest::new(b"0", 1, 2))
+        );
+
+        assert!(parser.parse(b"ltrim 0 1\r\n").is_err());
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::Error;
+use std::sync::Arc;
+
+/// Returns the values of one or more keys. Keys which do not exist or which do
+/// not hold a string have a null value.
+/// format is: mget key [key ...]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct MGetRequest {
+    keys: Box<[Arc<Box<[u8]>>]>,
+}
+
+impl TryFrom<Message> for MGetRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        let mut array = command_args(other, Arity::AtLeast(1))?;
+        let keys = take_keys(&mut array)?;
+        Ok(Self { keys })
+    }
+}
+
+impl MGetRequest {
+    pub fn new(keys: &[&[u8]]) -> Self {
+        Self {
+            keys: keys
+                .iter()
+                .map(|k| Arc::new(k.to_vec().into_boxed_slice()))
+                .collect(),
+        }
+    }
+
+    pub fn keys(&self) -> Box<[&[u8]]> {
+        self.keys.iter().map(|k| &***k).collect()
+    }
+}
+
+impl From<&MGetRequest> for Message {
+    fn from(other: &MGetRequest) -> Message {
+        let mut v = vec![Message::bulk_string(b"MGET")];
+        for key in other.keys.iter() {
+            v.push(Message::BulkString(BulkString::from(key.clone())));
+        }
+        Message::Array(Array { inner: Some(v) })
+    }
+}
+
+impl Compose for MGetRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for MGetRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        MGET.increment();
+        if let Response::Array(Array {
+            inner: Some(values),
+        }) = response
+        {
+            for (key, value) in self.keys.iter().zip(values.iter()) {
+                let (code, len) = match value {
+                    Response::BulkString(BulkString { inner: Some(v) }) => (HIT, v.len()),
+                    _ => (MISS, 0),
+                };
+                klog!("\"mget {}\" {} {}", string_key(key), code, len);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"mget 0\r\n").unwrap().into_inner(),
+            Request::MGet(MGetRequest::new(&[b"0"]))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*2\r\n$4\r\nMGET\r\n$1\r\n0\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::MGet(MGetRequest::new(&[b"0"]))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*3\r\n$4\r\nMGET\r\n$1\r\n0\r\n$1\r\n1\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::MGet(MGetRequest::new(&[b"0", b"1"]))
+        );
+
+        assert!(matches!(
+            parser.parse(b"mget\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+    }
+}
@@ -4,29 +4,207 @@
 
 use crate::message::*;
 use crate::*;
+use logger::Klog;
 use protocol_common::BufMut;
+use protocol_common::Correlate;
+use protocol_common::ErrorResponse;
 use protocol_common::Parse;
 use protocol_common::ParseOk;
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
 use std::io::{Error, ErrorKind};
 use std::sync::Arc;
 
+mod append;
 mod badd;
+mod bcard;
+mod bdel;
+mod bget;
+mod brange;
+mod decr;
+mod decrby;
+mod del;
+mod discard;
+mod echo;
+mod exec;
+mod exists;
+mod expire;
+mod flushdb;
 mod get;
+mod getset;
+mod hdel;
+mod hello;
+mod hexists;
+mod hget;
+mod hgetall;
+mod hincrby;
+mod hlen;
+mod hmget;
+mod hset;
+mod incr;
+mod incrby;
+mod invalid;
+mod lindex;
+mod llen;
+mod lpop;
+mod lpush;
+mod lrange;
+mod ltrim;
+mod mget;
+mod mset;
+mod multi;
+mod persist;
+mod pexpire;
+mod ping;
+mod pttl;
+mod resp3;
+mod rpop;
+mod rpush;
 mod set;
+mod setnx;
+mod strlen;
+mod ttl;
+mod r#type;
+mod unwatch;
+mod watch;
 
+pub use append::AppendRequest;
 pub use badd::BAddRequest;
+pub use bcard::BCardRequest;
+pub use bdel::BDelRequest;
+pub use bget::BGetRequest;
+pub use brange::BRangeRequest;
+pub use decr::DecrRequest;
+pub use decrby::DecrByRequest;
+pub use del::DelRequest;
+pub use discard::DiscardRequest;
+pub use echo::EchoRequest;
+pub use exec::ExecRequest;
+pub use exists::ExistsRequest;
+pub use expire::ExpireRequest;
+pub use flushdb::FlushDbRequest;
 pub use get::GetRequest;
-pub use set::SetRequest;
+pub use getset::GetSetRequest;
+pub use hdel::HDelRequest;
+pub use hello::{HelloRequest, ProtocolVersion};
+pub use hexists::HExistsRequest;
+pub use hget::HGetRequest;
+pub use hgetall::HGetAllRequest;
+pub use hincrby::HIncrByRequest;
+pub use hlen::HLenRequest;
+pub use hmget::HMGetRequest;
+pub use hset::HSetRequest;
+pub use incr::IncrRequest;
+pub use incrby::IncrByRequest;
+pub use invalid::InvalidRequest;
+pub use lindex::LIndexRequest;
+pub use llen::LLenRequest;
+pub use lpop::LPopRequest;
+pub use lpush::LPushRequest;
+pub use lrange::LRangeRequest;
+pub use ltrim::LTrimRequest;
+pub use mget::MGetRequest;
+pub use mset::MSetRequest;
+pub use multi::{MultiRequest, QueuedRequest};
+pub use persist::PersistRequest;
+pub use pexpire::PExpireRequest;
+pub use ping::PingRequest;
+pub use pttl::PTtlRequest;
+pub use r#type::TypeRequest;
+pub use resp3::Resp3Request;
+pub use rpop::RPopRequest;
+pub use rpush::RPushRequest;
+pub use set::{SetMode, SetRequest};
+pub use setnx::SetNxRequest;
+pub use strlen::StrLenRequest;
+pub use ttl::TtlRequest;
+pub use unwatch::UnwatchRequest;
+pub use watch::WatchRequest;
+
+use multi::Transaction;
+use watch::WatchedKeys;
+
+pub const DEFAULT_MAX_QUEUED: usize = 1024;
+
+// response codes for klog
+const MISS: u8 = 0;
+const HIT: u8 = 4;
+const STORED: u8 = 5;
+const DELETED: u8 = 7;
+const NOT_FOUND: u8 = 8;
+const NOT_STORED: u8 = 9;
+
+fn string_key(key: &[u8]) -> Cow<'_, str> {
+    String::from_utf8_lossy(key)
+}
+
+// formats multiple keys for klog, separated by spaces
+fn string_keys<'a>(keys: impl Iterator<Item = &'a [u8]>) -> String {
+    keys.map(string_key).collect::<Vec<_>>().join(" ")
+}
 
-#[derive(Default)]
 pub struct RequestParser {
     message_parser: MessageParser,
+    max_queued: usize,
+    // each session has its own clone of the parser, so this tracks the
+    // protocol version negotiated for the session
+    protocol: Cell<ProtocolVersion>,
+    // the commands queued since `MULTI`, or `None` outside of a transaction
+    transaction: RefCell<Option<Transaction>>,
+    watched: RefCell<WatchedKeys>,
+}
+
+// the watched keys are shared with requests, so a clone must not share them
+// with the parser it was cloned from
+impl Clone for RequestParser {
+    fn clone(&self) -> Self {
+        Self {
+            message_parser: self.message_parser.clone(),
+            max_queued: self.max_queued,
+            protocol: self.protocol.clone(),
+            transaction: self.transaction.clone(),
+            watched: RefCell::new(WatchedKeys::default()),
+        }
+    }
+}
+
+impl Default for RequestParser {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl RequestParser {
     pub fn new() -> Self {
         Self {
             message_parser: MessageParser {},
+            max_queued: DEFAULT_MAX_QUEUED,
+            protocol: Cell::new(ProtocolVersion::default()),
+            transaction: RefCell::new(None),
+            watched: RefCell::new(WatchedKeys::default()),
+        }
+    }
+
+    /// The maximum number of commands which can be queued in a transaction.
+    /// Any further command is answered with an error reply and the
+    /// transaction is aborted.
+    pub fn max_queued(mut self, count: usize) -> Self {
+        self.max_queued = count;
+        self
+    }
+
+    /// The protocol version which has been negotiated with `HELLO`.
+    pub fn protocol(&self) -> ProtocolVersion {
+        self.protocol.get()
+    }
+
+    /// Wraps the request if the session uses RESP3, so that its reply is
+    /// converted to the RESP3 types. `HELLO` already replies using the version
+    /// it negotiated, so it is never wrapped.
+    fn resp3(&self, request: Request) -> Request {
+        match (self.protocol.get(), &request) {
+            (ProtocolVersion::Resp3, Request::Hello(_)) | (ProtocolVersion::Resp2, _) => request,
+            (ProtocolVersion::Resp3, _) => Request::from(Resp3Request::new(request)),
         }
     }
 }
@@ -75,6 +253,17 @@ impl Parse<Request> for RequestParser {
             (message, consumed)
         };
 
+        // the command name is kept to report errors which keep the session open
+        let command = match &message {
+            Message::Array(Array { inner: Some(array) }) => match array.first() {
+                Some(Message::BulkString(BulkString {
+                    inner: Some(command),
+                })) => command.clone(),
+                _ => return Err(Error::new(ErrorKind::Other, "malformed command")),
+            },
+            _ => return Err(Error::new(ErrorKind::Other, "malformed command")),
+        };
+
         match &message {
             Message::Array(array) => {
                 if array.inner.is_none() {
@@ -89,15 +278,150 @@ impl Parse<Request> for RequestParser {
 
                 match &array[0] {
                     Message::BulkString(c) => match c.inner.as_ref().map(|v| v.as_ref().as_ref()) {
+                        Some(b"append") | Some(b"APPEND") => {
+                            AppendRequest::try_from(message).map(Request::from)
+                        }
                         Some(b"badd") | Some(b"BADD") => {
                             BAddRequest::try_from(message).map(Request::from)
                         }
+                        Some(b"bcard") | Some(b"BCARD") => {
+                            BCardRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"bdel") | Some(b"BDEL") => {
+                            BDelRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"bget") | Some(b"BGET") => {
+                            BGetRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"brange") | Some(b"BRANGE") => {
+                            BRangeRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"decr") | Some(b"DECR") => {
+                            DecrRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"decrby") | Some(b"DECRBY") => {
+                            DecrByRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"del") | Some(b"DEL") => {
+                            DelRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"discard") | Some(b"DISCARD") => DiscardRequest::try_from(message)
+                            .map(|discard| Request::from(self.discard(discard))),
+                        Some(b"echo") | Some(b"ECHO") => {
+                            EchoRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"exec") | Some(b"EXEC") => ExecRequest::try_from(message)
+                            .map(|exec| Request::from(self.exec(exec))),
+                        Some(b"exists") | Some(b"EXISTS") => {
+                            ExistsRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"expire") | Some(b"EXPIRE") => {
+                            ExpireRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"flushdb") | Some(b"FLUSHDB") => {
+                            FlushDbRequest::try_from(message).map(Request::from)
+                        }
                         Some(b"get") | Some(b"GET") => {
                             GetRequest::try_from(message).map(Request::from)
                         }
+                        Some(b"getset") | Some(b"GETSET") => {
+                            GetSetRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"hdel") | Some(b"HDEL") => {
+                            HDelRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"hello") | Some(b"HELLO") => HelloRequest::try_from(message)
+                            .map(|hello| Request::from(self.hello(hello))),
+                        Some(b"hexists") | Some(b"HEXISTS") => {
+                            HExistsRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"hget") | Some(b"HGET") => {
+                            HGetRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"hgetall") | Some(b"HGETALL") => {
+                            HGetAllRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"hincrby") | Some(b"HINCRBY") => {
+                            HIncrByRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"hlen") | Some(b"HLEN") => {
+                            HLenRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"hmget") | Some(b"HMGET") => {
+                            HMGetRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"hset") | Some(b"HSET") => {
+                            HSetRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"incr") | Some(b"INCR") => {
+                            IncrRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"incrby") | Some(b"INCRBY") => {
+                            IncrByRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"lindex") | Some(b"LINDEX") => {
+                            LIndexRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"llen") | Some(b"LLEN") => {
+                            LLenRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"lpop") | Some(b"LPOP") => {
+                            LPopRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"lpush") | Some(b"LPUSH") => {
+                            LPushRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"lrange") | Some(b"LRANGE") => {
+                            LRangeRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"ltrim") | Some(b"LTRIM") => {
+                            LTrimRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"mget") | Some(b"MGET") => {
+                            MGetRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"mset") | Some(b"MSET") => {
+                            MSetRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"multi") | Some(b"MULTI") => MultiRequest::try_from(message)
+                            .map(|multi| Request::from(self.multi(multi))),
+                        Some(b"persist") | Some(b"PERSIST") => {
+                            PersistRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"pexpire") | Some(b"PEXPIRE") => {
+                            PExpireRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"ping") | Some(b"PING") => {
+                            PingRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"pttl") | Some(b"PTTL") => {
+                            PTtlRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"rpop") | Some(b"RPOP") => {
+                            RPopRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"rpush") | Some(b"RPUSH") => {
+                            RPushRequest::try_from(message).map(Request::from)
+                        }
                         Some(b"set") | Some(b"SET") => {
                             SetRequest::try_from(message).map(Request::from)
                         }
+                        Some(b"setnx") | Some(b"SETNX") => {
+                            SetNxRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"strlen") | Some(b"STRLEN") => {
+                            StrLenRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"ttl") | Some(b"TTL") => {
+                            TtlRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"type") | Some(b"TYPE") => {
+                            TypeRequest::try_from(message).map(Request::from)
+                        }
+                        Some(b"unwatch") | Some(b"UNWATCH") => UnwatchRequest::try_from(message)
+                            .map(|unwatch| Request::from(self.unwatch(unwatch))),
+                        Some(b"watch") | Some(b"WATCH") => WatchRequest::try_from(message)
+                            .map(|watch| Request::from(self.watch(watch))),
                         _ => Err(Error::new(ErrorKind::Other, "unknown command")),
                     },
                     _ => {
@@ -111,25 +435,263 @@ impl Parse<Request> for RequestParser {
                 Err(Error::new(ErrorKind::Other, "malformed command"))
             }
         }
-        .map(|v| ParseOk::new(v, consumed))
+        .or_else(|e| self.invalid(command.clone(), e))
+        .map(|v| ParseOk::new(self.resp3(self.queue(command, v)), consumed))
     }
 }
 
 impl Compose for Request {
     fn compose(&self, buf: &mut dyn BufMut) -> usize {
         match self {
+            Self::Append(r) => r.compose(buf),
             Self::BAdd(r) => r.compose(buf),
+            Self::BCard(r) => r.compose(buf),
+            Self::BDel(r) => r.compose(buf),
+            Self::BGet(r) => r.compose(buf),
+            Self::BRange(r) => r.compose(buf),
+            Self::Decr(r) => r.compose(buf),
+            Self::DecrBy(r) => r.compose(buf),
+            Self::Del(r) => r.compose(buf),
+            Self::Discard(r) => r.compose(buf),
+            Self::Echo(r) => r.compose(buf),
+            Self::Exec(r) => r.compose(buf),
+            Self::Exists(r) => r.compose(buf),
+            Self::Expire(r) => r.compose(buf),
+            Self::FlushDb(r) => r.compose(buf),
             Self::Get(r) => r.compose(buf),
+            Self::GetSet(r) => r.compose(buf),
+            Self::HDel(r) => r.compose(buf),
+            Self::HExists(r) => r.compose(buf),
+            Self::HGet(r) => r.compose(buf),
+            Self::HGetAll(r) => r.compose(buf),
+            Self::HIncrBy(r) => r.compose(buf),
+            Self::HLen(r) => r.compose(buf),
+            Self::HMGet(r) => r.compose(buf),
+            Self::HSet(r) => r.compose(buf),
+            Self::Hello(r) => r.compose(buf),
+            Self::Incr(r) => r.compose(buf),
+            Self::IncrBy(r) => r.compose(buf),
+            Self::Invalid(r) => r.compose(buf),
+            Self::LIndex(r) => r.compose(buf),
+            Self::LLen(r) => r.compose(buf),
+            Self::LPop(r) => r.compose(buf),
+            Self::LPush(r) => r.compose(buf),
+            Self::LRange(r) => r.compose(buf),
+            Self::LTrim(r) => r.compose(buf),
+            Self::MGet(r) => r.compose(buf),
+            Self::MSet(r) => r.compose(buf),
+            Self::Multi(r) => r.compose(buf),
+            Self::Persist(r) => r.compose(buf),
+            Self::PExpire(r) => r.compose(buf),
+            Self::Ping(r) => r.compose(buf),
+            Self::PTtl(r) => r.compose(buf),
+            Self::Queued(r) => r.compose(buf),
+            Self::Resp3(r) => r.compose(buf),
+            Self::RPop(r) => r.compose(buf),
+            Self::RPush(r) => r.compose(buf),
             Self::Set(r) => r.compose(buf),
+            Self::SetNx(r) => r.compose(buf),
+            Self::StrLen(r) => r.compose(buf),
+            Self::Ttl(r) => r.compose(buf),
+            Self::Type(r) => r.compose(buf),
+            Self::Unwatch(r) => r.compose(buf),
+            Self::Watch(r) => r.compose(buf),
         }
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+impl Klog for Request {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        match self {
+            Self::Append(r) => r.klog(response),
+            Self::BAdd(r) => r.klog(response),
+            Self::BCard(r) => r.klog(response),
+            Self::BDel(r) => r.klog(response),
+            Self::BGet(r) => r.klog(response),
+            Self::BRange(r) => r.klog(response),
+            Self::Decr(r) => r.klog(response),
+            Self::DecrBy(r) => r.klog(response),
+            Self::Del(r) => r.klog(response),
+            Self::Discard(r) => r.klog(response),
+            Self::Echo(r) => r.klog(response),
+            Self::Exec(r) => r.klog(response),
+            Self::Exists(r) => r.klog(response),
+            Self::Expire(r) => r.klog(response),
+            Self::FlushDb(r) => r.klog(response),
+            Self::Get(r) => r.klog(response),
+            Self::GetSet(r) => r.klog(response),
+            Self::HDel(r) => r.klog(response),
+            Self::HExists(r) => r.klog(response),
+            Self::HGet(r) => r.klog(response),
+            Self::HGetAll(r) => r.klog(response),
+            Self::HIncrBy(r) => r.klog(response),
+            Self::HLen(r) => r.klog(response),
+            Self::HMGet(r) => r.klog(response),
+            Self::HSet(r) => r.klog(response),
+            Self::Hello(r) => r.klog(response),
+            Self::Incr(r) => r.klog(response),
+            Self::IncrBy(r) => r.klog(response),
+            Self::Invalid(r) => r.klog(response),
+            Self::LIndex(r) => r.klog(response),
+            Self::LLen(r) => r.klog(response),
+            Self::LPop(r) => r.klog(response),
+            Self::LPush(r) => r.klog(response),
+            Self::LRange(r) => r.klog(response),
+            Self::LTrim(r) => r.klog(response),
+            Self::MGet(r) => r.klog(response),
+            Self::MSet(r) => r.klog(response),
+            Self::Multi(r) => r.klog(response),
+            Self::Persist(r) => r.klog(response),
+            Self::PExpire(r) => r.klog(response),
+            Self::Ping(r) => r.klog(response),
+            Self::PTtl(r) => r.klog(response),
+            Self::Queued(r) => r.klog(response),
+            Self::Resp3(r) => r.klog(response),
+            Self::RPop(r) => r.klog(response),
+            Self::RPush(r) => r.klog(response),
+            Self::Set(r) => r.klog(response),
+            Self::SetNx(r) => r.klog(response),
+            Self::StrLen(r) => r.klog(response),
+            Self::Ttl(r) => r.klog(response),
+            Self::Type(r) => r.klog(response),
+            Self::Unwatch(r) => r.klog(response),
+            Self::Watch(r) => r.klog(response),
+        }
+    }
+}
+
+impl Correlate for Request {}
+
+impl ErrorResponse for Request {
+    type Response = Message;
+
+    fn error_response(&self, message: &str) -> Message {
+        Message::error(format!("ERR {}", message))
+    }
+
+    /// Checks that the response has the shape of a reply to this request.
+    /// Replies are paired with requests by their order, so a reply of the
+    /// wrong shape means that the pairing has been lost. Commands whose reply
+    /// may take several shapes are not checked.
+    fn is_reply(&self, response: &Message) -> bool {
+        if matches!(response, Message::Error(_)) {
+            return true;
+        }
+        match self {
+            Self::Append(_)
+            | Self::Decr(_)
+            | Self::DecrBy(_)
+            | Self::Del(_)
+            | Self::Exists(_)
+            | Self::Expire(_)
+            | Self::Incr(_)
+            | Self::IncrBy(_)
+            | Self::Persist(_)
+            | Self::PExpire(_)
+            | Self::PTtl(_)
+            | Self::SetNx(_)
+            | Self::StrLen(_)
+            | Self::Ttl(_) => matches!(response, Message::Integer(_)),
+            Self::Echo(_) | Self::Get(_) | Self::GetSet(_) => is_value(response),
+            Self::Discard(_)
+            | Self::FlushDb(_)
+            | Self::MSet(_)
+            | Self::Multi(_)
+            | Self::Queued(_)
+            | Self::Type(_)
+            | Self::Unwatch(_)
+            | Self::Watch(_) => matches!(response, Message::SimpleString(_)),
+            Self::Exec(_) => matches!(response, Message::Array(_) | Message::Null(_)),
+            Self::MGet(r) => is_values(response, r.keys().len()),
+            Self::HMGet(r) => is_values(response, r.fields().len()),
+            Self::Ping(_) => {
+                matches!(response, Message::SimpleString(_) | Message::BulkString(_))
+            }
+            Self::Set(_) => matches!(response, Message::SimpleString(_)) || is_value(response),
+            _ => true,
+        }
+    }
+}
+
+// a single value, which is nil if there is no value
+fn is_value(response: &Message) -> bool {
+    matches!(response, Message::BulkString(_) | Message::Null(_))
+}
+
+// one value, or nil, for each requested key or field
+fn is_values(response: &Message, len: usize) -> bool {
+    match response {
+        Message::Array(array) => match &array.inner {
+            Some(values) => values.len() == len && values.iter().all(is_value),
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Request {
+    Append(AppendRequest),
     BAdd(BAddRequest),
+    BCard(BCardRequest),
+    BDel(BDelRequest),
+    BGet(BGetRequest),
+    BRange(BRangeRequest),
+    Decr(DecrRequest),
+    DecrBy(DecrByRequest),
+    Del(DelRequest),
+    Discard(DiscardRequest),
+    Echo(EchoRequest),
+    Exec(ExecRequest),
+    Exists(ExistsRequest),
+    Expire(ExpireRequest),
+    FlushDb(FlushDbRequest),
     Get(GetRequest),
+    GetSet(GetSetRequest),
+    HDel(HDelRequest),
+    HExists(HExistsRequest),
+    HGet(HGetRequest),
+    HGetAll(HGetAllRequest),
+    HIncrBy(HIncrByRequest),
+    HLen(HLenRequest),
+    HMGet(HMGetRequest),
+    HSet(HSetRequest),
+    Hello(HelloRequest),
+    Incr(IncrRequest),
+    IncrBy(IncrByRequest),
+    Invalid(InvalidRequest),
+    LIndex(LIndexRequest),
+    LLen(LLenRequest),
+    LPop(LPopRequest),
+    LPush(LPushRequest),
+    LRange(LRangeRequest),
+    LTrim(LTrimRequest),
+    MGet(MGetRequest),
+    MSet(MSetRequest),
+    Multi(MultiRequest),
+    Persist(PersistRequest),
+    PExpire(PExpireRequest),
+    Ping(PingRequest),
+    PTtl(PTtlRequest),
+    Queued(QueuedRequest),
+    Resp3(Resp3Request),
+    RPop(RPopRequest),
+    RPush(RPushRequest),
     Set(SetRequest),
+    SetNx(SetNxRequest),
+    StrLen(StrLenRequest),
+    Ttl(TtlRequest),
+    Type(TypeRequest),
+    Unwatch(UnwatchRequest),
+    Watch(WatchRequest),
+}
+
+impl From<AppendRequest> for Request {
+    fn from(other: AppendRequest) -> Self {
+        Self::Append(other)
+    }
 }
 
 impl From<BAddRequest> for Request {
@@ -138,23 +700,364 @@ impl From<BAddRequest> for Request {
     }
 }
 
+impl From<BCardRequest> for Request {
+    fn from(other: BCardRequest) -> Self {
+        Self::BCard(other)
+    }
+}
+
+impl From<BDelRequest> for Request {
+    fn from(other: BDelRequest) -> Self {
+        Self::BDel(other)
+    }
+}
+
+impl From<BGetRequest> for Request {
+    fn from(other: BGetRequest) -> Self {
+        Self::BGet(other)
+    }
+}
+
+impl From<BRangeRequest> for Request {
+    fn from(other: BRangeRequest) -> Self {
+        Self::BRange(other)
+    }
+}
+
+impl From<DecrRequest> for Request {
+    fn from(other: DecrRequest) -> Self {
+        Self::Decr(other)
+    }
+}
+
+impl From<DecrByRequest> for Request {
+    fn from(other: DecrByRequest) -> Self {
+        Self::DecrBy(other)
+    }
+}
+
+impl From<DelRequest> for Request {
+    fn from(other: DelRequest) -> Self {
+        Self::Del(other)
+    }
+}
+
+impl From<DiscardRequest> for Request {
+    fn from(other: DiscardRequest) -> Self {
+        Self::Discard(other)
+    }
+}
+
+impl From<EchoRequest> for Request {
+    fn from(other: EchoRequest) -> Self {
+        Self::Echo(other)
+    }
+}
+
+impl From<ExecRequest> for Request {
+    fn from(other: ExecRequest) -> Self {
+        Self::Exec(other)
+    }
+}
+
+impl From<ExistsRequest> for Request {
+    fn from(other: ExistsRequest) -> Self {
+        Self::Exists(other)
+    }
+}
+
+impl From<ExpireRequest> for Request {
+    fn from(other: ExpireRequest) -> Self {
+        Self::Expire(other)
+    }
+}
+
+impl From<FlushDbRequest> for Request {
+    fn from(other: FlushDbRequest) -> Self {
+        Self::FlushDb(other)
+    }
+}
+
 impl From<GetRequest> for Request {
     fn from(other: GetRequest) -> Self {
         Self::Get(other)
     }
 }
 
+impl From<GetSetRequest> for Request {
+    fn from(other: GetSetRequest) -> Self {
+        Self::GetSet(other)
+    }
+}
+
+impl From<HDelRequest> for Request {
+    fn from(other: HDelRequest) -> Self {
+        Self::HDel(other)
+    }
+}
+
+impl From<HExistsRequest> for Request {
+    fn from(other: HExistsRequest) -> Self {
+        Self::HExists(other)
+    }
+}
+
+impl From<HGetRequest> for Request {
+    fn from(other: HGetRequest) -> Self {
+        Self::HGet(other)
+    }
+}
+
+impl From<HGetAllRequest> for Request {
+    fn from(other: HGetAllRequest) -> Self {
+        Self::HGetAll(other)
+    }
+}
+
+impl From<HIncrByRequest> for Request {
+    fn from(other: HIncrByRequest) -> Self {
+        Self::HIncrBy(other)
+    }
+}
+
+impl From<HLenRequest> for Request {
+    fn from(other: HLenRequest) -> Self {
+        Self::HLen(other)
+    }
+}
+
+impl From<HMGetRequest> for Request {
+    fn from(other: HMGetRequest) -> Self {
+        Self::HMGet(other)
+    }
+}
+
+impl From<HSetRequest> for Request {
+    fn from(other: HSetRequest) -> Self {
+        Self::HSet(other)
+    }
+}
+
+impl From<HelloRequest> for Request {
+    fn from(other: HelloRequest) -> Self {
+        Self::Hello(other)
+    }
+}
+
+impl From<IncrRequest> for Request {
+    fn from(other: IncrRequest) -> Self {
+        Self::Incr(other)
+    }
+}
+
+impl From<IncrByRequest> for Request {
+    fn from(other: IncrByRequest) -> Self {
+        Self::IncrBy(other)
+    }
+}
+
+impl From<InvalidRequest> for Request {
+    fn from(other: InvalidRequest) -> Self {
+        Self::Invalid(other)
+    }
+}
+
+impl From<LIndexRequest> for Request {
+    fn from(other: LIndexRequest) -> Self {
+        Self::LIndex(other)
+    }
+}
+
+impl From<LLenRequest> for Request {
+    fn from(other: LLenRequest) -> Self {
+        Self::LLen(other)
+    }
+}
+
+impl From<LPopRequest> for Request {
+    fn from(other: LPopRequest) -> Self {
+        Self::LPop(other)
+    }
+}
+
+impl From<LPushRequest> for Request {
+    fn from(other: LPushRequest) -> Self {
+        Self::LPush(other)
+    }
+}
+
+impl From<LRangeRequest> for Request {
+    fn from(other: LRangeRequest) -> Self {
+        Self::LRange(other)
+    }
+}
+
+impl From<LTrimRequest> for Request {
+    fn from(other: LTrimRequest) -> Self {
+        Self::LTrim(other)
+    }
+}
+
+impl From<MGetRequest> for Request {
+    fn from(other: MGetRequest) -> Self {
+        Self::MGet(other)
+    }
+}
+
+impl From<MSetRequest> for Request {
+    fn from(other: MSetRequest) -> Self {
+        Self::MSet(other)
+    }
+}
+
+impl From<MultiRequest> for Request {
+    fn from(other: MultiRequest) -> Self {
+        Self::Multi(other)
+    }
+}
+
+impl From<PersistRequest> for Request {
+    fn from(other: PersistRequest) -> Self {
+        Self::Persist(other)
+    }
+}
+
+impl From<PExpireRequest> for Request {
+    fn from(other: PExpireRequest) -> Self {
+        Self::PExpire(other)
+    }
+}
+
+impl From<PingRequest> for Request {
+    fn from(other: PingRequest) -> Self {
+        Self::Ping(other)
+    }
+}
+
+impl From<PTtlRequest> for Request {
+    fn from(other: PTtlRequest) -> Self {
+        Self::PTtl(other)
+    }
+}
+
+impl From<QueuedRequest> for Request {
+    fn from(other: QueuedRequest) -> Self {
+        Self::Queued(other)
+    }
+}
+
+impl From<Resp3Request> for Request {
+    fn from(other: Resp3Request) -> Self {
+        Self::Resp3(other)
+    }
+}
+
+impl From<RPopRequest> for Request {
+    fn from(other: RPopRequest) -> Self {
+        Self::RPop(other)
+    }
+}
+
+impl From<RPushRequest> for Request {
+    fn from(other: RPushRequest) -> Self {
+        Self::RPush(other)
+    }
+}
+
 impl From<SetRequest> for Request {
     fn from(other: SetRequest) -> Self {
         Self::Set(other)
     }
 }
 
+impl From<SetNxRequest> for Request {
+    fn from(other: SetNxRequest) -> Self {
+        Self::SetNx(other)
+    }
+}
+
+impl From<StrLenRequest> for Request {
+    fn from(other: StrLenRequest) -> Self {
+        Self::StrLen(other)
+    }
+}
+
+impl From<TtlRequest> for Request {
+    fn from(other: TtlRequest) -> Self {
+        Self::Ttl(other)
+    }
+}
+
+impl From<TypeRequest> for Request {
+    fn from(other: TypeRequest) -> Self {
+        Self::Type(other)
+    }
+}
+
+impl From<UnwatchRequest> for Request {
+    fn from(other: UnwatchRequest) -> Self {
+        Self::Unwatch(other)
+    }
+}
+
+impl From<WatchRequest> for Request {
+    fn from(other: WatchRequest) -> Self {
+        Self::Watch(other)
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum Command {
+    Append,
     BAdd,
+    BCard,
+    BDel,
+    BGet,
+    BRange,
+    Decr,
+    DecrBy,
+    Del,
+    Discard,
+    Echo,
+    Exec,
+    Exists,
+    Expire,
+    FlushDb,
     Get,
+    GetSet,
+    HDel,
+    HExists,
+    HGet,
+    HGetAll,
+    HIncrBy,
+    HLen,
+    HMGet,
+    HSet,
+    Hello,
+    Incr,
+    IncrBy,
+    LIndex,
+    LLen,
+    LPop,
+    LPush,
+    LRange,
+    LTrim,
+    MGet,
+    MSet,
+    Multi,
+    Persist,
+    PExpire,
+    Ping,
+    PTtl,
+    RPop,
+    RPush,
     Set,
+    SetNx,
+    StrLen,
+    Ttl,
+    Type,
+    Unwatch,
+    Watch,
 }
 
 impl TryFrom<&[u8]> for Command {
@@ -162,9 +1065,56 @@ impl TryFrom<&[u8]> for Command {
 
     fn try_from(other: &[u8]) -> Result<Self, ()> {
         match other {
+            b"append" | b"APPEND" => Ok(Command::Append),
             b"badd" | b"BADD" => Ok(Command::BAdd),
+            b"bcard" | b"BCARD" => Ok(Command::BCard),
+            b"bdel" | b"BDEL" => Ok(Command::BDel),
+            b"bget" | b"BGET" => Ok(Command::BGet),
+            b"brange" | b"BRANGE" => Ok(Command::BRange),
+            b"decr" | b"DECR" => Ok(Command::Decr),
+            b"decrby" | b"DECRBY" => Ok(Command::DecrBy),
+            b"del" | b"DEL" => Ok(Command::Del),
+            b"discard" | b"DISCARD" => Ok(Command::Discard),
+            b"echo" | b"ECHO" => Ok(Command::Echo),
+            b"exec" | b"EXEC" => Ok(Command::Exec),
+            b"exists" | b"EXISTS" => Ok(Command::Exists),
+            b"expire" | b"EXPIRE" => Ok(Command::Expire),
+            b"flushdb" | b"FLUSHDB" => Ok(Command::FlushDb),
             b"get" | b"GET" => Ok(Command::Get),
+            b"getset" | b"GETSET" => Ok(Command::GetSet),
+            b"hdel" | b"HDEL" => Ok(Command::HDel),
+            b"hello" | b"HELLO" => Ok(Command::Hello),
+            b"hexists" | b"HEXISTS" => Ok(Command::HExists),
+            b"hget" | b"HGET" => Ok(Command::HGet),
+            b"hgetall" | b"HGETALL" => Ok(Command::HGetAll),
+            b"hincrby" | b"HINCRBY" => Ok(Command::HIncrBy),
+            b"hlen" | b"HLEN" => Ok(Command::HLen),
+            b"hmget" | b"HMGET" => Ok(Command::HMGet),
+            b"hset" | b"HSET" => Ok(Command::HSet),
+            b"incr" | b"INCR" => Ok(Command::Incr),
+            b"incrby" | b"INCRBY" => Ok(Command::IncrBy),
+            b"lindex" | b"LINDEX" => Ok(Command::LIndex),
+            b"llen" | b"LLEN" => Ok(Command::LLen),
+            b"lpop" | b"LPOP" => Ok(Command::LPop),
+            b"lpush" | b"LPUSH" => Ok(Command::LPush),
+            b"lrange" | b"LRANGE" => Ok(Command::LRange),
+            b"ltrim" | b"LTRIM" => Ok(Command::LTrim),
+            b"mget" | b"MGET" => Ok(Command::MGet),
+            b"mset" | b"MSET" => Ok(Command::MSet),
+            b"multi" | b"MULTI" => Ok(Command::Multi),
+            b"persist" | b"PERSIST" => Ok(Command::Persist),
+            b"pexpire" | b"PEXPIRE" => Ok(Command::PExpire),
+            b"ping" | b"PING" => Ok(Command::Ping),
+            b"pttl" | b"PTTL" => Ok(Command::PTtl),
+            b"rpop" | b"RPOP" => Ok(Command::RPop),
+            b"rpush" | b"RPUSH" => Ok(Command::RPush),
             b"set" | b"SET" => Ok(Command::Set),
+            b"setnx" | b"SETNX" => Ok(Command::SetNx),
+            b"strlen" | b"STRLEN" => Ok(Command::StrLen),
+            b"ttl" | b"TTL" => Ok(Command::Ttl),
+            b"type" | b"TYPE" => Ok(Command::Type),
+            b"unwatch" | b"UNWATCH" => Ok(Command::Unwatch),
+            b"watch" | b"WATCH" => Ok(Command::Watch),
             _ => Err(()),
         }
     }
@@ -0,0 +1,129 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::Error;
+use std::sync::Arc;
+
+/// Sets one or more keys to their values. Any existing expirations are
+/// discarded.
+/// format is: mset key value [key value ...]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+#[allow(clippy::type_complexity)]
+pub struct MSetRequest {
+    pairs: Box<[(Arc<Box<[u8]>>, Arc<Box<[u8]>>)]>,
+}
+
+impl TryFrom<Message> for MSetRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        let mut array = command_args(other, Arity::AtLeast(2))?;
+
+        // the command must be followed by complete pairs
+        if array.len() % 2 != 1 {
+            return Err(wrong_arity());
+        }
+
+        let mut pairs = Vec::with_capacity(array.len() / 2);
+        while array.len() >= 3 {
+            let key = take_key(&mut array)?;
+            let value = take_bulk_string(&mut array)?;
+            pairs.push((key, value));
+        }
+
+        Ok(Self {
+            pairs: pairs.into_boxed_slice(),
+        })
+    }
+}
+
+impl MSetRequest {
+    pub fn new(pairs: &[(&[u8], &[u8])]) -> Self {
+        Self {
+            pairs: pairs
+                .iter()
+                .map(|(k, v)| {
+                    (
+                        Arc::new(k.to_vec().into_boxed_slice()),
+                        Arc::new(v.to_vec().into_boxed_slice()),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    pub fn pairs(&self) -> Box<[(&[u8], &[u8])]> {
+        self.pairs.iter().map(|(k, v)| (&***k, &***v)).collect()
+    }
+}
+
+impl From<&MSetRequest> for Message {
+    fn from(other: &MSetRequest) -> Message {
+        let mut v = vec![Message::bulk_string(b"MSET")];
+        for (key, value) in other.pairs.iter() {
+            v.push(Message::BulkString(BulkString::from(key.clone())));
+            v.push(Message::BulkString(BulkString::from(value.clone())));
+        }
+        Message::Array(Array { inner: Some(v) })
+    }
+}
+
+impl Compose for MSetRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for MSetRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        MSET.increment();
+        let code = match response {
+            Response::SimpleString(_) => STORED,
+            _ => NOT_STORED,
+        };
+        for (key, value) in self.pairs.iter() {
+            klog!("\"mset {} {}\" {}", string_key(key), value.len(), code);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"mset 0 1\r\n").unwrap().into_inner(),
+            Request::MSet(MSetRequest::new(&[(b"0", b"1")]))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*5\r\n$4\r\nMSET\r\n$1\r\n0\r\n$1\r\n1\r\n$1\r\n2\r\n$1\r\n3\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::MSet(MSetRequest::new(&[(b"0", b"1"), (b"2", b"3")]))
+        );
+
+        assert!(matches!(
+            parser.parse(b"mset\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+        assert!(matches!(
+            parser.parse(b"mset 0\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+        assert!(matches!(
+            parser.parse(b"mset 0 1 2\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+    }
+}
@@ -0,0 +1,212 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+
+/// Starts a transaction. Commands which follow are queued by the
+/// `RequestParser` until `EXEC` runs them as one unit or `DISCARD` drops them.
+/// format is: multi
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MultiRequest {
+    nested: bool,
+}
+
+impl TryFrom<Message> for MultiRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        if let Message::Array(array) = other {
+            if array.inner.is_none() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let array = array.inner.unwrap();
+
+            if array.len() != 1 {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            Ok(Self { nested: false })
+        } else {
+            Err(Error::new(ErrorKind::Other, "malformed command"))
+        }
+    }
+}
+
+impl MultiRequest {
+    pub fn new() -> Self {
+        Self { nested: false }
+    }
+
+    /// The reply to the request, which does not depend on the storage.
+    pub fn response(&self) -> Response {
+        if self.nested {
+            Response::error("ERR MULTI calls can not be nested")
+        } else {
+            Response::simple_string("OK")
+        }
+    }
+}
+
+impl From<&MultiRequest> for Message {
+    fn from(_other: &MultiRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![Message::bulk_string(b"MULTI")]),
+        })
+    }
+}
+
+impl Compose for MultiRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for MultiRequest {
+    type Response = Response;
+
+    fn klog(&self, _response: &Self::Response) {
+        MULTI.increment();
+    }
+}
+
+/// A command which was received inside a transaction. The `RequestParser`
+/// keeps its own copy of the command for `EXEC`, so the reply only
+/// acknowledges that the command was queued.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedRequest {
+    request: Box<Request>,
+}
+
+impl QueuedRequest {
+    pub fn new(request: Request) -> Self {
+        Self {
+            request: Box::new(request),
+        }
+    }
+
+    pub fn request(&self) -> &Request {
+        &self.request
+    }
+
+    /// The reply to the request, which does not depend on the storage.
+    pub fn response(&self) -> Response {
+        Response::simple_string("QUEUED")
+    }
+}
+
+impl Compose for QueuedRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        self.request.compose(buf)
+    }
+}
+
+impl Klog for QueuedRequest {
+    type Response = Response;
+
+    // the command is logged with its reply when the transaction is executed
+    fn klog(&self, _response: &Self::Response) {}
+}
+
+/// The commands queued by a session since `MULTI`.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Transaction {
+    pub(crate) requests: Vec<Request>,
+    // set when a command could not be queued, which makes `EXEC` fail
+    pub(crate) aborted: bool,
+}
+
+impl RequestParser {
+    /// Starts a transaction for the session, unless one is already started.
+    pub(crate) fn multi(&self, mut request: MultiRequest) -> MultiRequest {
+        let mut transaction = self.transaction.borrow_mut();
+        if transaction.is_some() {
+            request.nested = true;
+        } else {
+            *transaction = Some(Transaction::default());
+        }
+        request
+    }
+
+    /// Queues the request if the session is in a transaction. The commands
+    /// which control the transaction, and commands which are answered with an
+    /// error reply, are never queued. An error reply, or a full queue, aborts
+    /// the transaction.
+    #[allow(clippy::redundant_allocation)]
+    pub(crate) fn queue(&self, command: Arc<Box<[u8]>>, request: Request) -> Request {
+        if matches!(
+            request,
+            Request::Discard(_) | Request::Exec(_) | Request::Multi(_) | Request::Watch(_)
+        ) {
+            return request;
+        }
+
+        let mut transaction = self.transaction.borrow_mut();
+        let transaction = match transaction.as_mut() {
+            Some(transaction) => transaction,
+            None => return request,
+        };
+
+        if matches!(request, Request::Invalid(_)) {
+            transaction.aborted = true;
+            return request;
+        }
+
+        if transaction.requests.len() >= self.max_queued {
+            transaction.aborted = true;
+            return Request::from(InvalidRequest::too_many_queued(command));
+        }
+
+        transaction.requests.push(request.clone());
+        Request::Queued(QueuedRequest::new(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"multi\r\n").unwrap().into_inner(),
+            Request::Multi(MultiRequest::new())
+        );
+
+        assert_eq!(
+            parser.parse(b"get 0\r\n").unwrap().into_inner(),
+            Request::Queued(QueuedRequest::new(Request::Get(GetRequest::new(b"0"))))
+        );
+
+        let nested = parser.parse(b"*1\r\n$5\r\nMULTI\r\n").unwrap().into_inner();
+        if let Request::Multi(multi) = nested {
+            assert!(matches!(multi.response(), Response::Error(_)));
+        } else {
+            panic!("expected multi request");
+        }
+
+        // inside a transaction a malformed command is an error reply
+        assert!(matches!(
+            parser.parse(b"multi 0\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+        assert!(RequestParser::new().parse(b"multi 0\r\n").is_err());
+    }
+
+    #[test]
+    fn response() {
+        assert_eq!(
+            MultiRequest::new().response(),
+            Response::simple_string("OK")
+        );
+        assert_eq!(
+            QueuedRequest::new(Request::Get(GetRequest::new(b"0"))).response(),
+            Response::simple_string("QUEUED")
+        );
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::Error;
+use std::sync::Arc;
+
+/// Removes the expiration from the key, so that it is kept until it is
+/// deleted or evicted.
+/// format is: persist key
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct PersistRequest {
+    key: Arc<Box<[u8]>>,
+}
+
+impl TryFrom<Message> for PersistRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        let mut array = command_args(other, Arity::Exactly(1))?;
+        let key = take_key(&mut array)?;
+        Ok(Self { key })
+    }
+}
+
+impl PersistRequest {
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            key: Arc::new(key.to_owned().into_boxed_slice()),
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+}
+
+impl From<&PersistRequest> for Message {
+    fn from(other: &PersistRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![
+                Message::bulk_string(b"PERSIST"),
+                Message::BulkString(BulkString::from(other.key.clone())),
+            ]),
+        })
+    }
+}
+
+impl Compose for PersistRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for PersistRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        PERSIST.increment();
+        if let Response::Integer(ref res) = response {
+            let code = if res.inner > 0 { STORED } else { NOT_FOUND };
+            klog!("\"persist {}\" {}", string_key(self.key()), code);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"persist 0\r\n").unwrap().into_inner(),
+            Request::Persist(PersistRequest::new(b"0"))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*2\r\n$7\r\nPERSIST\r\n$1\r\n0\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::Persist(PersistRequest::new(b"0"))
+        );
+
+        assert!(matches!(
+            parser.parse(b"persist\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+        assert!(matches!(
+            parser.parse(b"persist 0 1\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::Error;
+use std::sync::Arc;
+
+/// Sets the time to live of the key in milliseconds. A time to live which is
+/// not positive deletes the key.
+/// format is: pexpire key milliseconds
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct PExpireRequest {
+    key: Arc<Box<[u8]>>,
+    milliseconds: i64,
+}
+
+impl TryFrom<Message> for PExpireRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        let mut array = command_args(other, Arity::Exactly(2))?;
+        let key = take_key(&mut array)?;
+        let milliseconds = take_bulk_string_as_i64(&mut array)?;
+        Ok(Self { key, milliseconds })
+    }
+}
+
+impl PExpireRequest {
+    pub fn new(key: &[u8], milliseconds: i64) -> Self {
+        Self {
+            key: Arc::new(key.to_owned().into_boxed_slice()),
+            milliseconds,
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn milliseconds(&self) -> i64 {
+        self.milliseconds
+    }
+}
+
+impl From<&PExpireRequest> for Message {
+    fn from(other: &PExpireRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![
+                Message::bulk_string(b"PEXPIRE"),
+                Message::BulkString(BulkString::from(other.key.clone())),
+                Message::bulk_string(format!("{}", other.milliseconds).as_bytes()),
+            ]),
+        })
+    }
+}
+
+impl Compose for PExpireRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for PExpireRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        PEXPIRE.increment();
+        if let Response::Integer(ref res) = response {
+            let code = if res.inner > 0 { STORED } else { NOT_FOUND };
+            klog!(
+                "\"pexpire {} {}\" {}",
+                string_key(self.key()),
+                self.milliseconds,
+                code
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"pexpire 0 10000\r\n").unwrap().into_inner(),
+            Request::PExpire(PExpireRequest::new(b"0", 10000))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*3\r\n$7\r\nPEXPIRE\r\n$1\r\n0\r\n$5\r\n10000\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::PExpire(PExpireRequest::new(b"0", 10000))
+        );
+
+        assert!(matches!(
+            parser.parse(b"pexpire 0\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+        assert!(parser.parse(b"pexpire 0 a\r\n").is_err());
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::Error;
+use std::sync::Arc;
+
+/// Checks that the server is responsive. The reply is `PONG`, or a copy of the
+/// message if one is provided.
+/// format is: ping [message]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct PingRequest {
+    message: Option<Arc<Box<[u8]>>>,
+}
+
+impl TryFrom<Message> for PingRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        let mut array = command_args(other, Arity::Between(0, 1))?;
+        let message = if array.len() > 1 {
+            Some(take_bulk_string(&mut array)?)
+        } else {
+            None
+        };
+        Ok(Self { message })
+    }
+}
+
+impl PingRequest {
+    pub fn new(message: Option<&[u8]>) -> Self {
+        Self {
+            message: message.map(|m| Arc::new(m.to_owned().into_boxed_slice())),
+        }
+    }
+
+    pub fn message(&self) -> Option<&[u8]> {
+        self.message.as_ref().map(|m| m.as_ref().as_ref())
+    }
+
+    /// The reply to the request, which does not depend on the storage.
+    pub fn response(&self) -> Response {
+        match self.message() {
+            Some(message) => Response::bulk_string(message),
+            None => Response::simple_string("PONG"),
+        }
+    }
+}
+
+impl From<&PingRequest> for Message {
+    fn from(other: &PingRequest) -> Message {
+        let mut v = vec![Message::bulk_string(b"PING")];
+        if let Some(message) = &other.message {
+            v.push(Message::BulkString(BulkString::from(message.clone())));
+        }
+        Message::Array(Array { inner: Some(v) })
+    }
+}
+
+impl Compose for PingRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for PingRequest {
+    type Response = Response;
+
+    fn klog(&self, _response: &Self::Response) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"ping\r\n").unwrap().into_inner(),
+            Request::Ping(PingRequest::new(None))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*2\r\n$4\r\nPING\r\n$5\r\nhello\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::Ping(PingRequest::new(Some(b"hello")))
+        );
+
+        assert!(matches!(
+            parser.parse(b"ping a b\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn response() {
+        assert_eq!(
+            PingRequest::new(None).response(),
+            Response::simple_string("PONG")
+        );
+        assert_eq!(
+            PingRequest::new(Some(b"hello")).response(),
+            Response::bulk_string(b"hello")
+        );
+    }
+}
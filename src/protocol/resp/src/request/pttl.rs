@@ -0,0 +1,99 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::Error;
+use std::sync::Arc;
+
+/// Returns the remaining time to live of the key in milliseconds. The reply
+/// is -1 if the key does not expire and -2 if the key does not exist.
+/// format is: pttl key
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct PTtlRequest {
+    key: Arc<Box<[u8]>>,
+}
+
+impl TryFrom<Message> for PTtlRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        let mut array = command_args(other, Arity::Exactly(1))?;
+        let key = take_key(&mut array)?;
+        Ok(Self { key })
+    }
+}
+
+impl PTtlRequest {
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            key: Arc::new(key.to_owned().into_boxed_slice()),
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+}
+
+impl From<&PTtlRequest> for Message {
+    fn from(other: &PTtlRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![
+                Message::bulk_string(b"PTTL"),
+                Message::BulkString(BulkString::from(other.key.clone())),
+            ]),
+        })
+    }
+}
+
+impl Compose for PTtlRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for PTtlRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        PTTL.increment();
+        if let Response::Integer(ref res) = response {
+            let code = if res.inner == -2 { MISS } else { HIT };
+            klog!("\"pttl {}\" {} {}", string_key(self.key()), code, res.inner);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"pttl 0\r\n").unwrap().into_inner(),
+            Request::PTtl(PTtlRequest::new(b"0"))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*2\r\n$4\r\nPTTL\r\n$1\r\n0\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::PTtl(PTtlRequest::new(b"0"))
+        );
+
+        assert!(matches!(
+            parser.parse(b"pttl\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+        assert!(matches!(
+            parser.parse(b"pttl 0 1\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+    }
+}
@@ -0,0 +1,201 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+/// A request which was received on a session which negotiated RESP3 with
+/// `HELLO`. Storage replies to the inner request using the RESP2 types, and
+/// the reply is converted to the RESP3 types before it is sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resp3Request {
+    request: Box<Request>,
+}
+
+impl Resp3Request {
+    pub fn new(request: Request) -> Self {
+        Self {
+            request: Box::new(request),
+        }
+    }
+
+    /// The request that is executed by the storage.
+    pub fn request(&self) -> &Request {
+        &self.request
+    }
+
+    /// Converts the reply to the inner request so that it uses the RESP3
+    /// types: nil is sent as the RESP3 null, and the field-value pairs from
+    /// `HGETALL` are sent as a map.
+    pub fn response(&self, response: Response) -> Response {
+        resp3(&self.request, response)
+    }
+}
+
+fn resp3(request: &Request, response: Response) -> Response {
+    match (request, response) {
+        (
+            Request::HGetAll(_),
+            Response::Array(Array {
+                inner: Some(values),
+            }),
+        ) => {
+            let mut pairs = Vec::with_capacity(values.len() / 2);
+            let mut values = values.into_iter();
+            while let (Some(field), Some(value)) = (values.next(), values.next()) {
+                pairs.push((field, value));
+            }
+            Response::map(pairs)
+        }
+        // each reply within a transaction is converted for the command which
+        // it is the reply to
+        (
+            Request::Exec(exec),
+            Response::Array(Array {
+                inner: Some(replies),
+            }),
+        ) => match exec.requests() {
+            Some(requests) if requests.len() == replies.len() => Response::array(
+                requests
+                    .iter()
+                    .zip(replies)
+                    .map(|(request, reply)| resp3(request, reply))
+                    .collect(),
+            ),
+            _ => null(Response::array(replies)),
+        },
+        (_, response) => null(response),
+    }
+}
+
+// replaces the null bulk string and null array with the RESP3 null
+fn null(response: Response) -> Response {
+    match response {
+        Response::BulkString(BulkString { inner: None })
+        | Response::Array(Array { inner: None }) => Response::resp3_null(),
+        Response::Array(Array {
+            inner: Some(values),
+        }) => Response::array(values.into_iter().map(null).collect()),
+        response => response,
+    }
+}
+
+impl Compose for Resp3Request {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        self.request.compose(buf)
+    }
+}
+
+impl Klog for Resp3Request {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        self.request.klog(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"get 0\r\n").unwrap().into_inner(),
+            Request::Get(GetRequest::new(b"0"))
+        );
+
+        // after negotiating RESP3, requests are wrapped so that their replies
+        // are converted
+        parser.parse(b"hello 3\r\n").unwrap();
+        assert_eq!(
+            parser.parse(b"get 0\r\n").unwrap().into_inner(),
+            Request::Resp3(Resp3Request::new(Request::Get(GetRequest::new(b"0"))))
+        );
+
+        parser.parse(b"hello 2\r\n").unwrap();
+        assert_eq!(
+            parser.parse(b"get 0\r\n").unwrap().into_inner(),
+            Request::Get(GetRequest::new(b"0"))
+        );
+    }
+
+    #[test]
+    fn response() {
+        let get = Resp3Request::new(Request::Get(GetRequest::new(b"0")));
+        assert_eq!(get.response(Response::null()), Response::resp3_null());
+        assert_eq!(
+            get.response(Response::bulk_string(b"a")),
+            Response::bulk_string(b"a")
+        );
+
+        let mget = Resp3Request::new(Request::MGet(MGetRequest::new(&[b"a", b"b"])));
+        assert_eq!(
+            mget.response(Response::array(vec![
+                Response::bulk_string(b"a"),
+                Response::null(),
+            ])),
+            Response::array(vec![Response::bulk_string(b"a"), Response::resp3_null()])
+        );
+
+        let hgetall = Resp3Request::new(Request::HGetAll(HGetAllRequest::new(b"h")));
+        assert_eq!(
+            hgetall.response(Response::array(vec![
+                Response::bulk_string(b"f"),
+                Response::bulk_string(b"v"),
+            ])),
+            Response::map(vec![(
+                Response::bulk_string(b"f"),
+                Response::bulk_string(b"v"),
+            )])
+        );
+        assert_eq!(
+            hgetall.response(Response::array(Vec::new())),
+            Response::map(Vec::new())
+        );
+
+        // errors are unchanged
+        assert_eq!(
+            hgetall.response(Response::error("ERR")),
+            Response::error("ERR")
+        );
+    }
+
+    #[test]
+    fn transaction() {
+        let parser = RequestParser::new();
+        parser.parse(b"hello 3\r\n").unwrap();
+        parser.parse(b"multi\r\n").unwrap();
+        parser.parse(b"hgetall h\r\n").unwrap();
+        parser.parse(b"get 0\r\n").unwrap();
+
+        let exec = match parser.parse(b"exec\r\n").unwrap().into_inner() {
+            Request::Resp3(exec) => exec,
+            request => panic!("unexpected request: {:?}", request),
+        };
+        let replies = Response::array(vec![
+            Response::array(vec![
+                Response::bulk_string(b"f"),
+                Response::bulk_string(b"v"),
+            ]),
+            Response::null(),
+        ]);
+        assert_eq!(
+            exec.response(replies),
+            Response::array(vec![
+                Response::map(vec![(
+                    Response::bulk_string(b"f"),
+                    Response::bulk_string(b"v"),
+                )]),
+                Response::resp3_null(),
+            ])
+        );
+
+        // an aborted transaction is nil
+        assert_eq!(
+            exec.response(Response::null_array()),
+            Response::resp3_null()
+        );
+    }
+}
@@ -13,7 +13,7 @@ pub enum SetMode {
     Set,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[allow(clippy::redundant_allocation)]
 pub struct SetRequest {
     key: Arc<Box<[u8]>>,
@@ -220,6 +220,27 @@ impl Compose for SetRequest {
     }
 }
 
+impl Klog for SetRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        let code = match response {
+            Response::SimpleString(_) => STORED,
+            Response::BulkString(_) if self.get_old => STORED,
+            Response::BulkString(_) => NOT_STORED,
+            _ => {
+                return;
+            }
+        };
+        klog!(
+            "\"set {} {}\" {}",
+            string_key(self.key()),
+            self.value().len(),
+            code
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,104 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::Error;
+use std::sync::Arc;
+
+/// Returns the length of the string stored at the key, or zero if the key
+/// does not exist.
+/// format is: strlen key
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct StrLenRequest {
+    key: Arc<Box<[u8]>>,
+}
+
+impl TryFrom<Message> for StrLenRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        let mut array = command_args(other, Arity::Exactly(1))?;
+        let key = take_key(&mut array)?;
+        Ok(Self { key })
+    }
+}
+
+impl StrLenRequest {
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            key: Arc::new(key.to_owned().into_boxed_slice()),
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+}
+
+impl From<&StrLenRequest> for Message {
+    fn from(other: &StrLenRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![
+                Message::bulk_string(b"STRLEN"),
+                Message::BulkString(BulkString::from(other.key.clone())),
+            ]),
+        })
+    }
+}
+
+impl Compose for StrLenRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for StrLenRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        STRLEN.increment();
+        if let Response::Integer(ref res) = response {
+            let code = if res.inner > 0 { HIT } else { MISS };
+            klog!(
+                "\"strlen {}\" {} {}",
+                string_key(self.key()),
+                code,
+                res.inner
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"strlen 0\r\n").unwrap().into_inner(),
+            Request::StrLen(StrLenRequest::new(b"0"))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*2\r\n$6\r\nSTRLEN\r\n$1\r\n0\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::StrLen(StrLenRequest::new(b"0"))
+        );
+
+        assert!(matches!(
+            parser.parse(b"strlen\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+        assert!(matches!(
+            parser.parse(b"strlen 0 1\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::Error;
+use std::sync::Arc;
+
+/// Returns the remaining time to live of the key in seconds. The reply is -1
+/// if the key does not expire and -2 if the key does not exist.
+/// format is: ttl key
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct TtlRequest {
+    key: Arc<Box<[u8]>>,
+}
+
+impl TryFrom<Message> for TtlRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        let mut array = command_args(other, Arity::Exactly(1))?;
+        let key = take_key(&mut array)?;
+        Ok(Self { key })
+    }
+}
+
+impl TtlRequest {
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            key: Arc::new(key.to_owned().into_boxed_slice()),
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+}
+
+impl From<&TtlRequest> for Message {
+    fn from(other: &TtlRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![
+                Message::bulk_string(b"TTL"),
+                Message::BulkString(BulkString::from(other.key.clone())),
+            ]),
+        })
+    }
+}
+
+impl Compose for TtlRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for TtlRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        TTL.increment();
+        if let Response::Integer(ref res) = response {
+            let code = if res.inner == -2 { MISS } else { HIT };
+            klog!("\"ttl {}\" {} {}", string_key(self.key()), code, res.inner);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"ttl 0\r\n").unwrap().into_inner(),
+            Request::Ttl(TtlRequest::new(b"0"))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*2\r\n$3\r\nTTL\r\n$1\r\n0\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::Ttl(TtlRequest::new(b"0"))
+        );
+
+        assert!(matches!(
+            parser.parse(b"ttl\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+        assert!(matches!(
+            parser.parse(b"ttl 0 1\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::Error;
+use std::sync::Arc;
+
+/// Returns the type of the value stored at the key, or `none` if the key does
+/// not exist.
+/// format is: type key
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct TypeRequest {
+    key: Arc<Box<[u8]>>,
+}
+
+impl TryFrom<Message> for TypeRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        let mut array = command_args(other, Arity::Exactly(1))?;
+        let key = take_key(&mut array)?;
+        Ok(Self { key })
+    }
+}
+
+impl TypeRequest {
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            key: Arc::new(key.to_owned().into_boxed_slice()),
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+}
+
+impl From<&TypeRequest> for Message {
+    fn from(other: &TypeRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![
+                Message::bulk_string(b"TYPE"),
+                Message::BulkString(BulkString::from(other.key.clone())),
+            ]),
+        })
+    }
+}
+
+impl Compose for TypeRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for TypeRequest {
+    type Response = Response;
+
+    fn klog(&self, response: &Self::Response) {
+        TYPE.increment();
+        if let Response::SimpleString(ref res) = response {
+            let code = if res.inner == "none" { MISS } else { HIT };
+            klog!("\"type {}\" {} {}", string_key(self.key()), code, res.inner);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"type 0\r\n").unwrap().into_inner(),
+            Request::Type(TypeRequest::new(b"0"))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*2\r\n$4\r\nTYPE\r\n$1\r\n0\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::Type(TypeRequest::new(b"0"))
+        );
+
+        assert!(matches!(
+            parser.parse(b"type\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+        assert!(matches!(
+            parser.parse(b"type 0 1\r\n").unwrap().into_inner(),
+            Request::Invalid(_)
+        ));
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::{Error, ErrorKind};
+
+/// Forgets all keys watched by the session. Inside a transaction the request
+/// is queued and has no effect, as `EXEC` forgets the watched keys anyway.
+/// format is: unwatch
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct UnwatchRequest {}
+
+impl TryFrom<Message> for UnwatchRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        if let Message::Array(array) = other {
+            if array.inner.is_none() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let array = array.inner.unwrap();
+
+            if array.len() != 1 {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            Ok(Self {})
+        } else {
+            Err(Error::new(ErrorKind::Other, "malformed command"))
+        }
+    }
+}
+
+impl UnwatchRequest {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// The reply to the request, which does not depend on the storage.
+    pub fn response(&self) -> Response {
+        Response::simple_string("OK")
+    }
+}
+
+impl From<&UnwatchRequest> for Message {
+    fn from(_other: &UnwatchRequest) -> Message {
+        Message::Array(Array {
+            inner: Some(vec![Message::bulk_string(b"UNWATCH")]),
+        })
+    }
+}
+
+impl Compose for UnwatchRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for UnwatchRequest {
+    type Response = Response;
+
+    fn klog(&self, _response: &Self::Response) {
+        UNWATCH.increment();
+    }
+}
+
+impl RequestParser {
+    /// Forgets the watched keys of the session, unless it is in a transaction.
+    pub(crate) fn unwatch(&self, request: UnwatchRequest) -> UnwatchRequest {
+        if self.transaction.borrow().is_none() {
+            self.watched.take();
+        }
+        request
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"unwatch\r\n").unwrap().into_inner(),
+            Request::Unwatch(UnwatchRequest::new())
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*1\r\n$7\r\nUNWATCH\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::Unwatch(UnwatchRequest::new())
+        );
+
+        assert!(parser.parse(b"unwatch 0\r\n").is_err());
+    }
+}
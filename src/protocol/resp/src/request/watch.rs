@@ -0,0 +1,208 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use std::io::{Error, ErrorKind};
+use std::sync::{Arc, Mutex};
+
+/// Watches keys for changes, so that the next `EXEC` on the session aborts
+/// the transaction if any of them were changed in the meantime.
+/// format is: watch key [key ...]
+///
+/// The storage records the version of each key when it executes the request,
+/// and checks the versions again when it executes `EXEC`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::redundant_allocation)]
+pub struct WatchRequest {
+    keys: Box<[Arc<Box<[u8]>>]>,
+    // `None` if the request was sent inside a transaction
+    watched: Option<WatchedKeys>,
+}
+
+impl TryFrom<Message> for WatchRequest {
+    type Error = Error;
+
+    fn try_from(other: Message) -> Result<Self, Error> {
+        if let Message::Array(array) = other {
+            if array.inner.is_none() {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let mut array = array.inner.unwrap();
+
+            if array.len() < 2 {
+                return Err(Error::new(ErrorKind::Other, "malformed command"));
+            }
+
+            let mut keys = Vec::with_capacity(array.len() - 1);
+            while array.len() >= 2 {
+                let key = take_bulk_string(&mut array)?;
+                if key.is_empty() {
+                    return Err(Error::new(ErrorKind::Other, "malformed command"));
+                }
+                keys.push(key);
+            }
+
+            Ok(Self {
+                keys: keys.into_boxed_slice(),
+                watched: Some(WatchedKeys::default()),
+            })
+        } else {
+            Err(Error::new(ErrorKind::Other, "malformed command"))
+        }
+    }
+}
+
+impl WatchRequest {
+    pub fn new(keys: &[&[u8]]) -> Self {
+        Self {
+            keys: keys
+                .iter()
+                .map(|k| Arc::new(k.to_vec().into_boxed_slice()))
+                .collect(),
+            watched: Some(WatchedKeys::default()),
+        }
+    }
+
+    pub fn keys(&self) -> Box<[&[u8]]> {
+        self.keys.iter().map(|k| &***k).collect()
+    }
+
+    /// Records the current version of each key, where `None` is used for keys
+    /// which do not exist, and returns the reply to the request.
+    pub fn watch(&self, mut version: impl FnMut(&[u8]) -> Option<u32>) -> Response {
+        match &self.watched {
+            Some(watched) => {
+                let mut watched = watched.inner.lock().unwrap();
+                for key in self.keys.iter() {
+                    watched.push((key.clone(), version(key)));
+                }
+                Response::simple_string("OK")
+            }
+            None => Response::error("ERR WATCH inside MULTI is not allowed"),
+        }
+    }
+}
+
+impl From<&WatchRequest> for Message {
+    fn from(other: &WatchRequest) -> Message {
+        let mut v = vec![Message::bulk_string(b"WATCH")];
+        for key in other.keys.iter() {
+            v.push(Message::BulkString(BulkString::from(key.clone())));
+        }
+        Message::Array(Array { inner: Some(v) })
+    }
+}
+
+impl Compose for WatchRequest {
+    fn compose(&self, buf: &mut dyn BufMut) -> usize {
+        let message = Message::from(self);
+        message.compose(buf)
+    }
+}
+
+impl Klog for WatchRequest {
+    type Response = Response;
+
+    fn klog(&self, _response: &Self::Response) {
+        WATCH.increment();
+    }
+}
+
+/// The keys watched by a session and the version of each key at the time it
+/// was watched. It is shared between the `RequestParser` of the session and
+/// the watch and exec requests, as the versions are only known once the
+/// storage executes the watch requests.
+#[derive(Debug, Default, Clone)]
+#[allow(clippy::redundant_allocation, clippy::type_complexity)]
+pub(crate) struct WatchedKeys {
+    inner: Arc<Mutex<Vec<(Arc<Box<[u8]>>, Option<u32>)>>>,
+}
+
+impl WatchedKeys {
+    pub(crate) fn unchanged(&self, mut version: impl FnMut(&[u8]) -> Option<u32>) -> bool {
+        self.inner
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|(key, watched)| version(key) == *watched)
+    }
+}
+
+impl PartialEq for WatchedKeys {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+            || *self.inner.lock().unwrap() == *other.inner.lock().unwrap()
+    }
+}
+
+impl Eq for WatchedKeys {}
+
+impl RequestParser {
+    /// Shares the watched keys of the session with the request. Keys cannot
+    /// be watched once a transaction is started.
+    pub(crate) fn watch(&self, mut request: WatchRequest) -> WatchRequest {
+        request.watched = if self.transaction.borrow().is_some() {
+            None
+        } else {
+            Some(self.watched.borrow().clone())
+        };
+        request
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let parser = RequestParser::new();
+        assert_eq!(
+            parser.parse(b"watch 0\r\n").unwrap().into_inner(),
+            Request::Watch(WatchRequest::new(&[b"0"]))
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"*3\r\n$5\r\nWATCH\r\n$1\r\n0\r\n$1\r\n1\r\n")
+                .unwrap()
+                .into_inner(),
+            Request::Watch(WatchRequest::new(&[b"0", b"1"]))
+        );
+
+        assert!(parser.parse(b"watch\r\n").is_err());
+    }
+
+    #[test]
+    fn watch() {
+        let parser = RequestParser::new();
+        parser.parse(b"multi\r\n").unwrap();
+
+        // watching inside a transaction is an error and is not queued
+        if let Request::Watch(watch) = parser.parse(b"watch 0\r\n").unwrap().into_inner() {
+            assert!(matches!(watch.watch(|_| None), Response::Error(_)));
+        } else {
+            panic!("expected watch request");
+        }
+
+        let parser = RequestParser::new();
+        let watch = match parser.parse(b"watch 0 1\r\n").unwrap().into_inner() {
+            Request::Watch(watch) => watch,
+            _ => panic!("expected watch request"),
+        };
+        assert_eq!(
+            watch.watch(|key| (key == b"0").then_some(1)),
+            Response::simple_string("OK")
+        );
+
+        let exec = match parser.parse(b"exec\r\n").unwrap().into_inner() {
+            Request::Exec(exec) => exec,
+            _ => panic!("expected exec request"),
+        };
+        assert!(exec.unchanged(|key| (key == b"0").then_some(1)));
+        assert!(!exec.unchanged(|_| Some(1)));
+        assert!(!exec.unchanged(|_| None));
+    }
+}
@@ -4,5 +4,178 @@
 
 // Responses are implemented as RESP Messages
 
+use crate::message::message;
+use crate::*;
+use protocol_common::{Correlate, Parse, ParseOk};
+
 pub use crate::message::Message as Response;
-pub use crate::message::MessageParser as ResponseParser;
+
+// RESP has no message ids, replies are paired with requests by their order
+impl Correlate for Response {}
+
+/// Parses the replies sent by a RESP server. Each parsed response is the reply
+/// to the oldest pending request, so RESP3 push messages, which are sent
+/// without a matching request, are skipped. Attributes are removed and the
+/// reply which they annotate is returned in their place.
+#[derive(Default, Clone)]
+pub struct ResponseParser {}
+
+impl ResponseParser {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Parse<Response> for ResponseParser {
+    fn parse(&self, buffer: &[u8]) -> Result<ParseOk<Response>, std::io::Error> {
+        let mut input = buffer;
+        loop {
+            match message(input) {
+                Ok((remaining, Response::Push(_))) => {
+                    input = remaining;
+                }
+                Ok((remaining, response)) => {
+                    let response = match response {
+                        Response::Attribute(attribute) => *attribute.inner,
+                        response => response,
+                    };
+                    return Ok(ParseOk::new(response, buffer.len() - remaining.len()));
+                }
+                Err(Err::Incomplete(_)) => {
+                    return Err(std::io::Error::from(std::io::ErrorKind::WouldBlock));
+                }
+                Err(_) => {
+                    return Err(std::io::Error::from(std::io::ErrorKind::InvalidInput));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protocol_common::ErrorResponse;
+
+    #[test]
+    fn parser() {
+        let parser = ResponseParser::new();
+
+        let response = parser.parse(b"+OK\r\n").unwrap();
+        assert_eq!(response.consumed(), 5);
+        assert_eq!(response.into_inner(), Response::simple_string("OK"));
+
+        assert_eq!(
+            parser.parse(b"$-1\r\n").unwrap().into_inner(),
+            Response::null()
+        );
+        assert_eq!(
+            parser.parse(b"*-1\r\n").unwrap().into_inner(),
+            Response::null_array()
+        );
+        assert_eq!(
+            parser.parse(b"_\r\n").unwrap().into_inner(),
+            Response::resp3_null()
+        );
+
+        // mget replies with nil for keys which are not found
+        assert_eq!(
+            parser
+                .parse(b"*3\r\n$1\r\na\r\n$-1\r\n$1\r\nc\r\n")
+                .unwrap()
+                .into_inner(),
+            Response::array(vec![
+                Response::bulk_string(b"a"),
+                Response::null(),
+                Response::bulk_string(b"c"),
+            ])
+        );
+    }
+
+    #[test]
+    fn incomplete() {
+        let parser = ResponseParser::new();
+        for buffer in [&b"$5\r\nhel"[..], b"*2\r\n$1\r\na\r\n", b":1", b""] {
+            assert_eq!(
+                parser.parse(buffer).err().map(|e| e.kind()),
+                Some(std::io::ErrorKind::WouldBlock)
+            );
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        let parser = ResponseParser::new();
+        assert_eq!(
+            parser.parse(b"OK\r\n").err().map(|e| e.kind()),
+            Some(std::io::ErrorKind::InvalidInput)
+        );
+    }
+
+    #[test]
+    fn out_of_band() {
+        let parser = ResponseParser::new();
+
+        let buffer = b">2\r\n$10\r\ninvalidate\r\n*1\r\n$1\r\na\r\n:1\r\n";
+        let response = parser.parse(buffer).unwrap();
+        assert_eq!(response.consumed(), buffer.len());
+        assert_eq!(response.into_inner(), Response::integer(1));
+
+        // a push without the reply which follows it is not enough
+        assert_eq!(
+            parser
+                .parse(b">2\r\n$10\r\ninvalidate\r\n*1\r\n$1\r\na\r\n")
+                .err()
+                .map(|e| e.kind()),
+            Some(std::io::ErrorKind::WouldBlock)
+        );
+
+        assert_eq!(
+            parser
+                .parse(b"|1\r\n+ttl\r\n:3600\r\n$1\r\na\r\n")
+                .unwrap()
+                .into_inner(),
+            Response::bulk_string(b"a")
+        );
+    }
+
+    #[test]
+    fn correlate() {
+        // replies are paired with requests by their order on the connection
+        let mut request = Request::Get(GetRequest::new(b"a"));
+        assert_eq!(request.correlation_id(), None);
+        assert!(!request.set_correlation_id(1));
+
+        let mut response = Response::null();
+        assert_eq!(response.correlation_id(), None);
+        assert!(!response.set_correlation_id(1));
+    }
+
+    #[test]
+    fn is_reply() {
+        let mget = Request::MGet(MGetRequest::new(&[b"a", b"b"]));
+        assert!(mget.is_reply(&Response::array(vec![
+            Response::bulk_string(b"a"),
+            Response::null(),
+        ])));
+        assert!(mget.is_reply(&Response::error("ERR")));
+        assert!(!mget.is_reply(&Response::array(vec![Response::null()])));
+        assert!(!mget.is_reply(&Response::null_array()));
+        assert!(!mget.is_reply(&Response::integer(1)));
+
+        let get = Request::Get(GetRequest::new(b"a"));
+        assert!(get.is_reply(&Response::null()));
+        assert!(get.is_reply(&Response::resp3_null()));
+        assert!(!get.is_reply(&Response::array(vec![Response::null()])));
+
+        let incr = Request::Incr(IncrRequest::new(b"a"));
+        assert!(incr.is_reply(&Response::integer(1)));
+        assert!(!incr.is_reply(&Response::simple_string("OK")));
+
+        // the proxy replies with an error when the backend fails
+        assert_eq!(
+            get.error_response("backend connection failed"),
+            Response::error("ERR backend connection failed")
+        );
+    }
+}
@@ -9,6 +9,7 @@ pub use protocol_common::Compose;
 pub use std::io::{Error, ErrorKind, Write};
 
 use crate::message::*;
+use std::fmt;
 use std::sync::Arc;
 
 // consumes one or more literal spaces
@@ -62,3 +63,87 @@ pub fn take_bulk_string_as_u64(array: &mut Vec<Message>) -> Result<u64, Error> {
         .parse::<u64>()
         .map_err(|_| Error::new(ErrorKind::Other, "bulk string is not a u64"))
 }
+
+pub fn take_bulk_string_as_i64(array: &mut Vec<Message>) -> Result<i64, Error> {
+    let s = take_bulk_string(array)?;
+    std::str::from_utf8(&s)
+        .map_err(|_| Error::new(ErrorKind::Other, "bulk string not valid utf8"))?
+        .parse::<i64>()
+        .map_err(|_| Error::new(ErrorKind::Other, "bulk string is not an i64"))
+}
+
+/// The number of arguments which a command accepts, not counting the command
+/// name itself.
+#[derive(Debug, Copy, Clone)]
+pub enum Arity {
+    Exactly(usize),
+    AtLeast(usize),
+    Between(usize, usize),
+}
+
+// the error for a command with the wrong number of arguments, which is sent
+// as an error reply instead of closing the session
+#[derive(Debug)]
+struct WrongArity;
+
+impl fmt::Display for WrongArity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "wrong number of arguments")
+    }
+}
+
+impl std::error::Error for WrongArity {}
+
+pub fn wrong_arity() -> Error {
+    Error::new(ErrorKind::Other, WrongArity)
+}
+
+pub fn is_wrong_arity(error: &Error) -> bool {
+    error
+        .get_ref()
+        .map(|e| e.is::<WrongArity>())
+        .unwrap_or(false)
+}
+
+/// Unwraps the array of a command after checking the number of arguments.
+/// The command name is left as the first element, so the arguments can be
+/// taken in order with `take_bulk_string` and friends.
+pub fn command_args(message: Message, arity: Arity) -> Result<Vec<Message>, Error> {
+    let array = match message {
+        Message::Array(Array { inner: Some(array) }) if !array.is_empty() => array,
+        _ => return Err(Error::new(ErrorKind::Other, "malformed command")),
+    };
+
+    let args = array.len() - 1;
+    let valid = match arity {
+        Arity::Exactly(n) => args == n,
+        Arity::AtLeast(n) => args >= n,
+        Arity::Between(min, max) => args >= min && args <= max,
+    };
+
+    if valid {
+        Ok(array)
+    } else {
+        Err(wrong_arity())
+    }
+}
+
+/// Takes the next argument as a key, which must not be empty.
+#[allow(clippy::redundant_allocation)]
+pub fn take_key(array: &mut Vec<Message>) -> Result<Arc<Box<[u8]>>, Error> {
+    let key = take_bulk_string(array)?;
+    if key.is_empty() {
+        return Err(Error::new(ErrorKind::Other, "malformed command"));
+    }
+    Ok(key)
+}
+
+/// Takes all of the remaining arguments as keys, none of which may be empty.
+#[allow(clippy::redundant_allocation, clippy::type_complexity)]
+pub fn take_keys(array: &mut Vec<Message>) -> Result<Box<[Arc<Box<[u8]>>]>, Error> {
+    let mut keys = Vec::with_capacity(array.len() - 1);
+    while array.len() >= 2 {
+        keys.push(take_key(array)?);
+    }
+    Ok(keys.into_boxed_slice())
+}
@@ -0,0 +1,421 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Decoding of the message header of the Thrift binary and compact protocols.
+//! The header is at the start of every message and carries the method name,
+//! which allows messages to be introspected without decoding the arguments or
+//! result.
+
+use core::ops::Range;
+
+// a strict binary message starts with the version and the message type, while
+// an older message starts with the length of the method name, which is never
+// negative
+const VERSION_MASK: u32 = 0xffff_0000;
+const VERSION_1: u32 = 0x8001_0000;
+const TYPE_MASK: u32 = 0x0000_00ff;
+
+// a compact message starts with the protocol id, followed by a byte with the
+// message type in the upper three bits and the version in the lower five
+const COMPACT_PROTOCOL_ID: u8 = 0x82;
+const COMPACT_VERSION: u8 = 1;
+const COMPACT_VERSION_MASK: u8 = 0x1f;
+const COMPACT_TYPE_SHIFT: u8 = 5;
+
+// the message type of a reply carrying a `TApplicationException`, and the
+// exception type for an internal error
+const EXCEPTION: u8 = 3;
+const INTERNAL_ERROR: i32 = 6;
+
+/// The encoding of a Thrift message.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Protocol {
+    Binary,
+    Compact,
+}
+
+/// The type of a Thrift message.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MessageType {
+    Call,
+    Reply,
+    /// A reply carrying a `TApplicationException`.
+    Exception,
+    Oneway,
+}
+
+impl TryFrom<u8> for MessageType {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, ()> {
+        match value {
+            1 => Ok(Self::Call),
+            2 => Ok(Self::Reply),
+            3 => Ok(Self::Exception),
+            4 => Ok(Self::Oneway),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The decoded header of a message. The method name is kept as a range of
+/// the message data so that nothing is copied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Header {
+    pub(crate) protocol: Protocol,
+    pub(crate) version: u16,
+    pub(crate) message_type: MessageType,
+    pub(crate) method: Range<usize>,
+    pub(crate) sequence_id: i32,
+    // the encoded sequence id, which is replaced when the id is rewritten
+    pub(crate) sequence: Range<usize>,
+}
+
+impl Header {
+    /// Decodes the header of a message which starts at the beginning of the
+    /// data, detecting which protocol is used. Returns `None` if the data does
+    /// not start with a valid header.
+    pub(crate) fn parse(data: &[u8]) -> Option<Self> {
+        if data.first() == Some(&COMPACT_PROTOCOL_ID) {
+            Self::parse_compact(data, 0)
+        } else {
+            Self::parse_binary(data, 0)
+        }
+    }
+
+    /// Decodes the header of a binary protocol message which starts at the
+    /// offset.
+    pub(crate) fn parse_binary(data: &[u8], offset: usize) -> Option<Self> {
+        let word = read_u32(data, offset)?;
+
+        if word & 0x8000_0000 != 0 {
+            // strict: version and type, method name, sequence id
+            if word & VERSION_MASK != VERSION_1 {
+                return None;
+            }
+            let message_type = MessageType::try_from((word & TYPE_MASK) as u8).ok()?;
+            let method = string(data, offset + 4)?;
+            let sequence_id = read_u32(data, method.end)? as i32;
+            let sequence = method.end..(method.end + 4);
+
+            Some(Self {
+                protocol: Protocol::Binary,
+                version: ((word & VERSION_MASK) >> 16) as u16 & 0x7fff,
+                message_type,
+                method,
+                sequence_id,
+                sequence,
+            })
+        } else {
+            // old: method name, type, sequence id
+            let method = string(data, offset)?;
+            let message_type = MessageType::try_from(*data.get(method.end)?).ok()?;
+            let sequence_id = read_u32(data, method.end + 1)? as i32;
+            let sequence = (method.end + 1)..(method.end + 5);
+
+            Some(Self {
+                protocol: Protocol::Binary,
+                version: 0,
+                message_type,
+                method,
+                sequence_id,
+                sequence,
+            })
+        }
+    }
+
+    /// Decodes the header of a compact protocol message which starts at the
+    /// offset.
+    pub(crate) fn parse_compact(data: &[u8], offset: usize) -> Option<Self> {
+        if *data.get(offset)? != COMPACT_PROTOCOL_ID {
+            return None;
+        }
+
+        let byte = *data.get(offset + 1)?;
+        let version = byte & COMPACT_VERSION_MASK;
+        if version != COMPACT_VERSION {
+            return None;
+        }
+        let message_type = MessageType::try_from(byte >> COMPACT_TYPE_SHIFT).ok()?;
+
+        // sequence id, then the method name
+        let (sequence_id, next) = read_varint(data, offset + 2)?;
+        let (len, start) = read_varint(data, next)?;
+        let end = start.checked_add(len as usize)?;
+        if end > data.len() {
+            return None;
+        }
+
+        Some(Self {
+            protocol: Protocol::Compact,
+            version: version as u16,
+            message_type,
+            method: start..end,
+            sequence_id: sequence_id as i32,
+            sequence: (offset + 2)..next,
+        })
+    }
+
+    /// Replaces the sequence id in the message data. A compact protocol
+    /// sequence id is a varint, so the data may change in length, in which
+    /// case the method name which follows it is moved as well.
+    pub(crate) fn set_sequence_id(&mut self, data: &mut Box<[u8]>, sequence_id: i32) {
+        let encoded = match self.protocol {
+            Protocol::Binary => sequence_id.to_be_bytes().to_vec(),
+            Protocol::Compact => write_varint(sequence_id as u32),
+        };
+
+        if encoded.len() == self.sequence.len() {
+            data[self.sequence.clone()].copy_from_slice(&encoded);
+        } else {
+            let mut replaced = Vec::with_capacity(data.len() + encoded.len());
+            replaced.extend_from_slice(&data[..self.sequence.start]);
+            replaced.extend_from_slice(&encoded);
+            replaced.extend_from_slice(&data[self.sequence.end..]);
+            *data = replaced.into_boxed_slice();
+
+            let end = self.sequence.start + encoded.len();
+            if self.method.start >= self.sequence.end {
+                let len = self.method.len();
+                self.method.start = self.method.start - self.sequence.end + end;
+                self.method.end = self.method.start + len;
+            }
+            self.sequence.end = end;
+        }
+
+        self.sequence_id = sequence_id;
+    }
+
+    /// Encodes a `TApplicationException` reply to the message with this
+    /// header, which is found in the data, using the same protocol and
+    /// version as the message.
+    pub(crate) fn exception(&self, data: &[u8], message: &str) -> Vec<u8> {
+        let method = &data[self.method.clone()];
+        let mut encoded = Vec::with_capacity(method.len() + message.len() + 32);
+
+        match self.protocol {
+            Protocol::Binary => {
+                if self.version == 0 {
+                    encoded.extend_from_slice(&(method.len() as u32).to_be_bytes());
+                    encoded.extend_from_slice(method);
+                    encoded.push(EXCEPTION);
+                } else {
+                    encoded.extend_from_slice(&(VERSION_1 | EXCEPTION as u32).to_be_bytes());
+                    encoded.extend_from_slice(&(method.len() as u32).to_be_bytes());
+                    encoded.extend_from_slice(method);
+                }
+                encoded.extend_from_slice(&self.sequence_id.to_be_bytes());
+
+                // field 1 is the message string, field 2 is the i32 type
+                encoded.extend_from_slice(&[0x0b, 0x00, 0x01]);
+                encoded.extend_from_slice(&(message.len() as u32).to_be_bytes());
+                encoded.extend_from_slice(message.as_bytes());
+                encoded.extend_from_slice(&[0x08, 0x00, 0x02]);
+                encoded.extend_from_slice(&INTERNAL_ERROR.to_be_bytes());
+            }
+            Protocol::Compact => {
+                encoded.push(COMPACT_PROTOCOL_ID);
+                encoded.push((EXCEPTION << COMPACT_TYPE_SHIFT) | COMPACT_VERSION);
+                encoded.extend_from_slice(&write_varint(self.sequence_id as u32));
+                encoded.extend_from_slice(&write_varint(method.len() as u32));
+                encoded.extend_from_slice(method);
+
+                // field headers carry the id delta and the type, and the i32
+                // is zigzag encoded
+                encoded.push(0x18);
+                encoded.extend_from_slice(&write_varint(message.len() as u32));
+                encoded.extend_from_slice(message.as_bytes());
+                encoded.push(0x15);
+                encoded.extend_from_slice(&write_varint((INTERNAL_ERROR << 1) as u32));
+            }
+        }
+
+        // the end of the struct
+        encoded.push(0x00);
+        encoded
+    }
+}
+
+pub(crate) fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..(offset + 2))?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..(offset + 4))?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Reads an unsigned LEB128 varint of up to 32 bits. Returns the value and
+/// the offset of the byte after it.
+pub(crate) fn read_varint(data: &[u8], offset: usize) -> Option<(u32, usize)> {
+    let mut value: u32 = 0;
+    for i in 0..5 {
+        let byte = *data.get(offset + i)?;
+        value |= ((byte & 0x7f) as u32) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, offset + i + 1));
+        }
+    }
+    None
+}
+
+/// Encodes an unsigned LEB128 varint.
+pub(crate) fn write_varint(mut value: u32) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(5);
+    while value >= 0x80 {
+        encoded.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    encoded.push(value as u8);
+    encoded
+}
+
+pub(crate) fn write_u32(data: &mut [u8], offset: usize, value: u32) -> Option<()> {
+    data.get_mut(offset..(offset + 4))?
+        .copy_from_slice(&value.to_be_bytes());
+    Some(())
+}
+
+// a string is encoded as its length followed by the bytes
+fn string(data: &[u8], offset: usize) -> Option<Range<usize>> {
+    let len = read_u32(data, offset)? as usize;
+    let start = offset + 4;
+    let end = start.checked_add(len)?;
+    if end > data.len() {
+        return None;
+    }
+    Some(start..end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strict() {
+        let mut data = vec![0x80, 0x01, 0x00, 0x01];
+        data.extend_from_slice(&3_u32.to_be_bytes());
+        data.extend_from_slice(b"get");
+        data.extend_from_slice(&42_i32.to_be_bytes());
+        data.extend_from_slice(b"ARGS");
+
+        let header = Header::parse(&data).expect("failed to parse");
+        assert_eq!(header.protocol, Protocol::Binary);
+        assert_eq!(header.version, 1);
+        assert_eq!(header.message_type, MessageType::Call);
+        assert_eq!(&data[header.method], b"get");
+        assert_eq!(header.sequence_id, 42);
+    }
+
+    #[test]
+    fn old() {
+        let mut data = 3_u32.to_be_bytes().to_vec();
+        data.extend_from_slice(b"get");
+        data.push(3);
+        data.extend_from_slice(&(-1_i32).to_be_bytes());
+
+        let header = Header::parse(&data).expect("failed to parse");
+        assert_eq!(header.version, 0);
+        assert_eq!(header.message_type, MessageType::Exception);
+        assert_eq!(&data[header.method], b"get");
+        assert_eq!(header.sequence_id, -1);
+    }
+
+    #[test]
+    fn compact() {
+        // reply, sequence id of 300 as a two byte varint
+        let mut data = vec![0x82, 0x41, 0xac, 0x02, 0x03];
+        data.extend_from_slice(b"get");
+        data.extend_from_slice(b"RESULT");
+
+        let header = Header::parse(&data).expect("failed to parse");
+        assert_eq!(header.protocol, Protocol::Compact);
+        assert_eq!(header.version, 1);
+        assert_eq!(header.message_type, MessageType::Reply);
+        assert_eq!(&data[header.method], b"get");
+        assert_eq!(header.sequence_id, 300);
+
+        // bad version
+        data[1] = 0x42;
+        assert!(Header::parse(&data).is_none());
+
+        // method name longer than the message
+        let data = vec![0x82, 0x21, 0x01, 0x10, b'g'];
+        assert!(Header::parse(&data).is_none());
+
+        // unterminated varint
+        let data = vec![0x82, 0x21, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+        assert!(Header::parse(&data).is_none());
+    }
+
+    #[test]
+    fn sequence_id() {
+        let mut data = vec![0x80, 0x01, 0x00, 0x01];
+        data.extend_from_slice(&3_u32.to_be_bytes());
+        data.extend_from_slice(b"get");
+        data.extend_from_slice(&42_i32.to_be_bytes());
+        data.extend_from_slice(b"ARGS");
+        let mut data = data.into_boxed_slice();
+
+        let mut header = Header::parse(&data).expect("failed to parse");
+        header.set_sequence_id(&mut data, -7);
+        assert_eq!(header, Header::parse(&data).expect("failed to parse"));
+        assert_eq!(header.sequence_id, -7);
+        assert_eq!(data.len(), 19);
+
+        // a larger varint moves the method name
+        let mut data = vec![0x82, 0x21, 0x05, 0x03];
+        data.extend_from_slice(b"get");
+        data.extend_from_slice(b"ARGS");
+        let mut data = data.into_boxed_slice();
+
+        let mut header = Header::parse(&data).expect("failed to parse");
+        header.set_sequence_id(&mut data, 300);
+        assert_eq!(header, Header::parse(&data).expect("failed to parse"));
+        assert_eq!(&data[header.method.clone()], b"get");
+        assert_eq!(&data[header.method.end..], b"ARGS");
+
+        // and a smaller one moves it back
+        header.set_sequence_id(&mut data, 5);
+        assert_eq!(header, Header::parse(&data).expect("failed to parse"));
+        assert_eq!(
+            &data[..],
+            &[0x82, 0x21, 0x05, 0x03, b'g', b'e', b't', b'A', b'R', b'G', b'S']
+        );
+
+        // negative ids are encoded as five bytes
+        header.set_sequence_id(&mut data, -1);
+        assert_eq!(header, Header::parse(&data).expect("failed to parse"));
+        assert_eq!(header.sequence.len(), 5);
+    }
+
+    #[test]
+    fn invalid() {
+        // bad version
+        let mut data = vec![0x80, 0x02, 0x00, 0x01];
+        data.extend_from_slice(&0_u32.to_be_bytes());
+        data.extend_from_slice(&0_i32.to_be_bytes());
+        assert!(Header::parse(&data).is_none());
+
+        // bad message type
+        data[1] = 0x01;
+        data[3] = 0x05;
+        assert!(Header::parse(&data).is_none());
+
+        // method name longer than the message
+        let mut data = vec![0x80, 0x01, 0x00, 0x01];
+        data.extend_from_slice(&u32::MAX.to_be_bytes());
+        data.extend_from_slice(b"get");
+        assert!(Header::parse(&data).is_none());
+
+        // missing sequence id
+        let mut data = vec![0x80, 0x01, 0x00, 0x02];
+        data.extend_from_slice(&3_u32.to_be_bytes());
+        data.extend_from_slice(b"get");
+        assert!(Header::parse(&data).is_none());
+
+        assert!(Header::parse(b"").is_none());
+    }
+}
@@ -2,23 +2,62 @@
 // Licensed under the Apache License, Version 2.0
 // http://www.apache.org/licenses/LICENSE-2.0
 
-//! A protocol crate for Thrift binary protocol.
+//! A protocol crate for Thrift. Messages may use the binary or the compact
+//! protocol, and may be framed with a length or with the THeader transport.
 
+#[macro_use]
+extern crate logger;
+
+mod header;
+mod stats;
+mod theader;
+
+pub use header::{MessageType, Protocol};
+pub use stats::track_methods;
+pub use theader::Transform;
+
+use header::Header;
+use logger::Klog;
 use protocol_common::BufMut;
 use protocol_common::Compose;
+use protocol_common::Correlate;
+use protocol_common::ErrorResponse;
 use protocol_common::Parse;
 use protocol_common::ParseOk;
 use rustcommon_metrics::*;
+use theader::TransportHeader;
 
 const THRIFT_HEADER_LEN: usize = std::mem::size_of::<u32>();
 
+type Instant = common::time::Instant<common::time::Nanoseconds<u64>>;
+
 // Stats
 counter!(MESSAGES_PARSED);
 counter!(MESSAGES_COMPOSED);
+counter!(MESSAGES_UNKNOWN);
+counter!(MESSAGES_TRANSFORMED);
+counter!(MESSAGES_BINARY);
+counter!(MESSAGES_COMPACT);
+counter!(MESSAGES_THEADER);
+counter!(METHODS_UNTRACKED);
+counter!(METHODS_UNTRACKED_EXCEPTION);
 
-/// An opaque Thrift message
+/// A Thrift message. The message is forwarded as an opaque blob, but the
+/// message header is decoded when the message is parsed so that the method
+/// name and sequence id are available.
+///
+/// For THeader frames, the transport header is decoded as well. The message
+/// header can only be decoded if no transforms were applied to the payload.
+/// Transforms such as zlib are not reversed, so a transformed frame has no
+/// method name: it is counted by `messages_transformed`, it is not included
+/// in the per-method metrics and it is sent to the default backend.
 pub struct Message {
     data: Box<[u8]>,
+    transport: Option<TransportHeader>,
+    header: Option<Header>,
+    // set on a reply which closes the connection rather than being sent
+    hangup: bool,
+    timestamp: Instant,
 }
 
 #[allow(clippy::len_without_is_empty)]
@@ -26,15 +65,138 @@ impl Message {
     pub fn len(&self) -> usize {
         self.data.len()
     }
+
+    /// The version of the protocol, which is `0` for messages in the older
+    /// format without a version. Returns `None` if the message header could
+    /// not be decoded.
+    pub fn version(&self) -> Option<u16> {
+        self.header.as_ref().map(|h| h.version)
+    }
+
+    /// The name of the method called, or replied to, by this message.
+    pub fn method(&self) -> Option<&[u8]> {
+        self.header.as_ref().map(|h| &self.data[h.method.clone()])
+    }
+
+    pub fn message_type(&self) -> Option<MessageType> {
+        self.header.as_ref().map(|h| h.message_type)
+    }
+
+    pub fn sequence_id(&self) -> Option<i32> {
+        self.header.as_ref().map(|h| h.sequence_id)
+    }
+
+    /// Replaces the sequence id of the message. Returns `false` if the message
+    /// header could not be decoded.
+    pub fn set_sequence_id(&mut self, sequence_id: i32) -> bool {
+        match self.header.as_mut() {
+            Some(header) => {
+                header.set_sequence_id(&mut self.data, sequence_id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The protocol used to encode the message.
+    pub fn protocol(&self) -> Option<Protocol> {
+        match &self.transport {
+            Some(transport) => transport.protocol,
+            None => self.header.as_ref().map(|h| h.protocol),
+        }
+    }
+
+    /// Returns `true` if the message was sent with the THeader transport.
+    pub fn is_theader(&self) -> bool {
+        self.transport.is_some()
+    }
+
+    /// The transforms applied to the payload of a THeader frame.
+    pub fn transforms(&self) -> &[Transform] {
+        self.transport
+            .as_ref()
+            .map(|t| t.transforms.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// The key-value info headers of a THeader frame.
+    pub fn info_headers(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+        self.transport
+            .iter()
+            .flat_map(|t| t.info.iter())
+            .map(|(k, v)| (&self.data[k.clone()], &self.data[v.clone()]))
+    }
+}
+
+/// Messages are correlated by their sequence id. For a THeader frame with a
+/// transformed payload the message header is not decoded, so the sequence
+/// number of the transport header is used instead.
+impl Correlate for Message {
+    fn correlation_id(&self) -> Option<i32> {
+        match (&self.header, &self.transport) {
+            (Some(header), _) => Some(header.sequence_id),
+            (None, Some(transport)) => Some(transport.sequence_number as i32),
+            (None, None) => None,
+        }
+    }
+
+    fn set_correlation_id(&mut self, id: i32) -> bool {
+        if self.set_sequence_id(id) {
+            return true;
+        }
+        match self.transport.as_mut() {
+            Some(transport) => transport.set_sequence_number(&mut self.data, id as u32),
+            None => false,
+        }
+    }
+
+    // oneway calls are never replied to
+    fn expects_response(&self) -> bool {
+        self.message_type() != Some(MessageType::Oneway)
+    }
 }
 
 impl Compose for Message {
     fn compose(&self, session: &mut dyn BufMut) -> usize {
+        if self.hangup {
+            return 0;
+        }
         MESSAGES_COMPOSED.increment();
         session.put_slice(&(self.data.len() as u32).to_be_bytes());
         session.put_slice(&self.data);
         std::mem::size_of::<u32>() + self.data.len()
     }
+
+    fn should_hangup(&self) -> bool {
+        self.hangup
+    }
+}
+
+/// The error reply is a `TApplicationException` with the method name and the
+/// sequence id of the request. It is only composed for messages which are
+/// framed with a length. The transport header of a THeader frame is not
+/// composed, and a message without a decoded header can not be replied to, so
+/// for these the reply closes the connection instead.
+impl ErrorResponse for Message {
+    type Response = Message;
+
+    fn error_response(&self, message: &str) -> Message {
+        let (data, header) = match (&self.header, &self.transport) {
+            (Some(header), None) => {
+                let data = header.exception(&self.data, message).into_boxed_slice();
+                let header = Header::parse(&data);
+                (data, header)
+            }
+            _ => (Box::default(), None),
+        };
+        Message {
+            hangup: header.is_none(),
+            data,
+            transport: None,
+            header,
+            timestamp: Instant::now(),
+        }
+    }
 }
 
 /// A parser which retrieves the bytes for a complete Thrift message.
@@ -70,7 +232,37 @@ impl Parse<Message> for MessageParser {
             let data = buffer[THRIFT_HEADER_LEN..framed_len]
                 .to_vec()
                 .into_boxed_slice();
-            let message = Message { data };
+            let (transport, header) = if TransportHeader::is_header(&data) {
+                MESSAGES_THEADER.increment();
+                let transport = TransportHeader::parse(&data);
+                let header = transport.as_ref().and_then(|t| {
+                    if !t.transforms.is_empty() {
+                        return None;
+                    }
+                    match t.protocol? {
+                        Protocol::Binary => Header::parse_binary(&data, t.payload),
+                        Protocol::Compact => Header::parse_compact(&data, t.payload),
+                    }
+                });
+                (transport, header)
+            } else {
+                (None, Header::parse(&data))
+            };
+            match header.as_ref().map(|h| h.protocol) {
+                Some(Protocol::Binary) => MESSAGES_BINARY.increment(),
+                Some(Protocol::Compact) => MESSAGES_COMPACT.increment(),
+                None if transport.iter().any(|t| !t.transforms.is_empty()) => {
+                    MESSAGES_TRANSFORMED.increment()
+                }
+                None => MESSAGES_UNKNOWN.increment(),
+            }
+            let message = Message {
+                data,
+                transport,
+                header,
+                hangup: false,
+                timestamp: Instant::now(),
+            };
             Ok(ParseOk::new(message, framed_len))
         }
     }
@@ -96,6 +288,237 @@ mod tests {
 
         assert_eq!(consumed, body.len() + THRIFT_HEADER_LEN);
         assert_eq!(*parsed.data, body);
+        assert_eq!(parsed.method(), None);
+    }
+
+    #[test]
+    fn introspection() {
+        let mut body = vec![0x80, 0x01, 0x00, 0x01];
+        body.extend_from_slice(&3_u32.to_be_bytes());
+        body.extend_from_slice(b"get");
+        body.extend_from_slice(&7_i32.to_be_bytes());
+        body.extend_from_slice(b"ARGS");
+
+        let mut message = (body.len() as u32).to_be_bytes().to_vec();
+        message.extend_from_slice(&body);
+
+        let parser = MessageParser::new(1024);
+
+        let parsed = parser.parse(&message).expect("failed to parse");
+        let parsed = parsed.into_inner();
+
+        assert_eq!(parsed.version(), Some(1));
+        assert_eq!(parsed.method(), Some(&b"get"[..]));
+        assert_eq!(parsed.message_type(), Some(MessageType::Call));
+        assert_eq!(parsed.sequence_id(), Some(7));
+        assert_eq!(parsed.protocol(), Some(Protocol::Binary));
+        assert!(!parsed.is_theader());
+        assert_eq!(*parsed.data, body);
+    }
+
+    #[test]
+    fn correlate() {
+        // compact protocol, the sequence id grows from one to two bytes
+        let mut body = vec![0x82, 0x21, 0x05, 0x03];
+        body.extend_from_slice(b"get");
+        body.extend_from_slice(b"ARGS");
+
+        let mut message = (body.len() as u32).to_be_bytes().to_vec();
+        message.extend_from_slice(&body);
+
+        let parser = MessageParser::new(1024);
+
+        let mut parsed = parser
+            .parse(&message)
+            .expect("failed to parse")
+            .into_inner();
+        assert_eq!(parsed.correlation_id(), Some(5));
+        assert!(parsed.set_correlation_id(300));
+        assert_eq!(parsed.correlation_id(), Some(300));
+        assert_eq!(parsed.method(), Some(&b"get"[..]));
+
+        // the composed message is framed with the new length
+        let mut composed = Vec::new();
+        parsed.compose(&mut composed);
+        let reparsed = parser.parse(&composed).expect("failed to parse");
+        assert_eq!(reparsed.consumed(), message.len() + 1);
+        let reparsed = reparsed.into_inner();
+        assert_eq!(reparsed.sequence_id(), Some(300));
+        assert_eq!(reparsed.method(), Some(&b"get"[..]));
+
+        // a transformed THeader frame uses the transport sequence number
+        let mut body = vec![0x0f, 0xff, 0x00, 0x00];
+        body.extend_from_slice(&9_u32.to_be_bytes());
+        body.extend_from_slice(&1_u16.to_be_bytes());
+        body.extend_from_slice(&[0x00, 0x01, 0x01, 0x00]);
+        body.extend_from_slice(b"DEFLATED");
+
+        let mut message = (body.len() as u32).to_be_bytes().to_vec();
+        message.extend_from_slice(&body);
+
+        let mut parsed = parser
+            .parse(&message)
+            .expect("failed to parse")
+            .into_inner();
+        assert_eq!(parsed.sequence_id(), None);
+        assert_eq!(parsed.correlation_id(), Some(9));
+        assert!(parsed.set_correlation_id(-2));
+        assert_eq!(parsed.correlation_id(), Some(-2));
+        assert_eq!(&parsed.data[4..8], &(-2_i32).to_be_bytes());
+
+        // a message which could not be decoded has no id
+        let mut message = 6_u32.to_be_bytes().to_vec();
+        message.extend_from_slice(b"COFFEE");
+        let mut parsed = parser
+            .parse(&message)
+            .expect("failed to parse")
+            .into_inner();
+        assert_eq!(parsed.correlation_id(), None);
+        assert!(!parsed.set_correlation_id(1));
+    }
+
+    #[test]
+    fn error_response() {
+        let parser = MessageParser::new(1024);
+        let parse = |body: &[u8]| {
+            let mut message = (body.len() as u32).to_be_bytes().to_vec();
+            message.extend_from_slice(body);
+            parser
+                .parse(&message)
+                .expect("failed to parse")
+                .into_inner()
+        };
+        let reparse = |message: &Message| {
+            let mut composed = Vec::new();
+            message.compose(&mut composed);
+            parser
+                .parse(&composed)
+                .expect("failed to parse")
+                .into_inner()
+        };
+
+        // strict binary, older binary and compact calls
+        let mut strict = vec![0x80, 0x01, 0x00, 0x01];
+        strict.extend_from_slice(&3_u32.to_be_bytes());
+        strict.extend_from_slice(b"get");
+        strict.extend_from_slice(&7_i32.to_be_bytes());
+        let mut old = 3_u32.to_be_bytes().to_vec();
+        old.extend_from_slice(b"get");
+        old.push(0x01);
+        old.extend_from_slice(&7_i32.to_be_bytes());
+        let compact = [0x82, 0x21, 0x07, 0x03, b'g', b'e', b't'];
+
+        for (body, protocol, version) in [
+            (&strict[..], Protocol::Binary, 1),
+            (&old[..], Protocol::Binary, 0),
+            (&compact[..], Protocol::Compact, 1),
+        ] {
+            let response = parse(body).error_response("failed");
+            assert!(!response.should_hangup());
+            let response = reparse(&response);
+            assert_eq!(response.message_type(), Some(MessageType::Exception));
+            assert_eq!(response.method(), Some(&b"get"[..]));
+            assert_eq!(response.sequence_id(), Some(7));
+            assert_eq!(response.protocol(), Some(protocol));
+            assert_eq!(response.version(), Some(version));
+        }
+
+        // the exception carries the message and the internal error type
+        let response = parse(&strict).error_response("failed");
+        let mut payload = vec![0x0b, 0x00, 0x01];
+        payload.extend_from_slice(&6_u32.to_be_bytes());
+        payload.extend_from_slice(b"failed");
+        payload.extend_from_slice(&[0x08, 0x00, 0x02, 0x00, 0x00, 0x00, 0x06, 0x00]);
+        assert_eq!(&response.data[strict.len()..], &payload[..]);
+
+        // a THeader frame or a message which was not decoded hangs up instead
+        let mut theader = vec![0x0f, 0xff, 0x00, 0x00];
+        theader.extend_from_slice(&1_u32.to_be_bytes());
+        theader.extend_from_slice(&1_u16.to_be_bytes());
+        theader.extend_from_slice(&[0x02, 0x00, 0x00, 0x00]);
+        theader.extend_from_slice(&compact);
+        for body in [&theader[..], &b"COFFEE"[..]] {
+            let response = parse(body).error_response("failed");
+            assert!(response.should_hangup());
+            let mut composed = Vec::new();
+            assert_eq!(response.compose(&mut composed), 0);
+            assert!(composed.is_empty());
+        }
+    }
+
+    #[test]
+    fn oneway() {
+        let mut body = vec![0x80, 0x01, 0x00, 0x04];
+        body.extend_from_slice(&3_u32.to_be_bytes());
+        body.extend_from_slice(b"log");
+        body.extend_from_slice(&7_i32.to_be_bytes());
+
+        let mut message = (body.len() as u32).to_be_bytes().to_vec();
+        message.extend_from_slice(&body);
+
+        let parser = MessageParser::new(1024);
+        let parsed = parser
+            .parse(&message)
+            .expect("failed to parse")
+            .into_inner();
+        assert_eq!(parsed.message_type(), Some(MessageType::Oneway));
+        assert!(!parsed.expects_response());
+
+        body[3] = 0x01;
+        let mut message = (body.len() as u32).to_be_bytes().to_vec();
+        message.extend_from_slice(&body);
+        let parsed = parser
+            .parse(&message)
+            .expect("failed to parse")
+            .into_inner();
+        assert!(parsed.expects_response());
+    }
+
+    #[test]
+    fn theader() {
+        // compact protocol with one info header, padded to a 32bit boundary
+        let header = [0x02, 0x00, 0x01, 0x01, 0x01, b'k', 0x01, b'v'];
+        let mut body = vec![0x0f, 0xff, 0x00, 0x00];
+        body.extend_from_slice(&1_u32.to_be_bytes());
+        body.extend_from_slice(&2_u16.to_be_bytes());
+        body.extend_from_slice(&header);
+        body.extend_from_slice(&[0x82, 0x21, 0x05, 0x03]);
+        body.extend_from_slice(b"get");
+        body.extend_from_slice(b"ARGS");
+
+        let mut message = (body.len() as u32).to_be_bytes().to_vec();
+        message.extend_from_slice(&body);
+
+        let parser = MessageParser::new(1024);
+
+        let parsed = parser.parse(&message).expect("failed to parse");
+        assert_eq!(parsed.consumed(), message.len());
+        let parsed = parsed.into_inner();
+
+        assert!(parsed.is_theader());
+        assert_eq!(parsed.protocol(), Some(Protocol::Compact));
+        assert_eq!(parsed.method(), Some(&b"get"[..]));
+        assert_eq!(parsed.message_type(), Some(MessageType::Call));
+        assert_eq!(parsed.sequence_id(), Some(5));
+        assert_eq!(
+            parsed.info_headers().collect::<Vec<_>>(),
+            vec![(&b"k"[..], &b"v"[..])]
+        );
+
+        // the payload of a transformed frame is not decoded, but the frame is
+        // counted
+        let transformed = MESSAGES_TRANSFORMED.value();
+        body[10..18].copy_from_slice(&[0x02, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        let mut message = (body.len() as u32).to_be_bytes().to_vec();
+        message.extend_from_slice(&body);
+
+        let parsed = parser.parse(&message).expect("failed to parse");
+        let parsed = parsed.into_inner();
+
+        assert!(parsed.is_theader());
+        assert_eq!(parsed.transforms(), &[Transform::Zlib]);
+        assert_eq!(parsed.method(), None);
+        assert_eq!(MESSAGES_TRANSFORMED.value(), transformed + 1);
     }
 }
 
@@ -0,0 +1,166 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Per-method metrics, which are kept for the methods which are configured
+//! when the process starts. As method names come from clients, requests for
+//! any other method are only counted in aggregate. THeader frames with a
+//! transformed payload have no method name, so they are not counted here.
+
+use crate::*;
+use rustcommon_metrics::time::{Duration, Nanoseconds};
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind};
+use std::sync::{Arc, RwLock};
+
+// max latency recorded by the per-method heatmaps, in nanoseconds
+const LATENCY_MAX: u64 = 1_000_000_000;
+
+type Methods = HashMap<Box<[u8]>, Arc<MethodStats>>;
+
+static METHODS: RwLock<Option<Methods>> = RwLock::new(None);
+
+struct MethodStats {
+    request: DynBoxedMetric<Counter>,
+    exception: DynBoxedMetric<Counter>,
+    latency: DynBoxedMetric<Heatmap>,
+}
+
+impl MethodStats {
+    fn new(name: &str) -> Self {
+        Self {
+            request: MetricBuilder::new(format!("thrift_{}_request", name))
+                .description("the number of requests for the method")
+                .build(Counter::new()),
+            exception: MetricBuilder::new(format!("thrift_{}_exception", name))
+                .description("the number of TApplicationException replies for the method")
+                .build(Counter::new()),
+            latency: MetricBuilder::new(format!("thrift_{}_latency", name))
+                .description("distribution of the latency of requests for the method")
+                .build(Heatmap::new(
+                    LATENCY_MAX,
+                    3,
+                    Duration::<Nanoseconds<u64>>::from_secs(60),
+                    Duration::<Nanoseconds<u64>>::from_secs(1),
+                )),
+        }
+    }
+}
+
+/// Registers per-method metrics for each of the methods. Returns an error if
+/// two methods would share the same metric names, which happens as the names
+/// are kept to ascii alphanumerics and underscores.
+pub fn track_methods<T: AsRef<str>>(methods: &[T]) -> Result<(), Error> {
+    let mut tracked = METHODS.write().unwrap();
+    let tracked = tracked.get_or_insert_with(HashMap::new);
+
+    for method in methods.iter().map(|m| m.as_ref().as_bytes()) {
+        if tracked.contains_key(method) {
+            continue;
+        }
+
+        let name = metric_name(method);
+        if let Some(other) = tracked.keys().find(|other| metric_name(other) == name) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "thrift methods {} and {} have the same metric name: {}",
+                    String::from_utf8_lossy(other),
+                    String::from_utf8_lossy(method),
+                    name
+                ),
+            ));
+        }
+
+        tracked.insert(method.into(), Arc::new(MethodStats::new(&name)));
+    }
+
+    Ok(())
+}
+
+// method names may contain any bytes, but metric names are kept to ascii
+// alphanumerics and underscores
+fn metric_name(method: &[u8]) -> String {
+    String::from_utf8_lossy(method)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn method_stats(method: &[u8]) -> Option<Arc<MethodStats>> {
+    METHODS
+        .read()
+        .unwrap()
+        .as_ref()
+        .and_then(|methods| methods.get(method))
+        .cloned()
+}
+
+impl Klog for Message {
+    type Response = Message;
+
+    fn klog(&self, response: &Self::Response) {
+        let method = match self.method() {
+            Some(method) => method,
+            None => {
+                return;
+            }
+        };
+
+        let exception = response.message_type() == Some(MessageType::Exception);
+
+        match method_stats(method) {
+            Some(stats) => {
+                let now = Instant::now();
+                stats.request.increment();
+                if exception {
+                    stats.exception.increment();
+                }
+                stats
+                    .latency
+                    .increment(now, (now - self.timestamp).as_nanos(), 1);
+            }
+            None => {
+                METHODS_UNTRACKED.increment();
+                if exception {
+                    METHODS_UNTRACKED_EXCEPTION.increment();
+                }
+            }
+        }
+
+        klog!(
+            "\"{}\" {} {}",
+            String::from_utf8_lossy(method),
+            if exception { 1 } else { 0 },
+            response.len()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name() {
+        assert_eq!(metric_name(b"get"), "get");
+        assert_eq!(metric_name(b"Service:get.v2"), "Service_get_v2");
+    }
+
+    #[test]
+    fn track() {
+        assert!(method_stats(b"stats_untracked").is_none());
+
+        track_methods(&["stats.get", "stats.put"]).unwrap();
+        assert!(method_stats(b"stats.get").is_some());
+        assert!(method_stats(b"stats.put").is_some());
+        assert!(method_stats(b"stats_untracked").is_none());
+
+        // methods which are already tracked are skipped
+        track_methods(&["stats.get"]).unwrap();
+
+        // but methods which would share metric names are rejected
+        assert!(track_methods(&["stats_get"]).is_err());
+        assert!(method_stats(b"stats_get").is_none());
+    }
+}
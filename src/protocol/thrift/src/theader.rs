@@ -0,0 +1,221 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Decoding of the THeader transport. A THeader frame starts with a magic
+//! number, followed by a header which names the protocol of the payload, the
+//! transforms applied to the payload and a set of info headers.
+//!
+//! The frame is forwarded unchanged, the header is only decoded so that the
+//! message header in the payload can be found.
+
+use crate::header::{read_u16, read_u32, read_varint, write_u32};
+use crate::Protocol;
+use core::ops::Range;
+
+const HEADER_MAGIC: u16 = 0x0fff;
+
+// magic, flags, sequence number and header size
+const FIXED_LEN: usize = 10;
+
+// the header size is given in 32bit words
+const HEADER_WORD: usize = 4;
+
+const PROTOCOL_BINARY: u32 = 0;
+const PROTOCOL_COMPACT: u32 = 2;
+
+const INFO_PADDING: u32 = 0;
+const INFO_KEYVALUE: u32 = 1;
+const INFO_PKEYVALUE: u32 = 2;
+
+/// A transform applied to the payload of a THeader frame.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Transform {
+    Zlib,
+    Hmac,
+    Snappy,
+    Qlz,
+    Zstd,
+    Unknown(u32),
+}
+
+impl From<u32> for Transform {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => Self::Zlib,
+            2 => Self::Hmac,
+            3 => Self::Snappy,
+            4 => Self::Qlz,
+            5 => Self::Zstd,
+            _ => Self::Unknown(value),
+        }
+    }
+}
+
+/// The decoded header of a THeader frame. The info headers are kept as ranges
+/// of the frame data so that nothing is copied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TransportHeader {
+    pub(crate) flags: u16,
+    pub(crate) sequence_number: u32,
+    pub(crate) protocol: Option<Protocol>,
+    pub(crate) transforms: Vec<Transform>,
+    pub(crate) info: Vec<(Range<usize>, Range<usize>)>,
+    pub(crate) payload: usize,
+}
+
+impl TransportHeader {
+    /// Returns `true` if the frame data starts with the THeader magic.
+    pub(crate) fn is_header(data: &[u8]) -> bool {
+        read_u16(data, 0) == Some(HEADER_MAGIC)
+    }
+
+    /// Decodes the header of a THeader frame. Returns `None` if the header
+    /// is malformed.
+    pub(crate) fn parse(data: &[u8]) -> Option<Self> {
+        if !Self::is_header(data) {
+            return None;
+        }
+
+        let flags = read_u16(data, 2)?;
+        let sequence_number = read_u32(data, 4)?;
+        let size = read_u16(data, 8)? as usize * HEADER_WORD;
+
+        let payload = FIXED_LEN + size;
+        if payload > data.len() {
+            return None;
+        }
+
+        // the variable length header must not be read beyond its own end
+        let header = &data[..payload];
+
+        let (protocol, offset) = read_varint(header, FIXED_LEN)?;
+        let protocol = match protocol {
+            PROTOCOL_BINARY => Some(Protocol::Binary),
+            PROTOCOL_COMPACT => Some(Protocol::Compact),
+            _ => None,
+        };
+
+        let (count, mut offset) = read_varint(header, offset)?;
+        let mut transforms = Vec::new();
+        for _ in 0..count {
+            let (id, next) = read_varint(header, offset)?;
+            transforms.push(Transform::from(id));
+            offset = next;
+        }
+
+        let mut info = Vec::new();
+        while offset < header.len() {
+            let (id, next) = read_varint(header, offset)?;
+            offset = next;
+            match id {
+                INFO_KEYVALUE | INFO_PKEYVALUE => {
+                    let (count, next) = read_varint(header, offset)?;
+                    offset = next;
+                    for _ in 0..count {
+                        let key = string(header, offset)?;
+                        let value = string(header, key.end)?;
+                        offset = value.end;
+                        info.push((key, value));
+                    }
+                }
+                // padding fills the rest of the header, and the size of any
+                // other info header is unknown, so nothing more can be read
+                INFO_PADDING => break,
+                _ => break,
+            }
+        }
+
+        Some(Self {
+            flags,
+            sequence_number,
+            protocol,
+            transforms,
+            info,
+            payload,
+        })
+    }
+
+    /// Replaces the sequence number in the frame data, which is at a fixed
+    /// offset.
+    pub(crate) fn set_sequence_number(&mut self, data: &mut [u8], sequence_number: u32) -> bool {
+        if write_u32(data, 4, sequence_number).is_none() {
+            return false;
+        }
+        self.sequence_number = sequence_number;
+        true
+    }
+}
+
+// a string is encoded as a varint length followed by the bytes
+fn string(data: &[u8], offset: usize) -> Option<Range<usize>> {
+    let (len, start) = read_varint(data, offset)?;
+    let end = start.checked_add(len as usize)?;
+    if end > data.len() {
+        return None;
+    }
+    Some(start..end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // builds a THeader frame around the payload
+    fn frame(header: &[u8], payload: &[u8]) -> Vec<u8> {
+        let mut header = header.to_vec();
+        let padding = (HEADER_WORD - header.len() % HEADER_WORD) % HEADER_WORD;
+        header.resize(header.len() + padding, 0);
+
+        let mut data = vec![0x0f, 0xff, 0x00, 0x00];
+        data.extend_from_slice(&7_u32.to_be_bytes());
+        data.extend_from_slice(&((header.len() / HEADER_WORD) as u16).to_be_bytes());
+        data.extend_from_slice(&header);
+        data.extend_from_slice(payload);
+        data
+    }
+
+    #[test]
+    fn parse() {
+        // compact protocol, no transforms, one key-value info header
+        let data = frame(
+            &[0x02, 0x00, 0x01, 0x01, 0x03, b'k', b'e', b'y', 0x01, b'v'],
+            b"PAYLOAD",
+        );
+
+        let header = TransportHeader::parse(&data).expect("failed to parse");
+        assert_eq!(header.sequence_number, 7);
+        assert_eq!(header.protocol, Some(Protocol::Compact));
+        assert!(header.transforms.is_empty());
+        assert_eq!(header.info.len(), 1);
+        assert_eq!(&data[header.info[0].0.clone()], b"key");
+        assert_eq!(&data[header.info[0].1.clone()], b"v");
+        assert_eq!(&data[header.payload..], b"PAYLOAD");
+    }
+
+    #[test]
+    fn transforms() {
+        // binary protocol, zlib transform
+        let data = frame(&[0x00, 0x01, 0x01], b"PAYLOAD");
+
+        let header = TransportHeader::parse(&data).expect("failed to parse");
+        assert_eq!(header.protocol, Some(Protocol::Binary));
+        assert_eq!(header.transforms, vec![Transform::Zlib]);
+        assert!(header.info.is_empty());
+    }
+
+    #[test]
+    fn invalid() {
+        // not a THeader frame
+        assert!(TransportHeader::parse(&[0x80, 0x01, 0x00, 0x01]).is_none());
+
+        // header size beyond the end of the frame
+        let mut data = frame(&[0x00, 0x00], b"");
+        data[9] = 0x10;
+        assert!(TransportHeader::parse(&data).is_none());
+
+        // info header beyond the end of the header
+        let data = frame(&[0x00, 0x00, 0x01, 0x01, 0x10], b"PAYLOAD");
+        assert!(TransportHeader::parse(&data).is_none());
+    }
+}
@@ -4,6 +4,7 @@
 
 use crate::protocol::*;
 use crate::*;
+use protocol_memcache::Compose;
 use session::Buf;
 
 pub(crate) async fn handle_memcache_client(
@@ -45,6 +46,13 @@ pub(crate) async fn handle_memcache_client(
                             break;
                         }
                     }
+                    memcache::Request::Invalid(r) => {
+                        let mut response = Vec::new();
+                        r.response().compose(&mut response);
+                        if socket.write_all(&response).await.is_err() {
+                            break;
+                        }
+                    }
                     _ => {
                         debug!("unsupported command: {}", request);
                     }
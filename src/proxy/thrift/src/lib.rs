@@ -2,10 +2,12 @@
 // Licensed under the Apache License, Version 2.0
 // http://www.apache.org/licenses/LICENSE-2.0
 
-use config::PingproxyConfig;
+use config::proxy::BackendConfig;
+use config::{ThriftConfig, ThriftproxyConfig};
 use logger::configure_logging;
 use protocol_thrift::*;
 use proxy::{Process, ProcessBuilder};
+use std::collections::HashMap;
 
 const MAX_SIZE: usize = 16 * 1024 * 1024; // 16MB
 
@@ -24,23 +26,27 @@ pub struct Thriftproxy {
 
 impl Default for Thriftproxy {
     fn default() -> Self {
-        Self::new(PingproxyConfig::default())
+        Self::new(ThriftproxyConfig::default())
     }
 }
 
 impl Thriftproxy {
-    /// Creates a new `Pingproxy` process from the given `PingproxyConfig`.
-    pub fn new(config: PingproxyConfig) -> Self {
+    /// Creates a new `Thriftproxy` process from the given `ThriftproxyConfig`.
+    pub fn new(config: ThriftproxyConfig) -> Self {
         // initialize logging
         let log_drain = configure_logging(&config);
 
         // initialize metrics
         common::metrics::init();
+        track_methods(config.thrift().methods()).expect("bad thrift method");
 
         // initialize parsers
         let request_parser = MessageParser::new(MAX_SIZE);
         let response_parser = MessageParser::new(MAX_SIZE);
 
+        // map method names to backend pools
+        let routes = routes(&config).expect("bad thrift route");
+
         // initialize process
         let process_builder = ProcessBuilder::<
             BackendParser,
@@ -50,7 +56,14 @@ impl Thriftproxy {
             FrontendRequest,
             FrontendResponse,
         >::new(&config, log_drain, response_parser, request_parser)
-        .expect("failed to launch");
+        .expect("failed to launch")
+        .router(move |request: &Message| {
+            request
+                .method()
+                .and_then(|method| routes.get(method))
+                .copied()
+                .unwrap_or(0)
+        });
         let process = process_builder.spawn();
 
         Self { process }
@@ -70,4 +83,24 @@ impl Thriftproxy {
     }
 }
 
+// finds the backend pool for each method with a route, where the pools are
+// numbered from `1` as pool `0` is the default endpoints
+fn routes(config: &ThriftproxyConfig) -> Result<HashMap<Box<[u8]>, usize>, std::io::Error> {
+    let pools = config.backend().pools();
+    let mut routes = HashMap::new();
+    for route in config.thrift().routes() {
+        let pool = pools
+            .iter()
+            .position(|pool| pool.name() == route.pool())
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    format!("no backend pool named: {}", route.pool()),
+                )
+            })?;
+        routes.insert(route.method().as_bytes().into(), pool + 1);
+    }
+    Ok(routes)
+}
+
 common::metrics::test_no_duplicates!();
@@ -8,7 +8,7 @@ extern crate logger;
 use backtrace::Backtrace;
 use clap::App;
 use clap::Arg;
-use config::PingproxyConfig;
+use config::ThriftproxyConfig;
 use rustcommon_metrics::*;
 use thriftproxy::Thriftproxy;
 
@@ -27,8 +27,15 @@ fn main() {
         .version(env!("CARGO_PKG_VERSION"))
         .version_short("v")
         .long_about(
-            "A Pelikan proxy server which speaks the Thrift binary protocol \
-            and routes messages to a backend server.",
+            "A Pelikan proxy server which speaks Thrift, using the binary or \
+            compact protocol with framed or THeader transport, and routes \
+            messages to a backend server.\n\n\
+            Requests for a method can be routed to a named pool of backend \
+            endpoints, and per-method request counts, exceptions and \
+            latencies are exported as metrics.\n\n\
+            Requests are pipelined on the backend connections and matched to \
+            their responses by sequence id, which is rewritten so that the \
+            ids of different clients never collide.",
         )
         .arg(
             Arg::with_name("stats")
@@ -81,7 +88,7 @@ fn main() {
 
     // load config from file
     let config = if let Some(file) = matches.value_of("CONFIG") {
-        match PingproxyConfig::load(file) {
+        match ThriftproxyConfig::load(file) {
             Ok(c) => c,
             Err(e) => {
                 println!("{}", e);
@@ -0,0 +1,66 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Cdbserver serves a read-only dataset from a memory-mapped cdb file using a
+//! subset of the Memcache protocol. The dataset is built offline, and a new
+//! version of the file can be swapped in by renaming it into place and sending
+//! `reload` to the admin port.
+
+use config::*;
+use entrystore::Cdb;
+use logger::*;
+use protocol_memcache::{Request, RequestParser, Response};
+use server::{Process, ProcessBuilder};
+
+type Parser = RequestParser;
+type Storage = Cdb;
+
+/// This structure represents a running `Cdbserver` process.
+#[allow(dead_code)]
+pub struct Cdbserver {
+    process: Process,
+}
+
+impl Cdbserver {
+    /// Creates a new `Cdbserver` process from the given `CdbserverConfig`.
+    pub fn new(config: CdbserverConfig) -> Result<Self, std::io::Error> {
+        // initialize logging
+        let log_drain = configure_logging(&config);
+
+        // initialize metrics
+        common::metrics::init();
+
+        // initialize storage
+        let storage = Storage::new(&config)?;
+
+        // initialize parser
+        let parser = Parser::new().time_type(config.time().time_type());
+
+        // initialize process
+        let process_builder = ProcessBuilder::<Parser, Request, Response, Storage>::new(
+            &config, log_drain, parser, storage,
+        )?
+        .version(env!("CARGO_PKG_VERSION"));
+
+        // spawn threads
+        let process = process_builder.spawn();
+
+        Ok(Self { process })
+    }
+
+    /// Wait for all threads to complete. Blocks until the process has fully
+    /// terminated. Under normal conditions, this will block indefinitely.
+    pub fn wait(self) {
+        self.process.wait()
+    }
+
+    /// Triggers a shutdown of the process and blocks until the process has
+    /// fully terminated. This is more likely to be used for running integration
+    /// tests or other automated testing.
+    pub fn shutdown(self) {
+        self.process.shutdown()
+    }
+}
+
+common::metrics::test_no_duplicates!();
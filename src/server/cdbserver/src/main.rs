@@ -0,0 +1,125 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Cdbserver is an implementation of a backend that implements the retrieval
+//! commands of the Memcache ASCII protocol and serves an immutable dataset
+//! from a memory-mapped cdb file. Files are built offline with the
+//! `pelikan_cdb_builder` tool.
+//!
+//! Running this binary is the primary way of using Cdbserver.
+
+#[macro_use]
+extern crate logger;
+
+use backtrace::Backtrace;
+use clap::{App, Arg};
+use config::CdbserverConfig;
+use pelikan_cdbserver_rs::Cdbserver;
+use rustcommon_metrics::*;
+use server::PERCENTILES;
+
+/// The entry point into the running Cdbserver instance. This function parses the
+/// command line options, loads the configuration, and launches the core
+/// threads.
+fn main() {
+    // custom panic hook to terminate whole process after unwinding
+    std::panic::set_hook(Box::new(|s| {
+        eprintln!("{}", s);
+        eprintln!("{:?}", Backtrace::new());
+        std::process::exit(101);
+    }));
+
+    // parse command line options
+    let matches = App::new(env!("CARGO_BIN_NAME"))
+        .version(env!("CARGO_PKG_VERSION"))
+        .version_short("v")
+        .long_about(
+            "One of the unified cache backends implemented in Rust. It \
+            uses djb's CDB format (a read-only data store) to serve key/val \
+            pairs. It speaks the memcached ASCII protocol and supports the \
+            get and gets commands.",
+        )
+        .arg(
+            Arg::with_name("stats")
+                .short("s")
+                .long("stats")
+                .help("List all metrics in stats")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("CONFIG")
+                .help("Server configuration file")
+                .index(1),
+        )
+        .arg(
+            Arg::with_name("print-config")
+                .help("List all options in config")
+                .long("config")
+                .short("c"),
+        )
+        .get_matches();
+
+    // output stats descriptions and exit if the `stats` option was provided
+    if matches.is_present("stats") {
+        println!("{:<31} {:<15} DESCRIPTION", "NAME", "TYPE");
+
+        let mut metrics = Vec::new();
+
+        for metric in &rustcommon_metrics::metrics() {
+            let any = match metric.as_any() {
+                Some(any) => any,
+                None => {
+                    continue;
+                }
+            };
+
+            if any.downcast_ref::<Counter>().is_some() {
+                metrics.push(format!("{:<31} counter", metric.name()));
+            } else if any.downcast_ref::<Gauge>().is_some() {
+                metrics.push(format!("{:<31} gauge", metric.name()));
+            } else if any.downcast_ref::<Heatmap>().is_some() {
+                for (label, _) in PERCENTILES {
+                    let name = format!("{}_{}", metric.name(), label);
+                    metrics.push(format!("{:<31} percentile", name));
+                }
+            } else {
+                continue;
+            }
+        }
+
+        metrics.sort();
+        for metric in metrics {
+            println!("{}", metric);
+        }
+        std::process::exit(0);
+    }
+
+    // load config from file
+    let config = if let Some(file) = matches.value_of("CONFIG") {
+        debug!("loading config: {}", file);
+        match CdbserverConfig::load(file) {
+            Ok(c) => c,
+            Err(error) => {
+                eprintln!("error loading config file: {file}\n{error}");
+                std::process::exit(1);
+            }
+        }
+    } else {
+        Default::default()
+    };
+
+    if matches.is_present("print-config") {
+        config.print();
+        std::process::exit(0);
+    }
+
+    // launch cdbserver
+    match Cdbserver::new(config) {
+        Ok(cdbserver) => cdbserver.wait(),
+        Err(e) => {
+            eprintln!("error launching cdbserver: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
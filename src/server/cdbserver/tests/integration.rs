@@ -0,0 +1,109 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! This test module runs the integration test suite against a single-threaded
+//! instance of Cdbserver, including swapping in a new file from the admin port.
+
+mod common;
+
+#[macro_use]
+extern crate logger;
+
+use crate::common::*;
+
+use cdb::Writer;
+use config::CdbserverConfig;
+use pelikan_cdbserver_rs::Cdbserver;
+use tempfile::TempDir;
+
+use std::path::Path;
+use std::time::Duration;
+
+// builds a new cdb file and atomically renames it into place
+fn build(path: &Path, records: &[(&[u8], &[u8])]) {
+    let tmp = path.with_extension("tmp");
+    let mut writer = Writer::create(&tmp).expect("failed to create cdb");
+    for (key, value) in records {
+        writer.insert(key, value).expect("failed to insert");
+    }
+    writer.finish().expect("failed to finish cdb");
+    std::fs::rename(&tmp, path).expect("failed to rename cdb");
+}
+
+fn main() {
+    let dir = TempDir::new().expect("failed to create tempdir");
+    let data = dir.path().join("db.cdb");
+    let config = dir.path().join("cdbserver.toml");
+
+    build(&data, &[(b"coffee", b"strong"), (b"tea", b"green")]);
+
+    std::fs::write(
+        &config,
+        format!("[cdb]\npath = \"{}\"\n", data.to_str().unwrap()),
+    )
+    .expect("failed to write config");
+
+    debug!("launching server");
+    let config = CdbserverConfig::load(config.to_str().unwrap()).expect("failed to load config");
+    let server = Cdbserver::new(config).expect("failed to launch cdbserver");
+
+    // wait for server to startup. duration is chosen to be longer than we'd
+    // expect startup to take in a slow ci environment.
+    std::thread::sleep(Duration::from_secs(10));
+
+    test("get miss", &[("get water\r\n", Some("END\r\n"))]);
+    test(
+        "get hit",
+        &[(
+            "get coffee tea\r\n",
+            Some("VALUE coffee 0 6\r\nstrong\r\nVALUE tea 0 5\r\ngreen\r\nEND\r\n"),
+        )],
+    );
+    test(
+        "gets hit",
+        &[("gets tea\r\n", Some("VALUE tea 0 5 0\r\ngreen\r\nEND\r\n"))],
+    );
+    test(
+        "writes are rejected",
+        &[
+            (
+                "set tea 0 0 5\r\nblack\r\n",
+                Some("SERVER_ERROR read-only\r\n"),
+            ),
+            ("delete tea\r\n", Some("SERVER_ERROR read-only\r\n")),
+            // the connection remains usable and the value is unchanged
+            ("get tea\r\n", Some("VALUE tea 0 5\r\ngreen\r\nEND\r\n")),
+        ],
+    );
+
+    // swap in a new file and trigger a reload
+    build(&data, &[(b"tea", b"black")]);
+    admin_test("reload", &[("reload\r\n", Some("OK\r\n"))]);
+    std::thread::sleep(Duration::from_millis(500));
+
+    test("get after reload", &[("get coffee\r\n", Some("END\r\n"))]);
+    test(
+        "get updated after reload",
+        &[("get tea\r\n", Some("VALUE tea 0 5\r\nblack\r\nEND\r\n"))],
+    );
+
+    // a reload of an invalid file keeps serving the previous file. the file
+    // is renamed into place, as modifying a mapped file is not allowed
+    let tmp = data.with_extension("tmp");
+    std::fs::write(&tmp, b"not a cdb").expect("failed to write file");
+    std::fs::rename(&tmp, &data).expect("failed to rename file");
+    admin_test("reload invalid", &[("reload\r\n", Some("OK\r\n"))]);
+    std::thread::sleep(Duration::from_millis(500));
+
+    test(
+        "get after failed reload",
+        &[("get tea\r\n", Some("VALUE tea 0 5\r\nblack\r\nEND\r\n"))],
+    );
+
+    // shutdown server and join
+    info!("shutdown...");
+    let _ = server.shutdown();
+
+    info!("passed!");
+}
@@ -0,0 +1,67 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Httpcache is a cache which serves keys over HTTP and stores its data in
+//! segment-structured storage. It runs on the same worker and storage threads
+//! as the other servers, and allows services which cannot use a memcache
+//! client to use the cache.
+
+use config::*;
+use entrystore::Seg;
+use logger::*;
+use protocol_http::{ParseData, RequestParser, Response};
+use server::{Process, ProcessBuilder};
+
+type Parser = RequestParser;
+type Request = ParseData;
+type Storage = Seg;
+
+/// This structure represents a running `Httpcache` process.
+#[allow(dead_code)]
+pub struct Httpcache {
+    process: Process,
+}
+
+impl Httpcache {
+    /// Creates a new `Httpcache` process from the given `HttpcacheConfig`.
+    pub fn new(config: HttpcacheConfig) -> Result<Self, std::io::Error> {
+        // initialize logging
+        let log_drain = configure_logging(&config);
+
+        // initialize metrics
+        common::metrics::init();
+
+        // initialize storage
+        let storage = Storage::new(&config)?;
+
+        // initialize parser
+        let parser = Parser::new().max_value_size(config.seg().segment_size() as usize);
+
+        // initialize process
+        let process_builder = ProcessBuilder::<Parser, Request, Response, Storage>::new(
+            &config, log_drain, parser, storage,
+        )?
+        .version(env!("CARGO_PKG_VERSION"));
+
+        // spawn threads
+        let process = process_builder.spawn();
+
+        Ok(Self { process })
+    }
+
+    /// Wait for all threads to complete. Blocks until the process has fully
+    /// terminated. Under normal conditions, this will block indefinitely.
+    pub fn wait(self) {
+        self.process.wait()
+    }
+
+    /// Triggers a shutdown of the process and blocks until the process has
+    /// fully terminated. This is more likely to be used for running integration
+    /// tests or other automated testing.
+    pub fn shutdown(self) {
+        self.process.shutdown()
+    }
+}
+
+common::metrics::test_no_duplicates!();
@@ -0,0 +1,365 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! This module provides a set of integration tests and a function to run the
+//! tests against an Httpcache instance.
+
+use logger::*;
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+// the connection headers of a response which keeps the connection open
+const KEEP_ALIVE: &str = "Connection: keep-alive\r\nKeep-Alive: timeout=60\r\n";
+
+pub fn tests() {
+    debug!("beginning tests");
+    println!();
+
+    // get on a key that is not in the cache results in a not found
+    test(
+        "get miss",
+        &[(
+            "GET /0 HTTP/1.1\r\n\r\n",
+            Some(&format!(
+                "HTTP/1.1 404 Not Found\r\n{}Content-Length: 0\r\n\r\n",
+                KEEP_ALIVE
+            )),
+        )],
+    );
+
+    // check that we can store and retrieve a key
+    test(
+        "put and get",
+        &[
+            // store the key
+            (
+                "PUT /1 HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello",
+                Some(&format!(
+                    "HTTP/1.1 201 Created\r\n{}Content-Length: 0\r\n\r\n",
+                    KEEP_ALIVE
+                )),
+            ),
+            // retrieve the key
+            (
+                "GET /1 HTTP/1.1\r\n\r\n",
+                Some(&format!(
+                    "HTTP/1.1 200 OK\r\nX-Pelikan-Flags: 0\r\n{}Content-Length: 5\r\n\r\nhello",
+                    KEEP_ALIVE
+                )),
+            ),
+        ],
+    );
+
+    // replacing a key has no content to return
+    test(
+        "put replace",
+        &[
+            (
+                "PUT /2 HTTP/1.1\r\nContent-Length: 1\r\n\r\na",
+                Some(&format!(
+                    "HTTP/1.1 201 Created\r\n{}Content-Length: 0\r\n\r\n",
+                    KEEP_ALIVE
+                )),
+            ),
+            (
+                "PUT /2 HTTP/1.1\r\nContent-Length: 1\r\n\r\nb",
+                Some(&format!("HTTP/1.1 204 No Content\r\n{}\r\n", KEEP_ALIVE)),
+            ),
+            (
+                "GET /2 HTTP/1.1\r\n\r\n",
+                Some(&format!(
+                    "HTTP/1.1 200 OK\r\nX-Pelikan-Flags: 0\r\n{}Content-Length: 1\r\n\r\nb",
+                    KEEP_ALIVE
+                )),
+            ),
+        ],
+    );
+
+    // the client flags are returned with the value
+    test(
+        "put with flags",
+        &[
+            (
+                "PUT /3 HTTP/1.1\r\nContent-Length: 1\r\nX-Pelikan-Flags: 42\r\nX-Pelikan-TTL: 3600\r\n\r\na",
+                Some(&format!("HTTP/1.1 201 Created\r\n{}Content-Length: 0\r\n\r\n", KEEP_ALIVE)),
+            ),
+            (
+                "GET /3 HTTP/1.1\r\n\r\n",
+                Some(&format!(
+                    "HTTP/1.1 200 OK\r\nX-Pelikan-Flags: 42\r\n{}Content-Length: 1\r\n\r\na",
+                    KEEP_ALIVE
+                )),
+            ),
+        ],
+    );
+
+    // delete removes the key, and a second delete does not find it
+    test(
+        "delete",
+        &[
+            (
+                "PUT /4 HTTP/1.1\r\nContent-Length: 1\r\n\r\na",
+                Some(&format!(
+                    "HTTP/1.1 201 Created\r\n{}Content-Length: 0\r\n\r\n",
+                    KEEP_ALIVE
+                )),
+            ),
+            (
+                "DELETE /4 HTTP/1.1\r\n\r\n",
+                Some(&format!("HTTP/1.1 204 No Content\r\n{}\r\n", KEEP_ALIVE)),
+            ),
+            (
+                "GET /4 HTTP/1.1\r\n\r\n",
+                Some(&format!(
+                    "HTTP/1.1 404 Not Found\r\n{}Content-Length: 0\r\n\r\n",
+                    KEEP_ALIVE
+                )),
+            ),
+            (
+                "DELETE /4 HTTP/1.1\r\n\r\n",
+                Some(&format!(
+                    "HTTP/1.1 404 Not Found\r\n{}Content-Length: 0\r\n\r\n",
+                    KEEP_ALIVE
+                )),
+            ),
+        ],
+    );
+
+    // a chunked body is reassembled before it is stored
+    test(
+        "put chunked",
+        &[
+            (
+                "PUT /7 HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nhel\r\n2\r\nlo\r\n0\r\n\r\n",
+                Some(&format!("HTTP/1.1 201 Created\r\n{}Content-Length: 0\r\n\r\n", KEEP_ALIVE)),
+            ),
+            (
+                "GET /7 HTTP/1.1\r\n\r\n",
+                Some(&format!(
+                    "HTTP/1.1 200 OK\r\nX-Pelikan-Flags: 0\r\n{}Content-Length: 5\r\n\r\nhello",
+                    KEEP_ALIVE
+                )),
+            ),
+        ],
+    );
+
+    // the body is only sent once the server agrees to continue
+    test(
+        "put expect continue",
+        &[
+            (
+                "PUT /8 HTTP/1.1\r\nContent-Length: 1\r\nExpect: 100-continue\r\n\r\n",
+                Some("HTTP/1.1 100 Continue\r\n\r\n"),
+            ),
+            (
+                "a",
+                Some(&format!(
+                    "HTTP/1.1 201 Created\r\n{}Content-Length: 0\r\n\r\n",
+                    KEEP_ALIVE
+                )),
+            ),
+        ],
+    );
+
+    // pipelined requests are answered in order
+    test(
+        "pipelined",
+        &[(
+            "PUT /9 HTTP/1.1\r\nContent-Length: 1\r\n\r\naDELETE /9 HTTP/1.1\r\n\r\n",
+            Some(&format!(
+                "HTTP/1.1 201 Created\r\n{0}Content-Length: 0\r\n\r\nHTTP/1.1 204 No Content\r\n{0}\r\n",
+                KEEP_ALIVE
+            )),
+        )],
+    );
+
+    // an HTTP/1.0 client without keep-alive has its connection closed
+    test(
+        "http/1.0 close",
+        &[(
+            "GET /9 HTTP/1.0\r\n\r\n",
+            Some("HTTP/1.1 404 Not Found\r\nConnection: close\r\nContent-Length: 0\r\n\r\n"),
+        )],
+    );
+
+    // a TTL which is not a number is rejected
+    test(
+        "put bad ttl",
+        &[(
+            "PUT /5 HTTP/1.1\r\nContent-Length: 1\r\nX-Pelikan-TTL: soon\r\n\r\na",
+            Some("HTTP/1.1 400 Bad Request\r\n"),
+        )],
+    );
+
+    // a post is parsed, but is not supported by the cache
+    test(
+        "post",
+        &[(
+            "POST /6 HTTP/1.1\r\nContent-Length: 1\r\n\r\na",
+            Some(&format!(
+                "HTTP/1.1 405 Method Not Allowed\r\nAllow: GET, PUT, DELETE\r\n{}Content-Length: 0\r\n\r\n",
+                KEEP_ALIVE
+            )),
+        )],
+    );
+
+    // unsupported methods close the connection
+    test(
+        "unsupported method",
+        &[(
+            "PATCH /6 HTTP/1.1\r\n\r\n",
+            Some("HTTP/1.1 405 Method Not Allowed\r\nContent-Type: text/plain\r\nConnection: close\r\n"),
+        )],
+    );
+
+    std::thread::sleep(Duration::from_millis(500));
+}
+
+// opens a new connection, operating on request + response pairs from the
+// provided data.
+fn test(name: &str, data: &[(&str, Option<&str>)]) {
+    info!("testing: {}", name);
+    debug!("connecting to server");
+    let mut stream = TcpStream::connect("127.0.0.1:12321").expect("failed to connect");
+    stream
+        .set_read_timeout(Some(Duration::from_millis(250)))
+        .expect("failed to set read timeout");
+    stream
+        .set_write_timeout(Some(Duration::from_millis(250)))
+        .expect("failed to set write timeout");
+
+    debug!("sending request");
+    for (request, response) in data {
+        match stream.write(request.as_bytes()) {
+            Ok(bytes) => {
+                if bytes == request.len() {
+                    debug!("full request sent");
+                } else {
+                    error!("incomplete write");
+                    panic!("status: failed\n");
+                }
+            }
+            Err(_) => {
+                error!("error sending request");
+                panic!("status: failed\n");
+            }
+        }
+
+        std::thread::sleep(Duration::from_millis(10));
+        let mut buf = vec![0; 4096];
+
+        if let Some(response) = response {
+            if stream.read(&mut buf).is_err() {
+                std::thread::sleep(Duration::from_millis(500));
+                panic!("error reading response");
+            } else if response.as_bytes() != &buf[0..response.len()] {
+                error!("expected: {:?}", response.as_bytes());
+                error!("received: {:?}", &buf[0..response.len()]);
+                std::thread::sleep(Duration::from_millis(500));
+                panic!("status: failed\n");
+            } else {
+                debug!("correct response");
+            }
+            assert_eq!(response.as_bytes(), &buf[0..response.len()]);
+        } else if let Err(e) = stream.read(&mut buf) {
+            if e.kind() == std::io::ErrorKind::WouldBlock {
+                debug!("got no response");
+            } else {
+                error!("error reading response");
+                std::thread::sleep(Duration::from_millis(500));
+                panic!("status: failed\n");
+            }
+        } else {
+            error!("expected no response");
+            std::thread::sleep(Duration::from_millis(500));
+            panic!("status: failed\n");
+        }
+
+        if data.len() > 1 {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+    info!("status: passed\n");
+}
+
+pub fn admin_tests() {
+    debug!("beginning admin tests");
+    println!();
+
+    admin_test(
+        "version",
+        &[(
+            "version\r\n",
+            Some(&format!("VERSION {}\r\n", env!("CARGO_PKG_VERSION"))),
+        )],
+    );
+}
+
+// opens a new connection to the admin port, sends a request, and checks the response.
+fn admin_test(name: &str, data: &[(&str, Option<&str>)]) {
+    info!("testing: {}", name);
+    debug!("connecting to server");
+    let mut stream = TcpStream::connect("127.0.0.1:9999").expect("failed to connect");
+    stream
+        .set_read_timeout(Some(Duration::from_millis(250)))
+        .expect("failed to set read timeout");
+    stream
+        .set_write_timeout(Some(Duration::from_millis(250)))
+        .expect("failed to set write timeout");
+
+    debug!("sending request");
+    for (request, response) in data {
+        match stream.write(request.as_bytes()) {
+            Ok(bytes) => {
+                if bytes == request.len() {
+                    debug!("full request sent");
+                } else {
+                    error!("incomplete write");
+                    panic!("status: failed\n");
+                }
+            }
+            Err(_) => {
+                error!("error sending request");
+                panic!("status: failed\n");
+            }
+        }
+
+        std::thread::sleep(Duration::from_millis(10));
+        let mut buf = vec![0; 4096];
+
+        if let Some(response) = response {
+            if stream.read(&mut buf).is_err() {
+                std::thread::sleep(Duration::from_millis(500));
+                panic!("error reading response");
+            } else if response.as_bytes() != &buf[0..response.len()] {
+                error!("expected: {:?}", response.as_bytes());
+                error!("received: {:?}", &buf[0..response.len()]);
+                std::thread::sleep(Duration::from_millis(500));
+                panic!("status: failed\n");
+            } else {
+                debug!("correct response");
+            }
+            assert_eq!(response.as_bytes(), &buf[0..response.len()]);
+        } else if let Err(e) = stream.read(&mut buf) {
+            if e.kind() == std::io::ErrorKind::WouldBlock {
+                debug!("got no response");
+            } else {
+                error!("error reading response");
+                std::thread::sleep(Duration::from_millis(500));
+                panic!("status: failed\n");
+            }
+        } else {
+            error!("expected no response");
+            std::thread::sleep(Duration::from_millis(500));
+            panic!("status: failed\n");
+        }
+
+        if data.len() > 1 {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+    info!("status: passed\n");
+}
@@ -0,0 +1,37 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! This test module runs the integration test suite against a single-threaded
+//! instance of Httpcache.
+
+mod common;
+
+#[macro_use]
+extern crate logger;
+
+use crate::common::*;
+
+use config::HttpcacheConfig;
+use pelikan_httpcache_rs::Httpcache;
+
+use std::time::Duration;
+
+fn main() {
+    debug!("launching server");
+    let server = Httpcache::new(HttpcacheConfig::default()).expect("failed to launch httpcache");
+
+    // wait for server to startup. duration is chosen to be longer than we'd
+    // expect startup to take in a slow ci environment.
+    std::thread::sleep(Duration::from_secs(10));
+
+    tests();
+
+    admin_tests();
+
+    // shutdown server and join
+    info!("shutdown...");
+    let _ = server.shutdown();
+
+    info!("passed!");
+}
@@ -0,0 +1,531 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! This module provides a set of integration tests and a function to run the
+//! tests against an Rds instance.
+
+use logger::*;
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+pub fn tests() {
+    debug!("beginning tests");
+    println!();
+
+    // get on a key that is not in the cache results in a null bulk string
+    test(
+        "get miss",
+        &[("*2\r\n$3\r\nget\r\n$1\r\n0\r\n", Some("$-1\r\n"))],
+    );
+
+    // check that we can store and retrieve a key
+    test(
+        "set and get",
+        &[
+            // store the key
+            ("*3\r\n$3\r\nset\r\n$1\r\n1\r\n$1\r\n1\r\n", Some("+OK\r\n")),
+            // retrieve the key
+            ("*2\r\n$3\r\nget\r\n$1\r\n1\r\n", Some("$1\r\n1\r\n")),
+        ],
+    );
+
+    // list commands on a missing key behave as if the list were empty
+    test(
+        "list miss",
+        &[
+            ("*2\r\n$4\r\nllen\r\n$1\r\n2\r\n", Some(":0\r\n")),
+            (
+                "*4\r\n$6\r\nlrange\r\n$1\r\n2\r\n$1\r\n0\r\n$2\r\n-1\r\n",
+                Some("*0\r\n"),
+            ),
+            ("*2\r\n$4\r\nlpop\r\n$1\r\n2\r\n", Some("$-1\r\n")),
+        ],
+    );
+
+    // push to both ends of a list and read it back
+    test(
+        "push and range",
+        &[
+            (
+                "*4\r\n$5\r\nrpush\r\n$1\r\n3\r\n$1\r\nb\r\n$1\r\nc\r\n",
+                Some(":2\r\n"),
+            ),
+            (
+                "*3\r\n$5\r\nlpush\r\n$1\r\n3\r\n$1\r\na\r\n",
+                Some(":3\r\n"),
+            ),
+            ("*2\r\n$4\r\nllen\r\n$1\r\n3\r\n", Some(":3\r\n")),
+            (
+                "*4\r\n$6\r\nlrange\r\n$1\r\n3\r\n$1\r\n0\r\n$2\r\n-1\r\n",
+                Some("*3\r\n$1\r\na\r\n$1\r\nb\r\n$1\r\nc\r\n"),
+            ),
+            (
+                "*3\r\n$6\r\nlindex\r\n$1\r\n3\r\n$2\r\n-1\r\n",
+                Some("$1\r\nc\r\n"),
+            ),
+        ],
+    );
+
+    // pop from both ends until the list is empty
+    test(
+        "push and pop",
+        &[
+            (
+                "*5\r\n$5\r\nrpush\r\n$1\r\n4\r\n$1\r\na\r\n$1\r\nb\r\n$1\r\nc\r\n",
+                Some(":3\r\n"),
+            ),
+            ("*2\r\n$4\r\nlpop\r\n$1\r\n4\r\n", Some("$1\r\na\r\n")),
+            ("*2\r\n$4\r\nrpop\r\n$1\r\n4\r\n", Some("$1\r\nc\r\n")),
+            (
+                "*3\r\n$4\r\nlpop\r\n$1\r\n4\r\n$1\r\n5\r\n",
+                Some("*1\r\n$1\r\nb\r\n"),
+            ),
+            ("*2\r\n$4\r\nllen\r\n$1\r\n4\r\n", Some(":0\r\n")),
+        ],
+    );
+
+    // trim a list down to a range
+    test(
+        "trim",
+        &[
+            (
+                "*6\r\n$5\r\nrpush\r\n$1\r\n5\r\n$1\r\na\r\n$1\r\nb\r\n$1\r\nc\r\n$1\r\nd\r\n",
+                Some(":4\r\n"),
+            ),
+            (
+                "*4\r\n$5\r\nltrim\r\n$1\r\n5\r\n$1\r\n1\r\n$1\r\n2\r\n",
+                Some("+OK\r\n"),
+            ),
+            (
+                "*4\r\n$6\r\nlrange\r\n$1\r\n5\r\n$1\r\n0\r\n$2\r\n-1\r\n",
+                Some("*2\r\n$1\r\nb\r\n$1\r\nc\r\n"),
+            ),
+        ],
+    );
+
+    // commands against a key holding the wrong type are rejected
+    test(
+        "wrong type",
+        &[
+            ("*3\r\n$3\r\nset\r\n$1\r\n6\r\n$1\r\n6\r\n", Some("+OK\r\n")),
+            (
+                "*3\r\n$5\r\nrpush\r\n$1\r\n6\r\n$1\r\na\r\n",
+                Some("-WRONGTYPE"),
+            ),
+            (
+                "*3\r\n$5\r\nrpush\r\n$1\r\n7\r\n$1\r\na\r\n",
+                Some(":1\r\n"),
+            ),
+            ("*2\r\n$3\r\nget\r\n$1\r\n7\r\n", Some("-WRONGTYPE")),
+        ],
+    );
+
+    // set, read, increment, and delete hash fields
+    test(
+        "hash",
+        &[
+            (
+                "*6\r\n$4\r\nhset\r\n$1\r\n8\r\n$1\r\na\r\n$1\r\n1\r\n$1\r\nb\r\n$1\r\n2\r\n",
+                Some(":2\r\n"),
+            ),
+            (
+                "*3\r\n$4\r\nhget\r\n$1\r\n8\r\n$1\r\na\r\n",
+                Some("$1\r\n1\r\n"),
+            ),
+            (
+                "*4\r\n$5\r\nhmget\r\n$1\r\n8\r\n$1\r\nb\r\n$1\r\nc\r\n",
+                Some("*2\r\n$1\r\n2\r\n$-1\r\n"),
+            ),
+            (
+                "*4\r\n$7\r\nhincrby\r\n$1\r\n8\r\n$1\r\na\r\n$2\r\n10\r\n",
+                Some(":11\r\n"),
+            ),
+            (
+                "*3\r\n$7\r\nhexists\r\n$1\r\n8\r\n$1\r\nb\r\n",
+                Some(":1\r\n"),
+            ),
+            ("*3\r\n$4\r\nhdel\r\n$1\r\n8\r\n$1\r\nb\r\n", Some(":1\r\n")),
+            ("*2\r\n$4\r\nhlen\r\n$1\r\n8\r\n", Some(":1\r\n")),
+            (
+                "*2\r\n$7\r\nhgetall\r\n$1\r\n8\r\n",
+                Some("*2\r\n$1\r\na\r\n$2\r\n11\r\n"),
+            ),
+            ("*2\r\n$4\r\nllen\r\n$1\r\n8\r\n", Some("-WRONGTYPE")),
+        ],
+    );
+
+    // hash fields which are larger than the configured limit are rejected
+    let field = "f".repeat(1025);
+    let hset = format!(
+        "*4\r\n$4\r\nhset\r\n$1\r\n8\r\n${}\r\n{}\r\n$1\r\n1\r\n",
+        field.len(),
+        field
+    );
+    test(
+        "hash limits",
+        &[
+            (&hset, Some("-ERR hash field is too large\r\n")),
+            ("*2\r\n$4\r\nhlen\r\n$1\r\n8\r\n", Some(":1\r\n")),
+        ],
+    );
+
+    // sorted maps keep their inner keys in order
+    test(
+        "sorted map",
+        &[
+            (
+                "*6\r\n$4\r\nbadd\r\n$1\r\n9\r\n$1\r\nb\r\n$1\r\n2\r\n$1\r\na\r\n$1\r\n1\r\n",
+                Some(":2\r\n"),
+            ),
+            ("*2\r\n$5\r\nbcard\r\n$1\r\n9\r\n", Some(":2\r\n")),
+            (
+                "*3\r\n$4\r\nbget\r\n$1\r\n9\r\n$1\r\nb\r\n",
+                Some("$1\r\n2\r\n"),
+            ),
+            (
+                "*2\r\n$6\r\nbrange\r\n$1\r\n9\r\n",
+                Some("*4\r\n$1\r\na\r\n$1\r\n1\r\n$1\r\nb\r\n$1\r\n2\r\n"),
+            ),
+            ("*3\r\n$4\r\nbdel\r\n$1\r\n9\r\n$1\r\na\r\n", Some(":1\r\n")),
+            (
+                "*3\r\n$6\r\nbrange\r\n$1\r\n9\r\n$1\r\na\r\n",
+                Some("*2\r\n$1\r\nb\r\n$1\r\n2\r\n"),
+            ),
+        ],
+    );
+
+    // health checks used by client libraries
+    test(
+        "ping and echo",
+        &[
+            ("*1\r\n$4\r\nping\r\n", Some("+PONG\r\n")),
+            ("*2\r\n$4\r\necho\r\n$2\r\nhi\r\n", Some("$2\r\nhi\r\n")),
+        ],
+    );
+
+    // integer and string commands modify the value in place
+    test(
+        "incr and append",
+        &[
+            ("*2\r\n$4\r\nincr\r\n$2\r\n10\r\n", Some(":1\r\n")),
+            (
+                "*3\r\n$6\r\nincrby\r\n$2\r\n10\r\n$2\r\n10\r\n",
+                Some(":11\r\n"),
+            ),
+            (
+                "*3\r\n$6\r\ndecrby\r\n$2\r\n10\r\n$1\r\n2\r\n",
+                Some(":9\r\n"),
+            ),
+            (
+                "*3\r\n$6\r\nappend\r\n$2\r\n10\r\n$1\r\n0\r\n",
+                Some(":2\r\n"),
+            ),
+            ("*2\r\n$6\r\nstrlen\r\n$2\r\n10\r\n", Some(":2\r\n")),
+            (
+                "*3\r\n$6\r\ngetset\r\n$2\r\n10\r\n$1\r\na\r\n",
+                Some("$2\r\n90\r\n"),
+            ),
+            (
+                "*2\r\n$4\r\nincr\r\n$2\r\n10\r\n",
+                Some("-ERR value is not an integer"),
+            ),
+            ("*2\r\n$4\r\nincr\r\n$1\r\n8\r\n", Some("-WRONGTYPE")),
+        ],
+    );
+
+    // commands which operate on multiple keys
+    test(
+        "multiple keys",
+        &[
+            (
+                "*5\r\n$4\r\nmset\r\n$2\r\n11\r\n$1\r\na\r\n$2\r\n12\r\n$1\r\nb\r\n",
+                Some("+OK\r\n"),
+            ),
+            (
+                "*4\r\n$4\r\nmget\r\n$2\r\n11\r\n$2\r\n12\r\n$2\r\n13\r\n",
+                Some("*3\r\n$1\r\na\r\n$1\r\nb\r\n$-1\r\n"),
+            ),
+            (
+                "*3\r\n$5\r\nsetnx\r\n$2\r\n11\r\n$1\r\nc\r\n",
+                Some(":0\r\n"),
+            ),
+            (
+                "*4\r\n$6\r\nexists\r\n$2\r\n11\r\n$2\r\n12\r\n$2\r\n13\r\n",
+                Some(":2\r\n"),
+            ),
+            (
+                "*4\r\n$3\r\ndel\r\n$2\r\n11\r\n$2\r\n12\r\n$2\r\n13\r\n",
+                Some(":2\r\n"),
+            ),
+            ("*2\r\n$6\r\nexists\r\n$2\r\n11\r\n", Some(":0\r\n")),
+        ],
+    );
+
+    // expiration and type of keys
+    test(
+        "expire and type",
+        &[
+            (
+                "*3\r\n$3\r\nset\r\n$2\r\n14\r\n$1\r\na\r\n",
+                Some("+OK\r\n"),
+            ),
+            ("*2\r\n$3\r\nttl\r\n$2\r\n14\r\n", Some(":-1\r\n")),
+            (
+                "*3\r\n$6\r\nexpire\r\n$2\r\n14\r\n$3\r\n100\r\n",
+                Some(":1\r\n"),
+            ),
+            ("*2\r\n$3\r\nttl\r\n$2\r\n14\r\n", Some(":")),
+            ("*2\r\n$7\r\npersist\r\n$2\r\n14\r\n", Some(":1\r\n")),
+            ("*2\r\n$4\r\npttl\r\n$2\r\n14\r\n", Some(":-1\r\n")),
+            ("*2\r\n$3\r\nttl\r\n$2\r\n15\r\n", Some(":-2\r\n")),
+            ("*2\r\n$4\r\ntype\r\n$2\r\n14\r\n", Some("+string\r\n")),
+            ("*2\r\n$4\r\ntype\r\n$1\r\n8\r\n", Some("+hash\r\n")),
+            ("*2\r\n$4\r\ntype\r\n$2\r\n15\r\n", Some("+none\r\n")),
+        ],
+    );
+
+    // queue commands and run them as one unit
+    test(
+        "multi and exec",
+        &[
+            ("*1\r\n$5\r\nmulti\r\n", Some("+OK\r\n")),
+            ("*2\r\n$4\r\nincr\r\n$2\r\n16\r\n", Some("+QUEUED\r\n")),
+            ("*2\r\n$4\r\nincr\r\n$2\r\n16\r\n", Some("+QUEUED\r\n")),
+            ("*1\r\n$4\r\nexec\r\n", Some("*2\r\n:1\r\n:2\r\n")),
+            ("*1\r\n$4\r\nexec\r\n", Some("-ERR EXEC without MULTI")),
+            ("*1\r\n$5\r\nmulti\r\n", Some("+OK\r\n")),
+            ("*2\r\n$4\r\nincr\r\n$2\r\n16\r\n", Some("+QUEUED\r\n")),
+            ("*1\r\n$7\r\ndiscard\r\n", Some("+OK\r\n")),
+            ("*2\r\n$3\r\nget\r\n$2\r\n16\r\n", Some("$1\r\n2\r\n")),
+        ],
+    );
+
+    // a change to a watched key aborts the transaction
+    test(
+        "watch",
+        &[
+            ("*2\r\n$5\r\nwatch\r\n$2\r\n17\r\n", Some("+OK\r\n")),
+            (
+                "*3\r\n$3\r\nset\r\n$2\r\n17\r\n$1\r\na\r\n",
+                Some("+OK\r\n"),
+            ),
+            ("*1\r\n$5\r\nmulti\r\n", Some("+OK\r\n")),
+            ("*2\r\n$3\r\nget\r\n$2\r\n17\r\n", Some("+QUEUED\r\n")),
+            ("*1\r\n$4\r\nexec\r\n", Some("*-1\r\n")),
+            ("*2\r\n$5\r\nwatch\r\n$2\r\n17\r\n", Some("+OK\r\n")),
+            // writes to other keys do not abort the transaction
+            (
+                "*3\r\n$3\r\nset\r\n$2\r\n21\r\n$1\r\nb\r\n",
+                Some("+OK\r\n"),
+            ),
+            ("*1\r\n$5\r\nmulti\r\n", Some("+OK\r\n")),
+            ("*2\r\n$3\r\nget\r\n$2\r\n17\r\n", Some("+QUEUED\r\n")),
+            ("*1\r\n$4\r\nexec\r\n", Some("*1\r\n$1\r\na\r\n")),
+        ],
+    );
+
+    // a command which cannot be queued aborts the transaction
+    test(
+        "exec abort",
+        &[
+            ("*1\r\n$5\r\nmulti\r\n", Some("+OK\r\n")),
+            ("*2\r\n$4\r\nincr\r\n$2\r\n22\r\n", Some("+QUEUED\r\n")),
+            (
+                "*3\r\n$6\r\nexpire\r\n$2\r\n22\r\n$1\r\na\r\n",
+                Some("-ERR invalid 'expire' command\r\n"),
+            ),
+            ("*1\r\n$4\r\nexec\r\n", Some("-EXECABORT")),
+            ("*2\r\n$3\r\nget\r\n$2\r\n22\r\n", Some("$-1\r\n")),
+        ],
+    );
+
+    // replies use the RESP3 types once they are negotiated with hello
+    test(
+        "resp3",
+        &[
+            (
+                "*4\r\n$4\r\nhset\r\n$2\r\n18\r\n$1\r\nf\r\n$1\r\nv\r\n",
+                Some(":1\r\n"),
+            ),
+            ("*2\r\n$5\r\nhello\r\n$1\r\n3\r\n", Some("%6\r\n")),
+            (
+                "*2\r\n$7\r\nhgetall\r\n$2\r\n18\r\n",
+                Some("%1\r\n$1\r\nf\r\n$1\r\nv\r\n"),
+            ),
+            ("*2\r\n$3\r\nget\r\n$2\r\n19\r\n", Some("_\r\n")),
+            ("*2\r\n$5\r\nhello\r\n$1\r\n2\r\n", Some("*12\r\n")),
+            (
+                "*2\r\n$7\r\nhgetall\r\n$2\r\n18\r\n",
+                Some("*2\r\n$1\r\nf\r\n$1\r\nv\r\n"),
+            ),
+            ("*2\r\n$3\r\nget\r\n$2\r\n19\r\n", Some("$-1\r\n")),
+        ],
+    );
+
+    // a wrong number of arguments is an error reply and the session stays open
+    test(
+        "wrong arity",
+        &[
+            (
+                "*2\r\n$6\r\nexpire\r\n$2\r\n20\r\n",
+                Some("-ERR wrong number of arguments for 'expire' command\r\n"),
+            ),
+            (
+                "*3\r\n$3\r\nset\r\n$2\r\n20\r\n$1\r\na\r\n",
+                Some("+OK\r\n"),
+            ),
+            ("*2\r\n$3\r\nget\r\n$2\r\n20\r\n", Some("$1\r\na\r\n")),
+        ],
+    );
+
+    std::thread::sleep(Duration::from_millis(500));
+}
+
+// opens a new connection, operating on request + response pairs from the
+// provided data.
+fn test(name: &str, data: &[(&str, Option<&str>)]) {
+    info!("testing: {}", name);
+    debug!("connecting to server");
+    let mut stream = TcpStream::connect("127.0.0.1:12321").expect("failed to connect");
+    stream
+        .set_read_timeout(Some(Duration::from_millis(250)))
+        .expect("failed to set read timeout");
+    stream
+        .set_write_timeout(Some(Duration::from_millis(250)))
+        .expect("failed to set write timeout");
+
+    debug!("sending request");
+    for (request, response) in data {
+        match stream.write(request.as_bytes()) {
+            Ok(bytes) => {
+                if bytes == request.len() {
+                    debug!("full request sent");
+                } else {
+                    error!("incomplete write");
+                    panic!("status: failed\n");
+                }
+            }
+            Err(_) => {
+                error!("error sending request");
+                panic!("status: failed\n");
+            }
+        }
+
+        std::thread::sleep(Duration::from_millis(10));
+        let mut buf = vec![0; 4096];
+
+        if let Some(response) = response {
+            if stream.read(&mut buf).is_err() {
+                std::thread::sleep(Duration::from_millis(500));
+                panic!("error reading response");
+            } else if response.as_bytes() != &buf[0..response.len()] {
+                error!("expected: {:?}", response.as_bytes());
+                error!("received: {:?}", &buf[0..response.len()]);
+                std::thread::sleep(Duration::from_millis(500));
+                panic!("status: failed\n");
+            } else {
+                debug!("correct response");
+            }
+            assert_eq!(response.as_bytes(), &buf[0..response.len()]);
+        } else if let Err(e) = stream.read(&mut buf) {
+            if e.kind() == std::io::ErrorKind::WouldBlock {
+                debug!("got no response");
+            } else {
+                error!("error reading response");
+                std::thread::sleep(Duration::from_millis(500));
+                panic!("status: failed\n");
+            }
+        } else {
+            error!("expected no response");
+            std::thread::sleep(Duration::from_millis(500));
+            panic!("status: failed\n");
+        }
+
+        if data.len() > 1 {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+    info!("status: passed\n");
+}
+
+pub fn admin_tests() {
+    debug!("beginning admin tests");
+    println!();
+
+    admin_test(
+        "version",
+        &[(
+            "version\r\n",
+            Some(&format!("VERSION {}\r\n", env!("CARGO_PKG_VERSION"))),
+        )],
+    );
+}
+
+// opens a new connection to the admin port, sends a request, and checks the response.
+fn admin_test(name: &str, data: &[(&str, Option<&str>)]) {
+    info!("testing: {}", name);
+    debug!("connecting to server");
+    let mut stream = TcpStream::connect("127.0.0.1:9999").expect("failed to connect");
+    stream
+        .set_read_timeout(Some(Duration::from_millis(250)))
+        .expect("failed to set read timeout");
+    stream
+        .set_write_timeout(Some(Duration::from_millis(250)))
+        .expect("failed to set write timeout");
+
+    debug!("sending request");
+    for (request, response) in data {
+        match stream.write(request.as_bytes()) {
+            Ok(bytes) => {
+                if bytes == request.len() {
+                    debug!("full request sent");
+                } else {
+                    error!("incomplete write");
+                    panic!("status: failed\n");
+                }
+            }
+            Err(_) => {
+                error!("error sending request");
+                panic!("status: failed\n");
+            }
+        }
+
+        std::thread::sleep(Duration::from_millis(10));
+        let mut buf = vec![0; 4096];
+
+        if let Some(response) = response {
+            if stream.read(&mut buf).is_err() {
+                std::thread::sleep(Duration::from_millis(500));
+                panic!("error reading response");
+            } else if response.as_bytes() != &buf[0..response.len()] {
+                error!("expected: {:?}", response.as_bytes());
+                error!("received: {:?}", &buf[0..response.len()]);
+                std::thread::sleep(Duration::from_millis(500));
+                panic!("status: failed\n");
+            } else {
+                debug!("correct response");
+            }
+            assert_eq!(response.as_bytes(), &buf[0..response.len()]);
+        } else if let Err(e) = stream.read(&mut buf) {
+            if e.kind() == std::io::ErrorKind::WouldBlock {
+                debug!("got no response");
+            } else {
+                error!("error reading response");
+                std::thread::sleep(Duration::from_millis(500));
+                panic!("status: failed\n");
+            }
+        } else {
+            error!("expected no response");
+            std::thread::sleep(Duration::from_millis(500));
+            panic!("status: failed\n");
+        }
+
+        if data.len() > 1 {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+    info!("status: passed\n");
+}
@@ -9,7 +9,7 @@
 use config::*;
 use entrystore::Seg;
 use logger::*;
-use protocol_memcache::{Request, RequestParser, Response};
+use protocol_memcache::{Credentials, Request, RequestParser, Response};
 use server::{Process, ProcessBuilder};
 
 type Parser = RequestParser;
@@ -34,14 +34,37 @@ impl Segcache {
         let storage = Storage::new(&config)?;
 
         // initialize parser
-        let parser = Parser::new()
+        let mut parser = Parser::new()
             .max_value_size(config.seg().segment_size() as usize)
             .time_type(config.time().time_type());
 
+        // require authentication if credentials are configured
+        if let Some(path) = config.auth().credentials() {
+            let credentials = Credentials::load(&path).map_err(|e| {
+                std::io::Error::new(
+                    e.kind(),
+                    format!("failed to load credentials from {}: {}", path, e),
+                )
+            })?;
+            parser = parser.credentials(credentials);
+
+            // there are no sessions over UDP, so there is no way to log in
+            if config.server().udp_port().is_some() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "UDP cannot be enabled when authentication is required",
+                ));
+            }
+        }
+
+        // UDP only serves gets
+        let udp_parser = parser.clone().udp();
+
         // initialize process
         let process_builder = ProcessBuilder::<Parser, Request, Response, Storage>::new(
             &config, log_drain, parser, storage,
         )?
+        .udp(&config, udp_parser)?
         .version(env!("CARGO_PKG_VERSION"));
 
         // spawn threads
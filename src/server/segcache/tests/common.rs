@@ -202,6 +202,26 @@ pub fn tests() {
         &[("prepend 8 0 0 1\r\n0\r\n", Some("ERROR\r\n"))],
     );
 
+    // invalid requests are reported without closing the connection
+    test(
+        "unknown command",
+        &[
+            ("foo 0\r\n", Some("ERROR\r\n")),
+            ("get 0\r\n", Some("END\r\n")),
+        ],
+    );
+    test(
+        "malformed set",
+        &[
+            // the data block is skipped along with the command line
+            (
+                "set 0 abc 0 1\r\n0\r\n",
+                Some("CLIENT_ERROR bad command line format\r\n"),
+            ),
+            ("get 0\r\n", Some("END\r\n")),
+        ],
+    );
+
     std::thread::sleep(Duration::from_millis(500));
 }
 
@@ -0,0 +1,68 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Slimcache is a cache implementation which uses cuckoo hash based storage and
+//! a subset of the Memcache protocol. Items are stored in fixed-size slots which
+//! results in a very low per-item overhead, making it well suited for workloads
+//! with small keys and values.
+
+use config::*;
+use entrystore::Cuckoo;
+use logger::*;
+use protocol_memcache::{Request, RequestParser, Response};
+use server::{Process, ProcessBuilder};
+
+type Parser = RequestParser;
+type Storage = Cuckoo;
+
+/// This structure represents a running `Slimcache` process.
+#[allow(dead_code)]
+pub struct Slimcache {
+    process: Process,
+}
+
+impl Slimcache {
+    /// Creates a new `Slimcache` process from the given `SlimcacheConfig`.
+    pub fn new(config: SlimcacheConfig) -> Result<Self, std::io::Error> {
+        // initialize logging
+        let log_drain = configure_logging(&config);
+
+        // initialize metrics
+        common::metrics::init();
+
+        // initialize storage
+        let storage = Storage::new(&config)?;
+
+        // initialize parser
+        let parser = Parser::new()
+            .max_value_size(config.cuckoo().item_size())
+            .time_type(config.time().time_type());
+
+        // initialize process
+        let process_builder = ProcessBuilder::<Parser, Request, Response, Storage>::new(
+            &config, log_drain, parser, storage,
+        )?
+        .version(env!("CARGO_PKG_VERSION"));
+
+        // spawn threads
+        let process = process_builder.spawn();
+
+        Ok(Self { process })
+    }
+
+    /// Wait for all threads to complete. Blocks until the process has fully
+    /// terminated. Under normal conditions, this will block indefinitely.
+    pub fn wait(self) {
+        self.process.wait()
+    }
+
+    /// Triggers a shutdown of the process and blocks until the process has
+    /// fully terminated. This is more likely to be used for running integration
+    /// tests or other automated testing.
+    pub fn shutdown(self) {
+        self.process.shutdown()
+    }
+}
+
+common::metrics::test_no_duplicates!();
@@ -66,6 +66,13 @@ where
         Ok(size)
     }
 
+    /// Sends a message which the server never replies to. Unlike `send`, the
+    /// message is not queued to await a response.
+    pub fn send_oneway(&mut self, tx: Tx) -> Result<usize> {
+        SESSION_SEND.increment();
+        Ok(tx.compose(&mut self.session))
+    }
+
     /// Attempts to return a pair of messages, the one sent to the server as
     /// well as the one received from the server, from the underlying session
     /// buffer. This operates only on buffered data and does not result in a
@@ -96,6 +103,56 @@ where
         }
     }
 
+    /// Like `receive`, but the response is paired with the pending message
+    /// which has the same correlation id, rather than with the oldest pending
+    /// message. This allows the server to return responses out of order when
+    /// messages are pipelined on the session.
+    pub fn receive_correlated(&mut self) -> Result<(Tx, Rx)>
+    where
+        Tx: Correlate,
+        Rx: Correlate,
+    {
+        let src: &[u8] = self.session.borrow();
+        match self.parser.parse(src) {
+            Ok(res) => {
+                SESSION_RECV.increment();
+                let now = Instant::now();
+                let consumed = res.consumed();
+                let msg = res.into_inner();
+                self.session.consume(consumed);
+                let id = msg
+                    .correlation_id()
+                    .ok_or_else(|| Error::from(ErrorKind::InvalidData))?;
+                let index = self
+                    .pending
+                    .iter()
+                    .position(|(_, tx)| tx.correlation_id() == Some(id))
+                    .ok_or_else(|| Error::from(ErrorKind::InvalidData))?;
+                let (timestamp, request) = self.pending.remove(index).unwrap();
+                let latency = now - timestamp;
+                REQUEST_LATENCY.increment(now, latency.as_nanos(), 1);
+                Ok((request, msg))
+            }
+            Err(e) => {
+                if e.kind() != ErrorKind::WouldBlock {
+                    SESSION_RECV_EX.increment();
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Returns the number of messages which are awaiting a response.
+    pub fn outstanding(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Removes the messages which are awaiting a response, for example when
+    /// the session is closed before the server replied to them.
+    pub fn drain_pending(&mut self) -> impl Iterator<Item = Tx> + '_ {
+        self.pending.drain(..).map(|(_, tx)| tx)
+    }
+
     /// Attempts to flush the session write buffer.
     pub fn flush(&mut self) -> Result<()> {
         self.session.flush()?;
@@ -27,6 +27,7 @@ use core::borrow::{Borrow, BorrowMut};
 use core::fmt::Debug;
 use core::marker::PhantomData;
 use protocol_common::Compose;
+use protocol_common::Correlate;
 use protocol_common::Parse;
 use rustcommon_metrics::*;
 use rustcommon_time::Nanoseconds;
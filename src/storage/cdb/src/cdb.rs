@@ -0,0 +1,134 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! A memory-mapped reader for cdb files.
+
+use crate::*;
+
+use memmap2::{Mmap, MmapOptions};
+
+use std::fs::File;
+use std::path::Path;
+
+/// A read-only view of a memory-mapped cdb file.
+pub struct Cdb {
+    mmap: Mmap,
+}
+
+impl Cdb {
+    /// Open and memory-map the cdb file at the given path. The header and hash
+    /// tables are validated so that a truncated or otherwise malformed file is
+    /// rejected before it is used to serve requests.
+    ///
+    /// The file should not be modified while it is mapped. New versions of a
+    /// file should be written to a temporary path and then renamed into place.
+    pub fn open<T: AsRef<Path>>(path: T) -> Result<Self, CdbError> {
+        CDB_OPEN.increment();
+        let result = Self::map(path.as_ref());
+        match &result {
+            Ok(cdb) => CDB_BYTES.set(cdb.mmap.len() as _),
+            Err(_) => CDB_OPEN_EX.increment(),
+        }
+        result
+    }
+
+    fn map(path: &Path) -> Result<Self, CdbError> {
+        let file = File::open(path)?;
+        let mmap = unsafe { MmapOptions::new().populate().map(&file)? };
+
+        if mmap.len() < HEADER_SIZE || mmap.len() > u32::MAX as usize {
+            return Err(CdbError::Corrupt);
+        }
+
+        // check that every hash table is within the bounds of the file
+        for table in 0..TABLES {
+            let pos = read_u32(&mmap, table * 8).ok_or(CdbError::Corrupt)? as usize;
+            let len = read_u32(&mmap, table * 8 + 4).ok_or(CdbError::Corrupt)? as usize;
+            if len > 0 && (pos < HEADER_SIZE || pos + len * 8 > mmap.len()) {
+                return Err(CdbError::Corrupt);
+            }
+        }
+
+        Ok(Self { mmap })
+    }
+
+    /// Returns the value for the key if it is present in the file.
+    ///
+    /// ```
+    /// use cdb::{Cdb, Writer};
+    ///
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let path = dir.path().join("example.cdb");
+    ///
+    /// let mut writer = Writer::create(&path).unwrap();
+    /// writer.insert(b"coffee", b"strong").unwrap();
+    /// writer.finish().unwrap();
+    ///
+    /// let cdb = Cdb::open(&path).unwrap();
+    /// assert_eq!(cdb.get(b"coffee"), Some(&b"strong"[..]));
+    /// assert_eq!(cdb.get(b"tea"), None);
+    /// ```
+    pub fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        CDB_GET.increment();
+        let result = self.find(key);
+        if result.is_some() {
+            CDB_GET_KEY_HIT.increment();
+        } else {
+            CDB_GET_KEY_MISS.increment();
+        }
+        result
+    }
+
+    /// Returns the size of the mapped file in bytes.
+    pub fn len(&self) -> usize {
+        self.mmap.len()
+    }
+
+    /// Returns true if the mapped file is empty. A valid cdb file is never
+    /// empty, as it always contains the header.
+    pub fn is_empty(&self) -> bool {
+        self.mmap.is_empty()
+    }
+
+    fn find(&self, key: &[u8]) -> Option<&[u8]> {
+        let data: &[u8] = &self.mmap;
+
+        let hash = hash(key);
+        let table = (hash as usize % TABLES) * 8;
+        let pos = read_u32(data, table)? as usize;
+        let len = read_u32(data, table + 4)? as usize;
+
+        if len == 0 {
+            return None;
+        }
+
+        // linear probe starting from the slot selected by the hash
+        let start = (hash as usize >> 8) % len;
+        for i in 0..len {
+            let slot = pos + ((start + i) % len) * 8;
+            let slot_hash = read_u32(data, slot)?;
+            let record = read_u32(data, slot + 4)? as usize;
+
+            // an empty slot terminates the probe sequence
+            if record == 0 {
+                return None;
+            }
+
+            if slot_hash != hash {
+                continue;
+            }
+
+            let klen = read_u32(data, record)? as usize;
+            let vlen = read_u32(data, record + 4)? as usize;
+            let kstart = record + 8;
+            let vstart = kstart + klen;
+
+            if data.get(kstart..vstart)? == key {
+                return data.get(vstart..vstart + vlen);
+            }
+        }
+
+        None
+    }
+}
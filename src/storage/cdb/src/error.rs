@@ -0,0 +1,18 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+/// Possible errors returned when building or opening a cdb file.
+pub enum CdbError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("file is corrupt or not in cdb format")]
+    Corrupt,
+    #[error("file would exceed the maximum size of 4GiB")]
+    TooLarge,
+    #[error("invalid input on line {line}")]
+    InvalidInput { line: usize },
+}
@@ -0,0 +1,12 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+const SEED: u32 = 5381;
+
+/// The cdb hash function. This must not be changed, as it determines the
+/// on-disk format.
+pub fn hash(key: &[u8]) -> u32 {
+    key.iter()
+        .fold(SEED, |h, b| (h.wrapping_shl(5).wrapping_add(h)) ^ *b as u32)
+}
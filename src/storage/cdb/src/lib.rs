@@ -0,0 +1,55 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! A read-only constant database which uses djb's
+//! [cdb](https://cr.yp.to/cdb/cdb.txt) file format. Files are built offline
+//! with a [`Writer`] and are then memory-mapped by [`Cdb`] for serving.
+//!
+//! A cdb file is composed of a fixed-size header, followed by the records, and
+//! then 256 hash tables which index the records:
+//!
+//! ```text
+//! ┌───────────────────┬─────────────────────────┬───────────────────────┐
+//! │      HEADER       │         RECORDS         │      HASH TABLES      │
+//! │                   │                         │                       │
+//! │ 256 * (POS, LEN)  │ (KLEN, VLEN, KEY, VALUE)│ 256 * LEN*(HASH, POS) │
+//! └───────────────────┴─────────────────────────┴───────────────────────┘
+//! ```
+//!
+//! All integers are 32bit little-endian, which limits the total size of the
+//! file to 4GiB.
+
+mod cdb;
+mod error;
+mod hash;
+mod make;
+mod metrics;
+mod writer;
+
+#[cfg(test)]
+mod tests;
+
+pub use crate::cdb::Cdb;
+pub use error::CdbError;
+pub use make::cdbmake;
+pub use writer::Writer;
+
+pub(crate) use hash::hash;
+pub(crate) use metrics::*;
+
+/// The number of hash tables in the file.
+pub(crate) const TABLES: usize = 256;
+
+/// The size of the header in bytes. The header holds the position and number
+/// of slots for each hash table.
+pub(crate) const HEADER_SIZE: usize = TABLES * 8;
+
+/// Reads a little-endian `u32` from the buffer at the offset, returning `None`
+/// if the read would be out of bounds.
+pub(crate) fn read_u32(buf: &[u8], offset: usize) -> Option<u32> {
+    buf.get(offset..offset.checked_add(4)?)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+common::metrics::test_no_duplicates!();
@@ -0,0 +1,86 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! A tool which builds a cdb file from input in the `cdbmake` format. The file
+//! is written to a temporary path and then renamed into place, which allows
+//! a running server to safely reload the new file.
+
+use cdb::{cdbmake, Writer};
+use clap::{App, Arg};
+
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::PathBuf;
+
+fn main() {
+    // parse command line options
+    let matches = App::new(env!("CARGO_BIN_NAME"))
+        .version(env!("CARGO_PKG_VERSION"))
+        .version_short("v")
+        .long_about(
+            "Builds a cdb file for use with the cdb server. Records are read \
+            in the cdbmake format, one record per line as \
+            '+KLEN,VLEN:KEY->VALUE', with the input terminated by an empty \
+            line.",
+        )
+        .arg(
+            Arg::with_name("input")
+                .short("i")
+                .long("input")
+                .help("Input file in cdbmake format, defaults to stdin")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("OUTPUT")
+                .help("Path of the cdb file to create")
+                .required(true)
+                .index(1),
+        )
+        .get_matches();
+
+    let output = PathBuf::from(matches.value_of("OUTPUT").unwrap());
+
+    let input: Box<dyn Read> = match matches.value_of("input") {
+        Some(file) => match File::open(file) {
+            Ok(f) => Box::new(f),
+            Err(e) => {
+                eprintln!("error opening input file: {file}\n{e}");
+                std::process::exit(1);
+            }
+        },
+        None => Box::new(std::io::stdin()),
+    };
+
+    // build into a temporary file alongside the output so the final rename is
+    // atomic
+    let mut tmp = output.clone().into_os_string();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    let result = Writer::create(&tmp).and_then(|mut writer| {
+        let count = cdbmake(BufReader::new(input), &mut writer)?;
+        writer.finish()?;
+        Ok(count)
+    });
+
+    match result {
+        Ok(count) => {
+            if let Err(e) = std::fs::rename(&tmp, &output) {
+                eprintln!(
+                    "error renaming {} to {}: {e}",
+                    tmp.display(),
+                    output.display()
+                );
+                let _ = std::fs::remove_file(&tmp);
+                std::process::exit(1);
+            }
+            println!("wrote {} records to {}", count, output.display());
+        }
+        Err(e) => {
+            eprintln!("error building cdb: {e}");
+            let _ = std::fs::remove_file(&tmp);
+            std::process::exit(1);
+        }
+    }
+}
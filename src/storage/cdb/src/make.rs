@@ -0,0 +1,99 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Support for the `cdbmake` input format, which allows datasets to be
+//! produced by other tools and then converted into a cdb file.
+//!
+//! Each record is written as `+KLEN,VLEN:KEY->VALUE` followed by a newline,
+//! where the lengths are given in decimal. The key and value may contain
+//! arbitrary bytes. The input is terminated by an empty line.
+//!
+//! ```text
+//! +6,6:coffee->strong
+//! +3,5:tea->green
+//!
+//! ```
+
+use crate::*;
+
+use std::io::{BufRead, ErrorKind};
+
+/// Reads records in the `cdbmake` format from the input and inserts them into
+/// the writer. Returns the number of records which were inserted.
+pub fn cdbmake<R: BufRead>(mut input: R, writer: &mut Writer) -> Result<usize, CdbError> {
+    let mut count = 0;
+
+    loop {
+        let line = count + 1;
+
+        match next_byte(&mut input)? {
+            // an empty line or the end of the input terminates the records
+            None | Some(b'\n') => return Ok(count),
+            Some(b'+') => {}
+            Some(_) => return Err(CdbError::InvalidInput { line }),
+        }
+
+        let klen = length(&mut input, b',', line)?;
+        let vlen = length(&mut input, b':', line)?;
+
+        let mut key = vec![0; klen];
+        read_exact(&mut input, &mut key, line)?;
+        expect(&mut input, b"->", line)?;
+
+        let mut value = vec![0; vlen];
+        read_exact(&mut input, &mut value, line)?;
+        expect(&mut input, b"\n", line)?;
+
+        writer.insert(&key, &value)?;
+        count += 1;
+    }
+}
+
+fn next_byte<R: BufRead>(input: &mut R) -> Result<Option<u8>, CdbError> {
+    let mut byte = [0];
+    match input.read_exact(&mut byte) {
+        Ok(()) => Ok(Some(byte[0])),
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn read_exact<R: BufRead>(input: &mut R, buf: &mut [u8], line: usize) -> Result<(), CdbError> {
+    input.read_exact(buf).map_err(|e| {
+        if e.kind() == ErrorKind::UnexpectedEof {
+            CdbError::InvalidInput { line }
+        } else {
+            e.into()
+        }
+    })
+}
+
+fn expect<R: BufRead>(input: &mut R, expected: &[u8], line: usize) -> Result<(), CdbError> {
+    let mut buf = vec![0; expected.len()];
+    read_exact(input, &mut buf, line)?;
+    if buf != expected {
+        return Err(CdbError::InvalidInput { line });
+    }
+    Ok(())
+}
+
+/// Parses a decimal length which is terminated by the delimiter.
+fn length<R: BufRead>(input: &mut R, delimiter: u8, line: usize) -> Result<usize, CdbError> {
+    let mut value: usize = 0;
+    let mut digits = 0;
+
+    loop {
+        match next_byte(input)? {
+            Some(b) if b == delimiter && digits > 0 => return Ok(value),
+            Some(b) if b.is_ascii_digit() => {
+                value = value
+                    .checked_mul(10)
+                    .and_then(|v| v.checked_add((b - b'0') as usize))
+                    .ok_or(CdbError::TooLarge)?;
+                digits += 1;
+            }
+            _ => return Err(CdbError::InvalidInput { line }),
+        }
+    }
+}
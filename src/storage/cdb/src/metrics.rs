@@ -0,0 +1,15 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use rustcommon_metrics::*;
+
+counter!(CDB_GET, "number of get operations");
+counter!(
+    CDB_GET_KEY_HIT,
+    "number of get operations which found the key"
+);
+counter!(CDB_GET_KEY_MISS, "number of get operations which missed");
+counter!(CDB_OPEN, "number of times a cdb file was opened");
+counter!(CDB_OPEN_EX, "number of times opening a cdb file failed");
+gauge!(CDB_BYTES, "size of the currently mapped cdb file in bytes");
@@ -0,0 +1,128 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+use tempfile::TempDir;
+
+use std::path::PathBuf;
+
+fn path(dir: &TempDir) -> PathBuf {
+    dir.path().join("test.cdb")
+}
+
+#[test]
+fn get() {
+    let dir = TempDir::new().expect("failed to create tempdir");
+    let path = path(&dir);
+
+    let mut writer = Writer::create(&path).expect("failed to create writer");
+    writer.insert(b"coffee", b"strong").unwrap();
+    writer.insert(b"tea", b"green").unwrap();
+    writer.insert(b"empty", b"").unwrap();
+    writer.finish().expect("failed to finish");
+
+    let cdb = Cdb::open(&path).expect("failed to open");
+    assert_eq!(cdb.get(b"coffee"), Some(&b"strong"[..]));
+    assert_eq!(cdb.get(b"tea"), Some(&b"green"[..]));
+    assert_eq!(cdb.get(b"empty"), Some(&b""[..]));
+    assert_eq!(cdb.get(b"water"), None);
+}
+
+#[test]
+fn empty() {
+    let dir = TempDir::new().expect("failed to create tempdir");
+    let path = path(&dir);
+
+    Writer::create(&path).unwrap().finish().unwrap();
+
+    let cdb = Cdb::open(&path).expect("failed to open");
+    assert_eq!(cdb.len(), HEADER_SIZE);
+    assert_eq!(cdb.get(b"coffee"), None);
+}
+
+#[test]
+fn many() {
+    let dir = TempDir::new().expect("failed to create tempdir");
+    let path = path(&dir);
+
+    let mut writer = Writer::create(&path).unwrap();
+    for i in 0..10_000 {
+        writer
+            .insert(format!("key{}", i).as_bytes(), format!("{}", i).as_bytes())
+            .unwrap();
+    }
+    writer.finish().unwrap();
+
+    let cdb = Cdb::open(&path).expect("failed to open");
+    for i in 0..10_000 {
+        assert_eq!(
+            cdb.get(format!("key{}", i).as_bytes()),
+            Some(format!("{}", i).as_bytes())
+        );
+    }
+    assert_eq!(cdb.get(b"key10000"), None);
+}
+
+#[test]
+fn corrupt() {
+    let dir = TempDir::new().expect("failed to create tempdir");
+    let path = path(&dir);
+
+    // shorter than the header
+    std::fs::write(&path, b"not a cdb").unwrap();
+    assert!(matches!(Cdb::open(&path), Err(CdbError::Corrupt)));
+
+    // a hash table which points beyond the end of the file
+    let mut data = vec![0; HEADER_SIZE];
+    data[0..4].copy_from_slice(&(HEADER_SIZE as u32).to_le_bytes());
+    data[4..8].copy_from_slice(&16_u32.to_le_bytes());
+    std::fs::write(&path, &data).unwrap();
+    assert!(matches!(Cdb::open(&path), Err(CdbError::Corrupt)));
+
+    // missing file
+    assert!(matches!(
+        Cdb::open(dir.path().join("missing.cdb")),
+        Err(CdbError::Io(_))
+    ));
+}
+
+#[test]
+fn make() {
+    let dir = TempDir::new().expect("failed to create tempdir");
+    let path = path(&dir);
+
+    let input: &[u8] = b"+6,6:coffee->strong\n+3,5:tea->green\n+3,3:a\nb->c\nd\n\n";
+
+    let mut writer = Writer::create(&path).unwrap();
+    assert_eq!(cdbmake(input, &mut writer).unwrap(), 3);
+    writer.finish().unwrap();
+
+    let cdb = Cdb::open(&path).expect("failed to open");
+    assert_eq!(cdb.get(b"coffee"), Some(&b"strong"[..]));
+    assert_eq!(cdb.get(b"tea"), Some(&b"green"[..]));
+    assert_eq!(cdb.get(b"a\nb"), Some(&b"c\nd"[..]));
+}
+
+#[test]
+fn make_invalid() {
+    let dir = TempDir::new().expect("failed to create tempdir");
+    let path = path(&dir);
+
+    let inputs: &[(&[u8], usize)] = &[
+        (b"coffee->strong\n", 1),
+        (b"+6,6:coffee->strong\n+3;5:tea->green\n", 2),
+        (b"+6,6:coffee=>strong\n", 1),
+        (b"+6,10:coffee->strong\n", 1),
+        (b"+,6:coffee->strong\n", 1),
+    ];
+
+    for (input, line) in inputs {
+        let mut writer = Writer::create(&path).unwrap();
+        match cdbmake(*input, &mut writer) {
+            Err(CdbError::InvalidInput { line: l }) => assert_eq!(l, *line),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
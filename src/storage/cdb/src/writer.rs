@@ -0,0 +1,100 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! A writer which is used to build new cdb files.
+
+use crate::*;
+
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Builds a new cdb file by appending records and then writing the hash tables
+/// once all records have been inserted.
+pub struct Writer {
+    file: BufWriter<File>,
+    /// the hash and record position for each record, grouped by table
+    entries: Vec<Vec<(u32, u32)>>,
+    /// the position in the file where the next record will be written
+    pos: u32,
+}
+
+impl Writer {
+    /// Create a new cdb file at the given path, truncating any existing file.
+    pub fn create<T: AsRef<Path>>(path: T) -> Result<Self, CdbError> {
+        let mut file = BufWriter::new(File::create(path)?);
+
+        // reserve space for the header which is written when finished
+        file.write_all(&[0; HEADER_SIZE])?;
+
+        Ok(Self {
+            file,
+            entries: vec![Vec::new(); TABLES],
+            pos: HEADER_SIZE as u32,
+        })
+    }
+
+    /// Append a record to the file. If the same key is inserted more than
+    /// once, lookups will return the value which was inserted first.
+    pub fn insert(&mut self, key: &[u8], value: &[u8]) -> Result<(), CdbError> {
+        let klen: u32 = key.len().try_into().map_err(|_| CdbError::TooLarge)?;
+        let vlen: u32 = value.len().try_into().map_err(|_| CdbError::TooLarge)?;
+
+        let size = 8_u32
+            .checked_add(klen)
+            .and_then(|s| s.checked_add(vlen))
+            .ok_or(CdbError::TooLarge)?;
+        let next = self.pos.checked_add(size).ok_or(CdbError::TooLarge)?;
+
+        self.file.write_all(&klen.to_le_bytes())?;
+        self.file.write_all(&vlen.to_le_bytes())?;
+        self.file.write_all(key)?;
+        self.file.write_all(value)?;
+
+        let hash = hash(key);
+        self.entries[hash as usize % TABLES].push((hash, self.pos));
+        self.pos = next;
+
+        Ok(())
+    }
+
+    /// Write the hash tables and header, completing the file.
+    pub fn finish(mut self) -> Result<(), CdbError> {
+        let mut header = Vec::with_capacity(HEADER_SIZE);
+
+        for entries in self.entries.iter() {
+            // tables are sized at twice the number of entries to keep probe
+            // sequences short
+            let len = entries.len() * 2;
+            let mut table = vec![(0_u32, 0_u32); len];
+
+            for (hash, pos) in entries.iter() {
+                let mut slot = (*hash as usize >> 8) % len;
+                while table[slot].1 != 0 {
+                    slot = (slot + 1) % len;
+                }
+                table[slot] = (*hash, *pos);
+            }
+
+            header.extend_from_slice(&self.pos.to_le_bytes());
+            header.extend_from_slice(&(len as u32).to_le_bytes());
+
+            let size = (len as u32).checked_mul(8).ok_or(CdbError::TooLarge)?;
+            self.pos = self.pos.checked_add(size).ok_or(CdbError::TooLarge)?;
+
+            for (hash, pos) in table {
+                self.file.write_all(&hash.to_le_bytes())?;
+                self.file.write_all(&pos.to_le_bytes())?;
+            }
+        }
+
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.write_all(&header)?;
+
+        let file = self.file.into_inner().map_err(|e| e.into_error())?;
+        file.sync_all()?;
+
+        Ok(())
+    }
+}
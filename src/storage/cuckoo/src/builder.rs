@@ -0,0 +1,165 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! A builder for configuring a new [`Cuckoo`] instance.
+
+use crate::*;
+use datapool::*;
+use std::path::{Path, PathBuf};
+
+/// A builder that is used to construct a new [`Cuckoo`] instance.
+pub struct Builder {
+    item_size: usize,
+    nitem: usize,
+    max_displace: usize,
+    max_ttl: u32,
+    policy: Policy,
+    datapool_path: Option<PathBuf>,
+}
+
+// Defines the default parameters
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            item_size: 64,
+            nitem: 1024,
+            max_displace: 2,
+            max_ttl: 30 * 24 * 60 * 60,
+            policy: Policy::Random,
+            datapool_path: None,
+        }
+    }
+}
+
+impl Builder {
+    /// Specify the size of each item slot in bytes. This includes the item
+    /// header, key, value, and optional data. Items which do not fit in a
+    /// single slot cannot be stored.
+    ///
+    /// ```
+    /// use cuckoo::Cuckoo;
+    ///
+    /// // create a cache which can hold items of up to 32 bytes
+    /// let cache = Cuckoo::builder().item_size(32).build();
+    /// ```
+    pub fn item_size(mut self, bytes: usize) -> Self {
+        assert!(
+            bytes > ITEM_HDR_SIZE,
+            "item size must be larger than the item header"
+        );
+        self.item_size = bytes;
+        self
+    }
+
+    /// Specify the total number of item slots to allocate. The total size of
+    /// the storage will be `item_size * nitem` bytes.
+    ///
+    /// ```
+    /// use cuckoo::Cuckoo;
+    ///
+    /// // create a cache with room for ~1M items
+    /// let cache = Cuckoo::builder().nitem(1 << 20).build();
+    /// ```
+    pub fn nitem(mut self, count: usize) -> Self {
+        assert!(count > 0, "nitem must be greater than zero");
+        assert!(
+            count <= u32::MAX as usize,
+            "nitem must fit within a 32bit integer"
+        );
+        self.nitem = count;
+        self
+    }
+
+    /// Specify the maximum number of displacements which will be attempted
+    /// when inserting into a full set of candidate slots. Longer displacement
+    /// paths increase the achievable load factor at the cost of additional
+    /// work on insert.
+    ///
+    /// ```
+    /// use cuckoo::Cuckoo;
+    ///
+    /// let cache = Cuckoo::builder().max_displace(4).build();
+    /// ```
+    pub fn max_displace(mut self, count: usize) -> Self {
+        self.max_displace = count;
+        self
+    }
+
+    /// Specify the maximum TTL in seconds. Items which are stored without an
+    /// expiration, or with a longer TTL, will expire after this duration.
+    ///
+    /// ```
+    /// use cuckoo::Cuckoo;
+    ///
+    /// // items will expire after at most one day
+    /// let cache = Cuckoo::builder().max_ttl(86400).build();
+    /// ```
+    pub fn max_ttl(mut self, seconds: u32) -> Self {
+        assert!(seconds > 0, "max ttl must be greater than zero");
+        self.max_ttl = seconds;
+        self
+    }
+
+    /// Specify the policy used to choose which item to displace when all
+    /// candidate slots are occupied. See the `Policy` documentation for more
+    /// details about each strategy.
+    ///
+    /// ```
+    /// use cuckoo::{Cuckoo, Policy};
+    ///
+    /// let cache = Cuckoo::builder().policy(Policy::Expire).build();
+    /// ```
+    pub fn policy(mut self, policy: Policy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Specify a backing file to be used for item storage.
+    ///
+    /// # Panics
+    ///
+    /// This will panic if the file already exists
+    pub fn datapool_path<T: AsRef<Path>>(mut self, path: Option<T>) -> Self {
+        self.datapool_path = path.map(|p| p.as_ref().to_owned());
+        self
+    }
+
+    /// Consumes the builder and returns a fully-allocated `Cuckoo` instance.
+    ///
+    /// ```
+    /// use cuckoo::{Cuckoo, Policy};
+    ///
+    /// let cache = Cuckoo::builder()
+    ///     .item_size(64)
+    ///     .nitem(1024)
+    ///     .policy(Policy::Random)
+    ///     .build();
+    /// ```
+    pub fn build(self) -> Result<Cuckoo, std::io::Error> {
+        let heap_size = self.item_size * self.nitem;
+
+        // NOTE: restoring from an existing datapool file is not supported, so
+        // this matches seg and always creates a new file to mmap if a datapool
+        // path is provided.
+        let data: Box<dyn Datapool> = if let Some(file) = self.datapool_path {
+            Box::new(MmapFile::create(file, heap_size, crate::VERSION)?)
+        } else {
+            Box::new(Memory::create(heap_size)?)
+        };
+
+        debug!(
+            "cuckoo has: {} slots of {} bytes for a total of {} bytes",
+            self.nitem, self.item_size, heap_size,
+        );
+
+        Ok(Cuckoo::new(
+            data,
+            self.item_size,
+            self.nitem,
+            self.max_displace,
+            self.max_ttl,
+            self.policy,
+        ))
+    }
+}
@@ -0,0 +1,479 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Core datastructure
+
+use crate::*;
+use ahash::RandomState;
+use core::hash::{BuildHasher, Hasher};
+use datapool::Datapool;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+/// The number of candidate slots for each key.
+const D: usize = 4;
+
+/// Cuckoo hashing requires a different hash function for each of the candidate
+/// slots. We approximate this by seeding the same hash function with one of
+/// these values. They can be picked arbitrarily as long as they are different.
+const IV: [u32; D] = [0x3ac5d673, 0x6d7839d0, 0x2b581cf5, 0x4dd2be0a];
+
+/// A pre-allocated key-value store for small items. Items are stored in
+/// fixed-size slots and located using cuckoo hashing, which results in a very
+/// low per-item overhead.
+pub struct Cuckoo {
+    data: Box<dyn Datapool>,
+    hash_builder: Box<RandomState>,
+    item_size: usize,
+    nitem: usize,
+    max_displace: usize,
+    max_ttl: u32,
+    policy: Policy,
+    rng: Box<SmallRng>,
+    cas: u32,
+    started: Instant,
+}
+
+impl Cuckoo {
+    /// Returns a new `Builder` which is used to configure and construct a
+    /// `Cuckoo` instance.
+    ///
+    /// ```
+    /// use cuckoo::{Cuckoo, Policy};
+    ///
+    /// // create a cache with room for 1024 items of up to 64 bytes each
+    /// let cache = Cuckoo::builder()
+    ///     .item_size(64)
+    ///     .nitem(1024)
+    ///     .policy(Policy::Random)
+    ///     .build()
+    ///     .expect("failed to create cache");
+    /// ```
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    pub(crate) fn new(
+        data: Box<dyn Datapool>,
+        item_size: usize,
+        nitem: usize,
+        max_displace: usize,
+        max_ttl: u32,
+        policy: Policy,
+    ) -> Self {
+        let hash_builder = RandomState::with_seeds(
+            0xbb8c484891ec6c86,
+            0x0522a25ae9c769f9,
+            0xeed2797b9571bc75,
+            0x4feb29c1fbbd59d0,
+        );
+
+        CUCKOO_ITEM_CURRENT.set(0);
+        CUCKOO_ITEM_CURRENT_BYTES.set(0);
+
+        Self {
+            data,
+            hash_builder: Box::new(hash_builder),
+            item_size,
+            nitem,
+            max_displace,
+            max_ttl,
+            policy,
+            rng: Box::new(SmallRng::from_entropy()),
+            cas: 0,
+            started: Instant::recent(),
+        }
+    }
+
+    /// Get the item in the `Cuckoo` with the provided key
+    ///
+    /// ```
+    /// use cuckoo::Cuckoo;
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Cuckoo::builder().build().expect("failed to create cache");
+    /// assert!(cache.get(b"coffee").is_none());
+    ///
+    /// cache.insert(b"coffee", b"strong", None, Duration::ZERO);
+    /// let item = cache.get(b"coffee").expect("didn't get item back");
+    /// assert_eq!(item.value(), b"strong");
+    /// ```
+    pub fn get(&mut self, key: &[u8]) -> Option<Item> {
+        CUCKOO_GET.increment();
+        let now = self.now();
+        let offsets = self.hash(key);
+        self.find(key, &offsets, now)
+            .map(|offset| Item::new(self.slot(offset)))
+    }
+
+    /// Insert a new item into the cache. If an item with the same key exists,
+    /// it is replaced in-place. May return an error indicating that the insert
+    /// was not successful.
+    ///
+    /// ```
+    /// use cuckoo::Cuckoo;
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Cuckoo::builder().build().expect("failed to create cache");
+    /// assert!(cache.get(b"drink").is_none());
+    ///
+    /// cache.insert(b"drink", b"coffee", None, Duration::ZERO);
+    /// let item = cache.get(b"drink").expect("didn't get item back");
+    /// assert_eq!(item.value(), b"coffee");
+    ///
+    /// cache.insert(b"drink", b"whisky", None, Duration::ZERO);
+    /// let item = cache.get(b"drink").expect("didn't get item back");
+    /// assert_eq!(item.value(), b"whisky");
+    /// ```
+    pub fn insert<'a, T: Into<Value<'a>>>(
+        &mut self,
+        key: &'a [u8],
+        value: T,
+        optional: Option<&[u8]>,
+        ttl: std::time::Duration,
+    ) -> Result<(), CuckooError> {
+        CUCKOO_INSERT.increment();
+
+        let value: Value = value.into();
+
+        // default optional data is empty
+        let optional = optional.unwrap_or(&[]);
+
+        // each length is encoded in a single byte within the item header
+        let size = item_size(key, &value, optional);
+        if size > self.item_size
+            || key.len() > u8::MAX as usize
+            || value.len() > u8::MAX as usize
+            || optional.len() > u8::MAX as usize
+        {
+            CUCKOO_INSERT_EX.increment();
+            return Err(CuckooError::ItemOversized { size });
+        }
+
+        let now = self.now();
+        let expire = self.expire_at(ttl, now);
+        let cas = self.next_cas();
+        let offsets = self.hash(key);
+
+        let offset = if let Some(offset) = self.find(key, &offsets, now) {
+            // an existing item with the same key is updated in-place
+            CUCKOO_UPDATE.increment();
+            self.unlink(offset);
+            offset
+        } else if let Some(offset) = offsets
+            .iter()
+            .copied()
+            .find(|offset| !self.is_live(*offset, now))
+        {
+            // use an unoccupied candidate slot
+            self.reclaim(offset);
+            offset
+        } else {
+            // all candidates are occupied, displace one of them
+            let offset = self.select_candidate(&offsets);
+            self.displace(offset, now);
+            offset
+        };
+
+        let slot = self.slot_mut(offset);
+        define(slot, key, value, optional, expire, cas);
+
+        CUCKOO_ITEM_INSERT.increment();
+        CUCKOO_ITEM_CURRENT.increment();
+        CUCKOO_ITEM_CURRENT_BYTES.add(data_len(self.slot(offset)) as _);
+
+        Ok(())
+    }
+
+    /// Performs a CAS operation, inserting the item only if the CAS value
+    /// matches the current value for that item.
+    ///
+    /// ```
+    /// use cuckoo::{Cuckoo, CuckooError};
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Cuckoo::builder().build().expect("failed to create cache");
+    ///
+    /// // If the item is not in the cache, CAS will fail as 'NotFound'
+    /// assert_eq!(
+    ///     cache.cas(b"drink", b"coffee", None, Duration::ZERO, 0),
+    ///     Err(CuckooError::NotFound)
+    /// );
+    ///
+    /// // If a stale CAS value is provided, CAS will fail as 'Exists'
+    /// cache.insert(b"drink", b"coffee", None, Duration::ZERO);
+    /// assert_eq!(
+    ///     cache.cas(b"drink", b"coffee", None, Duration::ZERO, 0),
+    ///     Err(CuckooError::Exists)
+    /// );
+    ///
+    /// // Getting the CAS value and then performing the operation ensures
+    /// // success in absence of a race with another client
+    /// let current = cache.get(b"drink").expect("not found").cas();
+    /// assert!(cache.cas(b"drink", b"whisky", None, Duration::ZERO, current).is_ok());
+    /// let item = cache.get(b"drink").expect("not found");
+    /// assert_eq!(item.value(), b"whisky"); // item is updated
+    /// ```
+    pub fn cas<'a, T: Into<Value<'a>>>(
+        &mut self,
+        key: &'a [u8],
+        value: T,
+        optional: Option<&[u8]>,
+        ttl: std::time::Duration,
+        cas: u32,
+    ) -> Result<(), CuckooError> {
+        let now = self.now();
+        let offsets = self.hash(key);
+        match self.find(key, &offsets, now) {
+            Some(offset) => {
+                if crate::item::cas(self.slot(offset)) == cas {
+                    self.insert(key, value, optional, ttl)
+                } else {
+                    Err(CuckooError::Exists)
+                }
+            }
+            None => Err(CuckooError::NotFound),
+        }
+    }
+
+    /// Remove the item with the given key, returns a bool indicating if it was
+    /// removed.
+    ///
+    /// ```
+    /// use cuckoo::Cuckoo;
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Cuckoo::builder().build().expect("failed to create cache");
+    ///
+    /// // If the item is not in the cache, delete will return false
+    /// assert_eq!(cache.delete(b"coffee"), false);
+    ///
+    /// // And will return true on success
+    /// cache.insert(b"coffee", b"strong", None, Duration::ZERO);
+    /// assert!(cache.get(b"coffee").is_some());
+    /// assert_eq!(cache.delete(b"coffee"), true);
+    /// assert!(cache.get(b"coffee").is_none());
+    /// ```
+    pub fn delete(&mut self, key: &[u8]) -> bool {
+        CUCKOO_DELETE.increment();
+        let now = self.now();
+        let offsets = self.hash(key);
+        if let Some(offset) = self.find(key, &offsets, now) {
+            CUCKOO_ITEM_DELETE.increment();
+            self.unlink(offset);
+            set_expire(self.slot_mut(offset), 0);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Items in `Cuckoo` storage are expired lazily, as their slots are
+    /// accessed or reused. This refreshes the clock which is used to determine
+    /// if an item has expired.
+    pub fn expire(&mut self) {
+        common::time::refresh_clock();
+    }
+
+    /// Remove all items from the cache.
+    pub fn clear(&mut self) {
+        common::time::refresh_clock();
+        self.data.as_mut_slice().fill(0);
+        CUCKOO_ITEM_CURRENT.set(0);
+        CUCKOO_ITEM_CURRENT_BYTES.set(0);
+    }
+
+    /// Perform a wrapping addition on the value stored at the supplied key.
+    /// Returns an error if the item is not found, or the stored value is not a
+    /// numeric type.
+    pub fn wrapping_add(&mut self, key: &[u8], rhs: u64) -> Result<Item, CuckooError> {
+        self.numeric_op(key, |v| v.wrapping_add(rhs))
+    }
+
+    /// Perform a saturating subtraction on the value stored at the supplied
+    /// key. Returns an error if the item is not found, or the stored value is
+    /// not a numeric type.
+    pub fn saturating_sub(&mut self, key: &[u8], rhs: u64) -> Result<Item, CuckooError> {
+        self.numeric_op(key, |v| v.saturating_sub(rhs))
+    }
+
+    fn numeric_op<F: FnOnce(u64) -> u64>(&mut self, key: &[u8], f: F) -> Result<Item, CuckooError> {
+        let now = self.now();
+        let offsets = self.hash(key);
+        let offset = self.find(key, &offsets, now).ok_or(CuckooError::NotFound)?;
+        let cas = self.next_cas();
+        update_numeric(self.slot_mut(offset), cas, f)?;
+        Ok(Item::new(self.slot(offset)))
+    }
+
+    /// Returns the current time, in seconds, relative to the creation of the
+    /// storage.
+    fn now(&self) -> u32 {
+        (Instant::recent() - self.started).as_secs()
+    }
+
+    /// Calculates the expiration time for an item with the provided TTL. A TTL
+    /// of zero, or one exceeding the max TTL, is treated as the max TTL.
+    fn expire_at(&self, ttl: std::time::Duration, now: u32) -> u32 {
+        let ttl = if ttl.is_zero() || ttl.as_secs() > self.max_ttl as u64 {
+            self.max_ttl
+        } else {
+            ttl.as_secs() as u32
+        };
+        now.saturating_add(ttl)
+    }
+
+    /// Returns the next CAS value. Zero is skipped so that it is never a valid
+    /// CAS value for a stored item.
+    fn next_cas(&mut self) -> u32 {
+        self.cas = self.cas.wrapping_add(1);
+        if self.cas == 0 {
+            self.cas = 1;
+        }
+        self.cas
+    }
+
+    /// Calculates the candidate slots for a key.
+    fn hash(&self, key: &[u8]) -> [usize; D] {
+        let mut offsets = [0; D];
+        for (offset, iv) in offsets.iter_mut().zip(IV.iter()) {
+            let mut hasher = self.hash_builder.build_hasher();
+            hasher.write_u32(*iv);
+            hasher.write(key);
+            *offset = (hasher.finish() % self.nitem as u64) as usize;
+        }
+        offsets
+    }
+
+    /// Finds the slot holding a live item with the provided key.
+    fn find(&self, key: &[u8], offsets: &[usize; D], now: u32) -> Option<usize> {
+        offsets.iter().copied().find(|offset| {
+            self.is_live(*offset, now) && crate::item::key(self.slot(*offset)) == key
+        })
+    }
+
+    fn slot(&self, offset: usize) -> &[u8] {
+        let start = offset * self.item_size;
+        &self.data.as_slice()[start..(start + self.item_size)]
+    }
+
+    fn slot_mut(&mut self, offset: usize) -> &mut [u8] {
+        let start = offset * self.item_size;
+        &mut self.data.as_mut_slice()[start..(start + self.item_size)]
+    }
+
+    fn is_live(&self, offset: usize, now: u32) -> bool {
+        crate::item::expire(self.slot(offset)) > now
+    }
+
+    /// Removes the item in the slot from the item metrics.
+    fn unlink(&mut self, offset: usize) {
+        CUCKOO_ITEM_CURRENT.decrement();
+        CUCKOO_ITEM_CURRENT_BYTES.sub(data_len(self.slot(offset)) as _);
+    }
+
+    /// Prepares an unoccupied slot for reuse. If the slot holds an expired
+    /// item, it is accounted for as an expiration.
+    fn reclaim(&mut self, offset: usize) {
+        if crate::item::expire(self.slot(offset)) != 0 {
+            CUCKOO_ITEM_EXPIRE.increment();
+            self.unlink(offset);
+            set_expire(self.slot_mut(offset), 0);
+        }
+    }
+
+    /// Selects which of the candidate slots will be displaced to make room for
+    /// a new item.
+    fn select_candidate(&mut self, offsets: &[usize; D]) -> usize {
+        match self.policy {
+            Policy::Random => offsets[self.rng.gen_range(0..D)],
+            Policy::Expire => *offsets
+                .iter()
+                .min_by_key(|offset| crate::item::expire(self.slot(**offset)))
+                .unwrap(),
+        }
+    }
+
+    /// Orders the candidate slots by their preference for displacement
+    /// according to the policy.
+    fn sort_candidates(&mut self, offsets: &[usize; D]) -> [usize; D] {
+        let mut ordered = *offsets;
+        match self.policy {
+            Policy::Random => {
+                // pick the first candidate randomly, keep the rest in order
+                ordered.rotate_left(self.rng.gen_range(0..D));
+            }
+            Policy::Expire => {
+                ordered.sort_by_key(|offset| crate::item::expire(self.slot(*offset)));
+            }
+        }
+        ordered
+    }
+
+    /// Moves the item in the provided slot to one of its alternate slots,
+    /// recursively displacing items up to the configured limit. If no
+    /// unoccupied slot is found, the item at the end of the displacement path
+    /// is evicted. When this returns, the provided slot is unoccupied.
+    fn displace(&mut self, start: usize, now: u32) {
+        CUCKOO_DISPLACE.increment();
+
+        let mut path = Vec::with_capacity(self.max_displace + 1);
+        path.push(start);
+
+        let mut displaced = start;
+        let mut evict = true;
+
+        while path.len() <= self.max_displace {
+            let offsets = self.hash(crate::item::key(self.slot(displaced)));
+
+            // first try to find an unoccupied slot
+            if let Some(offset) = offsets
+                .iter()
+                .copied()
+                .find(|offset| !path.contains(offset) && !self.is_live(*offset, now))
+            {
+                CUCKOO_ITEM_DISPLACE.increment();
+                self.reclaim(offset);
+                path.push(offset);
+                evict = false;
+                break;
+            }
+
+            // otherwise we need to displace another item, which must be at a
+            // location not already on the path to avoid cycles
+            let ordered = self.sort_candidates(&offsets);
+            match ordered
+                .iter()
+                .copied()
+                .find(|offset| !path.contains(offset))
+            {
+                Some(offset) => {
+                    CUCKOO_ITEM_DISPLACE.increment();
+                    path.push(offset);
+                    displaced = offset;
+                }
+                None => {
+                    trace!("running out of displacement candidates");
+                    break;
+                }
+            }
+        }
+
+        if evict {
+            trace!("one item evicted during displacement");
+            CUCKOO_ITEM_EVICT.increment();
+            self.unlink(*path.last().unwrap());
+        }
+
+        // move items along the path, starting from the end
+        let size = self.item_size;
+        let data = self.data.as_mut_slice();
+        for idx in (1..path.len()).rev() {
+            let src = path[idx - 1] * size;
+            data.copy_within(src..(src + size), path[idx] * size);
+        }
+
+        set_expire(self.slot_mut(start), 0);
+    }
+}
@@ -0,0 +1,20 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Top-level errors that will be returned to a caller of this library.
+
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq, Copy, Clone)]
+/// Possible errors returned by the top-level API
+pub enum CuckooError {
+    #[error("item oversized ({size:?} bytes)")]
+    ItemOversized { size: usize },
+    #[error("item exists")]
+    Exists,
+    #[error("item not found")]
+    NotFound,
+    #[error("item is not numeric")]
+    NotNumeric,
+}
@@ -0,0 +1,187 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Items are the base unit of data stored within the cache. Every slot in the
+//! storage array holds at most one item, and all slots have the same size.
+//!
+//! ```text
+//! ┌──────────┬──────────┬──────┬──────┬──────┬──────┬─────────┬─────┬───────┐
+//! │  EXPIRE  │   CAS    │ KLEN │ VLEN │ OLEN │FLAGS │OPTIONAL │ KEY │ VALUE │
+//! │          │          │      │      │      │      │         │     │       │
+//! │  32 bit  │  32 bit  │8 bit │8 bit │8 bit │8 bit │  OLEN   │KLEN │ VLEN  │
+//! └──────────┴──────────┴──────┴──────┴──────┴──────┴─────────┴─────┴───────┘
+//! ```
+//!
+//! An expiration time of zero indicates that the slot is empty. Otherwise, the
+//! expiration time is the number of seconds since the storage was created at
+//! which the item is no longer valid.
+
+use crate::{CuckooError, Value};
+
+/// The number of bytes used by the per-item header.
+pub const ITEM_HDR_SIZE: usize = 12;
+
+const EXPIRE_OFFSET: usize = 0;
+const CAS_OFFSET: usize = 4;
+const KLEN_OFFSET: usize = 8;
+const VLEN_OFFSET: usize = 9;
+const OLEN_OFFSET: usize = 10;
+const FLAGS_OFFSET: usize = 11;
+
+// flag bits
+const TYPED_NUMERIC: u8 = 0x01;
+
+/// Returns the number of bytes required to store an item with the given key,
+/// value, and optional data.
+pub(crate) fn item_size(key: &[u8], value: &Value, optional: &[u8]) -> usize {
+    ITEM_HDR_SIZE + optional.len() + key.len() + value.len()
+}
+
+/// Returns the expiration time for the item in the slot. Zero indicates that
+/// the slot is empty.
+pub(crate) fn expire(slot: &[u8]) -> u32 {
+    u32::from_le_bytes(slot[EXPIRE_OFFSET..EXPIRE_OFFSET + 4].try_into().unwrap())
+}
+
+/// Set the expiration time for the item in the slot. Setting this to zero marks
+/// the slot as empty.
+pub(crate) fn set_expire(slot: &mut [u8], expire: u32) {
+    slot[EXPIRE_OFFSET..EXPIRE_OFFSET + 4].copy_from_slice(&expire.to_le_bytes());
+}
+
+pub(crate) fn cas(slot: &[u8]) -> u32 {
+    u32::from_le_bytes(slot[CAS_OFFSET..CAS_OFFSET + 4].try_into().unwrap())
+}
+
+fn klen(slot: &[u8]) -> usize {
+    slot[KLEN_OFFSET] as usize
+}
+
+fn vlen(slot: &[u8]) -> usize {
+    slot[VLEN_OFFSET] as usize
+}
+
+fn olen(slot: &[u8]) -> usize {
+    slot[OLEN_OFFSET] as usize
+}
+
+fn is_numeric(slot: &[u8]) -> bool {
+    slot[FLAGS_OFFSET] & TYPED_NUMERIC != 0
+}
+
+/// Borrow the key of the item in the slot.
+pub(crate) fn key(slot: &[u8]) -> &[u8] {
+    let start = ITEM_HDR_SIZE + olen(slot);
+    &slot[start..start + klen(slot)]
+}
+
+/// Returns the number of key and value bytes for the item in the slot.
+pub(crate) fn data_len(slot: &[u8]) -> usize {
+    klen(slot) + vlen(slot)
+}
+
+/// Writes an item into the slot. The caller must ensure that the item fits
+/// within the slot.
+pub(crate) fn define(
+    slot: &mut [u8],
+    key: &[u8],
+    value: Value,
+    optional: &[u8],
+    expire: u32,
+    cas: u32,
+) {
+    set_expire(slot, expire);
+    slot[CAS_OFFSET..CAS_OFFSET + 4].copy_from_slice(&cas.to_le_bytes());
+    slot[KLEN_OFFSET] = key.len() as u8;
+    slot[VLEN_OFFSET] = value.len() as u8;
+    slot[OLEN_OFFSET] = optional.len() as u8;
+
+    let mut offset = ITEM_HDR_SIZE;
+    slot[offset..offset + optional.len()].copy_from_slice(optional);
+    offset += optional.len();
+    slot[offset..offset + key.len()].copy_from_slice(key);
+    offset += key.len();
+
+    match value {
+        Value::Bytes(v) => {
+            slot[FLAGS_OFFSET] = 0;
+            slot[offset..offset + v.len()].copy_from_slice(v);
+        }
+        Value::U64(v) => {
+            slot[FLAGS_OFFSET] = TYPED_NUMERIC;
+            slot[offset..offset + 8].copy_from_slice(&v.to_le_bytes());
+        }
+    }
+}
+
+/// Replaces a numeric value for the item in the slot. Returns the new value.
+pub(crate) fn update_numeric<F: FnOnce(u64) -> u64>(
+    slot: &mut [u8],
+    cas: u32,
+    f: F,
+) -> Result<u64, CuckooError> {
+    if !is_numeric(slot) {
+        return Err(CuckooError::NotNumeric);
+    }
+    let offset = ITEM_HDR_SIZE + olen(slot) + klen(slot);
+    let current = u64::from_le_bytes(slot[offset..offset + 8].try_into().unwrap());
+    let value = f(current);
+    slot[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+    slot[CAS_OFFSET..CAS_OFFSET + 4].copy_from_slice(&cas.to_le_bytes());
+    Ok(value)
+}
+
+/// Items are the base unit of data stored within the cache. An `Item` borrows
+/// the slot it is stored in.
+pub struct Item<'a> {
+    slot: &'a [u8],
+}
+
+impl<'a> Item<'a> {
+    pub(crate) fn new(slot: &'a [u8]) -> Self {
+        Self { slot }
+    }
+
+    /// Borrow the item key
+    pub fn key(&self) -> &[u8] {
+        key(self.slot)
+    }
+
+    /// Borrow the item value
+    pub fn value(&self) -> Value {
+        let offset = ITEM_HDR_SIZE + olen(self.slot) + klen(self.slot);
+        if is_numeric(self.slot) {
+            Value::U64(u64::from_le_bytes(
+                self.slot[offset..offset + 8].try_into().unwrap(),
+            ))
+        } else {
+            Value::Bytes(&self.slot[offset..offset + vlen(self.slot)])
+        }
+    }
+
+    /// CAS value for the item
+    pub fn cas(&self) -> u32 {
+        cas(self.slot)
+    }
+
+    /// Borrow the optional data
+    pub fn optional(&self) -> Option<&[u8]> {
+        let olen = olen(self.slot);
+        if olen == 0 {
+            None
+        } else {
+            Some(&self.slot[ITEM_HDR_SIZE..ITEM_HDR_SIZE + olen])
+        }
+    }
+}
+
+impl std::fmt::Debug for Item<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        f.debug_struct("Item")
+            .field("cas", &self.cas())
+            .field("key", &self.key())
+            .field("value", &self.value())
+            .finish()
+    }
+}
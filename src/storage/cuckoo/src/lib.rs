@@ -0,0 +1,76 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! This crate is a Rust implementation of the cuckoo storage layer which backs
+//! the legacy Slimcache server.
+//!
+//! Items are stored in a pre-allocated array of fixed-size slots. Each key has
+//! a small number of candidate slots, determined by hashing the key with
+//! several different seeds. When all candidate slots are occupied, an existing
+//! item is displaced to one of its alternate slots, following a bounded
+//! displacement path. If no free slot can be found along that path, the item
+//! at the end of the path is evicted.
+//!
+//! Because there is no hashtable or allocator metadata beyond a small header
+//! in each slot, the per-item overhead is very low. This makes the design well
+//! suited for small values of a known maximum size, such as counters.
+//!
+//! Goals:
+//! * very low per-item overhead
+//! * predictable memory footprint
+//!
+//! Non-goals:
+//! * not designed for variable or large item sizes
+//! * not designed for concurrent access
+//!
+
+// macro includes
+#[macro_use]
+extern crate logger;
+
+// external crate includes
+use common::time::Seconds;
+
+// NOTE: this represents the versioning of the internal data layout and must be
+// incremented when breaking changes are made to the datastructures
+const VERSION: u64 = 0;
+
+// submodules
+mod builder;
+mod cuckoo;
+mod error;
+mod item;
+mod metrics;
+
+// tests
+#[cfg(test)]
+mod tests;
+
+// publicly exported items from submodules
+pub use crate::cuckoo::Cuckoo;
+pub use builder::Builder;
+pub use error::CuckooError;
+pub use item::{Item, ITEM_HDR_SIZE};
+
+// publicly exported items from external crates
+pub use storage_types::Value;
+
+// type aliases
+pub(crate) type Instant = common::time::Instant<Seconds<u32>>;
+
+// items from submodules which are imported for convenience to the crate level
+pub(crate) use item::*;
+pub(crate) use metrics::*;
+
+/// The policy used to select which item is displaced when all of the candidate
+/// slots for a new item are occupied.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Policy {
+    /// Displace a randomly chosen candidate.
+    Random,
+    /// Displace the candidate which will expire soonest.
+    Expire,
+}
+
+common::metrics::test_no_duplicates!();
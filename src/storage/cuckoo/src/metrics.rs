@@ -0,0 +1,39 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// All metrics for the Cuckoo crate
+
+use rustcommon_metrics::*;
+
+// operations
+counter!(CUCKOO_GET, "number of cuckoo lookups");
+counter!(CUCKOO_INSERT, "number of cuckoo inserts");
+counter!(
+    CUCKOO_INSERT_EX,
+    "number of cuckoo inserts which failed, likely due to item size"
+);
+counter!(CUCKOO_DISPLACE, "number of displacement paths followed");
+counter!(
+    CUCKOO_UPDATE,
+    "number of in-place updates of existing items"
+);
+counter!(CUCKOO_DELETE, "number of cuckoo deletes");
+
+// item related
+counter!(CUCKOO_ITEM_INSERT, "number of items inserted");
+counter!(CUCKOO_ITEM_DELETE, "number of items removed by deletes");
+counter!(
+    CUCKOO_ITEM_DISPLACE,
+    "number of times items have been moved to an alternate slot"
+);
+counter!(CUCKOO_ITEM_EVICT, "number of items removed due to eviction");
+counter!(
+    CUCKOO_ITEM_EXPIRE,
+    "number of items removed due to expiration"
+);
+gauge!(CUCKOO_ITEM_CURRENT, "current number of live items");
+gauge!(
+    CUCKOO_ITEM_CURRENT_BYTES,
+    "current number of key and value bytes for live items"
+);
@@ -0,0 +1,155 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+use std::time::Duration;
+
+#[test]
+fn get() {
+    let ttl = Duration::ZERO;
+    let mut cache = Cuckoo::builder().build().expect("failed to create cache");
+    assert!(cache.get(b"coffee").is_none());
+    assert!(cache.insert(b"coffee", b"strong", None, ttl).is_ok());
+    let item = cache.get(b"coffee").expect("didn't get item back");
+    assert_eq!(item.key(), b"coffee");
+    assert_eq!(item.value(), b"strong");
+    assert!(item.optional().is_none());
+}
+
+#[test]
+fn optional() {
+    let ttl = Duration::ZERO;
+    let mut cache = Cuckoo::builder().build().expect("failed to create cache");
+    assert!(cache
+        .insert(b"coffee", b"strong", Some(&[0, 0, 0, 42]), ttl)
+        .is_ok());
+    let item = cache.get(b"coffee").expect("didn't get item back");
+    assert_eq!(item.value(), b"strong");
+    assert_eq!(item.optional(), Some(&[0, 0, 0, 42][..]));
+}
+
+#[test]
+fn overwrite() {
+    let ttl = Duration::ZERO;
+    let mut cache = Cuckoo::builder().build().expect("failed to create cache");
+    assert!(cache.insert(b"drink", b"coffee", None, ttl).is_ok());
+    let cas = cache.get(b"drink").expect("didn't get item back").cas();
+    assert!(cache.insert(b"drink", b"espresso", None, ttl).is_ok());
+    let item = cache.get(b"drink").expect("didn't get item back");
+    assert_eq!(item.value(), b"espresso");
+    assert_ne!(item.cas(), cas);
+}
+
+#[test]
+fn oversized() {
+    let ttl = Duration::ZERO;
+    let mut cache = Cuckoo::builder()
+        .item_size(32)
+        .build()
+        .expect("failed to create cache");
+    let value = [0; 32];
+    assert_eq!(
+        cache.insert(b"key", &value, None, ttl),
+        Err(CuckooError::ItemOversized {
+            size: ITEM_HDR_SIZE + 3 + 32
+        })
+    );
+    assert!(cache.get(b"key").is_none());
+}
+
+#[test]
+fn delete() {
+    let ttl = Duration::ZERO;
+    let mut cache = Cuckoo::builder().build().expect("failed to create cache");
+    assert!(!cache.delete(b"coffee"));
+    assert!(cache.insert(b"coffee", b"strong", None, ttl).is_ok());
+    assert!(cache.delete(b"coffee"));
+    assert!(cache.get(b"coffee").is_none());
+    assert!(!cache.delete(b"coffee"));
+}
+
+#[test]
+fn numeric() {
+    let ttl = Duration::ZERO;
+    let mut cache = Cuckoo::builder().build().expect("failed to create cache");
+    assert_eq!(
+        cache.wrapping_add(b"a", 1).err(),
+        Some(CuckooError::NotFound)
+    );
+    assert!(cache.insert(b"a", 1_u64, None, ttl).is_ok());
+    assert_eq!(cache.wrapping_add(b"a", 1).unwrap().value(), 2_u64);
+    assert_eq!(cache.saturating_sub(b"a", 5).unwrap().value(), 0_u64);
+    assert_eq!(
+        cache.wrapping_add(b"a", u64::MAX).unwrap().value(),
+        u64::MAX
+    );
+    assert_eq!(cache.wrapping_add(b"a", 1).unwrap().value(), 0_u64);
+
+    assert!(cache.insert(b"b", b"string", None, ttl).is_ok());
+    assert_eq!(
+        cache.wrapping_add(b"b", 1).err(),
+        Some(CuckooError::NotNumeric)
+    );
+}
+
+#[test]
+fn full() {
+    let ttl = Duration::ZERO;
+    let nitem = 64;
+    let mut cache = Cuckoo::builder()
+        .nitem(nitem)
+        .build()
+        .expect("failed to create cache");
+
+    // inserting far more items than there are slots must always succeed by
+    // displacing or evicting existing items
+    for i in 0..(nitem * 4) {
+        let key = format!("{}", i);
+        assert!(cache.insert(key.as_bytes(), i as u64, None, ttl).is_ok());
+        // the most recently inserted item is always retrievable
+        let item = cache.get(key.as_bytes()).expect("didn't get item back");
+        assert_eq!(item.value(), i as u64);
+    }
+
+    // the number of live items can never exceed the number of slots
+    let mut live = 0;
+    for i in 0..(nitem * 4) {
+        let key = format!("{}", i);
+        if let Some(item) = cache.get(key.as_bytes()) {
+            assert_eq!(item.value(), i as u64);
+            live += 1;
+        }
+    }
+    assert!(live <= nitem);
+}
+
+#[test]
+fn clear() {
+    let ttl = Duration::ZERO;
+    let mut cache = Cuckoo::builder().build().expect("failed to create cache");
+    assert!(cache.insert(b"coffee", b"strong", None, ttl).is_ok());
+    assert!(cache.insert(b"tea", b"green", None, ttl).is_ok());
+    cache.clear();
+    assert!(cache.get(b"coffee").is_none());
+    assert!(cache.get(b"tea").is_none());
+}
+
+#[test]
+fn expiration() {
+    let mut cache = Cuckoo::builder().build().expect("failed to create cache");
+    assert!(cache
+        .insert(b"coffee", b"strong", None, Duration::from_secs(1))
+        .is_ok());
+    assert!(cache
+        .insert(b"tea", b"green", None, Duration::from_secs(10))
+        .is_ok());
+    assert!(cache.get(b"coffee").is_some());
+
+    std::thread::sleep(Duration::from_secs(2));
+    cache.expire();
+
+    assert!(cache.get(b"coffee").is_none());
+    assert!(cache.get(b"tea").is_some());
+}
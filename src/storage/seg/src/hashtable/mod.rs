@@ -303,6 +303,21 @@ impl HashTable {
     /// frequency. This may be used to compose higher-level functions which do
     /// not want a successful item lookup to count as a hit for that item.
     pub fn get_no_freq_incr(&mut self, key: &[u8], segments: &mut Segments) -> Option<Item> {
+        let (item_info, cas) = self.get_item_info(key, segments)?;
+
+        let item = Item::new(segments.get_item(item_info).unwrap(), cas);
+        item.check_magic();
+
+        Some(item)
+    }
+
+    /// Lookup an item by key and return its item info along with the CAS
+    /// value of its bucket. The item frequency is not incremented.
+    pub(crate) fn get_item_info(
+        &mut self,
+        key: &[u8],
+        segments: &mut Segments,
+    ) -> Option<(u64, u32)> {
         let hash = self.hash(key);
 
         let iter = IterMut::new(self, hash);
@@ -315,13 +330,11 @@ impl HashTable {
                 if current_item.key() != key {
                     HASH_TAG_COLLISION.increment();
                 } else {
-                    let item = Item::new(
-                        current_item,
+                    let item_info = *item_info;
+                    return Some((
+                        item_info,
                         get_cas(self.data[(hash & self.mask) as usize].data[0]),
-                    );
-                    item.check_magic();
-
-                    return Some(item);
+                    ));
                 }
             }
         }
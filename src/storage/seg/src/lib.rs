@@ -47,6 +47,7 @@ mod metrics;
 mod rand;
 mod seg;
 mod segments;
+mod stats;
 mod ttl_buckets;
 
 // tests
@@ -59,6 +60,7 @@ pub use builder::Builder;
 pub use error::SegError;
 pub use eviction::Policy;
 pub use item::Item;
+pub use stats::{ItemInfo, SegmentInfo, SegmentStats, TtlBucketStats};
 
 // publicly exported items from external crates
 pub use storage_types::Value;
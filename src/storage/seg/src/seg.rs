@@ -87,6 +87,102 @@ impl Seg {
         self.hashtable.get_no_freq_incr(key, &mut self.segments)
     }
 
+    /// Returns the remaining TTL of the item with the provided key, without
+    /// increasing the item frequency. Items expire along with the segment
+    /// which holds them, so this is the time until that segment expires. Items
+    /// which were inserted without a TTL return `Duration::ZERO`.
+    /// ```
+    /// use seg::{Policy, Seg};
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Seg::builder().build().expect("failed to create cache");
+    /// assert!(cache.ttl(b"coffee").is_none());
+    ///
+    /// cache.insert(b"coffee", b"strong", None, Duration::ZERO);
+    /// assert_eq!(cache.ttl(b"coffee"), Some(Duration::ZERO));
+    ///
+    /// cache.insert(b"coffee", b"strong", None, Duration::from_secs(60));
+    /// let ttl = cache.ttl(b"coffee").expect("didn't get ttl");
+    /// assert!(ttl > Duration::ZERO && ttl <= Duration::from_secs(60));
+    /// ```
+    pub fn ttl(&mut self, key: &[u8]) -> Option<std::time::Duration> {
+        let (item_info, _) = self.hashtable.get_item_info(key, &mut self.segments)?;
+        let header = self.segments.header(get_seg_id(item_info)?)?;
+
+        // items without a ttl are stored in the last ttl bucket
+        if self.ttl_buckets.get_bucket_index(header.ttl()) == self.ttl_buckets.buckets.len() - 1 {
+            return Some(std::time::Duration::ZERO);
+        }
+
+        Some(std::time::Duration::from_secs(expires_in(header) as u64))
+    }
+
+    /// Returns the estimated access frequency of the item with the provided
+    /// key, without increasing it. The frequency of an item is zero until it
+    /// is first read.
+    /// ```
+    /// use seg::{Policy, Seg};
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Seg::builder().build().expect("failed to create cache");
+    /// assert!(cache.frequency(b"coffee").is_none());
+    ///
+    /// cache.insert(b"coffee", b"strong", None, Duration::ZERO);
+    /// assert_eq!(cache.frequency(b"coffee"), Some(0));
+    ///
+    /// cache.get(b"coffee");
+    /// assert_eq!(cache.frequency(b"coffee"), Some(1));
+    /// ```
+    pub fn frequency(&mut self, key: &[u8]) -> Option<u64> {
+        let (item_info, _) = self.hashtable.get_item_info(key, &mut self.segments)?;
+        Some(get_freq(item_info) & 0x7F)
+    }
+
+    /// Describe how the item with the provided key is stored, without
+    /// increasing the item frequency. This is intended for debugging.
+    /// ```
+    /// use seg::{Policy, Seg};
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Seg::builder().build().expect("failed to create cache");
+    /// assert!(cache.item_info(b"coffee").is_none());
+    ///
+    /// cache.insert(b"coffee", b"strong", None, Duration::from_secs(60));
+    /// let info = cache.item_info(b"coffee").expect("didn't get item info");
+    /// assert_eq!(info.value_len(), 6);
+    /// assert!(!info.typed());
+    /// assert!(!info.merging());
+    /// ```
+    pub fn item_info(&mut self, key: &[u8]) -> Option<ItemInfo> {
+        let (item_info, cas) = self.hashtable.get_item_info(key, &mut self.segments)?;
+
+        let (typed, value_len) = match self.segments.get_item(item_info)?.value() {
+            Value::Bytes(bytes) => (false, bytes.len()),
+            Value::U64(_) => (true, std::mem::size_of::<u64>()),
+        };
+
+        let id = get_seg_id(item_info)?;
+        let header = self.segments.header(id)?;
+
+        let bucket = &self.ttl_buckets.buckets[self.ttl_buckets.get_bucket_index(header.ttl())];
+        let merging = self
+            .segments
+            .merge_chain(bucket.next_to_merge())
+            .any(|header| header.id() == id);
+
+        Some(ItemInfo {
+            segment: id.get(),
+            offset: get_offset(item_info),
+            ttl: bucket.ttl() as u32,
+            remaining_ttl: expires_in(header),
+            cas,
+            frequency: get_freq(item_info) & 0x7F,
+            typed,
+            value_len,
+            merging,
+        })
+    }
+
     /// Insert a new item into the cache. May return an error indicating that
     /// the insert was not successful.
     /// ```
@@ -291,6 +387,117 @@ impl Seg {
             .clear(&mut self.hashtable, &mut self.segments)
     }
 
+    /// Returns a summary of each TTL bucket which currently holds segments,
+    /// ordered by increasing TTL.
+    ///
+    /// ```
+    /// use seg::{Policy, Seg};
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Seg::builder().build().expect("failed to create cache");
+    /// assert!(cache.ttl_bucket_stats().is_empty());
+    ///
+    /// cache.insert(b"coffee", b"strong", None, Duration::from_secs(60));
+    /// let stats = cache.ttl_bucket_stats();
+    /// assert_eq!(stats.len(), 1);
+    /// assert_eq!(stats[0].items(), 1);
+    /// ```
+    pub fn ttl_bucket_stats(&self) -> Vec<TtlBucketStats> {
+        self.ttl_buckets
+            .buckets
+            .iter()
+            .filter(|bucket| bucket.head().is_some())
+            .map(|bucket| {
+                let mut stats = TtlBucketStats {
+                    ttl: bucket.ttl() as u32,
+                    segments: 0,
+                    items: 0,
+                    bytes: 0,
+                    merge_chain: self.segments.merge_chain(bucket.next_to_merge()).count(),
+                };
+                for header in self.segments.chain(bucket.head()) {
+                    stats.segments += 1;
+                    stats.items += header.live_items() as usize;
+                    stats.bytes += header.live_bytes() as usize;
+                }
+                stats
+            })
+            .collect()
+    }
+
+    /// Returns a summary of each segment which is linked into a TTL bucket,
+    /// ordered by increasing TTL and then by position in the bucket's segment
+    /// chain.
+    ///
+    /// ```
+    /// use seg::{Policy, Seg};
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Seg::builder().build().expect("failed to create cache");
+    /// assert!(cache.segment_info().is_empty());
+    ///
+    /// cache.insert(b"coffee", b"strong", None, Duration::from_secs(60));
+    /// let segments = cache.segment_info();
+    /// assert_eq!(segments.len(), 1);
+    /// assert_eq!(segments[0].live_items(), 1);
+    /// assert!(!segments[0].merging());
+    /// ```
+    pub fn segment_info(&self) -> Vec<SegmentInfo> {
+        let mut segments = Vec::new();
+        for bucket in self.ttl_buckets.buckets.iter() {
+            let merging: Vec<u32> = self
+                .segments
+                .merge_chain(bucket.next_to_merge())
+                .map(|header| header.id().get())
+                .collect();
+            for header in self.segments.chain(bucket.head()) {
+                segments.push(SegmentInfo {
+                    id: header.id().get(),
+                    ttl: bucket.ttl() as u32,
+                    live_items: header.live_items() as usize,
+                    live_bytes: header.live_bytes() as usize,
+                    written_bytes: header.write_offset() as usize,
+                    merging: merging.contains(&header.id().get()),
+                });
+            }
+        }
+        segments
+    }
+
+    /// Returns a summary of segment utilization across the heap.
+    ///
+    /// ```
+    /// use seg::{Policy, Seg};
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Seg::builder().build().expect("failed to create cache");
+    /// let stats = cache.segment_stats();
+    /// assert_eq!(stats.free(), stats.total());
+    ///
+    /// cache.insert(b"coffee", b"strong", None, Duration::ZERO);
+    /// let stats = cache.segment_stats();
+    /// assert_eq!(stats.used(), 1);
+    /// assert_eq!(stats.live_items(), 1);
+    /// ```
+    pub fn segment_stats(&self) -> SegmentStats {
+        let mut stats = SegmentStats {
+            segment_size: self.segments.segment_size() as usize,
+            total: self.segments.cap(),
+            free: self.segments.free(),
+            live_items: 0,
+            live_bytes: 0,
+            written_bytes: 0,
+        };
+        for bucket in self.ttl_buckets.buckets.iter() {
+            for header in self.segments.chain(bucket.head()) {
+                stats.live_items += header.live_items() as usize;
+                stats.live_bytes += header.live_bytes() as usize;
+                stats.written_bytes += header.write_offset() as usize;
+            }
+        }
+        stats
+    }
+
     /// Checks the integrity of all segments
     /// *NOTE*: this operation is relatively expensive
     #[cfg(feature = "debug")]
@@ -326,3 +533,14 @@ impl Seg {
         Ok(item)
     }
 }
+
+/// Returns the number of seconds until the segment expires.
+fn expires_in(header: &SegmentHeader) -> u32 {
+    let expire_at = header.create_at() + header.ttl();
+    let now = Instant::recent();
+    if expire_at > now {
+        (expire_at - now).as_secs()
+    } else {
+        0
+    }
+}
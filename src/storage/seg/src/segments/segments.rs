@@ -109,11 +109,46 @@ impl Segments {
     }
 
     /// Returns the number of free segments
-    #[cfg(test)]
     pub fn free(&self) -> usize {
         self.free as usize
     }
 
+    /// Returns the total number of segments
+    pub fn cap(&self) -> usize {
+        self.cap as usize
+    }
+
+    /// Returns the header of the segment with the provided id.
+    pub(crate) fn header(&self, id: NonZeroU32) -> Option<&SegmentHeader> {
+        self.headers.get(id.get() as usize - 1)
+    }
+
+    /// Returns an iterator over the headers of the segment chain which begins
+    /// with the provided segment id.
+    pub(crate) fn chain(
+        &self,
+        head: Option<NonZeroU32>,
+    ) -> impl Iterator<Item = &SegmentHeader> + '_ {
+        let mut next = head;
+        std::iter::from_fn(move || {
+            let header = self.headers.get(next?.get() as usize - 1)?;
+            next = header.next_seg();
+            Some(header)
+        })
+    }
+
+    /// Returns an iterator over the headers of the active merge chain which
+    /// begins with the provided segment id. These are the segments which will
+    /// be considered by the next merge of the TTL bucket.
+    pub(crate) fn merge_chain(
+        &self,
+        start: Option<NonZeroU32>,
+    ) -> impl Iterator<Item = &SegmentHeader> + '_ {
+        self.chain(start)
+            .take_while(|header| header.can_evict())
+            .take(self.evict.max_merge())
+    }
+
     /// Returns the time the segments were last flushed
     pub fn flush_at(&self) -> Instant {
         self.flush_at
@@ -0,0 +1,223 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Point-in-time summaries of the internal state of a `Seg` instance. These
+//! are gathered by walking the segment headers and are intended for
+//! introspection, such as answering memcache `stats` requests.
+
+/// A summary of the segment chain for a single TTL bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TtlBucketStats {
+    pub(crate) ttl: u32,
+    pub(crate) segments: usize,
+    pub(crate) items: usize,
+    pub(crate) bytes: usize,
+    pub(crate) merge_chain: usize,
+}
+
+impl TtlBucketStats {
+    /// The smallest TTL, in seconds, of items stored in this bucket.
+    pub fn ttl(&self) -> u32 {
+        self.ttl
+    }
+
+    /// The number of segments in the bucket's segment chain.
+    pub fn segments(&self) -> usize {
+        self.segments
+    }
+
+    /// The number of live items stored in the bucket's segments.
+    pub fn items(&self) -> usize {
+        self.items
+    }
+
+    /// The number of live bytes stored in the bucket's segments.
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// The number of segments in the bucket's active merge chain. This is
+    /// only non-zero while a merge of the bucket is in progress.
+    pub fn merge_chain(&self) -> usize {
+        self.merge_chain
+    }
+}
+
+/// A summary of a single segment which is linked into a TTL bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentInfo {
+    pub(crate) id: u32,
+    pub(crate) ttl: u32,
+    pub(crate) live_items: usize,
+    pub(crate) live_bytes: usize,
+    pub(crate) written_bytes: usize,
+    pub(crate) merging: bool,
+}
+
+impl SegmentInfo {
+    /// The id of the segment.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// The smallest TTL, in seconds, of the bucket which holds the segment.
+    pub fn ttl(&self) -> u32 {
+        self.ttl
+    }
+
+    /// The number of live items stored in the segment.
+    pub fn live_items(&self) -> usize {
+        self.live_items
+    }
+
+    /// The number of bytes held by live items in the segment.
+    pub fn live_bytes(&self) -> usize {
+        self.live_bytes
+    }
+
+    /// The number of bytes which have been written into the segment.
+    pub fn written_bytes(&self) -> usize {
+        self.written_bytes
+    }
+
+    /// Returns true if the segment is part of the active merge chain of its
+    /// TTL bucket.
+    pub fn merging(&self) -> bool {
+        self.merging
+    }
+
+    /// The percentage of the bytes written into the segment which are still
+    /// held by live items.
+    pub fn utilization(&self) -> f64 {
+        if self.written_bytes == 0 {
+            0.0
+        } else {
+            100.0 * self.live_bytes as f64 / self.written_bytes as f64
+        }
+    }
+}
+
+/// Describes where and how a single item is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemInfo {
+    pub(crate) segment: u32,
+    pub(crate) offset: u64,
+    pub(crate) ttl: u32,
+    pub(crate) remaining_ttl: u32,
+    pub(crate) cas: u32,
+    pub(crate) frequency: u64,
+    pub(crate) typed: bool,
+    pub(crate) value_len: usize,
+    pub(crate) merging: bool,
+}
+
+impl ItemInfo {
+    /// The id of the segment which holds the item.
+    pub fn segment(&self) -> u32 {
+        self.segment
+    }
+
+    /// The offset of the item within its segment, in bytes.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// The smallest TTL, in seconds, of the bucket which holds the segment.
+    pub fn ttl(&self) -> u32 {
+        self.ttl
+    }
+
+    /// The number of seconds until the segment which holds the item expires.
+    pub fn remaining_ttl(&self) -> u32 {
+        self.remaining_ttl
+    }
+
+    /// The CAS value of the item.
+    pub fn cas(&self) -> u32 {
+        self.cas
+    }
+
+    /// The estimated access frequency of the item, which is used by the merge
+    /// eviction to decide which items to retain.
+    pub fn frequency(&self) -> u64 {
+        self.frequency
+    }
+
+    /// Returns true if the value is stored as a typed value, eg: a `u64`,
+    /// rather than as raw bytes.
+    pub fn typed(&self) -> bool {
+        self.typed
+    }
+
+    /// The length of the value in bytes, as it is stored.
+    pub fn value_len(&self) -> usize {
+        self.value_len
+    }
+
+    /// Returns true if the segment which holds the item is part of the active
+    /// merge chain of its TTL bucket.
+    pub fn merging(&self) -> bool {
+        self.merging
+    }
+}
+
+/// A summary of segment utilization across the entire heap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentStats {
+    pub(crate) segment_size: usize,
+    pub(crate) total: usize,
+    pub(crate) free: usize,
+    pub(crate) live_items: usize,
+    pub(crate) live_bytes: usize,
+    pub(crate) written_bytes: usize,
+}
+
+impl SegmentStats {
+    /// The size of each segment in bytes.
+    pub fn segment_size(&self) -> usize {
+        self.segment_size
+    }
+
+    /// The total number of segments in the heap.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// The number of segments which are on the free queue.
+    pub fn free(&self) -> usize {
+        self.free
+    }
+
+    /// The number of segments which are linked into a TTL bucket.
+    pub fn used(&self) -> usize {
+        self.total - self.free
+    }
+
+    /// The number of live items across all segments.
+    pub fn live_items(&self) -> usize {
+        self.live_items
+    }
+
+    /// The number of bytes held by live items across all segments.
+    pub fn live_bytes(&self) -> usize {
+        self.live_bytes
+    }
+
+    /// The number of bytes which have been written into segments, including
+    /// the space held by items which have since been removed or replaced.
+    pub fn written_bytes(&self) -> usize {
+        self.written_bytes
+    }
+
+    /// The percentage of the bytes written into segments which are still held
+    /// by live items. Low utilization indicates that segments are holding
+    /// mostly dead items which are waiting on merge or expiration.
+    pub fn utilization(&self) -> f64 {
+        if self.written_bytes == 0 {
+            0.0
+        } else {
+            100.0 * self.live_bytes as f64 / self.written_bytes as f64
+        }
+    }
+}
@@ -409,6 +409,147 @@ fn clear() {
     assert!(cache.get(b"coffee").is_none());
 }
 
+#[test]
+fn stats() {
+    let segment_size = 4096;
+    let segments = 64;
+    let heap_size = segments * segment_size as usize;
+
+    let mut cache = Seg::builder()
+        .segment_size(segment_size)
+        .heap_size(heap_size)
+        .build()
+        .expect("failed to create cache");
+
+    let stats = cache.segment_stats();
+    assert_eq!(stats.segment_size(), segment_size as usize);
+    assert_eq!(stats.total(), segments);
+    assert_eq!(stats.free(), segments);
+    assert_eq!(stats.live_items(), 0);
+    assert!(cache.ttl_bucket_stats().is_empty());
+
+    assert!(cache
+        .insert(b"coffee", b"strong", None, Duration::from_secs(10))
+        .is_ok());
+    assert!(cache
+        .insert(b"tea", b"earl grey", None, Duration::from_secs(10))
+        .is_ok());
+    assert!(cache
+        .insert(b"juice", b"orange", None, Duration::from_secs(3600))
+        .is_ok());
+
+    let buckets = cache.ttl_bucket_stats();
+    assert_eq!(buckets.len(), 2);
+    assert!(buckets[0].ttl() <= 10);
+    assert_eq!(buckets[0].segments(), 1);
+    assert_eq!(buckets[0].items(), 2);
+    assert!(buckets[1].ttl() <= 3600);
+    assert_eq!(buckets[1].items(), 1);
+
+    let stats = cache.segment_stats();
+    assert_eq!(stats.used(), 2);
+    assert_eq!(stats.live_items(), 3);
+    assert_eq!(
+        stats.live_bytes(),
+        buckets.iter().map(|b| b.bytes()).sum::<usize>()
+    );
+    assert!(stats.written_bytes() >= stats.live_bytes());
+
+    // removed items still occupy written space
+    assert!(cache.delete(b"tea"));
+    let stats = cache.segment_stats();
+    assert_eq!(stats.live_items(), 2);
+    assert!(stats.utilization() < 100.0);
+
+    cache.clear();
+    assert_eq!(cache.segment_stats().free(), segments);
+    assert!(cache.ttl_bucket_stats().is_empty());
+}
+
+#[test]
+fn segment_info() {
+    let segment_size = 4096;
+    let segments = 16;
+    let heap_size = segments * segment_size as usize;
+
+    let mut cache = Seg::builder()
+        .segment_size(segment_size)
+        .heap_size(heap_size)
+        .eviction(Policy::Merge {
+            max: 8,
+            merge: 4,
+            compact: 2,
+        })
+        .build()
+        .expect("failed to create cache");
+
+    assert!(cache.segment_info().is_empty());
+
+    // fill the heap with a single TTL bucket so that inserts must merge
+    let value = [0; 128];
+    for i in 0..1024_u32 {
+        assert!(cache
+            .insert(&i.to_be_bytes(), &value, None, Duration::from_secs(3600))
+            .is_ok());
+    }
+
+    let info = cache.segment_info();
+    assert_eq!(info.len(), cache.segment_stats().used());
+    assert_eq!(
+        info.iter().map(|s| s.live_items()).sum::<usize>(),
+        cache.segment_stats().live_items()
+    );
+    assert!(info.iter().all(|s| s.written_bytes() >= s.live_bytes()));
+
+    // the segments which are part of a merge chain are counted by the bucket
+    let buckets = cache.ttl_bucket_stats();
+    assert_eq!(buckets.len(), 1);
+    assert!(buckets[0].merge_chain() > 0);
+    assert_eq!(
+        info.iter().filter(|s| s.merging()).count(),
+        buckets[0].merge_chain()
+    );
+}
+
+#[test]
+fn item_info() {
+    let mut cache = Seg::builder().build().expect("failed to create cache");
+
+    assert!(cache.item_info(b"coffee").is_none());
+
+    assert!(cache
+        .insert(b"coffee", b"strong", None, Duration::from_secs(60))
+        .is_ok());
+    assert!(cache
+        .insert(b"drinks", 2_u64, None, Duration::from_secs(3600))
+        .is_ok());
+
+    let info = cache.item_info(b"coffee").expect("didn't get item info");
+    assert!(!info.typed());
+    assert_eq!(info.value_len(), 6);
+    assert!(info.ttl() <= 60);
+    assert!(info.remaining_ttl() <= 60);
+    assert!(!info.merging());
+
+    let item = cache.get_no_freq_incr(b"coffee").expect("didn't get item");
+    assert_eq!(info.cas(), item.cas());
+
+    // inspecting the item does not count as an access
+    let frequency = info.frequency();
+    let info = cache.item_info(b"coffee").expect("didn't get item info");
+    assert_eq!(info.frequency(), frequency);
+
+    assert!(cache.get(b"coffee").is_some());
+    let info = cache.item_info(b"coffee").expect("didn't get item info");
+    assert_eq!(info.frequency(), frequency + 1);
+
+    let typed = cache.item_info(b"drinks").expect("didn't get item info");
+    assert!(typed.typed());
+    assert_eq!(typed.value_len(), 8);
+    assert_ne!(typed.segment(), info.segment());
+    assert!(typed.ttl() > info.ttl());
+}
+
 #[test]
 fn wrapping_add() {
     let ttl = Duration::ZERO;
@@ -65,6 +65,12 @@ impl TtlBucket {
         self.head = id;
     }
 
+    /// Returns the smallest TTL, in seconds, of items stored in this
+    /// `TtlBucket`.
+    pub fn ttl(&self) -> i32 {
+        self.ttl
+    }
+
     /// Returns the segment ID of the next segment to merge within the
     /// `TtlBucket`.
     pub fn next_to_merge(&self) -> Option<NonZeroU32> {
@@ -0,0 +1,265 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! An owned ziplist which may be modified.
+
+use crate::*;
+
+/// An owned ziplist which supports adding and removing entries at either end
+/// of the list, inserting, replacing, or removing entries by index, and
+/// trimming it to a range of entries.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ZipListBuf {
+    data: Vec<u8>,
+}
+
+impl Default for ZipListBuf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ZipListBuf {
+    /// Create a new, empty ziplist.
+    pub fn new() -> Self {
+        Self {
+            data: vec![0; HEADER_SIZE],
+        }
+    }
+
+    /// Returns the number of entries in the list.
+    pub fn len(&self) -> usize {
+        nentry(&self.data)
+    }
+
+    /// Returns true if there are no entries in the list.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Borrow the list as a read-only view.
+    pub fn as_ziplist(&self) -> ZipList<'_> {
+        ZipList::new(&self.data).unwrap()
+    }
+
+    /// Borrow the encoded ziplist. These bytes may be stored and later decoded
+    /// with [`ZipList::new`] or [`ZipListBuf::try_from`].
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Add an entry to the front of the list. This moves all existing entries
+    /// and is more expensive than adding to the back of the list.
+    pub fn push_front(&mut self, value: &[u8]) {
+        let mut entry = Vec::with_capacity(entry::encoded_len(value.len()));
+        entry::write(&mut entry, value);
+        self.data.splice(HEADER_SIZE..HEADER_SIZE, entry);
+        self.set_len(self.len() + 1);
+    }
+
+    /// Add an entry to the back of the list.
+    ///
+    /// ```
+    /// use ziplist::ZipListBuf;
+    ///
+    /// let mut list = ZipListBuf::new();
+    /// list.push_back(b"coffee");
+    /// list.push_front(b"tea");
+    ///
+    /// let entries: Vec<&[u8]> = list.as_ziplist().iter().collect();
+    /// assert_eq!(entries, vec![&b"tea"[..], &b"coffee"[..]]);
+    /// ```
+    pub fn push_back(&mut self, value: &[u8]) {
+        self.data.reserve(entry::encoded_len(value.len()));
+        entry::write(&mut self.data, value);
+        self.set_len(self.len() + 1);
+    }
+
+    /// Remove and return the entry at the front of the list.
+    pub fn pop_front(&mut self) -> Option<Vec<u8>> {
+        if self.is_empty() {
+            return None;
+        }
+        let (value, next) = entry::next(&self.data, HEADER_SIZE)?;
+        let value = value.to_vec();
+        self.data.drain(HEADER_SIZE..next);
+        self.set_len(self.len() - 1);
+        Some(value)
+    }
+
+    /// Remove and return the entry at the back of the list.
+    ///
+    /// ```
+    /// use ziplist::ZipListBuf;
+    ///
+    /// let mut list = ZipListBuf::new();
+    /// list.push_back(b"coffee");
+    /// list.push_back(b"tea");
+    ///
+    /// assert_eq!(list.pop_back(), Some(b"tea".to_vec()));
+    /// assert_eq!(list.pop_front(), Some(b"coffee".to_vec()));
+    /// assert!(list.pop_back().is_none());
+    /// ```
+    pub fn pop_back(&mut self) -> Option<Vec<u8>> {
+        if self.is_empty() {
+            return None;
+        }
+        let (value, start) = entry::prev(&self.data, self.data.len())?;
+        let value = value.to_vec();
+        self.data.truncate(start);
+        self.set_len(self.len() - 1);
+        Some(value)
+    }
+
+    /// Insert an entry at the index, moving the entries which follow it towards
+    /// the back of the list. An index equal to the length of the list adds the
+    /// entry to the back. Returns `false` if the index is beyond the end of the
+    /// list.
+    ///
+    /// ```
+    /// use ziplist::ZipListBuf;
+    ///
+    /// let mut list = ZipListBuf::new();
+    /// list.push_back(b"coffee");
+    /// list.push_back(b"tea");
+    ///
+    /// assert!(list.insert(1, b"juice"));
+    /// assert!(!list.insert(4, b"water"));
+    /// let entries: Vec<&[u8]> = list.as_ziplist().iter().collect();
+    /// assert_eq!(entries, vec![&b"coffee"[..], &b"juice"[..], &b"tea"[..]]);
+    /// ```
+    pub fn insert(&mut self, index: usize, value: &[u8]) -> bool {
+        if index > self.len() {
+            return false;
+        }
+        let start = self.offset(index);
+
+        let mut entry = Vec::with_capacity(entry::encoded_len(value.len()));
+        entry::write(&mut entry, value);
+        self.data.splice(start..start, entry);
+        self.set_len(self.len() + 1);
+        true
+    }
+
+    /// Replace the value of the entry at the index. Returns `false` if the
+    /// index is beyond the end of the list.
+    ///
+    /// ```
+    /// use ziplist::ZipListBuf;
+    ///
+    /// let mut list = ZipListBuf::new();
+    /// list.push_back(b"coffee");
+    /// list.push_back(b"tea");
+    ///
+    /// assert!(list.replace(0, b"espresso"));
+    /// assert!(!list.replace(2, b"juice"));
+    /// assert_eq!(list.as_ziplist().get(0), Some(&b"espresso"[..]));
+    /// ```
+    pub fn replace(&mut self, index: usize, value: &[u8]) -> bool {
+        if index >= self.len() {
+            return false;
+        }
+        let start = self.offset(index);
+        let end = entry::next(&self.data, start).unwrap().1;
+
+        let mut entry = Vec::with_capacity(entry::encoded_len(value.len()));
+        entry::write(&mut entry, value);
+        self.data.splice(start..end, entry);
+        true
+    }
+
+    /// Remove and return the entry at the index.
+    pub fn remove(&mut self, index: usize) -> Option<Vec<u8>> {
+        if index >= self.len() {
+            return None;
+        }
+        let start = self.offset(index);
+        let (value, end) = entry::next(&self.data, start)?;
+        let value = value.to_vec();
+        self.data.drain(start..end);
+        self.set_len(self.len() - 1);
+        Some(value)
+    }
+
+    /// Keep only the entries with indices in the range `start..end`, removing
+    /// all others. Bounds which are beyond the end of the list are clamped,
+    /// and an empty range removes every entry.
+    ///
+    /// ```
+    /// use ziplist::ZipListBuf;
+    ///
+    /// let mut list = ZipListBuf::new();
+    /// for value in [b"a", b"b", b"c", b"d"] {
+    ///     list.push_back(value);
+    /// }
+    ///
+    /// list.trim(1, 3);
+    /// let entries: Vec<&[u8]> = list.as_ziplist().iter().collect();
+    /// assert_eq!(entries, vec![&b"b"[..], &b"c"[..]]);
+    /// ```
+    pub fn trim(&mut self, start: usize, end: usize) {
+        let len = self.len();
+        let end = end.min(len);
+        if start >= end {
+            self.data.truncate(HEADER_SIZE);
+            self.set_len(0);
+            return;
+        }
+
+        // find the byte offsets of the first entry to keep and of the end of
+        // the last entry to keep
+        let mut offset = HEADER_SIZE;
+        let mut first = HEADER_SIZE;
+        for index in 0..end {
+            if index == start {
+                first = offset;
+            }
+            // entries were validated when the list was created
+            offset = entry::next(&self.data, offset).unwrap().1;
+        }
+
+        self.data.truncate(offset);
+        self.data.drain(HEADER_SIZE..first);
+        self.set_len(end - start);
+    }
+
+    /// Returns the byte offset at which the entry with the index begins, or
+    /// the end of the list if the index is equal to its length. The index must
+    /// not be beyond the end of the list.
+    fn offset(&self, index: usize) -> usize {
+        let mut offset = HEADER_SIZE;
+        for _ in 0..index {
+            // entries were validated when the list was created
+            offset = entry::next(&self.data, offset).unwrap().1;
+        }
+        offset
+    }
+
+    fn set_len(&mut self, len: usize) {
+        self.data[0..HEADER_SIZE].copy_from_slice(&(len as u32).to_le_bytes());
+    }
+}
+
+impl TryFrom<&[u8]> for ZipListBuf {
+    type Error = ZipListError;
+
+    /// Copies an encoded ziplist into a new `ZipListBuf`. Unlike a `ZipList`,
+    /// every entry is validated, which guarantees that later modifications
+    /// will not encounter corrupt entries.
+    fn try_from(data: &[u8]) -> Result<Self, ZipListError> {
+        let list = ZipList::new(data)?;
+
+        let mut offset = HEADER_SIZE;
+        for _ in 0..list.len() {
+            offset = entry::next(data, offset).ok_or(ZipListError::Corrupt)?.1;
+        }
+        if offset != data.len() {
+            return Err(ZipListError::Corrupt);
+        }
+
+        Ok(Self {
+            data: data.to_vec(),
+        })
+    }
+}
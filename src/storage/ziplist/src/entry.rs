@@ -0,0 +1,70 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! Encoding and decoding of individual ziplist entries.
+
+/// Marker byte which indicates that the length is held in the next 4 bytes.
+const LONG: u8 = 0xFE;
+
+/// The largest value length which is encoded in a single byte.
+const MAX_SHORT: usize = 253;
+
+/// Returns the number of bytes needed to encode a value of the given length.
+pub(crate) fn encoded_len(len: usize) -> usize {
+    if len <= MAX_SHORT {
+        len + 2
+    } else {
+        len + 10
+    }
+}
+
+/// Appends the encoded entry for the value to the buffer.
+pub(crate) fn write(buf: &mut Vec<u8>, value: &[u8]) {
+    if value.len() <= MAX_SHORT {
+        buf.push(value.len() as u8);
+        buf.extend_from_slice(value);
+        buf.push(value.len() as u8);
+    } else {
+        let len = (value.len() as u32).to_le_bytes();
+        buf.push(LONG);
+        buf.extend_from_slice(&len);
+        buf.extend_from_slice(value);
+        buf.extend_from_slice(&len);
+        buf.push(LONG);
+    }
+}
+
+/// Decodes the entry which begins at the offset. Returns the value and the
+/// offset of the following entry, or `None` if the entry is not valid.
+pub(crate) fn next(data: &[u8], offset: usize) -> Option<(&[u8], usize)> {
+    let (len, start, trailer) = match *data.get(offset)? {
+        LONG => (read_u32(data, offset + 1)?, offset + 5, 5),
+        len => (len as usize, offset + 1, 1),
+    };
+    let end = start.checked_add(len)?;
+    let value = data.get(start..end)?;
+    let next = end + trailer;
+    if next > data.len() {
+        return None;
+    }
+    Some((value, next))
+}
+
+/// Decodes the entry which ends at the offset. Returns the value and the
+/// offset at which the entry begins, or `None` if the entry is not valid.
+pub(crate) fn prev(data: &[u8], end: usize) -> Option<(&[u8], usize)> {
+    let last = end.checked_sub(1)?;
+    let (len, stop, header) = match *data.get(last)? {
+        LONG => (read_u32(data, last.checked_sub(4)?)?, last - 4, 5),
+        len => (len as usize, last, 1),
+    };
+    let start = stop.checked_sub(len)?;
+    let value = data.get(start..stop)?;
+    Some((value, start.checked_sub(header)?))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<usize> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+}
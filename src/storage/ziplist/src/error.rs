@@ -0,0 +1,12 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq, Copy, Clone)]
+/// Possible errors returned when decoding a ziplist.
+pub enum ZipListError {
+    #[error("buffer is corrupt or not a ziplist")]
+    Corrupt,
+}
@@ -0,0 +1,58 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! A compact encoding for a list of byte strings, adapted from the ziplist in
+//! the legacy Pelikan codebase. The entire list is stored as a single
+//! contiguous buffer, which allows it to be stored as the value of an item in
+//! one of the existing storage types, such as `seg`.
+//!
+//! The list begins with a header which holds the number of entries, so the
+//! cardinality of the list can always be determined in O(1):
+//!
+//! ```text
+//! ┌──────────┬─────────┬─────────┬─────┬─────────┐
+//! │  NENTRY  │  ENTRY  │  ENTRY  │ ... │  ENTRY  │
+//! │          │         │         │     │         │
+//! │  32 bit  │         │         │     │         │
+//! └──────────┴─────────┴─────────┴─────┴─────────┘
+//! ```
+//!
+//! Each entry is the value surrounded by its length. The leading length is
+//! used to traverse the list forward and the trailing length allows traversal
+//! from the back, which makes operations at either end of the list cheap:
+//!
+//! ```text
+//! ┌──────────┬──────────┬──────────┐
+//! │   LEN    │   DATA   │   LEN    │
+//! │          │          │          │
+//! │ 1|5 byte │   LEN    │ 1|5 byte │
+//! └──────────┴──────────┴──────────┘
+//! ```
+//!
+//! Values of up to 253 bytes have their length encoded in a single byte.
+//! Longer values use a marker byte of `0xFE` together with a 32bit length,
+//! with the marker placed on the outer edge of the entry in both positions.
+//!
+//! All integers are little-endian.
+
+mod buf;
+mod entry;
+mod error;
+mod ziplist;
+
+#[cfg(test)]
+mod tests;
+
+pub use crate::ziplist::{Iter, ZipList};
+pub use buf::ZipListBuf;
+pub use error::ZipListError;
+
+/// The size of the header in bytes.
+pub(crate) const HEADER_SIZE: usize = 4;
+
+/// Reads the number of entries from the header. The caller must ensure that
+/// the buffer holds a complete header.
+pub(crate) fn nentry(data: &[u8]) -> usize {
+    u32::from_le_bytes([data[0], data[1], data[2], data[3]]) as usize
+}
@@ -0,0 +1,170 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+
+fn entries(list: &ZipListBuf) -> Vec<Vec<u8>> {
+    list.as_ziplist().iter().map(|v| v.to_vec()).collect()
+}
+
+#[test]
+fn empty() {
+    let list = ZipListBuf::new();
+    assert!(list.is_empty());
+    assert_eq!(list.as_bytes(), &[0, 0, 0, 0]);
+    assert!(list.as_ziplist().iter().next().is_none());
+    assert!(list.as_ziplist().get(0).is_none());
+}
+
+#[test]
+fn push_pop() {
+    let mut list = ZipListBuf::new();
+    list.push_back(b"b");
+    list.push_front(b"a");
+    list.push_back(b"c");
+    assert_eq!(list.len(), 3);
+    assert_eq!(
+        entries(&list),
+        vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()]
+    );
+
+    assert_eq!(list.pop_front(), Some(b"a".to_vec()));
+    assert_eq!(list.pop_back(), Some(b"c".to_vec()));
+    assert_eq!(list.pop_back(), Some(b"b".to_vec()));
+    assert_eq!(list.pop_front(), None);
+    assert_eq!(list, ZipListBuf::new());
+}
+
+#[test]
+fn long_entries() {
+    let long = vec![0xFE; 1000];
+    let mut list = ZipListBuf::new();
+    list.push_back(b"");
+    list.push_back(&long);
+    list.push_back(&[0xFE; 253]);
+    list.push_front(&[0xFE; 254]);
+
+    let view = list.as_ziplist();
+    assert_eq!(view.get(0), Some(&[0xFE; 254][..]));
+    assert_eq!(view.get(1), Some(&b""[..]));
+    assert_eq!(view.get(2), Some(&long[..]));
+    assert_eq!(view.get(3), Some(&[0xFE; 253][..]));
+
+    let reversed: Vec<&[u8]> = view.iter().rev().collect();
+    assert_eq!(
+        reversed,
+        vec![&[0xFE; 253][..], &long[..], b"", &[0xFE; 254][..]]
+    );
+
+    assert_eq!(list.pop_back(), Some(vec![0xFE; 253]));
+    assert_eq!(list.pop_back(), Some(long));
+    assert_eq!(list.pop_front(), Some(vec![0xFE; 254]));
+    assert_eq!(list.pop_front(), Some(vec![]));
+    assert!(list.is_empty());
+}
+
+#[test]
+fn iterate_both_ends() {
+    let mut list = ZipListBuf::new();
+    for i in 0..5_u8 {
+        list.push_back(&[i]);
+    }
+    let mut iter = list.as_ziplist().iter();
+    assert_eq!(iter.next(), Some(&[0][..]));
+    assert_eq!(iter.next_back(), Some(&[4][..]));
+    assert_eq!(iter.next(), Some(&[1][..]));
+    assert_eq!(iter.next_back(), Some(&[3][..]));
+    assert_eq!(iter.next(), Some(&[2][..]));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+}
+
+#[test]
+fn trim() {
+    let mut list = ZipListBuf::new();
+    for i in 0..10_u8 {
+        list.push_back(&[i]);
+    }
+
+    list.trim(2, 100);
+    assert_eq!(list.len(), 8);
+    assert_eq!(list.as_ziplist().get(0), Some(&[2][..]));
+
+    list.trim(0, 3);
+    assert_eq!(entries(&list), vec![vec![2], vec![3], vec![4]]);
+
+    list.trim(3, 3);
+    assert!(list.is_empty());
+    assert_eq!(list, ZipListBuf::new());
+}
+
+#[test]
+fn replace_remove() {
+    let mut list = ZipListBuf::new();
+    for value in [b"a", b"b", b"c"] {
+        list.push_back(value);
+    }
+
+    // grow an entry past the single byte length encoding and shrink it back
+    assert!(list.replace(1, &[0xFE; 300]));
+    assert_eq!(list.as_ziplist().get(1), Some(&[0xFE; 300][..]));
+    assert_eq!(list.as_ziplist().get(2), Some(&b"c"[..]));
+    assert!(list.replace(1, b"d"));
+    assert_eq!(
+        entries(&list),
+        vec![b"a".to_vec(), b"d".to_vec(), b"c".to_vec()]
+    );
+    assert!(!list.replace(3, b"e"));
+
+    assert!(list.insert(0, b"z"));
+    assert!(list.insert(4, &[0xFE; 300]));
+    assert!(!list.insert(6, b"y"));
+    assert_eq!(list.remove(4), Some(vec![0xFE; 300]));
+    assert_eq!(list.remove(0), Some(b"z".to_vec()));
+
+    assert_eq!(list.remove(1), Some(b"d".to_vec()));
+    assert_eq!(list.remove(2), None);
+    assert_eq!(entries(&list), vec![b"a".to_vec(), b"c".to_vec()]);
+    assert_eq!(list.remove(1), Some(b"c".to_vec()));
+    assert_eq!(list.remove(0), Some(b"a".to_vec()));
+    assert_eq!(list, ZipListBuf::new());
+}
+
+#[test]
+fn decode() {
+    let mut list = ZipListBuf::new();
+    list.push_back(b"coffee");
+    list.push_back(&[0; 300]);
+
+    let copy = ZipListBuf::try_from(list.as_bytes()).expect("failed to decode");
+    assert_eq!(copy, list);
+
+    assert_eq!(ZipList::new(&[0, 0]).err(), Some(ZipListError::Corrupt));
+
+    // truncated entry
+    let bytes = list.as_bytes();
+    assert_eq!(
+        ZipListBuf::try_from(&bytes[..bytes.len() - 1]).err(),
+        Some(ZipListError::Corrupt)
+    );
+
+    // trailing garbage
+    let mut bytes = list.as_bytes().to_vec();
+    bytes.push(0);
+    assert_eq!(
+        ZipListBuf::try_from(&bytes[..]).err(),
+        Some(ZipListError::Corrupt)
+    );
+
+    // entry count does not match the entries, a view stops iterating
+    let mut bytes = list.as_bytes().to_vec();
+    bytes[0] = 3;
+    assert_eq!(
+        ZipListBuf::try_from(&bytes[..]).err(),
+        Some(ZipListError::Corrupt)
+    );
+    let view = ZipList::new(&bytes).unwrap();
+    assert_eq!(view.iter().count(), 2);
+    assert_eq!(view.iter().rev().count(), 2);
+}
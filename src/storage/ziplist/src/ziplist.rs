@@ -0,0 +1,124 @@
+// Copyright 2022 Twitter, Inc.
+// Licensed under the Apache License, Version 2.0
+// http://www.apache.org/licenses/LICENSE-2.0
+
+//! A read-only view of an encoded ziplist.
+
+use crate::*;
+
+/// A borrowed, read-only view of an encoded ziplist. Only the header is
+/// validated on creation, iteration stops early if an entry is found to be
+/// corrupt.
+#[derive(Copy, Clone, Debug)]
+pub struct ZipList<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> ZipList<'a> {
+    /// Create a view of the ziplist which is encoded in the buffer.
+    ///
+    /// ```
+    /// use ziplist::{ZipList, ZipListBuf};
+    ///
+    /// let mut list = ZipListBuf::new();
+    /// list.push_back(b"coffee");
+    ///
+    /// let view = ZipList::new(list.as_bytes()).expect("not a ziplist");
+    /// assert_eq!(view.len(), 1);
+    /// ```
+    pub fn new(data: &'a [u8]) -> Result<Self, ZipListError> {
+        if data.len() < HEADER_SIZE {
+            return Err(ZipListError::Corrupt);
+        }
+        Ok(Self { data })
+    }
+
+    /// Returns the number of entries in the list.
+    pub fn len(&self) -> usize {
+        nentry(self.data)
+    }
+
+    /// Returns true if there are no entries in the list.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the entry at the index, traversing from whichever end of the
+    /// list is closer.
+    ///
+    /// ```
+    /// use ziplist::ZipListBuf;
+    ///
+    /// let mut list = ZipListBuf::new();
+    /// list.push_back(b"coffee");
+    /// list.push_back(b"tea");
+    ///
+    /// assert_eq!(list.as_ziplist().get(1), Some(&b"tea"[..]));
+    /// assert_eq!(list.as_ziplist().get(2), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&'a [u8]> {
+        let len = self.len();
+        if index >= len {
+            None
+        } else if index < len / 2 {
+            self.iter().nth(index)
+        } else {
+            self.iter().rev().nth(len - 1 - index)
+        }
+    }
+
+    /// Returns an iterator over the entries in the list.
+    pub fn iter(&self) -> Iter<'a> {
+        Iter {
+            data: self.data,
+            front: HEADER_SIZE,
+            back: self.data.len(),
+            remaining: self.len(),
+        }
+    }
+
+    /// Borrow the encoded ziplist.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+/// An iterator over the entries in a ziplist which may be traversed from
+/// either end.
+pub struct Iter<'a> {
+    data: &'a [u8],
+    front: usize,
+    back: usize,
+    remaining: usize,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let (value, next) = entry::next(&self.data[..self.back], self.front)?;
+        self.front = next;
+        self.remaining -= 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining))
+    }
+}
+
+impl<'a> DoubleEndedIterator for Iter<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let (value, start) =
+            entry::prev(&self.data[self.front..self.back], self.back - self.front)?;
+        self.back = self.front + start;
+        self.remaining -= 1;
+        Some(value)
+    }
+}